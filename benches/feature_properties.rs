@@ -0,0 +1,64 @@
+//! Compare reading features with and without their properties.
+//!
+//! Run with `cargo bench --bench feature_properties`.
+
+use std::time::Instant;
+
+use geo_types::Point;
+use rusqlite_gpkg::{
+    ColumnSpec, ColumnType, Dimension, FeatureReadOptions, GeometryType, Gpkg, Value,
+};
+
+fn main() -> rusqlite_gpkg::Result<()> {
+    let gpkg = Gpkg::open_in_memory()?;
+    let columns = vec![
+        ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        },
+        ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        },
+    ];
+    let layer = gpkg.create_layer(
+        "points",
+        "geom",
+        GeometryType::Point,
+        Dimension::Xy,
+        4326,
+        &columns,
+    )?;
+    layer.drop_spatial_index()?;
+    let features = (1..=1_000_000i64).map(|i| {
+        let properties = vec![
+            Value::from(format!("feature {i} with a longer description")),
+            Value::from(i),
+        ];
+        (Point::new(1.0, 2.0), properties)
+    });
+    layer.insert_many(features)?;
+
+    let start = Instant::now();
+    let features = layer.features()?;
+    println!(
+        "features() of {} rows: {:?}",
+        features.len(),
+        start.elapsed()
+    );
+    drop(features);
+
+    let options = FeatureReadOptions {
+        skip_properties: true,
+    };
+    let start = Instant::now();
+    let features = layer.features_with_options(&options)?;
+    println!(
+        "features_with_options(skip_properties) of {} rows: {:?}",
+        features.len(),
+        start.elapsed()
+    );
+    Ok(())
+}
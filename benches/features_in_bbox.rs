@@ -0,0 +1,42 @@
+//! Compare `GpkgLayer::features_in_bbox()` with and without the prepared
+//! statement cache.
+//!
+//! Run with `cargo bench --bench features_in_bbox`.
+
+use std::time::Instant;
+
+use geo_types::Point;
+use rusqlite_gpkg::{Dimension, GeometryType, Gpkg, Value};
+
+fn main() -> rusqlite_gpkg::Result<()> {
+    let gpkg = Gpkg::open_in_memory()?;
+    let layer = gpkg.create_layer(
+        "points",
+        "geom",
+        GeometryType::Point,
+        Dimension::Xy,
+        4326,
+        &[],
+    )?;
+    let features = (0..1000).map(|i| {
+        (
+            Point::new((i % 100) as f64, (i / 100) as f64),
+            Vec::<Value>::new(),
+        )
+    });
+    layer.insert_many(features)?;
+
+    for capacity in [0, 16] {
+        gpkg.set_prepared_statement_cache_capacity(capacity);
+        let start = Instant::now();
+        for i in 0..5000 {
+            let x = (i % 90) as f64;
+            layer.features_in_bbox(x, 0.0, x + 10.0, 10.0)?;
+        }
+        println!(
+            "features_in_bbox x 5000 with a cache of {capacity}: {:?}",
+            start.elapsed()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Write a GeoPackage whose geometry column declares z = 2 ("z values optional"),
+# as ArcGIS does, with a mix of XY and XYZ points. GDAL always writes z = 0 or
+# 1, so the file is written with plain SQL.
+
+root_dir="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+output_gpkg="${root_dir}/src/test/test_z_optional.gpkg"
+
+rm -f "${output_gpkg}"
+
+sqlite3 "${output_gpkg}" <<'SQL'
+PRAGMA application_id = 1196444487;
+PRAGMA user_version = 10200;
+
+CREATE TABLE gpkg_spatial_ref_sys (
+  srs_name TEXT NOT NULL,
+  srs_id INTEGER NOT NULL PRIMARY KEY,
+  organization TEXT NOT NULL,
+  organization_coordsys_id INTEGER NOT NULL,
+  definition  TEXT NOT NULL,
+  description TEXT
+);
+INSERT INTO gpkg_spatial_ref_sys VALUES
+  ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', 'undefined cartesian coordinate reference system'),
+  ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', 'undefined geographic coordinate reference system'),
+  ('WGS 84 geodetic', 4326, 'EPSG', 4326, 'GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]', 'longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid');
+
+CREATE TABLE gpkg_contents (
+  table_name TEXT NOT NULL PRIMARY KEY,
+  data_type TEXT NOT NULL,
+  identifier TEXT UNIQUE,
+  description TEXT DEFAULT '',
+  last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  min_x DOUBLE,
+  min_y DOUBLE,
+  max_x DOUBLE,
+  max_y DOUBLE,
+  srs_id INTEGER,
+  CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+
+CREATE TABLE gpkg_geometry_columns (
+  table_name TEXT NOT NULL,
+  column_name TEXT NOT NULL,
+  geometry_type_name TEXT NOT NULL,
+  srs_id INTEGER NOT NULL,
+  z TINYINT NOT NULL,
+  m TINYINT NOT NULL,
+  CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+  CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+  CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+);
+
+CREATE TABLE wells (
+  OBJECTID INTEGER PRIMARY KEY AUTOINCREMENT,
+  Shape POINT,
+  name TEXT
+);
+INSERT INTO gpkg_contents (table_name, data_type, identifier, min_x, min_y, max_x, max_y, srs_id)
+  VALUES ('wells', 'features', 'wells', 139.695, 35.685, 139.71, 35.6895, 4326);
+INSERT INTO gpkg_geometry_columns VALUES ('wells', 'Shape', 'POINT', 4326, 2, 0);
+
+-- POINT (139.7 35.6895), POINT Z (139.71 35.689 12.5), POINT Z (139.695 35.685 3)
+INSERT INTO wells (Shape, name) VALUES
+  (X'47500001E610000001010000006666666666766140C74B378941D84140', 'alpha'),
+  (X'47500001E610000001E90300001F85EB51B8766140D578E92631D841400000000000002940', 'beta'),
+  (X'47500001E610000001E90300000AD7A3703D76614048E17A14AED741400000000000000840', 'gamma');
+SQL
+
+echo "Wrote ${output_gpkg}"
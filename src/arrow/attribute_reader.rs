@@ -13,7 +13,7 @@ pub struct ArrowGpkgAttributeReader<'a> {
     stmt: rusqlite::Statement<'a>,
     property_columns: Vec<ColumnSpec>,
     batch_size: usize,
-    offset: u32,
+    last_id: i64,
     end_or_invalid_state: bool,
     schema_ref: SchemaRef,
 }
@@ -52,7 +52,7 @@ impl<'a> ArrowGpkgAttributeReader<'a> {
             stmt,
             batch_size: batch_size as usize,
             property_columns: table.property_columns.clone(),
-            offset: 0,
+            last_id: i64::MIN,
             end_or_invalid_state: false,
             schema_ref,
         }
@@ -75,8 +75,23 @@ impl<'a> ArrowGpkgAttributeReader<'a> {
                     crate::ColumnType::Integer => {
                         arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int64, true)
                     }
-                    crate::ColumnType::Date | crate::ColumnType::Datetime => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Utf8, true)
+                    crate::ColumnType::TinyInt => {
+                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int8, true)
+                    }
+                    crate::ColumnType::SmallInt => {
+                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int16, true)
+                    }
+                    crate::ColumnType::MediumInt => {
+                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int32, true)
+                    }
+                    crate::ColumnType::Float => {
+                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Float32, true)
+                    }
+                    crate::ColumnType::Date => {
+                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Date32, true)
+                    }
+                    crate::ColumnType::Datetime => {
+                        arrow_schema::Field::new(&col.name, super::datetime_data_type(), true)
                     }
                     crate::ColumnType::Blob => {
                         arrow_schema::Field::new(&col.name, arrow_schema::DataType::Binary, true)
@@ -101,20 +116,36 @@ impl<'a> ArrowGpkgAttributeReader<'a> {
                 crate::ColumnType::Boolean => AttributeArrayBuilder::Boolean(
                     arrow_array::builder::BooleanBuilder::with_capacity(self.batch_size),
                 ),
-                crate::ColumnType::Varchar
-                | crate::ColumnType::Date
-                | crate::ColumnType::Datetime => AttributeArrayBuilder::Varchar(
+                crate::ColumnType::Varchar => AttributeArrayBuilder::Varchar(
                     arrow_array::builder::StringBuilder::with_capacity(
                         self.batch_size,
                         8 * self.batch_size,
                     ),
                 ),
+                crate::ColumnType::Date => AttributeArrayBuilder::Date(
+                    arrow_array::builder::Date32Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::Datetime => {
+                    AttributeArrayBuilder::Datetime(super::datetime_builder(self.batch_size))
+                }
                 crate::ColumnType::Double => AttributeArrayBuilder::Double(
                     arrow_array::builder::Float64Builder::with_capacity(self.batch_size),
                 ),
                 crate::ColumnType::Integer => AttributeArrayBuilder::Integer(
                     arrow_array::builder::Int64Builder::with_capacity(self.batch_size),
                 ),
+                crate::ColumnType::TinyInt => AttributeArrayBuilder::TinyInt(
+                    arrow_array::builder::Int8Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::SmallInt => AttributeArrayBuilder::SmallInt(
+                    arrow_array::builder::Int16Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::MediumInt => AttributeArrayBuilder::MediumInt(
+                    arrow_array::builder::Int32Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::Float => AttributeArrayBuilder::Float(
+                    arrow_array::builder::Float32Builder::with_capacity(self.batch_size),
+                ),
                 crate::ColumnType::Blob | crate::ColumnType::Geometry => {
                     AttributeArrayBuilder::Blob(arrow_array::builder::BinaryBuilder::with_capacity(
                         self.batch_size,
@@ -132,8 +163,9 @@ impl<'a> ArrowGpkgAttributeReader<'a> {
 
     fn get_record_batch(&mut self) -> crate::error::Result<arrow_array::RecordBatch> {
         let mut builders = self.create_record_batch_builder();
-        let mut rows = self.stmt.query([self.offset])?;
+        let mut rows = self.stmt.query([self.last_id])?;
         while let Some(row) = rows.next()? {
+            self.last_id = row.get(0)?;
             builders.push(row)?;
         }
         builders.finish()
@@ -166,8 +198,6 @@ impl<'a> Iterator for ArrowGpkgAttributeReader<'a> {
             }
         }
 
-        self.offset += result_size as u32;
-
         Some(Ok(batch))
     }
 }
@@ -183,6 +213,12 @@ enum AttributeArrayBuilder {
     Varchar(arrow_array::builder::StringBuilder),
     Double(arrow_array::builder::Float64Builder),
     Integer(arrow_array::builder::Int64Builder),
+    TinyInt(arrow_array::builder::Int8Builder),
+    SmallInt(arrow_array::builder::Int16Builder),
+    MediumInt(arrow_array::builder::Int32Builder),
+    Float(arrow_array::builder::Float32Builder),
+    Date(arrow_array::builder::Date32Builder),
+    Datetime(arrow_array::builder::TimestampMicrosecondBuilder),
     Blob(arrow_array::builder::BinaryBuilder),
 }
 
@@ -201,7 +237,11 @@ impl AttributeArrayBuilder {
         match self {
             AttributeArrayBuilder::Boolean(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
-                rusqlite::types::Value::Integer(i) => builder.append_value(i == 1),
+                rusqlite::types::Value::Integer(0) => builder.append_value(false),
+                rusqlite::types::Value::Integer(1) => builder.append_value(true),
+                rusqlite::types::Value::Integer(_) => {
+                    return Err(GpkgError::ValueOutOfRange { target: "bool" });
+                }
                 other => {
                     return Err(GpkgError::InvalidArrowValue {
                         expected: "INTEGER or NULL",
@@ -222,9 +262,10 @@ impl AttributeArrayBuilder {
             AttributeArrayBuilder::Double(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
                 rusqlite::types::Value::Real(f) => builder.append_value(f),
+                rusqlite::types::Value::Integer(i) => builder.append_value(i as f64),
                 other => {
                     return Err(GpkgError::InvalidArrowValue {
-                        expected: "REAL or NULL",
+                        expected: "REAL, INTEGER or NULL",
                         actual: rusqlite_value_type_name(&other),
                     });
                 }
@@ -239,6 +280,89 @@ impl AttributeArrayBuilder {
                     });
                 }
             },
+            AttributeArrayBuilder::TinyInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i8::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i8" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            AttributeArrayBuilder::SmallInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i16::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i16" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            AttributeArrayBuilder::MediumInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i32::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i32" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            AttributeArrayBuilder::Float(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Real(f) => builder.append_value(f as f32),
+                rusqlite::types::Value::Integer(i) => builder.append_value(i as f32),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "REAL, INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            AttributeArrayBuilder::Date(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Text(t) => {
+                    let days = super::parse_date(&t).ok_or_else(|| {
+                        GpkgError::InvalidArrowTemporalValue {
+                            expected: "ISO 8601 date (YYYY-MM-DD)",
+                            value: t.clone(),
+                        }
+                    })?;
+                    builder.append_value(days);
+                }
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "TEXT or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            AttributeArrayBuilder::Datetime(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Text(t) => {
+                    let micros = super::parse_datetime(&t).ok_or_else(|| {
+                        GpkgError::InvalidArrowTemporalValue {
+                            expected: "ISO 8601 date-time (YYYY-MM-DDTHH:MM:SS.SSSZ)",
+                            value: t.clone(),
+                        }
+                    })?;
+                    builder.append_value(micros);
+                }
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "TEXT or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
             AttributeArrayBuilder::Blob(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
                 rusqlite::types::Value::Blob(b) => builder.append_value(b),
@@ -266,7 +390,7 @@ impl AttributeRecordBatchBuilder {
             let column_index = i + 1;
             match row.get::<usize, rusqlite::types::Value>(column_index) {
                 Ok(v) => builder.push(v)?,
-                Err(e) => return Err(GpkgError::Sql(e)),
+                Err(e) => return Err(super::row_value_error(row, column_index, 0, e)),
             }
         }
         Ok(())
@@ -289,6 +413,24 @@ impl AttributeRecordBatchBuilder {
                 AttributeArrayBuilder::Integer(mut builder) => {
                     arrow_array::builder::ArrayBuilder::finish(&mut builder)
                 }
+                AttributeArrayBuilder::TinyInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                AttributeArrayBuilder::SmallInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                AttributeArrayBuilder::MediumInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                AttributeArrayBuilder::Float(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                AttributeArrayBuilder::Date(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                AttributeArrayBuilder::Datetime(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
                 AttributeArrayBuilder::Blob(mut builder) => {
                     arrow_array::builder::ArrayBuilder::finish(&mut builder)
                 }
@@ -316,10 +458,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
         let table = gpkg.create_attribute_table("observations", &columns)?;
@@ -105,6 +105,7 @@ fn build_property_columns(schema: &SchemaRef) -> Result<Vec<ColumnSpec>> {
         columns.push(ColumnSpec {
             name: field.name().clone(),
             column_type,
+            ..Default::default()
         });
     }
     Ok(columns)
@@ -112,15 +113,17 @@ fn build_property_columns(schema: &SchemaRef) -> Result<Vec<ColumnSpec>> {
 
 fn arrow_type_to_column_type(dt: &arrow_schema::DataType) -> Result<ColumnType> {
     use arrow_schema::DataType;
+    if let Some(column_type) = super::temporal_column_type(dt) {
+        return Ok(column_type);
+    }
     match dt {
         DataType::Boolean => Ok(ColumnType::Boolean),
-        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
-            Ok(ColumnType::Integer)
-        }
-        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
-            Ok(ColumnType::Integer)
-        }
-        DataType::Float32 | DataType::Float64 => Ok(ColumnType::Double),
+        DataType::Int8 => Ok(ColumnType::TinyInt),
+        DataType::Int16 | DataType::UInt8 => Ok(ColumnType::SmallInt),
+        DataType::Int32 | DataType::UInt16 => Ok(ColumnType::MediumInt),
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 => Ok(ColumnType::Integer),
+        DataType::Float32 => Ok(ColumnType::Float),
+        DataType::Float64 => Ok(ColumnType::Double),
         DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => Ok(ColumnType::Varchar),
         DataType::Binary | DataType::LargeBinary | DataType::BinaryView => Ok(ColumnType::Blob),
         other => Err(GpkgError::GeoArrow(format!(
@@ -186,6 +189,9 @@ fn extract_value(array: &dyn arrow_array::Array, row_idx: usize) -> Result<rusql
     {
         return Ok(rusqlite::types::Value::Text(a.value(row_idx).to_string()));
     }
+    if let Some(value) = super::temporal_value(array, row_idx) {
+        return Ok(value);
+    }
     if let Some(a) = array.as_any().downcast_ref::<arrow_array::BinaryArray>() {
         return Ok(rusqlite::types::Value::Blob(a.value(row_idx).to_vec()));
     }
@@ -0,0 +1,262 @@
+//! GeoParquet export of feature layers.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use arrow_array::cast::AsArray;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use serde_json::{Value as JsonValue, json};
+use wkb::reader::{Dimension, GeometryType, Wkb};
+
+use super::reader::ArrowGpkgReader;
+use crate::error::Result;
+use crate::gpkg::Gpkg;
+use crate::ogc_sql::SQL_SELECT_SRS_DEFINITION;
+use crate::sql_functions::{Bounds, bounds_from_geometry, merge_bounds};
+use crate::types::{ParquetCompression, ParquetExportOptions};
+
+/// Write the layer `layer_name` of `gpkg` into the file at `path` as
+/// GeoParquet, and return the number of rows written.
+pub(crate) fn export_layer(
+    gpkg: &Gpkg,
+    layer_name: &str,
+    path: &Path,
+    options: &ParquetExportOptions,
+) -> Result<u64> {
+    let layer = gpkg.get_layer(layer_name)?;
+    let srs = gpkg
+        .conn
+        .query_row(SQL_SELECT_SRS_DEFINITION, [layer.srs_id], |row| {
+            Ok(Srs {
+                organization: row.get(0)?,
+                organization_coordsys_id: row.get(1)?,
+                definition: row.get(2)?,
+            })
+        })?;
+    let reader = ArrowGpkgReader::builder(gpkg, layer_name)
+        .batch_size(options.batch_size)
+        .build()?;
+    let schema = reader.schema();
+    let geometry_index = schema.index_of(&layer.geometry_column)?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(compression(options.compression)?)
+        .build();
+    let file = std::fs::File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(properties))?;
+
+    // The bbox and the geometry types are only known once all the rows are
+    // read, and the `geo` metadata is written in the footer.
+    let mut rows = 0;
+    let mut bounds: Option<Bounds> = None;
+    let mut geometry_types = BTreeSet::new();
+    for batch in reader {
+        let batch = batch?;
+        for buf in batch
+            .column(geometry_index)
+            .as_binary::<i32>()
+            .iter()
+            .flatten()
+        {
+            let geometry = wkb::reader::read_wkb(buf)?;
+            if let Some(geometry_bounds) = bounds_from_geometry(&geometry) {
+                merge_bounds(&mut bounds, geometry_bounds);
+            }
+            geometry_types.insert(geometry_type_name(&geometry));
+        }
+        rows += batch.num_rows() as u64;
+        writer.write(&batch)?;
+    }
+
+    let geo = geo_metadata(&layer.geometry_column, &srs, bounds, geometry_types);
+    writer.append_key_value_metadata(KeyValue::new("geo".to_string(), geo.to_string()));
+    // GeoParquet can only carry a CRS as PROJJSON, so the GeoPackage SRS is
+    // recorded as well.
+    writer.append_key_value_metadata(KeyValue::new(
+        "gpkg:srs_id".to_string(),
+        layer.srs_id.to_string(),
+    ));
+    writer.append_key_value_metadata(KeyValue::new(
+        "gpkg:srs_definition".to_string(),
+        srs.definition,
+    ));
+    writer.close()?;
+    Ok(rows)
+}
+
+// The row of `gpkg_spatial_ref_sys` recorded in the metadata.
+struct Srs {
+    organization: String,
+    organization_coordsys_id: i32,
+    definition: String,
+}
+
+fn compression(compression: ParquetCompression) -> Result<Compression> {
+    Ok(match compression {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Snappy => Compression::SNAPPY,
+        ParquetCompression::Zstd(level) => Compression::ZSTD(ZstdLevel::try_new(level)?),
+    })
+}
+
+// The `geo` file metadata of GeoParquet 1.1.0. A geometry with M values has no
+// GeoParquet geometry type, in which case the types are left empty (any type).
+fn geo_metadata(
+    geometry_column: &str,
+    srs: &Srs,
+    bounds: Option<Bounds>,
+    geometry_types: BTreeSet<Option<String>>,
+) -> JsonValue {
+    let geometry_types: Vec<String> = geometry_types
+        .into_iter()
+        .collect::<Option<_>>()
+        .unwrap_or_default();
+    let mut column = json!({
+        "encoding": "WKB",
+        "geometry_types": geometry_types,
+        "crs": crs_projjson(srs),
+    });
+    if let Some(bounds) = bounds {
+        column["bbox"] = json!([bounds.minx, bounds.miny, bounds.maxx, bounds.maxy]);
+    }
+    json!({
+        "version": "1.1.0",
+        "primary_column": geometry_column,
+        "columns": { geometry_column: column },
+    })
+}
+
+// The PROJJSON of an EPSG SRS, or null (an undefined CRS) if it's unknown.
+fn crs_projjson(srs: &Srs) -> JsonValue {
+    if !srs.organization.eq_ignore_ascii_case("EPSG") {
+        return JsonValue::Null;
+    }
+    epsg_utils::epsg_to_projjson(srs.organization_coordsys_id)
+        .ok()
+        .and_then(|projjson| serde_json::from_str(projjson).ok())
+        .unwrap_or(JsonValue::Null)
+}
+
+fn geometry_type_name(geometry: &Wkb<'_>) -> Option<String> {
+    let name = match geometry.geometry_type() {
+        GeometryType::Point => "Point",
+        GeometryType::LineString => "LineString",
+        GeometryType::Polygon => "Polygon",
+        GeometryType::MultiPoint => "MultiPoint",
+        GeometryType::MultiLineString => "MultiLineString",
+        GeometryType::MultiPolygon => "MultiPolygon",
+        GeometryType::GeometryCollection => "GeometryCollection",
+        _ => return None,
+    };
+    match geometry.dimension() {
+        Dimension::Xy => Some(name.to_string()),
+        Dimension::Xyz => Some(format!("{name} Z")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ColumnSpec, ColumnType, ParquetCompression, ParquetExportOptions};
+    use crate::{Gpkg, Result, params};
+    use arrow_array::cast::AsArray;
+    use geo_types::Point;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::basic::Compression;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use wkb::reader::{Dimension, GeometryType};
+
+    #[test]
+    fn exports_layer_as_geoparquet() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        for i in 0..25 {
+            layer.insert(
+                Point::new(i as f64, -(i as f64) / 2.0),
+                params![format!("p{i}")],
+            )?;
+        }
+        gpkg.conn
+            .execute("INSERT INTO places (geom, name) VALUES (NULL, 'null')", [])?;
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("rusqlite_gpkg_geoparquet_{nanos}.parquet"));
+        let options = ParquetExportOptions {
+            batch_size: 10,
+            compression: ParquetCompression::Zstd(3),
+        };
+        assert_eq!(gpkg.export_parquet("places", &path, &options)?, 26);
+
+        let file = std::fs::File::open(&path)?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let metadata = builder.metadata().clone();
+        assert_eq!(metadata.file_metadata().num_rows(), 26);
+        assert!(matches!(
+            metadata.row_group(0).column(0).compression(),
+            Compression::ZSTD(_)
+        ));
+        let key_value = |key: &str| {
+            metadata
+                .file_metadata()
+                .key_value_metadata()
+                .and_then(|kv| kv.iter().find(|kv| kv.key == key))
+                .and_then(|kv| kv.value.clone())
+                .expect(key)
+        };
+        let geo: serde_json::Value = serde_json::from_str(&key_value("geo")).expect("geo json");
+        assert_eq!(geo["primary_column"], "geom");
+        let column = &geo["columns"]["geom"];
+        assert_eq!(column["encoding"], "WKB");
+        assert_eq!(column["geometry_types"], serde_json::json!(["Point"]));
+        assert_eq!(column["bbox"], serde_json::json!([0.0, -12.0, 24.0, 0.0]));
+        assert_eq!(column["crs"]["id"]["code"], 4326);
+        assert_eq!(key_value("gpkg:srs_id"), "4326");
+
+        // Read back the rows, and compare a sample geometry.
+        let batches = builder
+            .build()?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 26);
+        let batch = &batches[0];
+        let names = batch
+            .column_by_name("name")
+            .expect("name")
+            .as_string::<i32>();
+        assert_eq!(names.value(3), "p3");
+        let geometries = batch
+            .column_by_name("geom")
+            .expect("geom")
+            .as_binary::<i32>();
+        let expected = layer.get_feature(4)?.expect("feature");
+        assert_eq!(geometries.value(3), expected.geometry()?.buf());
+        assert!(
+            batches
+                .last()
+                .expect("batch")
+                .column_by_name("geom")
+                .expect("geom")
+                .is_null(batches.last().expect("batch").num_rows() - 1)
+        );
+
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+}
@@ -22,6 +22,20 @@
 //! The reader borrows the `Gpkg` because it holds a prepared `rusqlite::Statement`,
 //! so the `Gpkg` must outlive the reader.
 //!
+//! ## Date and time columns
+//!
+//! GeoPackage stores `DATE` and `DATETIME` columns as ISO 8601 text. The readers
+//! convert them to Arrow `Date32` and `Timestamp(Microsecond, "UTC")` arrays
+//! respectively. A `DATETIME` value without a `Z` suffix is treated as UTC.
+//! [`ArrowGpkgReader`][reader::ArrowGpkgReader] turns values that fail to parse into
+//! nulls and counts them (see
+//! [`temporal_parse_failures`][reader::ArrowGpkgReader::temporal_parse_failures]), unless
+//! [`strict_temporal`][reader::ArrowGpkgReaderBuilder::strict_temporal] is set.
+//!
+//! In the other direction, the writers store `Date32` / `Date64` arrays as `DATE`
+//! columns and `Timestamp` arrays of any unit as `DATETIME` columns, formatted as
+//! `YYYY-MM-DD` and `YYYY-MM-DDTHH:MM:SS.SSSZ`.
+//!
 //! ## Geometry handling
 //!
 //! Geometry columns are stored as WKB in the record batch. You can access the raw
@@ -55,6 +69,9 @@ pub mod attribute_writer;
 pub mod reader;
 pub mod writer;
 
+#[cfg(feature = "parquet")]
+pub(crate) mod geoparquet;
+
 use geoarrow_array::builder::WkbBuilder;
 
 pub(crate) fn crs_from_srs_id(srs_id: u32) -> geoarrow_schema::Crs {
@@ -81,3 +98,275 @@ pub(crate) fn wkb_geometry_builder(srs_id: u32, batch_size: usize) -> WkbBuilder
         geoarrow_array::capacity::WkbCapacity::new(21 * batch_size, batch_size),
     )
 }
+
+// Convert the error of reading column `idx` of a row, reporting invalid UTF-8
+// text as `GpkgError::InvalidUtf8Text` with the primary key in column `id_idx`.
+pub(crate) fn row_value_error(
+    row: &rusqlite::Row<'_>,
+    idx: usize,
+    id_idx: usize,
+    err: rusqlite::Error,
+) -> crate::GpkgError {
+    match err {
+        rusqlite::Error::Utf8Error(..) => crate::GpkgError::InvalidUtf8Text {
+            column: row
+                .as_ref()
+                .column_name(idx)
+                .unwrap_or_default()
+                .to_string(),
+            id: row.get(id_idx).ok(),
+        },
+        err => crate::GpkgError::Sql(err),
+    }
+}
+
+pub(crate) fn datetime_data_type() -> arrow_schema::DataType {
+    arrow_schema::DataType::Timestamp(arrow_schema::TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+pub(crate) fn datetime_builder(
+    batch_size: usize,
+) -> arrow_array::builder::TimestampMicrosecondBuilder {
+    arrow_array::builder::TimestampMicrosecondBuilder::with_capacity(batch_size)
+        .with_timezone("UTC")
+}
+
+/// The GeoPackage column type for an Arrow date or timestamp type, if it is one.
+pub(crate) fn temporal_column_type(dt: &arrow_schema::DataType) -> Option<crate::ColumnType> {
+    use arrow_schema::DataType;
+    match dt {
+        DataType::Date32 | DataType::Date64 => Some(crate::ColumnType::Date),
+        DataType::Timestamp(_, _) => Some(crate::ColumnType::Datetime),
+        _ => None,
+    }
+}
+
+/// Format the value of an Arrow date or timestamp array as ISO 8601 text, or
+/// `None` if the array is not a temporal one.
+pub(crate) fn temporal_value(
+    array: &dyn arrow_array::Array,
+    row_idx: usize,
+) -> Option<rusqlite::types::Value> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{
+        Date32Type, Date64Type, TimestampMicrosecondType, TimestampMillisecondType,
+        TimestampNanosecondType, TimestampSecondType,
+    };
+    use arrow_schema::{DataType, TimeUnit};
+
+    let text = match array.data_type() {
+        DataType::Date32 => format_date(array.as_primitive::<Date32Type>().value(row_idx) as i64),
+        DataType::Date64 => format_date(
+            array
+                .as_primitive::<Date64Type>()
+                .value(row_idx)
+                .div_euclid(86_400_000),
+        ),
+        DataType::Timestamp(unit, _) => {
+            let micros = match unit {
+                TimeUnit::Second => array
+                    .as_primitive::<TimestampSecondType>()
+                    .value(row_idx)
+                    .saturating_mul(1_000_000),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value(row_idx)
+                    .saturating_mul(1_000),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value(row_idx),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value(row_idx)
+                    .div_euclid(1_000),
+            };
+            format_datetime(micros)
+        }
+        _ => return None,
+    };
+    Some(rusqlite::types::Value::Text(text))
+}
+
+/// Format days since the Unix epoch as a GeoPackage `DATE` value (`YYYY-MM-DD`).
+pub(crate) fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Format microseconds since the Unix epoch as a GeoPackage `DATETIME` value
+/// (`YYYY-MM-DDTHH:MM:SS.SSSZ`). Anything below milliseconds is truncated.
+pub(crate) fn format_datetime(micros: i64) -> String {
+    let days = micros.div_euclid(86_400_000_000);
+    let micros_of_day = micros.rem_euclid(86_400_000_000);
+    let millis_of_day = micros_of_day / 1_000;
+    let (hour, minute) = (millis_of_day / 3_600_000, millis_of_day / 60_000 % 60);
+    let (second, millis) = (millis_of_day / 1_000 % 60, millis_of_day % 1_000);
+    format!(
+        "{}T{hour:02}:{minute:02}:{second:02}.{millis:03}Z",
+        format_date(days)
+    )
+}
+
+/// Parse a GeoPackage `DATE` value (`YYYY-MM-DD`) into days since the Unix epoch.
+pub(crate) fn parse_date(value: &str) -> Option<i32> {
+    parse_date_bytes(value.as_bytes())
+}
+
+// Work on bytes so that non-ASCII input is rejected instead of slicing a
+// `str` inside a multi-byte character.
+fn parse_date_bytes(bytes: &[u8]) -> Option<i32> {
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year = parse_digits(&bytes[0..4])?;
+    let month = parse_digits(&bytes[5..7])?;
+    let day = parse_digits(&bytes[8..10])?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    i32::try_from(days_from_civil(year, month, day)).ok()
+}
+
+/// Parse a GeoPackage `DATETIME` value (`YYYY-MM-DDTHH:MM:SS[.SSS][Z]`) into
+/// microseconds since the Unix epoch.
+pub(crate) fn parse_datetime(value: &str) -> Option<i64> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 19 {
+        return None;
+    }
+    let days = parse_date_bytes(&bytes[0..10])? as i64;
+
+    let rest = bytes[10..].strip_suffix(b"Z").unwrap_or(&bytes[10..]);
+    if rest.len() < 9 || !matches!(rest[0], b'T' | b' ') || rest[3] != b':' || rest[6] != b':' {
+        return None;
+    }
+    let hour = parse_digits(&rest[1..3])?;
+    let minute = parse_digits(&rest[4..6])?;
+    let second = parse_digits(&rest[7..9])?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Fractional seconds may have any precision; anything below microseconds is truncated.
+    let micros = match &rest[9..] {
+        [] => 0,
+        fraction => {
+            let digits = fraction.strip_prefix(b".")?;
+            if !digits.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            let digits = &digits[..digits.len().min(6)];
+            parse_digits(digits)? * 10_i64.pow(6 - digits.len() as u32)
+        }
+    };
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(seconds * 1_000_000 + micros)
+}
+
+fn parse_digits(bytes: &[u8]) -> Option<i64> {
+    if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0, |acc, b| acc * 10 + i64::from(b - b'0')),
+    )
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Howard Hinnant's civil_from_days algorithm, the inverse of days_from_civil.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Howard Hinnant's days_from_civil algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod tests {
+    use super::{format_date, format_datetime, parse_date, parse_datetime};
+
+    #[test]
+    fn parses_iso8601_dates() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-01-15"), Some(19_737));
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-1-15"), None);
+        assert_eq!(parse_date("not a date"), None);
+    }
+
+    #[test]
+    fn parses_iso8601_datetimes() {
+        assert_eq!(parse_datetime("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_datetime("2024-01-15T10:30:00.000Z"),
+            Some(1_705_314_600_000_000)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15T10:30:00"),
+            Some(1_705_314_600_000_000)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15T10:30:00.5Z"),
+            Some(1_705_314_600_500_000)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15 10:30:00.1234567"),
+            Some(1_705_314_600_123_456)
+        );
+        assert_eq!(parse_datetime("2024-01-15T25:00:00Z"), None);
+        assert_eq!(parse_datetime("2024-01-15T10:30:00.Z"), None);
+        assert_eq!(parse_datetime("2024-01-15"), None);
+        // Non-ASCII input must be rejected rather than panic on a char boundary.
+        assert_eq!(parse_datetime("2024-01-15T10:30:0é"), None);
+        assert_eq!(parse_datetime("2024-01-15T10:30:00.5é"), None);
+        assert_eq!(parse_datetime("2024-01-1éT10:30:00Z"), None);
+        assert_eq!(parse_date("2024-0é-15"), None);
+    }
+
+    #[test]
+    fn formats_iso8601_dates_and_datetimes() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(19_782), "2024-02-29");
+        assert_eq!(format_date(-1), "1969-12-31");
+        assert_eq!(format_datetime(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_datetime(1_705_314_600_123_456),
+            "2024-01-15T10:30:00.123Z"
+        );
+        assert_eq!(format_datetime(-1_000), "1969-12-31T23:59:59.999Z");
+
+        for text in ["2000-03-01T00:00:00.000Z", "2024-12-31T23:59:59.999Z"] {
+            assert_eq!(format_datetime(parse_datetime(text).unwrap()), text);
+        }
+    }
+}
@@ -2,10 +2,18 @@ use std::sync::Arc;
 
 use arrow_array::{ArrayRef, RecordBatchReader};
 use arrow_schema::{FieldRef, SchemaRef};
-use geoarrow_array::{GeoArrowArray, builder::WkbBuilder};
+use geoarrow_array::GeoArrowArray;
+use geoarrow_array::builder::{
+    LineStringBuilder, MultiLineStringBuilder, MultiPointBuilder, MultiPolygonBuilder,
+    PointBuilder, PolygonBuilder, WkbBuilder,
+};
+use geoarrow_schema::{
+    Dimension, GeoArrowType, LineStringType, Metadata, MultiLineStringType, MultiPointType,
+    MultiPolygonType, PointType, PolygonType, WkbType,
+};
 
 use crate::{
-    ColumnSpec, Gpkg, GpkgError, GpkgLayer, gpkg::gpkg_geometry_to_wkb_bytes,
+    ColumnSpec, Gpkg, GpkgError, GpkgLayer, ZmFlag, gpkg::gpkg_geometry_to_wkb_bytes,
     ogc_sql::sql_select_features,
 };
 
@@ -14,13 +22,48 @@ use crate::{
 /// `ArrowGpkgReader` is the primary entry point for Arrow users. Construct it with
 /// [`ArrowGpkgReader::new`] and then iterate to receive batches of features.
 /// The reader holds a prepared `rusqlite::Statement`, so it borrows the `Gpkg`
-/// that created it and must not outlive that `Gpkg`.
+/// that created it and must not outlive that `Gpkg`. Use
+/// [`ArrowGpkgReader::new_owned`] for a reader that owns the connection
+/// instead.
+///
+/// Like [`GpkgFeatureBatchIterator`](crate::GpkgFeatureBatchIterator), batches
+/// are paged by primary key, so modifying the layer between batches neither
+/// skips nor repeats rows.
 pub struct ArrowGpkgReader<'a> {
-    stmt: rusqlite::Statement<'a>,
+    source: ReaderSource<'a>,
+}
+
+enum ReaderSource<'a> {
+    Borrowed {
+        stmt: rusqlite::Statement<'a>,
+        state: BatchState,
+    },
+    Owned(OwnedReader),
+}
+
+/// A reader that owns its connection and prepares the (cached) statement for
+/// every batch. Unlike `rusqlite::Statement`, this is `Send`.
+struct OwnedReader {
+    conn: rusqlite::Connection,
+    sql: String,
+    state: BatchState,
+}
+
+/// The part of the reader state that doesn't depend on the statement.
+struct BatchState {
+    layer_name: String,
     property_columns: Vec<ColumnSpec>,
+    /// Whether the primary key is emitted as the first column.
+    include_primary_key: bool,
+    geometry_type: GeoArrowType,
+    /// Whether an invalid `DATE` / `DATETIME` value is an error instead of a null.
+    strict_temporal: bool,
+    temporal_parse_failures: u64,
     srs_id: u32,
     batch_size: usize,
-    offset: u32,
+    /// Number of rows left to read when a limit is set.
+    remaining: Option<u64>,
+    last_id: i64,
     end_or_invalid_state: bool,
     schema_ref: SchemaRef,
 }
@@ -34,37 +77,385 @@ impl<'a> ArrowGpkgReader<'a> {
     ///   connection, so the `Gpkg` must live outside this struct to define the
     ///   statement's lifetime.
     pub fn new(gpkg: &'a Gpkg, layer_name: &str, batch_size: u32) -> crate::error::Result<Self> {
+        Self::builder(gpkg, layer_name)
+            .batch_size(batch_size)
+            .build()
+    }
+
+    /// Create a builder to configure the columns, the number of rows and the
+    /// batch size of the reader.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgReader, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let reader = ArrowGpkgReader::builder(&gpkg, "points")
+    ///     .columns(&["name", "value"])
+    ///     .limit(10_000)
+    ///     .batch_size(2048)
+    ///     .build()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn builder(gpkg: &'a Gpkg, layer_name: &str) -> ArrowGpkgReaderBuilder<'a> {
+        ArrowGpkgReaderBuilder {
+            gpkg,
+            layer_name: layer_name.to_string(),
+            options: ReaderOptions::default(),
+        }
+    }
+
+    /// Create a new Arrow reader that takes ownership of `gpkg`, so it is not
+    /// tied to the lifetime of a borrowed `Gpkg`.
+    ///
+    /// This fails with `GpkgError::ConnectionInUse` if any layer or attribute
+    /// table obtained from `gpkg` is still alive, and with
+    /// `GpkgError::DumpInUse` if `gpkg` was opened by
+    /// `Gpkg::from_bytes_read_only()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgReader, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let reader = ArrowGpkgReader::new_owned(gpkg, "points", 1024)?;
+    /// for batch in reader {
+    ///     println!("rows = {}", batch?.num_rows());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_owned(
+        gpkg: Gpkg,
+        layer_name: &str,
+        batch_size: u32,
+    ) -> crate::error::Result<ArrowGpkgReader<'static>> {
         let layer = gpkg.get_layer(layer_name)?;
-        let columns = layer.property_columns.iter().map(|spec| spec.name.as_str());
-        let sql = sql_select_features(
-            &layer.layer_name,
-            &layer.geometry_column,
-            &layer.primary_key_column,
-            columns,
-            Some(batch_size),
-        );
+        let options = ReaderOptions {
+            batch_size,
+            ..Default::default()
+        };
+        let (sql, state) = select_sql_and_state(&layer, &options)?;
+        drop(layer);
 
-        let stmt = gpkg.conn.prepare(&sql)?;
-        Ok(Self::new_inner(stmt, &layer, batch_size))
+        Ok(ArrowGpkgReader {
+            source: ReaderSource::Owned(OwnedReader {
+                conn: gpkg.into_connection()?,
+                sql,
+                state,
+            }),
+        })
     }
 
-    pub(crate) fn new_inner(
-        stmt: rusqlite::Statement<'a>,
-        layer: &GpkgLayer,
+    /// Open the GeoPackage at `path` read-only and create an Arrow reader that
+    /// owns it, e.g. to return the reader from a function.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::ArrowGpkgReader;
+    ///
+    /// fn open_points() -> rusqlite_gpkg::Result<ArrowGpkgReader<'static>> {
+    ///     ArrowGpkgReader::open("data/example.gpkg", "points", 1024)
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open<P: AsRef<std::path::Path>>(
+        path: P,
+        layer_name: &str,
         batch_size: u32,
+    ) -> crate::error::Result<ArrowGpkgReader<'static>> {
+        Self::new_owned(Gpkg::open_read_only(path)?, layer_name, batch_size)
+    }
+
+    /// Export the reader as an Arrow C stream, e.g. to import it as a
+    /// `pyarrow.RecordBatchReader`.
+    ///
+    /// Only a reader created with [`ArrowGpkgReader::new_owned`] can be
+    /// exported, as the stream must own the connection.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgReader, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let reader = ArrowGpkgReader::new_owned(gpkg, "points", 1024)?;
+    /// let _stream = reader.into_ffi_stream()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "ffi")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+    pub fn into_ffi_stream(
+        self,
+    ) -> crate::error::Result<arrow_array::ffi_stream::FFI_ArrowArrayStream> {
+        match self.source {
+            ReaderSource::Owned(reader) => Ok(
+                arrow_array::ffi_stream::FFI_ArrowArrayStream::new(Box::new(reader)),
+            ),
+            ReaderSource::Borrowed { .. } => Err(GpkgError::Arrow(
+                arrow_schema::ArrowError::InvalidArgumentError(
+                    "only a reader created with ArrowGpkgReader::new_owned() can be exported as a stream"
+                        .to_string(),
+                ),
+            )),
+        }
+    }
+
+    /// Arrow schema of the record batches: the property columns followed by
+    /// the WKB geometry column.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgReader, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let reader = ArrowGpkgReader::new(&gpkg, "points", 1024)?;
+    /// println!("{:?}", reader.schema().fields());
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn schema(&self) -> SchemaRef {
+        match &self.source {
+            ReaderSource::Borrowed { state, .. } => state.schema_ref.clone(),
+            ReaderSource::Owned(reader) => reader.state.schema_ref.clone(),
+        }
+    }
+
+    /// Number of `DATE` / `DATETIME` values read so far that were not valid
+    /// ISO 8601 text and were returned as nulls.
+    ///
+    /// This is always 0 if the reader was built with
+    /// [`strict_temporal(true)`][ArrowGpkgReaderBuilder::strict_temporal].
+    pub fn temporal_parse_failures(&self) -> u64 {
+        match &self.source {
+            ReaderSource::Borrowed { state, .. } => state.temporal_parse_failures,
+            ReaderSource::Owned(reader) => reader.state.temporal_parse_failures,
+        }
+    }
+}
+
+/// Encoding of the geometry column in the record batches produced by
+/// [`ArrowGpkgReader`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GeometryEncoding {
+    /// WKB (`geoarrow.wkb`), which can hold any geometry type.
+    #[default]
+    Wkb,
+    /// The native GeoArrow array matching the layer's geometry type and
+    /// dimension, e.g. `geoarrow.point` for a `POINT` layer.
+    Native,
+}
+
+/// Builder for [`ArrowGpkgReader`], created by [`ArrowGpkgReader::builder`].
+pub struct ArrowGpkgReaderBuilder<'a> {
+    gpkg: &'a Gpkg,
+    layer_name: String,
+    options: ReaderOptions,
+}
+
+struct ReaderOptions {
+    columns: Option<Vec<String>>,
+    limit: Option<u64>,
+    batch_size: u32,
+    include_primary_key: bool,
+    geometry_encoding: GeometryEncoding,
+    wkb_fallback: bool,
+    strict_temporal: bool,
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            limit: None,
+            batch_size: 1024,
+            include_primary_key: false,
+            geometry_encoding: GeometryEncoding::Wkb,
+            wkb_fallback: true,
+            strict_temporal: false,
+        }
+    }
+}
+
+impl<'a> ArrowGpkgReaderBuilder<'a> {
+    /// Read only these property columns, in this order. The geometry column
+    /// is always read. By default, all the property columns are read.
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.options.columns = Some(columns.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Read at most `limit` rows in total.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Number of rows per record batch. Defaults to 1024.
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.options.batch_size = batch_size;
+        self
+    }
+
+    /// Whether to emit the feature id as the first column, a non-null `Int64`
+    /// field named after the primary key column. Defaults to `false`.
+    pub fn include_primary_key(mut self, include_primary_key: bool) -> Self {
+        self.options.include_primary_key = include_primary_key;
+        self
+    }
+
+    /// Encoding of the geometry column. Defaults to `GeometryEncoding::Wkb`.
+    pub fn geometry_encoding(mut self, geometry_encoding: GeometryEncoding) -> Self {
+        self.options.geometry_encoding = geometry_encoding;
+        self
+    }
+
+    /// Whether `GeometryEncoding::Native` falls back to WKB for a layer
+    /// declared as `GEOMETRY` or `GEOMETRYCOLLECTION`, or one whose Z or M
+    /// values are optional, which has no native GeoArrow array here. If
+    /// `false`, `build()` fails for such layers.
+    /// Defaults to `true`.
+    pub fn wkb_fallback(mut self, wkb_fallback: bool) -> Self {
+        self.options.wkb_fallback = wkb_fallback;
+        self
+    }
+
+    /// Whether a `DATE` / `DATETIME` value that is not valid ISO 8601 text fails
+    /// the batch with `GpkgError::InvalidArrowTemporalValue`. By default, such
+    /// values are read as nulls and counted by
+    /// [`ArrowGpkgReader::temporal_parse_failures`].
+    pub fn strict_temporal(mut self, strict_temporal: bool) -> Self {
+        self.options.strict_temporal = strict_temporal;
+        self
+    }
+
+    /// Create the reader.
+    ///
+    /// Fails with `GpkgError::MissingProperty` if a column passed to
+    /// `columns()` is not a property column of the layer.
+    pub fn build(self) -> crate::error::Result<ArrowGpkgReader<'a>> {
+        let layer = self.gpkg.get_layer(&self.layer_name)?;
+        let (sql, state) = select_sql_and_state(&layer, &self.options)?;
+        let stmt = self.gpkg.conn.prepare(&sql)?;
+        Ok(ArrowGpkgReader {
+            source: ReaderSource::Borrowed { stmt, state },
+        })
+    }
+}
+
+// Build the SELECT statement and the initial state of a reader.
+fn select_sql_and_state(
+    layer: &GpkgLayer,
+    options: &ReaderOptions,
+) -> crate::error::Result<(String, BatchState)> {
+    let property_columns = match &options.columns {
+        Some(columns) => columns
+            .iter()
+            .map(|name| {
+                layer
+                    .property_columns
+                    .iter()
+                    .find(|spec| &spec.name == name)
+                    .cloned()
+                    .ok_or_else(|| GpkgError::MissingProperty {
+                        property: name.clone(),
+                    })
+            })
+            .collect::<crate::error::Result<Vec<_>>>()?,
+        None => layer.property_columns.clone(),
+    };
+
+    let sql = sql_select_features(
+        &layer.layer_name,
+        &layer.geometry_column,
+        &layer.primary_key_column,
+        property_columns.iter().map(|spec| spec.name.as_str()),
+        None,
+        Some(options.batch_size),
+    );
+    let geometry_type = geometry_arrow_type(layer, options)?;
+    let state = BatchState::new(layer, property_columns, geometry_type, options);
+    Ok((sql, state))
+}
+
+// The GeoArrow type of the geometry column, with the CRS of the layer.
+fn geometry_arrow_type(
+    layer: &GpkgLayer,
+    options: &ReaderOptions,
+) -> crate::error::Result<GeoArrowType> {
+    use wkb::reader::GeometryType;
+
+    let metadata: Arc<Metadata> = Metadata::new(super::crs_from_srs_id(layer.srs_id), None).into();
+    if options.geometry_encoding == GeometryEncoding::Wkb {
+        return Ok(GeoArrowType::Wkb(WkbType::new(metadata)));
+    }
+
+    // A native array has a single dimension, but the geometries of a layer
+    // with optional Z or M values may differ in dimension.
+    if layer.z_flag == ZmFlag::Optional || layer.m_flag == ZmFlag::Optional {
+        if options.wkb_fallback {
+            return Ok(GeoArrowType::Wkb(WkbType::new(metadata)));
+        }
+        return Err(GpkgError::GeoArrow(format!(
+            "no native GeoArrow array for layer '{}' with optional Z or M values",
+            layer.layer_name
+        )));
+    }
+
+    let dim = match layer.geometry_dimension {
+        wkb::reader::Dimension::Xy => Dimension::XY,
+        wkb::reader::Dimension::Xyz => Dimension::XYZ,
+        wkb::reader::Dimension::Xym => Dimension::XYM,
+        wkb::reader::Dimension::Xyzm => Dimension::XYZM,
+    };
+    let geometry_type = match layer.geometry_type {
+        GeometryType::Point => GeoArrowType::Point(PointType::new(dim, metadata)),
+        GeometryType::LineString => GeoArrowType::LineString(LineStringType::new(dim, metadata)),
+        GeometryType::Polygon => GeoArrowType::Polygon(PolygonType::new(dim, metadata)),
+        GeometryType::MultiPoint => GeoArrowType::MultiPoint(MultiPointType::new(dim, metadata)),
+        GeometryType::MultiLineString => {
+            GeoArrowType::MultiLineString(MultiLineStringType::new(dim, metadata))
+        }
+        GeometryType::MultiPolygon => {
+            GeoArrowType::MultiPolygon(MultiPolygonType::new(dim, metadata))
+        }
+        _ if options.wkb_fallback => GeoArrowType::Wkb(WkbType::new(metadata)),
+        other => {
+            return Err(GpkgError::GeoArrow(format!(
+                "no native GeoArrow array for {other:?} layer '{}'",
+                layer.layer_name
+            )));
+        }
+    };
+    Ok(geometry_type)
+}
+
+impl BatchState {
+    fn new(
+        layer: &GpkgLayer,
+        property_columns: Vec<ColumnSpec>,
+        geometry_type: GeoArrowType,
+        options: &ReaderOptions,
     ) -> Self {
+        let primary_key_column = options
+            .include_primary_key
+            .then_some(layer.primary_key_column.as_str());
         let schema_ref = Self::construct_arrow_schema(
-            &layer.property_columns,
+            &property_columns,
             &layer.geometry_column,
+            &geometry_type,
+            primary_key_column,
             layer.srs_id,
         );
 
         Self {
-            stmt,
-            batch_size: batch_size as usize,
-            property_columns: layer.property_columns.clone(),
+            layer_name: layer.layer_name.clone(),
+            batch_size: options.batch_size as usize,
+            property_columns,
+            include_primary_key: options.include_primary_key,
+            geometry_type,
+            strict_temporal: options.strict_temporal,
+            temporal_parse_failures: 0,
             srs_id: layer.srs_id,
-            offset: 0,
+            remaining: options.limit,
+            last_id: i64::MIN,
             end_or_invalid_state: false,
             schema_ref,
         }
@@ -73,38 +464,62 @@ impl<'a> ArrowGpkgReader<'a> {
     fn construct_arrow_schema(
         property_columns: &[ColumnSpec],
         geometry_column: &str,
+        geometry_type: &GeoArrowType,
+        primary_key_column: Option<&str>,
         srs_id: u32,
     ) -> SchemaRef {
-        let mut fields: Vec<FieldRef> = property_columns
-            .iter()
-            .map(|col| {
-                let field = match col.column_type {
-                    crate::ColumnType::Boolean => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Boolean, true)
-                    }
-                    crate::ColumnType::Varchar => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Utf8, true)
-                    }
-                    crate::ColumnType::Double => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Float64, true)
-                    }
-                    crate::ColumnType::Integer => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int64, true)
-                    }
-                    crate::ColumnType::Date | crate::ColumnType::Datetime => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Utf8, true)
-                    }
-                    crate::ColumnType::Blob => {
-                        arrow_schema::Field::new(&col.name, arrow_schema::DataType::Binary, true)
-                    }
-                    crate::ColumnType::Geometry => super::wkb_geometry_field(&col.name, srs_id),
-                };
-
-                Arc::new(field)
+        let mut fields: Vec<FieldRef> = primary_key_column
+            .map(|name| {
+                Arc::new(arrow_schema::Field::new(
+                    name,
+                    arrow_schema::DataType::Int64,
+                    false,
+                ))
             })
+            .into_iter()
             .collect();
+        fields.extend(property_columns.iter().map(|col| {
+            let field = match col.column_type {
+                crate::ColumnType::Boolean => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Boolean, true)
+                }
+                crate::ColumnType::Varchar => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Utf8, true)
+                }
+                crate::ColumnType::Double => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Float64, true)
+                }
+                crate::ColumnType::Integer => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int64, true)
+                }
+                crate::ColumnType::TinyInt => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int8, true)
+                }
+                crate::ColumnType::SmallInt => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int16, true)
+                }
+                crate::ColumnType::MediumInt => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Int32, true)
+                }
+                crate::ColumnType::Float => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Float32, true)
+                }
+                crate::ColumnType::Date => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Date32, true)
+                }
+                crate::ColumnType::Datetime => {
+                    arrow_schema::Field::new(&col.name, super::datetime_data_type(), true)
+                }
+                crate::ColumnType::Blob => {
+                    arrow_schema::Field::new(&col.name, arrow_schema::DataType::Binary, true)
+                }
+                crate::ColumnType::Geometry => super::wkb_geometry_field(&col.name, srs_id),
+            };
+
+            Arc::new(field)
+        }));
 
-        fields.push(Arc::new(super::wkb_geometry_field(geometry_column, srs_id)));
+        fields.push(Arc::new(geometry_type.to_field(geometry_column, true)));
 
         Arc::new(arrow_schema::Schema::new(fields))
     }
@@ -129,11 +544,23 @@ impl<'a> ArrowGpkgReader<'a> {
                 crate::ColumnType::Integer => GpkgArrayBuilder::Integer(
                     arrow_array::builder::Int64Builder::with_capacity(self.batch_size),
                 ),
-                crate::ColumnType::Date | crate::ColumnType::Datetime => {
-                    GpkgArrayBuilder::Varchar(arrow_array::builder::StringBuilder::with_capacity(
-                        self.batch_size,
-                        8 * self.batch_size,
-                    ))
+                crate::ColumnType::TinyInt => GpkgArrayBuilder::TinyInt(
+                    arrow_array::builder::Int8Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::SmallInt => GpkgArrayBuilder::SmallInt(
+                    arrow_array::builder::Int16Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::MediumInt => GpkgArrayBuilder::MediumInt(
+                    arrow_array::builder::Int32Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::Float => GpkgArrayBuilder::Float(
+                    arrow_array::builder::Float32Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::Date => GpkgArrayBuilder::Date(
+                    arrow_array::builder::Date32Builder::with_capacity(self.batch_size),
+                ),
+                crate::ColumnType::Datetime => {
+                    GpkgArrayBuilder::Datetime(super::datetime_builder(self.batch_size))
                 }
                 crate::ColumnType::Blob => {
                     GpkgArrayBuilder::Blob(arrow_array::builder::BinaryBuilder::with_capacity(
@@ -149,32 +576,54 @@ impl<'a> ArrowGpkgReader<'a> {
 
         GpkgRecordBatchBuilder {
             schema_ref: self.schema_ref.clone(),
+            id_builder: self
+                .include_primary_key
+                .then(|| arrow_array::builder::Int64Builder::with_capacity(self.batch_size)),
             builders,
-            geo_builder: super::wkb_geometry_builder(self.srs_id, self.batch_size),
+            strict_temporal: self.strict_temporal,
+            temporal_parse_failures: 0,
+            geo_builder: GeometryArrayBuilder::new(
+                &self.geometry_type,
+                self.srs_id,
+                self.batch_size,
+            ),
         }
     }
 
-    // This doesn't advance the offset.
-    fn get_record_batch(&mut self) -> crate::error::Result<arrow_array::RecordBatch> {
+    fn get_record_batch(
+        &mut self,
+        stmt: &mut rusqlite::Statement<'_>,
+    ) -> crate::error::Result<arrow_array::RecordBatch> {
         let mut builders = self.create_record_batch_builder();
-        let mut rows = self.stmt.query([self.offset])?;
+        let mut rows = stmt.query([self.last_id])?;
         while let Some(row) = rows.next()? {
-            builders.push(row)?;
+            if self.remaining == Some(0) {
+                break;
+            }
+            self.last_id = row.get(1)?;
+            builders.push(row).map_err(|err| GpkgError::Feature {
+                layer_name: self.layer_name.clone(),
+                id: Some(self.last_id),
+                source: Box::new(err),
+            })?;
+            if let Some(remaining) = &mut self.remaining {
+                *remaining -= 1;
+            }
         }
 
+        self.temporal_parse_failures += builders.temporal_parse_failures;
         builders.finish()
     }
-}
-
-impl<'a> Iterator for ArrowGpkgReader<'a> {
-    type Item = Result<arrow_array::RecordBatch, arrow_schema::ArrowError>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.end_or_invalid_state {
+    fn next_batch(
+        &mut self,
+        stmt: crate::error::Result<&mut rusqlite::Statement<'_>>,
+    ) -> Option<Result<arrow_array::RecordBatch, arrow_schema::ArrowError>> {
+        if self.end_or_invalid_state || self.remaining == Some(0) {
             return None;
         }
 
-        let result = self.get_record_batch();
+        let result = stmt.and_then(|stmt| self.get_record_batch(stmt));
 
         let features = match result {
             Ok(features) => features,
@@ -187,22 +636,48 @@ impl<'a> Iterator for ArrowGpkgReader<'a> {
 
         // If the result is less than the batch size, it means it reached the end.
         let result_size = features.num_rows();
-        if result_size < self.batch_size {
+        if result_size < self.batch_size || self.remaining == Some(0) {
             self.end_or_invalid_state = true;
             if result_size == 0 {
                 return None;
             }
         }
 
-        self.offset += result_size as u32;
-
         Some(Ok(features))
     }
 }
 
+impl<'a> Iterator for ArrowGpkgReader<'a> {
+    type Item = Result<arrow_array::RecordBatch, arrow_schema::ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            ReaderSource::Borrowed { stmt, state } => state.next_batch(Ok(stmt)),
+            ReaderSource::Owned(reader) => reader.next(),
+        }
+    }
+}
+
 impl<'a> RecordBatchReader for ArrowGpkgReader<'a> {
     fn schema(&self) -> SchemaRef {
-        self.schema_ref.clone()
+        ArrowGpkgReader::schema(self)
+    }
+}
+
+impl Iterator for OwnedReader {
+    type Item = Result<arrow_array::RecordBatch, arrow_schema::ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.conn.prepare_cached(&self.sql) {
+            Ok(mut stmt) => self.state.next_batch(Ok(&mut stmt)),
+            Err(e) => self.state.next_batch(Err(e.into())),
+        }
+    }
+}
+
+impl RecordBatchReader for OwnedReader {
+    fn schema(&self) -> SchemaRef {
+        self.state.schema_ref.clone()
     }
 }
 
@@ -211,6 +686,12 @@ enum GpkgArrayBuilder {
     Varchar(arrow_array::builder::StringBuilder),
     Double(arrow_array::builder::Float64Builder),
     Integer(arrow_array::builder::Int64Builder),
+    TinyInt(arrow_array::builder::Int8Builder),
+    SmallInt(arrow_array::builder::Int16Builder),
+    MediumInt(arrow_array::builder::Int32Builder),
+    Float(arrow_array::builder::Float32Builder),
+    Date(arrow_array::builder::Date32Builder),
+    Datetime(arrow_array::builder::TimestampMicrosecondBuilder),
     Blob(arrow_array::builder::BinaryBuilder),
     // Note: Since WkbBuilder doesn't implement ArrayBuilder trait, we cannot use Box<dyn ArrayBuilder> to unify this
     Geometry(WkbBuilder<i32>),
@@ -221,7 +702,11 @@ impl GpkgArrayBuilder {
         match self {
             GpkgArrayBuilder::Boolean(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
-                rusqlite::types::Value::Integer(i) => builder.append_value(i == 1),
+                rusqlite::types::Value::Integer(0) => builder.append_value(false),
+                rusqlite::types::Value::Integer(1) => builder.append_value(true),
+                rusqlite::types::Value::Integer(_) => {
+                    return Err(GpkgError::ValueOutOfRange { target: "bool" });
+                }
                 other => {
                     return Err(GpkgError::InvalidArrowValue {
                         expected: "INTEGER or NULL",
@@ -242,9 +727,10 @@ impl GpkgArrayBuilder {
             GpkgArrayBuilder::Double(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
                 rusqlite::types::Value::Real(f) => builder.append_value(f),
+                rusqlite::types::Value::Integer(i) => builder.append_value(i as f64),
                 other => {
                     return Err(GpkgError::InvalidArrowValue {
-                        expected: "REAL or NULL",
+                        expected: "REAL, INTEGER or NULL",
                         actual: rusqlite_value_type_name(&other),
                     });
                 }
@@ -259,6 +745,89 @@ impl GpkgArrayBuilder {
                     });
                 }
             },
+            GpkgArrayBuilder::TinyInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i8::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i8" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            GpkgArrayBuilder::SmallInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i16::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i16" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            GpkgArrayBuilder::MediumInt(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Integer(i) => builder.append_value(
+                    i32::try_from(i).map_err(|_| GpkgError::ValueOutOfRange { target: "i32" })?,
+                ),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            GpkgArrayBuilder::Float(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Real(f) => builder.append_value(f as f32),
+                rusqlite::types::Value::Integer(i) => builder.append_value(i as f32),
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "REAL, INTEGER or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            GpkgArrayBuilder::Date(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Text(t) => {
+                    let days = super::parse_date(&t).ok_or_else(|| {
+                        GpkgError::InvalidArrowTemporalValue {
+                            expected: "ISO 8601 date (YYYY-MM-DD)",
+                            value: t.clone(),
+                        }
+                    })?;
+                    builder.append_value(days);
+                }
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "TEXT or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
+            GpkgArrayBuilder::Datetime(builder) => match value {
+                rusqlite::types::Value::Null => builder.append_null(),
+                rusqlite::types::Value::Text(t) => {
+                    let micros = super::parse_datetime(&t).ok_or_else(|| {
+                        GpkgError::InvalidArrowTemporalValue {
+                            expected: "ISO 8601 date-time (YYYY-MM-DDTHH:MM:SS.SSSZ)",
+                            value: t.clone(),
+                        }
+                    })?;
+                    builder.append_value(micros);
+                }
+                other => {
+                    return Err(GpkgError::InvalidArrowValue {
+                        expected: "TEXT or NULL",
+                        actual: rusqlite_value_type_name(&other),
+                    });
+                }
+            },
             GpkgArrayBuilder::Blob(builder) => match value {
                 rusqlite::types::Value::Null => builder.append_null(),
                 rusqlite::types::Value::Blob(b) => builder.append_value(b),
@@ -294,18 +863,31 @@ impl GpkgArrayBuilder {
 
 struct GpkgRecordBatchBuilder {
     pub(crate) schema_ref: SchemaRef,
+    pub(crate) id_builder: Option<arrow_array::builder::Int64Builder>,
     pub(crate) builders: Vec<GpkgArrayBuilder>,
-    pub(crate) geo_builder: WkbBuilder<i32>,
+    pub(crate) geo_builder: GeometryArrayBuilder,
+    pub(crate) strict_temporal: bool,
+    pub(crate) temporal_parse_failures: u64,
 }
 
 impl GpkgRecordBatchBuilder {
     pub(crate) fn push(&mut self, row: &rusqlite::Row<'_>) -> crate::error::Result<()> {
+        if let Some(id_builder) = &mut self.id_builder {
+            id_builder.append_value(row.get(1)?);
+        }
+
         let n = self.builders.len();
         for i in 0..n {
             let column_index = i + 2;
             match row.get::<usize, rusqlite::types::Value>(column_index) {
-                Ok(v) => self.builders[i].push(v)?,
-                Err(e) => return Err(GpkgError::Sql(e)),
+                Ok(v) => match self.builders[i].push(v) {
+                    Err(GpkgError::InvalidArrowTemporalValue { .. }) if !self.strict_temporal => {
+                        self.builders[i].push(rusqlite::types::Value::Null)?;
+                        self.temporal_parse_failures += 1;
+                    }
+                    result => result?,
+                },
+                Err(e) => return Err(super::row_value_error(row, column_index, 1, e)),
             }
         }
 
@@ -313,12 +895,12 @@ impl GpkgRecordBatchBuilder {
             Ok(rusqlite::types::Value::Blob(b)) => {
                 let wkb_bytes = gpkg_geometry_to_wkb_bytes(&b)?;
                 self.geo_builder
-                    .push_wkb(Some(wkb_bytes))
+                    .push(Some(wkb_bytes))
                     .map_err(|e| GpkgError::GeoArrow(format!("{e:?}")))?;
             }
             Ok(rusqlite::types::Value::Null) => {
                 self.geo_builder
-                    .push_wkb(None)
+                    .push(None)
                     .map_err(|e| GpkgError::GeoArrow(format!("{e:?}")))?;
             }
             Ok(other) => {
@@ -350,18 +932,106 @@ impl GpkgRecordBatchBuilder {
                 GpkgArrayBuilder::Integer(mut builder) => {
                     arrow_array::builder::ArrayBuilder::finish(&mut builder)
                 }
+                GpkgArrayBuilder::TinyInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                GpkgArrayBuilder::SmallInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                GpkgArrayBuilder::MediumInt(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                GpkgArrayBuilder::Float(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                GpkgArrayBuilder::Date(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
+                GpkgArrayBuilder::Datetime(mut builder) => {
+                    arrow_array::builder::ArrayBuilder::finish(&mut builder)
+                }
                 GpkgArrayBuilder::Blob(mut builder) => {
                     arrow_array::builder::ArrayBuilder::finish(&mut builder)
                 }
                 GpkgArrayBuilder::Geometry(builder) => builder.finish().into_array_ref(),
             })
             .collect();
-        columns.push(self.geo_builder.finish().into_array_ref());
+        columns.push(self.geo_builder.finish());
+        if let Some(mut id_builder) = self.id_builder {
+            columns.insert(
+                0,
+                arrow_array::builder::ArrayBuilder::finish(&mut id_builder),
+            );
+        }
 
         Ok(arrow_array::RecordBatch::try_new(self.schema_ref, columns)?)
     }
 }
 
+// Builder of the geometry column, in WKB or one of the native GeoArrow arrays.
+enum GeometryArrayBuilder {
+    Wkb(WkbBuilder<i32>),
+    Point(PointBuilder),
+    LineString(LineStringBuilder),
+    Polygon(PolygonBuilder),
+    MultiPoint(MultiPointBuilder),
+    MultiLineString(MultiLineStringBuilder),
+    MultiPolygon(MultiPolygonBuilder),
+}
+
+impl GeometryArrayBuilder {
+    fn new(geometry_type: &GeoArrowType, srs_id: u32, batch_size: usize) -> Self {
+        match geometry_type {
+            GeoArrowType::Point(t) => Self::Point(PointBuilder::new(t.clone())),
+            GeoArrowType::LineString(t) => Self::LineString(LineStringBuilder::new(t.clone())),
+            GeoArrowType::Polygon(t) => Self::Polygon(PolygonBuilder::new(t.clone())),
+            GeoArrowType::MultiPoint(t) => Self::MultiPoint(MultiPointBuilder::new(t.clone())),
+            GeoArrowType::MultiLineString(t) => {
+                Self::MultiLineString(MultiLineStringBuilder::new(t.clone()))
+            }
+            GeoArrowType::MultiPolygon(t) => {
+                Self::MultiPolygon(MultiPolygonBuilder::new(t.clone()))
+            }
+            _ => Self::Wkb(super::wkb_geometry_builder(srs_id, batch_size)),
+        }
+    }
+
+    fn push(&mut self, wkb_bytes: Option<&[u8]>) -> geoarrow_schema::error::GeoArrowResult<()> {
+        if let Self::Wkb(builder) = self {
+            return builder.push_wkb(wkb_bytes);
+        }
+        let wkb = match wkb_bytes {
+            Some(bytes) => Some(
+                wkb::reader::Wkb::try_new(bytes)
+                    .map_err(|e| geoarrow_schema::error::GeoArrowError::Wkb(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let wkb = wkb.as_ref();
+        match self {
+            Self::Wkb(_) => unreachable!(),
+            Self::Point(builder) => builder.push_geometry(wkb),
+            Self::LineString(builder) => builder.push_geometry(wkb),
+            Self::Polygon(builder) => builder.push_geometry(wkb),
+            Self::MultiPoint(builder) => builder.push_geometry(wkb),
+            Self::MultiLineString(builder) => builder.push_geometry(wkb),
+            Self::MultiPolygon(builder) => builder.push_geometry(wkb),
+        }
+    }
+
+    fn finish(self) -> ArrayRef {
+        match self {
+            Self::Wkb(builder) => builder.finish().into_array_ref(),
+            Self::Point(builder) => builder.finish().into_array_ref(),
+            Self::LineString(builder) => builder.finish().into_array_ref(),
+            Self::Polygon(builder) => builder.finish().into_array_ref(),
+            Self::MultiPoint(builder) => builder.finish().into_array_ref(),
+            Self::MultiLineString(builder) => builder.finish().into_array_ref(),
+            Self::MultiPolygon(builder) => builder.finish().into_array_ref(),
+        }
+    }
+}
+
 fn rusqlite_value_type_name(value: &rusqlite::types::Value) -> &'static str {
     match value {
         rusqlite::types::Value::Null => "NULL",
@@ -374,16 +1044,20 @@ fn rusqlite_value_type_name(value: &rusqlite::types::Value) -> &'static str {
 
 #[cfg(all(test, feature = "arrow"))]
 mod tests {
-    use super::ArrowGpkgReader;
+    use super::{ArrowGpkgReader, GeometryEncoding};
     use crate::Result;
+    use crate::error::GpkgError;
     use crate::gpkg::Gpkg;
     use crate::params;
-    use crate::types::{ColumnSpec, ColumnType};
-    use arrow_array::{BooleanArray, Float64Array, Int64Array, StringArray};
-    use arrow_schema::DataType;
+    use crate::types::{ColumnSpec, ColumnType, CreateLayerOptions, Value};
+    use arrow_array::{
+        Array, BinaryArray, BooleanArray, Date32Array, Float32Array, Float64Array, Int16Array,
+        Int64Array, RecordBatchReader, StringArray, TimestampMicrosecondArray,
+    };
+    use arrow_schema::{DataType, TimeUnit};
     use geo_types::Point;
-    use geoarrow_array::GeoArrowArrayAccessor;
     use geoarrow_array::array::WkbArray;
+    use geoarrow_array::{GeoArrowArray, GeoArrowArrayAccessor};
     use wkb::reader::GeometryType;
 
     fn create_test_layer(gpkg: &Gpkg) -> Result<crate::GpkgLayer> {
@@ -391,18 +1065,22 @@ mod tests {
             ColumnSpec {
                 name: "active".to_string(),
                 column_type: ColumnType::Boolean,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "score".to_string(),
                 column_type: ColumnType::Double,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "count".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -464,8 +1142,8 @@ mod tests {
             .downcast_ref::<Int64Array>()
             .expect("int array");
 
-        assert_eq!(active.value(0), true);
-        assert_eq!(active.value(1), false);
+        assert!(active.value(0));
+        assert!(!active.value(1));
         assert_eq!(name.value(0), "alpha");
         assert_eq!(name.value(1), "beta");
         assert_eq!(score.value(0), 1.25);
@@ -484,16 +1162,23 @@ mod tests {
     }
 
     #[test]
-    fn record_batch_reads_date_and_datetime_columns() -> Result<()> {
+    fn record_batch_reads_date_datetime_and_blob_columns() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
         let columns = vec![
             ColumnSpec {
                 name: "d".to_string(),
                 column_type: ColumnType::Date,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "dt".to_string(),
                 column_type: ColumnType::Datetime,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "attachment".to_string(),
+                column_type: ColumnType::Blob,
+                ..Default::default()
             },
         ];
 
@@ -508,7 +1193,15 @@ mod tests {
 
         layer.insert(
             Point::new(1.0, 2.0),
-            params!["2024-01-15", "2024-01-15T10:30:00.000Z"],
+            params![
+                "2024-01-15",
+                "2024-01-15T10:30:00.000Z",
+                Value::Blob(vec![0, 1, 2, 255])
+            ],
+        )?;
+        layer.insert(
+            Point::new(3.0, 4.0),
+            params![Value::Null, Value::Null, Value::Null],
         )?;
 
         let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
@@ -517,22 +1210,167 @@ mod tests {
         let schema = batch.schema();
         let fields = schema.fields();
         assert_eq!(fields[0].name(), "d");
-        assert_eq!(fields[0].data_type(), &DataType::Utf8);
+        assert_eq!(fields[0].data_type(), &DataType::Date32);
         assert_eq!(fields[1].name(), "dt");
-        assert_eq!(fields[1].data_type(), &DataType::Utf8);
+        assert_eq!(
+            fields[1].data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert_eq!(fields[2].name(), "attachment");
+        assert_eq!(fields[2].data_type(), &DataType::Binary);
 
         let d = batch
             .column(0)
             .as_any()
-            .downcast_ref::<StringArray>()
-            .expect("string array");
+            .downcast_ref::<Date32Array>()
+            .expect("date32 array");
         let dt = batch
             .column(1)
             .as_any()
-            .downcast_ref::<StringArray>()
-            .expect("string array");
-        assert_eq!(d.value(0), "2024-01-15");
-        assert_eq!(dt.value(0), "2024-01-15T10:30:00.000Z");
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .expect("timestamp array");
+        let attachment = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .expect("binary array");
+        assert_eq!(d.value(0), 19_737);
+        assert_eq!(dt.value(0), 1_705_314_600_000_000);
+        assert_eq!(attachment.value(0), &[0_u8, 1, 2, 255]);
+        assert!(d.is_null(1));
+        assert!(dt.is_null(1));
+        assert!(attachment.is_null(1));
+
+        layer.insert(
+            Point::new(5.0, 6.0),
+            params!["15/01/2024", "2024-01-15T10:30:00", Value::Null],
+        )?;
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        let batch = iter.next().transpose()?.expect("first batch");
+        let d = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .expect("date32 array");
+        let dt = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .expect("timestamp array");
+        assert!(d.is_null(2));
+        assert_eq!(dt.value(2), 1_705_314_600_000_000);
+        assert_eq!(iter.temporal_parse_failures(), 1);
+
+        let mut iter = ArrowGpkgReader::builder(&gpkg, &layer.layer_name)
+            .strict_temporal(true)
+            .build()?;
+        assert!(iter.next().expect("error batch").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_batch_reports_mistyped_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        layer.insert(Point::new(1.0, 2.0), params![true, "alpha", 1.25, 7])?;
+
+        gpkg.conn
+            .execute("UPDATE arrow_points SET count = 'seven'", [])?;
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        let err = iter
+            .next()
+            .expect("error batch")
+            .expect_err("TEXT in INTEGER column");
+        assert!(
+            err.to_string().contains("expected INTEGER or NULL"),
+            "{err}"
+        );
+        assert!(
+            err.to_string()
+                .contains("failed to read feature 1 of layer 'arrow_points'"),
+            "{err}"
+        );
+        assert!(iter.next().is_none());
+
+        gpkg.conn
+            .execute("UPDATE arrow_points SET count = 7, active = 2", [])?;
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        assert!(iter.next().expect("error batch").is_err());
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_batch_reports_invalid_utf8_text() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        layer.insert(Point::new(1.0, 2.0), params![true, "alpha", 1.25, 7])?;
+        gpkg.conn
+            .execute("UPDATE arrow_points SET name = CAST(X'C328' AS TEXT)", [])?;
+
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        let err = iter
+            .next()
+            .expect("error batch")
+            .expect_err("invalid UTF-8");
+        assert!(
+            err.to_string()
+                .contains("column 'name' of the row with primary key 1 contains invalid UTF-8"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn record_batch_reads_narrow_numeric_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "small".to_string(),
+                column_type: ColumnType::SmallInt,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "ratio".to_string(),
+                column_type: ColumnType::Float,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "arrow_narrow",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![300_i64, 0.25])?;
+
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        let batch = iter.next().transpose()?.expect("first batch");
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int16);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float32);
+
+        let small = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int16Array>()
+            .expect("int16 array");
+        let ratio = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .expect("float32 array");
+        assert_eq!(small.value(0), 300);
+        assert_eq!(ratio.value(0), 0.25);
+
+        // Values that do not fit the declared width are reported instead of truncated.
+        layer.insert(Point::new(3.0, 4.0), params![70000_i64, 0.5])?;
+        let mut iter = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 10)?;
+        assert!(iter.next().expect("error batch").is_err());
 
         Ok(())
     }
@@ -543,6 +1381,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "rank".to_string(),
             column_type: ColumnType::Integer,
+            ..Default::default()
         }];
         let layer = gpkg.create_layer(
             "arrow_offsets",
@@ -578,4 +1417,300 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reader_can_be_used_as_record_batch_reader() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        for i in 0..5 {
+            layer.insert(Point::new(i as f64, 0.0), params![true, "alpha", 1.0, i])?;
+        }
+
+        let reader = ArrowGpkgReader::new(&gpkg, &layer.layer_name, 2)?;
+        let schema = reader.schema();
+        assert_eq!(schema.fields().len(), 5);
+
+        // Consume it the way APIs taking `Box<dyn RecordBatchReader>` do.
+        let reader: Box<dyn RecordBatchReader + '_> = Box::new(reader);
+        assert_eq!(reader.schema(), schema);
+        let mut rows = 0;
+        for batch in reader {
+            let batch = batch?;
+            assert_eq!(batch.schema(), schema);
+            rows += batch.num_rows();
+        }
+        assert_eq!(rows, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn owned_reader_matches_borrowed_reader() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        for i in 0..5 {
+            layer.insert(Point::new(i as f64, 0.0), params![true, "alpha", 1.0, i])?;
+        }
+        let expected = ArrowGpkgReader::new(&gpkg, "arrow_points", 2)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let bytes = gpkg.to_bytes()?;
+
+        // The layer still shares the connection.
+        let err = ArrowGpkgReader::new_owned(gpkg, "arrow_points", 2)
+            .err()
+            .expect("connection in use");
+        assert!(matches!(err, GpkgError::ConnectionInUse));
+
+        drop(layer);
+        let gpkg = Gpkg::from_bytes(bytes)?;
+        let reader = ArrowGpkgReader::new_owned(gpkg, "arrow_points", 2)?;
+        assert_eq!(reader.schema(), expected[0].schema());
+        let actual = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn open_returns_owned_reader() -> Result<()> {
+        fn open_points() -> Result<ArrowGpkgReader<'static>> {
+            ArrowGpkgReader::open("src/test/test_generated.gpkg", "points", 2)
+        }
+
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let expected = ArrowGpkgReader::new(&gpkg, "points", 2)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let actual = open_points()?.collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn exports_owned_reader_as_ffi_stream() -> Result<()> {
+        use arrow_array::ffi_stream::ArrowArrayStreamReader;
+
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let err = ArrowGpkgReader::new(&gpkg, "points", 2)?
+            .into_ffi_stream()
+            .expect_err("borrowed reader");
+        assert!(matches!(err, GpkgError::Arrow(_)));
+        let expected = ArrowGpkgReader::new(&gpkg, "points", 2)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let stream = ArrowGpkgReader::new_owned(gpkg, "points", 2)?.into_ffi_stream()?;
+        let reader = ArrowArrayStreamReader::try_new(stream)?;
+        assert_eq!(reader.schema(), expected[0].schema());
+        let actual = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_projects_columns_and_limits_rows() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        for i in 0..7 {
+            layer.insert(Point::new(i as f64, 0.0), params![true, "alpha", 1.0, i])?;
+        }
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "arrow_points")
+            .columns(&["count", "name"])
+            .limit(5)
+            .batch_size(2)
+            .build()?;
+        let schema = reader.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["count", "name", "geom"]);
+
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+        let sizes: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        let count = batches[2]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .expect("int array");
+        assert_eq!(count.value(0), 4);
+
+        // A limit that is a multiple of the batch size doesn't produce an
+        // empty trailing batch.
+        let reader = ArrowGpkgReader::builder(&gpkg, "arrow_points")
+            .limit(4)
+            .batch_size(2)
+            .build()?;
+        assert_eq!(reader.count(), 2);
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "arrow_points")
+            .limit(0)
+            .build()?;
+        assert_eq!(reader.count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_rejects_unknown_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        create_test_layer(&gpkg)?;
+        let err = ArrowGpkgReader::builder(&gpkg, "arrow_points")
+            .columns(&["name", "missing"])
+            .build()
+            .err()
+            .expect("unknown column");
+        assert!(matches!(
+            err,
+            GpkgError::MissingProperty { ref property } if property == "missing"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn builder_includes_primary_key() -> Result<()> {
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let layer = gpkg.get_layer("points")?;
+        let expected: Vec<i64> = layer.features()?.iter().map(|f| f.id()).collect();
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "points")
+            .columns(&["name"])
+            .include_primary_key(true)
+            .batch_size(2)
+            .build()?;
+        let schema = reader.schema();
+        let id_field = schema.field(0);
+        assert_eq!(id_field.name(), &layer.primary_key_column);
+        assert_eq!(id_field.data_type(), &DataType::Int64);
+        assert!(!id_field.is_nullable());
+        assert_eq!(schema.fields().len(), 3);
+
+        let mut ids = Vec::new();
+        for batch in reader {
+            let batch = batch?;
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .expect("int array");
+            ids.extend(column.values().iter().copied());
+        }
+        assert_eq!(ids, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_reads_native_points() -> Result<()> {
+        use geo_traits::{CoordTrait, PointTrait};
+        use geoarrow_array::array::PointArray;
+
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_test_layer(&gpkg)?;
+        layer.insert(Point::new(1.0, 2.0), params![true, "alpha", 1.25, 7])?;
+        layer.insert(Point::new(3.0, 4.0), params![false, "beta", 2.5, 9])?;
+
+        let mut reader = ArrowGpkgReader::builder(&gpkg, &layer.layer_name)
+            .geometry_encoding(GeometryEncoding::Native)
+            .build()?;
+        let batch = reader.next().transpose()?.expect("first batch");
+        let schema = batch.schema();
+        let field = schema.field_with_name("geom")?;
+        assert_eq!(
+            field.extension_type_name(),
+            Some("geoarrow.point"),
+            "geometry field should use the native point encoding"
+        );
+
+        let points = PointArray::try_from((batch.column(4).as_ref(), field))
+            .map_err(|e| GpkgError::GeoArrow(e.to_string()))?;
+        let coords: Vec<(f64, f64)> = (0..points.len())
+            .map(|i| {
+                let coord = points.value(i).unwrap().coord().expect("non-empty point");
+                (coord.x(), coord.y())
+            })
+            .collect();
+        assert_eq!(coords, vec![(1.0, 2.0), (3.0, 4.0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_reads_native_polygons() -> Result<()> {
+        use geoarrow_array::array::PolygonArray;
+
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let expected = gpkg.get_layer("polygons")?.features()?.len();
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "polygons")
+            .geometry_encoding(GeometryEncoding::Native)
+            .build()?;
+        let mut count = 0;
+        for batch in reader {
+            let batch = batch?;
+            let schema = batch.schema();
+            let index = schema.index_of("geom")?;
+            let polygons =
+                PolygonArray::try_from((batch.column(index).as_ref(), schema.field(index)))
+                    .map_err(|e| GpkgError::GeoArrow(e.to_string()))?;
+            count += polygons.len();
+        }
+        assert_eq!(count, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_falls_back_to_wkb_for_generic_geometry_layers() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer_with_options(
+            "mixed",
+            "geom",
+            GeometryType::GeometryCollection,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &[],
+            &CreateLayerOptions {
+                any_geometry_type: true,
+                ..Default::default()
+            },
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "mixed")
+            .geometry_encoding(GeometryEncoding::Native)
+            .build()?;
+        assert_eq!(
+            reader.schema().field(0).extension_type_name(),
+            Some("geoarrow.wkb")
+        );
+
+        let err = ArrowGpkgReader::builder(&gpkg, "mixed")
+            .geometry_encoding(GeometryEncoding::Native)
+            .wkb_fallback(false)
+            .build()
+            .err()
+            .expect("no native array for GEOMETRY");
+        assert!(matches!(err, GpkgError::GeoArrow(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn reads_layer_with_optional_z_as_wkb() -> Result<()> {
+        let gpkg = Gpkg::open_read_only("src/test/test_z_optional.gpkg")?;
+
+        let mut reader = ArrowGpkgReader::new(&gpkg, "wells", 16)?;
+        let schema = reader.schema();
+        let field = schema.field_with_name("Shape")?;
+        assert_eq!(field.extension_type_name(), Some("geoarrow.wkb"));
+        let batch = reader.next().expect("batch")?;
+        assert_eq!(batch.num_rows(), 3);
+
+        let reader = ArrowGpkgReader::builder(&gpkg, "wells")
+            .geometry_encoding(GeometryEncoding::Native)
+            .build()?;
+        let schema = reader.schema();
+        let field = schema.field_with_name("Shape")?;
+        assert_eq!(field.extension_type_name(), Some("geoarrow.wkb"));
+        let err = ArrowGpkgReader::builder(&gpkg, "wells")
+            .geometry_encoding(GeometryEncoding::Native)
+            .wkb_fallback(false)
+            .build()
+            .err()
+            .expect("no native array for optional Z");
+        assert!(matches!(err, GpkgError::GeoArrow(_)));
+        Ok(())
+    }
 }
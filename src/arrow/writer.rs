@@ -1,11 +1,17 @@
 use arrow_array::RecordBatch;
-use arrow_schema::SchemaRef;
-use geoarrow_schema::{CrsType, Metadata};
+use arrow_schema::{Field, SchemaRef};
+use geoarrow_array::GeoArrowArrayAccessor;
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::cast::to_wkb;
+use geoarrow_schema::{CrsType, GeoArrowType};
+use std::ops::ControlFlow;
+use wkb::reader::{Dimension, GeometryType};
 
 use crate::Gpkg;
 use crate::error::{GpkgError, Result};
 use crate::gpkg::GpkgLayer;
-use crate::gpkg::wkb_to_gpkg_geometry;
+use crate::gpkg::{Progress, no_progress, with_savepoint, wkb_to_gpkg_geometry};
+use crate::sql_functions::{Bounds, bounds_from_geometry, merge_bounds};
 use crate::types::{ColumnSpec, ColumnType};
 
 /// Writes Arrow `RecordBatch`es into a GeoPackage layer.
@@ -13,8 +19,12 @@ use crate::types::{ColumnSpec, ColumnType};
 /// `ArrowGpkgWriter` creates a new GeoPackage layer from the Arrow schema of
 /// the first batch and then inserts rows from each batch written via [`write`][Self::write].
 ///
-/// The geometry column is identified by its GeoArrow extension metadata. The
-/// EPSG code (srs_id) is derived from the CRS embedded in that metadata:
+/// The geometry column is identified by its GeoArrow extension metadata, and
+/// the schema must contain exactly one such column. It can use any GeoArrow
+/// encoding (WKB, WKT or a native type); native types such as `geoarrow.point`
+/// also determine the layer's geometry type and dimension, while the others
+/// create a `GEOMETRY` layer with XY dimension. The EPSG code (srs_id) is
+/// derived from the CRS embedded in that metadata:
 ///
 /// - **PROJJSON** — the EPSG code is read from the `id.authority` / `id.code` fields.
 /// - **WKT2** — parsed via [`epsg_utils::parse_wkt2`] and [`Crs::to_epsg`][epsg_utils::Crs::to_epsg].
@@ -23,6 +33,9 @@ use crate::types::{ColumnSpec, ColumnType};
 ///
 /// If the EPSG code cannot be resolved, layer creation will fail.
 ///
+/// Each batch is inserted in a single transaction (a savepoint if a
+/// transaction is already open), so a failing batch leaves no rows behind.
+///
 /// **Caveat:** When the writer auto-registers a new SRS entry in
 /// `gpkg_spatial_ref_sys`, the `definition` column is set to `"undefined"`.
 /// The GeoPackage spec requires WKT1 in this column, but this crate does not
@@ -51,6 +64,7 @@ pub struct ArrowGpkgWriter<'a> {
 /// Schema-derived state cached after initialization.
 struct WriterState {
     geom_index: usize,
+    geom_field: Field,
     srs_id: u32,
     insert_sql: String,
     /// Column indices in the Arrow schema that map to property columns (excludes geometry).
@@ -70,6 +84,25 @@ impl<'a> ArrowGpkgWriter<'a> {
         })
     }
 
+    /// Create a new writer and its layer from `schema` right away, instead of
+    /// waiting for the first batch.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgWriter, Gpkg};
+    /// # fn example(schema: arrow_schema::SchemaRef) -> Result<(), Box<dyn std::error::Error>> {
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let _writer = ArrowGpkgWriter::try_new(&gpkg, "my_layer", schema)?;
+    /// assert_eq!(gpkg.list_layers()?, vec!["my_layer".to_string()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_new(gpkg: &'a Gpkg, layer_name: &str, schema: SchemaRef) -> Result<Self> {
+        let mut writer = Self::new(gpkg, layer_name)?;
+        writer.initialize(schema)?;
+        Ok(writer)
+    }
+
     /// Write a `RecordBatch` into the GeoPackage layer.
     ///
     /// On the first call, the layer is created from the batch's schema. The
@@ -79,11 +112,50 @@ impl<'a> ArrowGpkgWriter<'a> {
         if self.state.is_none() {
             self.initialize(batch.schema())?;
         }
-        self.write_batch(batch)
+        self.write_batch(batch, &mut Progress::new(0, no_progress))
+    }
+
+    /// Write a `RecordBatch` like [`write`][Self::write], calling `progress`
+    /// with the number of rows written from the batch every `every_n` rows and
+    /// once at the end.
+    ///
+    /// If `progress` returns `ControlFlow::Break`, no row of the batch is
+    /// written and `GpkgError::Cancelled` is returned.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ArrowGpkgWriter, Gpkg};
+    /// use std::ops::ControlFlow;
+    /// # fn example(batch: arrow_array::RecordBatch) -> Result<(), Box<dyn std::error::Error>> {
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let mut writer = ArrowGpkgWriter::new(&gpkg, "my_layer")?;
+    /// writer.write_with_progress(&batch, 10_000, |done| {
+    ///     println!("{done} rows");
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_with_progress<F>(
+        &mut self,
+        batch: &RecordBatch,
+        every_n: u64,
+        progress: F,
+    ) -> Result<()>
+    where
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
+        if self.state.is_none() {
+            self.initialize(batch.schema())?;
+        }
+        self.write_batch(batch, &mut Progress::new(every_n, progress))
     }
 
     fn initialize(&mut self, schema: SchemaRef) -> Result<()> {
-        let (geom_index, geom_field_name, srs_id) = resolve_geometry_column(&schema)?;
+        let (geom_index, geoarrow_type) = resolve_geometry_column(&schema)?;
+        let geom_field = schema.field(geom_index).clone();
+        let srs_id = srs_id_from_crs(geoarrow_type.metadata().crs())?;
+        let (geometry_type, geometry_dimension) = layer_geometry_type(&geoarrow_type);
         let property_columns = build_property_columns(&schema, geom_index)?;
 
         // Register the SRS if it's not already present (e.g. non-4326 EPSG codes).
@@ -91,17 +163,15 @@ impl<'a> ArrowGpkgWriter<'a> {
 
         self.gpkg.create_layer(
             &self.layer_name,
-            &geom_field_name,
-            // GeoPackage stores WKB, which carries its own geometry type per row.
-            // Use GEOMETRY as the catch-all type.
-            wkb::reader::GeometryType::GeometryCollection,
-            wkb::reader::Dimension::Xy,
+            geom_field.name(),
+            geometry_type,
+            geometry_dimension,
             srs_id,
             &property_columns,
         )?;
 
         let insert_sql =
-            GpkgLayer::build_insert_sql(&self.layer_name, &geom_field_name, &property_columns);
+            GpkgLayer::build_insert_sql(&self.layer_name, geom_field.name(), &property_columns);
 
         let property_col_indices: Vec<usize> = (0..schema.fields().len())
             .filter(|&i| i != geom_index)
@@ -109,6 +179,7 @@ impl<'a> ArrowGpkgWriter<'a> {
 
         self.state = Some(WriterState {
             geom_index,
+            geom_field,
             srs_id,
             insert_sql,
             property_col_indices,
@@ -127,6 +198,11 @@ impl<'a> ArrowGpkgWriter<'a> {
             return Ok(());
         }
 
+        #[cfg(feature = "epsg-common")]
+        if crate::epsg::lookup(srs_id).is_some() {
+            return self.gpkg.register_epsg(srs_id);
+        }
+
         // GeoPackage requires a definition but we don't have a WKT1 source;
         // "undefined" is permitted by the spec for non-built-in SRS entries.
         let definition = "undefined";
@@ -142,27 +218,51 @@ impl<'a> ArrowGpkgWriter<'a> {
         Ok(())
     }
 
-    fn write_batch(&self, batch: &RecordBatch) -> Result<()> {
+    fn write_batch<F>(&self, batch: &RecordBatch, progress: &mut Progress<F>) -> Result<()>
+    where
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
+        with_savepoint(&self.gpkg.conn, || {
+            self.write_batch_in_savepoint(batch, progress)
+        })
+    }
+
+    fn write_batch_in_savepoint<F>(
+        &self,
+        batch: &RecordBatch,
+        progress: &mut Progress<F>,
+    ) -> Result<()>
+    where
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
         let state = self
             .state
             .as_ref()
             .expect("initialize must be called first");
 
+        // Convert any GeoArrow encoding into WKB.
+        let geom_array = from_arrow_array(batch.column(state.geom_index), &state.geom_field)
+            .map_err(|e| GpkgError::GeoArrow(format!("{e}")))?;
+        let wkb_array =
+            to_wkb::<i64>(geom_array.as_ref()).map_err(|e| GpkgError::GeoArrow(format!("{e}")))?;
+
         let mut stmt = self.gpkg.conn.prepare_cached(&state.insert_sql)?;
         let num_params = 1 + state.property_col_indices.len();
         let mut params: Vec<rusqlite::types::Value> = Vec::with_capacity(num_params);
+        let mut bounds: Option<Bounds> = None;
 
-        for row_idx in 0..batch.num_rows() {
+        for (row_idx, wkb) in wkb_array.iter().enumerate() {
             params.clear();
 
             // Geometry column first (matching the layer's insert SQL column order)
-            let geom_array = batch.column(state.geom_index);
-            if geom_array.is_null(row_idx) {
+            let Some(wkb) = wkb else {
                 return Err(GpkgError::NullGeometryValue);
+            };
+            let wkb = wkb.map_err(|e| GpkgError::GeoArrow(format!("{e}")))?;
+            if let Some(geom_bounds) = bounds_from_geometry(&wkb) {
+                merge_bounds(&mut bounds, geom_bounds);
             }
-            let wkb_bytes = extract_wkb_bytes(geom_array, row_idx)?;
-            let wkb = wkb::reader::Wkb::try_new(&wkb_bytes)?;
-            let geom_blob = wkb_to_gpkg_geometry(wkb, state.srs_id)?;
+            let geom_blob = wkb_to_gpkg_geometry(wkb, state.srs_id, None)?;
             params.push(rusqlite::types::Value::Blob(geom_blob));
 
             // Property columns in schema order (skipping the geometry column)
@@ -172,25 +272,60 @@ impl<'a> ArrowGpkgWriter<'a> {
             }
 
             stmt.execute(rusqlite::params_from_iter(&params))?;
+            progress.update(row_idx as u64 + 1)?;
         }
-        Ok(())
+        progress.finish(batch.num_rows() as u64)?;
+
+        let layer = self.gpkg.get_layer(&self.layer_name)?;
+        layer.expand_extent(bounds)?;
+        layer.touch_last_change()
     }
 }
 
-/// Identify the geometry column from GeoArrow extension metadata and extract its EPSG srs_id.
-fn resolve_geometry_column(schema: &SchemaRef) -> Result<(usize, String, u32)> {
+/// Identify the geometry column from GeoArrow extension metadata.
+fn resolve_geometry_column(schema: &SchemaRef) -> Result<(usize, GeoArrowType)> {
+    let mut found = None;
     for (i, field) in schema.fields().iter().enumerate() {
-        if field.extension_type_name().is_some() {
-            let metadata = Metadata::try_from(field.as_ref())
-                .map_err(|e| GpkgError::GeoArrow(format!("{e}")))?;
-            let srs_id = srs_id_from_crs(metadata.crs())?;
-            return Ok((i, field.name().clone(), srs_id));
+        let Some(geoarrow_type) = GeoArrowType::from_extension_field(field)
+            .map_err(|e| GpkgError::GeoArrow(format!("{e}")))?
+        else {
+            continue;
+        };
+        if found.is_some() {
+            return Err(GpkgError::GeoArrow(
+                "Arrow schema has more than one geometry column".to_string(),
+            ));
         }
-    }
-    Err(GpkgError::GeoArrow(
-        "No geometry column found in Arrow schema (missing GeoArrow extension metadata)"
-            .to_string(),
-    ))
+        found = Some((i, geoarrow_type));
+    }
+    found.ok_or_else(|| {
+        GpkgError::GeoArrow(
+            "No geometry column found in Arrow schema (missing GeoArrow extension metadata)"
+                .to_string(),
+        )
+    })
+}
+
+/// Map a GeoArrow type to the layer's geometry type and dimension.
+fn layer_geometry_type(geoarrow_type: &GeoArrowType) -> (GeometryType, Dimension) {
+    let geometry_type = match geoarrow_type {
+        GeoArrowType::Point(_) => GeometryType::Point,
+        GeoArrowType::LineString(_) => GeometryType::LineString,
+        GeoArrowType::Polygon(_) | GeoArrowType::Rect(_) => GeometryType::Polygon,
+        GeoArrowType::MultiPoint(_) => GeometryType::MultiPoint,
+        GeoArrowType::MultiLineString(_) => GeometryType::MultiLineString,
+        GeoArrowType::MultiPolygon(_) => GeometryType::MultiPolygon,
+        // WKB and WKT carry their own geometry type per row, so use GEOMETRY
+        // as the catch-all type.
+        _ => GeometryType::GeometryCollection,
+    };
+    let dimension = match geoarrow_type.dimension() {
+        Some(geoarrow_schema::Dimension::XYZ) => Dimension::Xyz,
+        Some(geoarrow_schema::Dimension::XYM) => Dimension::Xym,
+        Some(geoarrow_schema::Dimension::XYZM) => Dimension::Xyzm,
+        Some(geoarrow_schema::Dimension::XY) | None => Dimension::Xy,
+    };
+    (geometry_type, dimension)
 }
 
 /// Convert GeoArrow CRS metadata to an EPSG srs_id.
@@ -271,6 +406,7 @@ fn build_property_columns(schema: &SchemaRef, geom_index: usize) -> Result<Vec<C
         columns.push(ColumnSpec {
             name: field.name().clone(),
             column_type,
+            ..Default::default()
         });
     }
     Ok(columns)
@@ -278,15 +414,17 @@ fn build_property_columns(schema: &SchemaRef, geom_index: usize) -> Result<Vec<C
 
 fn arrow_type_to_column_type(dt: &arrow_schema::DataType) -> Result<ColumnType> {
     use arrow_schema::DataType;
+    if let Some(column_type) = super::temporal_column_type(dt) {
+        return Ok(column_type);
+    }
     match dt {
         DataType::Boolean => Ok(ColumnType::Boolean),
-        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
-            Ok(ColumnType::Integer)
-        }
-        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
-            Ok(ColumnType::Integer)
-        }
-        DataType::Float32 | DataType::Float64 => Ok(ColumnType::Double),
+        DataType::Int8 => Ok(ColumnType::TinyInt),
+        DataType::Int16 | DataType::UInt8 => Ok(ColumnType::SmallInt),
+        DataType::Int32 | DataType::UInt16 => Ok(ColumnType::MediumInt),
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 => Ok(ColumnType::Integer),
+        DataType::Float32 => Ok(ColumnType::Float),
+        DataType::Float64 => Ok(ColumnType::Double),
         DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => Ok(ColumnType::Varchar),
         DataType::Binary | DataType::LargeBinary | DataType::BinaryView => Ok(ColumnType::Blob),
         other => Err(GpkgError::GeoArrow(format!(
@@ -295,22 +433,6 @@ fn arrow_type_to_column_type(dt: &arrow_schema::DataType) -> Result<ColumnType>
     }
 }
 
-/// Extract raw WKB bytes from a geometry array at the given row index.
-fn extract_wkb_bytes(array: &dyn arrow_array::Array, row_idx: usize) -> Result<Vec<u8>> {
-    if let Some(binary) = array.as_any().downcast_ref::<arrow_array::BinaryArray>() {
-        return Ok(binary.value(row_idx).to_vec());
-    }
-    if let Some(binary) = array
-        .as_any()
-        .downcast_ref::<arrow_array::LargeBinaryArray>()
-    {
-        return Ok(binary.value(row_idx).to_vec());
-    }
-    Err(GpkgError::GeoArrow(
-        "Geometry column must be Binary or LargeBinary (WKB)".to_string(),
-    ))
-}
-
 /// Extract a rusqlite-compatible value from an Arrow array at the given row index.
 fn extract_value(array: &dyn arrow_array::Array, row_idx: usize) -> Result<rusqlite::types::Value> {
     if array.is_null(row_idx) {
@@ -369,6 +491,9 @@ fn extract_value(array: &dyn arrow_array::Array, row_idx: usize) -> Result<rusql
     {
         return Ok(rusqlite::types::Value::Text(a.value(row_idx).to_string()));
     }
+    if let Some(value) = super::temporal_value(array, row_idx) {
+        return Ok(value);
+    }
     if let Some(a) = array.as_any().downcast_ref::<arrow_array::BinaryArray>() {
         return Ok(rusqlite::types::Value::Blob(a.value(row_idx).to_vec()));
     }
@@ -393,15 +518,19 @@ fn extract_value(array: &dyn arrow_array::Array, row_idx: usize) -> Result<rusql
 
 #[cfg(all(test, feature = "arrow"))]
 mod tests {
-    use super::ArrowGpkgWriter;
+    use super::{ArrowGpkgWriter, layer_geometry_type};
     use crate::Result;
     use crate::arrow::reader::ArrowGpkgReader;
+    use crate::error::GpkgError;
     use crate::gpkg::Gpkg;
 
     use arrow_array::{Float64Array, Int64Array, RecordBatch, StringArray};
     use arrow_schema::{Field, Schema};
     use geoarrow_array::GeoArrowArray;
+    use geoarrow_array::builder::PointBuilder;
+    use geoarrow_schema::{GeoArrowType, Metadata, MultiPolygonType, PointType};
     use std::sync::Arc;
+    use wkb::reader::{Dimension, GeometryType};
 
     fn wkb_field_4326() -> Field {
         super::super::wkb_geometry_field("geom", 4326)
@@ -491,12 +620,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cancelling_write_with_progress_rolls_back_the_batch() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let coords: Vec<(f64, f64)> = (0..1000).map(|i| (i as f64, 0.0)).collect();
+        let schema = Arc::new(Schema::new(vec![Arc::new(wkb_field_4326())]));
+        let batch =
+            RecordBatch::try_new(schema, vec![make_wkb_array(&coords)]).expect("valid batch");
+
+        let mut writer = ArrowGpkgWriter::new(&gpkg, "points")?;
+        let err = writer
+            .write_with_progress(&batch, 100, |done| {
+                if done >= 100 {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
+                }
+            })
+            .expect_err("cancelled");
+        assert!(matches!(err, GpkgError::Cancelled), "{err}");
+        assert_eq!(gpkg.get_layer("points")?.count()?, 0);
+
+        let mut calls = Vec::new();
+        writer.write_with_progress(&batch, 400, |done| {
+            calls.push(done);
+            std::ops::ControlFlow::Continue(())
+        })?;
+        assert_eq!(calls, vec![400, 800, 1000]);
+        assert_eq!(gpkg.get_layer("points")?.count()?, 1000);
+        Ok(())
+    }
+
     #[test]
     fn write_with_float_column() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
 
         let geom_array = make_wkb_array(&[(1.0, 2.0)]);
-        let score_array = Arc::new(Float64Array::from(vec![3.14]));
+        let score_array = Arc::new(Float64Array::from(vec![2.75]));
 
         let schema = Arc::new(Schema::new(vec![
             Arc::new(wkb_field_4326()),
@@ -513,8 +673,179 @@ mod tests {
         let features = layer.features()?;
         assert_eq!(features.len(), 1);
         let score: f64 = features[0].property("score").unwrap().try_into()?;
-        assert!((score - 3.14).abs() < f64::EPSILON);
+        assert!((score - 2.75).abs() < f64::EPSILON);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_date_and_timestamp_columns() -> Result<()> {
+        use arrow_array::{
+            Array, Date32Array, TimestampMicrosecondArray, TimestampMillisecondArray,
+        };
+        use arrow_schema::{DataType, TimeUnit};
+
+        let gpkg = Gpkg::open_in_memory()?;
+
+        let geom_array = make_wkb_array(&[(1.0, 2.0), (3.0, 4.0)]);
+        let d_array = Arc::new(Date32Array::from(vec![Some(19_737), None]));
+        let dt_array = Arc::new(
+            TimestampMillisecondArray::from(vec![Some(1_705_314_600_500), Some(-1)])
+                .with_timezone("UTC"),
+        );
+
+        let schema = Arc::new(Schema::new(vec![
+            Arc::new(wkb_field_4326()),
+            Arc::new(Field::new("d", DataType::Date32, true)),
+            Arc::new(Field::new(
+                "dt",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                true,
+            )),
+        ]));
+        let batch =
+            RecordBatch::try_new(schema, vec![geom_array, d_array, dt_array]).expect("valid batch");
+
+        let mut writer = ArrowGpkgWriter::new(&gpkg, "dates")?;
+        writer.write(&batch)?;
+
+        let layer = gpkg.get_layer("dates")?;
+        let d = layer
+            .property_columns
+            .iter()
+            .find(|c| c.name == "d")
+            .unwrap();
+        let dt = layer
+            .property_columns
+            .iter()
+            .find(|c| c.name == "dt")
+            .unwrap();
+        assert_eq!(d.column_type, crate::ColumnType::Date);
+        assert_eq!(dt.column_type, crate::ColumnType::Datetime);
+
+        let features = layer.features()?;
+        let text: String = features[0].property("dt").unwrap().try_into()?;
+        assert_eq!(text, "2024-01-15T10:30:00.500Z");
+        let text: String = features[1].property("dt").unwrap().try_into()?;
+        assert_eq!(text, "1969-12-31T23:59:59.999Z");
+
+        let batch = ArrowGpkgReader::new(&gpkg, "dates", 10)?
+            .next()
+            .transpose()?
+            .expect("first batch");
+        let d = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .expect("date32 array");
+        let dt = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .expect("timestamp array");
+        assert_eq!(d.value(0), 19_737);
+        assert!(d.is_null(1));
+        assert_eq!(dt.value(0), 1_705_314_600_500_000);
+        assert_eq!(dt.value(1), -1_000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_generated_gpkg() -> Result<()> {
+        let src = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let dst = Gpkg::open_in_memory()?;
+        for layer_name in src.list_layers()? {
+            let expected = ArrowGpkgReader::new(&src, &layer_name, 2)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut writer = ArrowGpkgWriter::new(&dst, &layer_name)?;
+            for batch in &expected {
+                writer.write(batch)?;
+            }
+
+            let actual = ArrowGpkgReader::new(&dst, &layer_name, 2)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            assert_eq!(actual, expected, "layer {layer_name}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn creates_layer_from_native_geoarrow_type() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let point_type = PointType::new(
+            geoarrow_schema::Dimension::XY,
+            Metadata::new(crate::arrow::crs_from_srs_id(4326), None).into(),
+        );
+        let schema = Arc::new(Schema::new(vec![point_type.to_field("geom", true)]));
+
+        // The layer exists before any batch is written.
+        let mut writer = ArrowGpkgWriter::try_new(&gpkg, "points", schema.clone())?;
+        let layer = gpkg.get_layer("points")?;
+        assert_eq!(layer.geometry_type, GeometryType::Point);
+        assert_eq!(layer.geometry_dimension, Dimension::Xy);
+        assert_eq!(layer.srs_id, 4326);
+
+        let points = [
+            geo_types::Point::new(1.0, 2.0),
+            geo_types::Point::new(3.0, 4.0),
+        ];
+        let geom_array = PointBuilder::from_points(points.iter(), point_type).finish();
+        let batch =
+            RecordBatch::try_new(schema, vec![geom_array.into_array_ref()]).expect("valid batch");
+        writer.write(&batch)?;
+
+        assert_eq!(layer.count()?, 2);
+        assert_eq!(layer.extent()?, Some((1.0, 2.0, 3.0, 4.0)));
+
+        let multi_polygon = GeoArrowType::MultiPolygon(MultiPolygonType::new(
+            geoarrow_schema::Dimension::XYZ,
+            Default::default(),
+        ));
+        assert_eq!(
+            layer_geometry_type(&multi_polygon),
+            (GeometryType::MultiPolygon, Dimension::Xyz)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_multiple_geometry_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let schema = Arc::new(Schema::new(vec![
+            Arc::new(wkb_field_4326()),
+            Arc::new(super::super::wkb_geometry_field("geom2", 4326)),
+        ]));
+        let err = ArrowGpkgWriter::try_new(&gpkg, "points", schema)
+            .err()
+            .expect("two geometry columns");
+        assert!(matches!(err, GpkgError::GeoArrow(_)));
+        assert!(gpkg.list_layers()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_batch_inserts_nothing() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let mut builder = super::super::wkb_geometry_builder(4326, 2);
+        let mut wkb_bytes = Vec::new();
+        wkb::writer::write_geometry(
+            &mut wkb_bytes,
+            &geo_types::Point::new(1.0, 2.0),
+            &Default::default(),
+        )
+        .unwrap();
+        builder.push_wkb(Some(&wkb_bytes)).unwrap();
+        builder.push_wkb(None::<&[u8]>).unwrap();
+        let schema = Arc::new(Schema::new(vec![Arc::new(wkb_field_4326())]));
+        let batch = RecordBatch::try_new(schema, vec![builder.finish().into_array_ref()])
+            .expect("valid batch");
 
+        let mut writer = ArrowGpkgWriter::new(&gpkg, "points")?;
+        let err = writer.write(&batch).expect_err("null geometry");
+        assert!(matches!(err, GpkgError::NullGeometryValue));
+        assert_eq!(gpkg.get_layer("points")?.count()?, 0);
         Ok(())
     }
 }
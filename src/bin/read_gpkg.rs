@@ -1,5 +1,4 @@
 use rusqlite_gpkg::{Gpkg, Value};
-use wkt::to_wkt::write_geometry;
 
 fn main() {
     if let Err(err) = run() {
@@ -19,12 +18,13 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         let layer = gpkg.get_layer(&layer_name)?;
         println!("layer: {layer_name}");
 
-        for (row_idx, feature) in layer.features()?.enumerate() {
+        for (row_idx, feature) in layer.features()?.into_iter().enumerate() {
             let mut values = Vec::with_capacity(layer.property_columns.len() + 1);
-            let wkb = feature.geometry()?;
-            let mut wkt = String::new();
-            write_geometry(&mut wkt, &wkb)?;
-            values.push(format!("{}={wkt}", layer.geometry_column));
+            values.push(format!(
+                "{}={}",
+                layer.geometry_column,
+                feature.geometry_wkt()?
+            ));
 
             for column in &layer.property_columns {
                 let value = feature.property(&column.name).unwrap_or(Value::Null);
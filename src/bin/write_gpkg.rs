@@ -1,7 +1,5 @@
 use rusqlite_gpkg::{ColumnSpec, ColumnType, Gpkg, params};
 use std::f64::consts::PI;
-use std::str::FromStr;
-use wkt::Wkt;
 
 fn main() {
     if let Err(err) = run() {
@@ -21,26 +19,32 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         },
         ColumnSpec {
             name: "region".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         },
         ColumnSpec {
             name: "center_lat".to_string(),
             column_type: ColumnType::Double,
+            ..Default::default()
         },
         ColumnSpec {
             name: "center_lon".to_string(),
             column_type: ColumnType::Double,
+            ..Default::default()
         },
         ColumnSpec {
             name: "points".to_string(),
             column_type: ColumnType::Integer,
+            ..Default::default()
         },
         ColumnSpec {
             name: "note".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         },
     ];
 
@@ -54,13 +58,13 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     let tokyo_center = (139.767, 35.681);
-    let tokyo_star = star_polygon_wkt(tokyo_center.0, tokyo_center.1, 1.4, 0.6, 5)?;
+    let tokyo_star = star_polygon_wkt(tokyo_center.0, tokyo_center.1, 1.4, 0.6, 5);
     let tokyo_name = "Tokyo Star".to_string();
     let tokyo_region = "Tokyo".to_string();
     let tokyo_points = 5_i64;
     let tokyo_note = "Star polygon around Tokyo".to_string();
-    layer.insert(
-        tokyo_star,
+    layer.insert_wkt(
+        &tokyo_star,
         params![
             tokyo_name,
             tokyo_region,
@@ -72,13 +76,13 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     let hokkaido_center = (141.3468, 43.0642);
-    let hokkaido_star = star_polygon_wkt(hokkaido_center.0, hokkaido_center.1, 2.2, 0.9, 5)?;
+    let hokkaido_star = star_polygon_wkt(hokkaido_center.0, hokkaido_center.1, 2.2, 0.9, 5);
     let hokkaido_name = "Hokkaido Star".to_string();
     let hokkaido_region = "Hokkaido".to_string();
     let hokkaido_points = 5_i64;
     let hokkaido_note = "Star polygon around Hokkaido".to_string();
-    layer.insert(
-        hokkaido_star,
+    layer.insert_wkt(
+        &hokkaido_star,
         params![
             hokkaido_name,
             hokkaido_region,
@@ -98,7 +102,7 @@ fn star_polygon_wkt(
     outer_radius: f64,
     inner_radius: f64,
     points: usize,
-) -> Result<Wkt<f64>, Box<dyn std::error::Error>> {
+) -> String {
     let mut coords = Vec::with_capacity(points * 2 + 1);
     let total_vertices = points * 2;
     let start_angle = -PI / 2.0;
@@ -127,6 +131,5 @@ fn star_polygon_wkt(
         ring.push_str(&format!("{lon} {lat}"));
     }
 
-    let wkt = format!("POLYGON (({ring}))");
-    Ok(Wkt::from_str(&wkt)?)
+    format!("POLYGON (({ring}))")
 }
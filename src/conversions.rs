@@ -1,5 +1,5 @@
 use crate::error::GpkgError;
-use crate::types::ColumnType;
+use crate::types::{ColumnSpec, ColumnType, Value, ZmFlag};
 
 #[inline]
 pub(crate) fn geometry_type_to_str(geometry_type: wkb::reader::GeometryType) -> &'static str {
@@ -41,6 +41,23 @@ pub(crate) fn geometry_type_from_str(
     }
 }
 
+// The geometry types of the non-linear geometry extension, which have no
+// GeometryType.
+// cf. https://www.geopackage.org/spec140/index.html#extension_geometry_types
+pub(crate) fn is_non_linear_geometry_type(geometry_type_str: &str) -> bool {
+    [
+        "CIRCULARSTRING",
+        "COMPOUNDCURVE",
+        "CURVEPOLYGON",
+        "MULTICURVE",
+        "MULTISURFACE",
+        "CURVE",
+        "SURFACE",
+    ]
+    .iter()
+    .any(|name| geometry_type_str.eq_ignore_ascii_case(name))
+}
+
 #[inline]
 pub(crate) fn dimension_to_zm(dimension: wkb::reader::Dimension) -> (i8, i8) {
     match dimension {
@@ -52,23 +69,82 @@ pub(crate) fn dimension_to_zm(dimension: wkb::reader::Dimension) -> (i8, i8) {
 }
 
 #[inline]
-pub(crate) fn dimension_from_zm(z: i8, m: i8) -> Result<wkb::reader::Dimension, GpkgError> {
-    match (z, m) {
-        (0, 0) => Ok(wkb::reader::Dimension::Xy),
-        (1, 0) => Ok(wkb::reader::Dimension::Xyz),
-        (0, 1) => Ok(wkb::reader::Dimension::Xym),
-        (1, 1) => Ok(wkb::reader::Dimension::Xyzm),
-        // Note: the spec says z and m are
-        //
-        //   0: z/m values prohibited
-        //   1: z/m values mandatory
-        //   2: z/m values optional
-        //
-        // but I don't know how 2 can be handled, just treat as an invalid value
-        _ => Err(GpkgError::InvalidDimension { z, m }),
+pub(crate) fn zm_flags_from_dimension(dimension: wkb::reader::Dimension) -> (ZmFlag, ZmFlag) {
+    let (z, m) = dimension_to_zm(dimension);
+    (zm_flag_from_i8(z), zm_flag_from_i8(m))
+}
+
+#[inline]
+pub(crate) fn zm_flag_to_i8(flag: ZmFlag) -> i8 {
+    match flag {
+        ZmFlag::Prohibited => 0,
+        ZmFlag::Mandatory => 1,
+        ZmFlag::Optional => 2,
     }
 }
 
+// Only called with valid values; see zm_flags_from_i8() for the checked version.
+#[inline]
+fn zm_flag_from_i8(flag: i8) -> ZmFlag {
+    match flag {
+        0 => ZmFlag::Prohibited,
+        1 => ZmFlag::Mandatory,
+        _ => ZmFlag::Optional,
+    }
+}
+
+// The spec says z and m are
+//
+//   0: z/m values prohibited
+//   1: z/m values mandatory
+//   2: z/m values optional
+#[inline]
+pub(crate) fn zm_flags_from_i8(z: i8, m: i8) -> Result<(ZmFlag, ZmFlag), GpkgError> {
+    if !(0..=2).contains(&z) || !(0..=2).contains(&m) {
+        return Err(GpkgError::InvalidDimension { z, m });
+    }
+    Ok((zm_flag_from_i8(z), zm_flag_from_i8(m)))
+}
+
+// The dimension used for reading. An optional flag is read as the maximal
+// dimension; each geometry's WKB tells whether it actually has the values.
+#[inline]
+pub(crate) fn dimension_from_zm_flags(z: ZmFlag, m: ZmFlag) -> wkb::reader::Dimension {
+    match (z != ZmFlag::Prohibited, m != ZmFlag::Prohibited) {
+        (false, false) => wkb::reader::Dimension::Xy,
+        (true, false) => wkb::reader::Dimension::Xyz,
+        (false, true) => wkb::reader::Dimension::Xym,
+        (true, true) => wkb::reader::Dimension::Xyzm,
+    }
+}
+
+#[inline]
+pub(crate) fn dimension_from_zm(z: i8, m: i8) -> Result<wkb::reader::Dimension, GpkgError> {
+    let (z, m) = zm_flags_from_i8(z, m)?;
+    Ok(dimension_from_zm_flags(z, m))
+}
+
+// Whether a geometry of the dimension is allowed by the flags.
+#[inline]
+pub(crate) fn dimension_allowed_by_zm_flags(
+    dimension: wkb::reader::Dimension,
+    z: ZmFlag,
+    m: ZmFlag,
+) -> bool {
+    let (has_z, has_m) = match dimension {
+        wkb::reader::Dimension::Xy => (false, false),
+        wkb::reader::Dimension::Xyz => (true, false),
+        wkb::reader::Dimension::Xym => (false, true),
+        wkb::reader::Dimension::Xyzm => (true, true),
+    };
+    let allowed = |flag: ZmFlag, has: bool| match flag {
+        ZmFlag::Prohibited => !has,
+        ZmFlag::Mandatory => has,
+        ZmFlag::Optional => true,
+    };
+    allowed(z, has_z) && allowed(m, has_m)
+}
+
 #[inline]
 pub(crate) fn column_type_to_str(column_type: ColumnType) -> &'static str {
     match column_type {
@@ -80,23 +156,96 @@ pub(crate) fn column_type_to_str(column_type: ColumnType) -> &'static str {
         ColumnType::Blob => "BLOB",
         ColumnType::Date => "DATE",
         ColumnType::Datetime => "DATETIME",
+        ColumnType::TinyInt => "TINYINT",
+        ColumnType::SmallInt => "SMALLINT",
+        ColumnType::MediumInt => "MEDIUMINT",
+        ColumnType::Float => "FLOAT",
+    }
+}
+
+// Column definition for CREATE TABLE and ALTER TABLE ADD COLUMN, e.g.
+// `"name" TEXT NOT NULL DEFAULT 'unknown'`.
+pub(crate) fn column_definition_sql(spec: &ColumnSpec) -> String {
+    let mut def = format!(
+        r#""{}" {}"#,
+        spec.name,
+        column_type_to_str(spec.column_type)
+    );
+    if spec.not_null {
+        def.push_str(" NOT NULL");
+    }
+    if spec.unique {
+        def.push_str(" UNIQUE");
+    }
+    if let Some(default) = &spec.default {
+        def.push_str(" DEFAULT ");
+        def.push_str(&value_to_sql_literal(default));
+    }
+    def
+}
+
+fn value_to_sql_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(i) => i.to_string(),
+        // Debug formatting keeps the decimal point (e.g. `1.0`), so SQLite
+        // reads the literal back as REAL.
+        Value::Real(f) => format!("{f:?}"),
+        Value::Text(t) => format!("'{}'", t.replace('\'', "''")),
+        Value::Blob(b) | Value::Geometry(b) => {
+            let hex: String = b.iter().map(|byte| format!("{byte:02X}")).collect();
+            format!("X'{hex}'")
+        }
+    }
+}
+
+// Parse `dflt_value` of `pragma_table_info`. Only literals are recognized;
+// expressions such as `CURRENT_TIMESTAMP` or `(strftime(...))` yield `None`.
+pub(crate) fn default_value_from_sql(dflt_value: &str) -> Option<Value> {
+    let s = dflt_value.trim();
+    if s.eq_ignore_ascii_case("NULL") {
+        None
+    } else if let Some(text) = s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(Value::Text(text.replace("''", "'")))
+    } else if let Some(hex) = s
+        .strip_prefix("X'")
+        .or_else(|| s.strip_prefix("x'"))
+        .and_then(|s| s.strip_suffix('\''))
+    {
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()
+            .map(Value::Blob)
+    } else if let Ok(i) = s.parse::<i64>() {
+        Some(Value::Integer(i))
+    } else if let Ok(f) = s.parse::<f64>() {
+        // Rust also accepts words like "inf" and "NaN", which are not SQL literals.
+        s.bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E'))
+            .then_some(Value::Real(f))
+    } else {
+        None
     }
 }
 
 #[inline]
 pub(crate) fn column_type_from_str(column_type_str: &str) -> Option<ColumnType> {
     let s = column_type_str;
-    if s.eq_ignore_ascii_case("TINYINT")
-        || s.eq_ignore_ascii_case("SMALLINT")
-        || s.eq_ignore_ascii_case("MEDIUMINT")
-        || s.eq_ignore_ascii_case("INT")
-        || s.eq_ignore_ascii_case("INTEGER")
-    {
+    if s.eq_ignore_ascii_case("TINYINT") {
+        Some(ColumnType::TinyInt)
+    } else if s.eq_ignore_ascii_case("SMALLINT") {
+        Some(ColumnType::SmallInt)
+    } else if s.eq_ignore_ascii_case("MEDIUMINT") {
+        Some(ColumnType::MediumInt)
+    } else if s.eq_ignore_ascii_case("INT") || s.eq_ignore_ascii_case("INTEGER") {
         Some(ColumnType::Integer)
-    } else if s.eq_ignore_ascii_case("DOUBLE")
-        || s.eq_ignore_ascii_case("FLOAT")
-        || s.eq_ignore_ascii_case("REAL")
-    {
+    } else if s.eq_ignore_ascii_case("FLOAT") {
+        Some(ColumnType::Float)
+    } else if s.eq_ignore_ascii_case("DOUBLE") || s.eq_ignore_ascii_case("REAL") {
         Some(ColumnType::Double)
     } else if s.eq_ignore_ascii_case("TEXT") {
         Some(ColumnType::Varchar)
@@ -122,3 +271,58 @@ pub(crate) fn column_type_from_str(column_type_str: &str) -> Option<ColumnType>
         None
     }
 }
+
+/// Convert WKB into an owned `geo_types::Geometry`.
+#[cfg(feature = "geo")]
+pub(crate) fn wkb_to_geo(wkb: &wkb::reader::Wkb) -> Result<geo_types::Geometry<f64>, GpkgError> {
+    use geo_traits::to_geo::ToGeoGeometry;
+
+    let unsupported = || GpkgError::UnsupportedGeoTypesGeometry {
+        geometry_type: wkb.geometry_type(),
+        dimension: wkb.dimension(),
+    };
+    if wkb.dimension() != wkb::reader::Dimension::Xy {
+        return Err(unsupported());
+    }
+    wkb.try_to_geometry().ok_or_else(unsupported)
+}
+
+/// Write a geometry as WKT. If `precision` is given, every coordinate is
+/// written with that many decimal places.
+#[cfg(feature = "wkt")]
+pub(crate) fn geometry_to_wkt<G: geo_traits::GeometryTrait<T = f64>>(
+    geometry: &G,
+    precision: Option<usize>,
+) -> Result<String, GpkgError> {
+    let mut wkt = String::new();
+    wkt::to_wkt::write_geometry(&mut wkt, geometry)
+        .map_err(|err| GpkgError::Wkt(err.to_string()))?;
+    let Some(precision) = precision else {
+        return Ok(wkt);
+    };
+
+    // The coordinates are the only tokens that start with a digit, `-` or
+    // `.`; keywords like `POINT` or `EMPTY` and `NaN` start with a letter.
+    let mut out = String::with_capacity(wkt.len());
+    let mut chars = wkt.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_ascii_digit() || c == '-' || c == '.') {
+            out.push(c);
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '-' || c == '+') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        let token = &wkt[start..end];
+        match token.parse::<f64>() {
+            Ok(value) => out.push_str(&format!("{value:.precision$}")),
+            Err(_) => out.push_str(token),
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,455 @@
+//! CSV import of feature layers.
+
+use crate::error::{GpkgError, Result};
+use crate::gpkg::{Gpkg, GpkgLayer};
+use crate::types::{
+    ColumnSpec, ColumnType, CreateLayerOptions, CsvImportOptions, GeometryFrom, Value,
+};
+use csv::StringRecord;
+use std::io::Read;
+use std::str::FromStr;
+use wkb::reader::{Dimension, GeometryType, Wkb};
+
+/// Create `layer_name` from the CSV in `reader` and insert its rows. The
+/// caller runs this in a savepoint.
+pub(crate) fn create_layer<R: Read>(
+    gpkg: &Gpkg,
+    reader: R,
+    layer_name: &str,
+    options: &CsvImportOptions,
+) -> Result<GpkgLayer> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .from_reader(reader);
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    let geometry = GeometryColumns::new(&options.geometry, &headers)?;
+
+    // Rows of the sample are kept to be inserted after the layer is created.
+    // Malformed rows are kept too, so that errors are reported in file order.
+    let mut records = reader.into_records();
+    let sample: Vec<csv::Result<StringRecord>> = match options.sample_size {
+        Some(n) => records.by_ref().take(n).collect(),
+        None => records.by_ref().collect(),
+    };
+    let parsed: Vec<&StringRecord> = sample.iter().filter_map(|row| row.as_ref().ok()).collect();
+
+    let columns: Vec<(usize, ColumnSpec)> = headers
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !geometry.contains(*i))
+        .map(|(i, name)| {
+            let column_type = match options.column_types.get(name) {
+                Some(column_type) => *column_type,
+                None => infer_column_type(parsed.iter().filter_map(|record| record.get(i))),
+            };
+            let spec = ColumnSpec {
+                name: name.to_string(),
+                column_type,
+                ..Default::default()
+            };
+            (i, spec)
+        })
+        .collect();
+    let (geometry_type, dimension) = match &geometry {
+        GeometryColumns::LonLat { .. } => (Some(GeometryType::Point), Dimension::Xy),
+        GeometryColumns::Wkt { column } => {
+            infer_geometry_type(parsed.iter().filter_map(|record| record.get(*column)))
+        }
+    };
+    let specs: Vec<ColumnSpec> = columns.iter().map(|(_, spec)| spec.clone()).collect();
+
+    let layer = gpkg.create_layer_with_options(
+        layer_name,
+        "geom",
+        geometry_type.unwrap_or(GeometryType::GeometryCollection),
+        dimension,
+        options.srs_id,
+        &specs,
+        &CreateLayerOptions {
+            any_geometry_type: geometry_type.is_none(),
+            ..Default::default()
+        },
+    )?;
+    for row in sample.into_iter().chain(records) {
+        let result = row
+            .map_err(csv_error)
+            .and_then(|record| insert_row(&layer, &record, &geometry, &columns, options));
+        match result {
+            Ok(()) => {}
+            Err(err) if options.strict => return Err(err),
+            Err(_) => {}
+        }
+    }
+    Ok(layer)
+}
+
+/// The indices of the columns the geometry is read from.
+enum GeometryColumns {
+    LonLat { lon: usize, lat: usize },
+    Wkt { column: usize },
+}
+
+impl GeometryColumns {
+    fn new(geometry: &GeometryFrom, headers: &StringRecord) -> Result<Self> {
+        let find = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| GpkgError::InvalidCsv {
+                    line: 1,
+                    message: format!("no column named '{name}'"),
+                })
+        };
+        Ok(match geometry {
+            GeometryFrom::LonLat { lon, lat } => Self::LonLat {
+                lon: find(lon)?,
+                lat: find(lat)?,
+            },
+            GeometryFrom::Wkt { column } => Self::Wkt {
+                column: find(column)?,
+            },
+        })
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        match self {
+            Self::LonLat { lon, lat } => index == *lon || index == *lat,
+            Self::Wkt { column } => index == *column,
+        }
+    }
+}
+
+fn insert_row(
+    layer: &GpkgLayer,
+    record: &StringRecord,
+    geometry: &GeometryColumns,
+    columns: &[(usize, ColumnSpec)],
+    options: &CsvImportOptions,
+) -> Result<()> {
+    let line = record.position().map_or(0, |position| position.line());
+    let invalid = |message: String| GpkgError::InvalidCsv { line, message };
+
+    let mut values = Vec::with_capacity(columns.len());
+    for (i, spec) in columns {
+        let cell = record.get(*i).unwrap_or_default();
+        let value = cell_to_value(spec.column_type, cell, options.srs_id)
+            .map_err(|message| invalid(format!("column '{}': {message}", spec.name)))?;
+        values.push(value);
+    }
+
+    let result = match geometry {
+        GeometryColumns::LonLat { lon, lat } => {
+            let lon = record.get(*lon).unwrap_or_default();
+            let lat = record.get(*lat).unwrap_or_default();
+            if lon.is_empty() && lat.is_empty() {
+                layer.insert_without_geometry(&values)
+            } else {
+                let parse = |cell: &str| {
+                    cell.parse::<f64>()
+                        .map_err(|_| invalid(format!("invalid coordinate '{cell}'")))
+                };
+                let point = wkt::types::Point::from_coord(wkt::types::Coord {
+                    x: parse(lon)?,
+                    y: parse(lat)?,
+                    z: None,
+                    m: None,
+                });
+                layer.insert(point, &values)
+            }
+        }
+        GeometryColumns::Wkt { column } => match record.get(*column).unwrap_or_default() {
+            "" => layer.insert_without_geometry(&values),
+            text => layer.insert_wkt(text, &values),
+        },
+    };
+    result.map_err(|err| match err {
+        GpkgError::InvalidCsv { .. } => err,
+        err => invalid(err.to_string()),
+    })
+}
+
+// Parse a cell as a value of the column type. An empty cell is NULL.
+fn cell_to_value(
+    column_type: ColumnType,
+    cell: &str,
+    srs_id: u32,
+) -> std::result::Result<Value, String> {
+    if cell.is_empty() {
+        return Ok(Value::Null);
+    }
+    let value = match column_type {
+        ColumnType::Boolean => match parse_bool(cell) {
+            Some(v) => Value::Integer(v as i64),
+            None => return Err(format!("invalid boolean '{cell}'")),
+        },
+        ColumnType::Integer
+        | ColumnType::MediumInt
+        | ColumnType::SmallInt
+        | ColumnType::TinyInt => Value::Integer(
+            cell.parse()
+                .map_err(|_| format!("invalid integer '{cell}'"))?,
+        ),
+        ColumnType::Double | ColumnType::Float => Value::Real(
+            cell.parse()
+                .map_err(|_| format!("invalid number '{cell}'"))?,
+        ),
+        ColumnType::Varchar | ColumnType::Date | ColumnType::Datetime => {
+            Value::Text(cell.to_string())
+        }
+        ColumnType::Blob => Value::Blob(cell.as_bytes().to_vec()),
+        ColumnType::Geometry => {
+            let geometry = wkt::Wkt::<f64>::from_str(cell).map_err(|err| err.to_string())?;
+            Value::from_geometry(&geometry, srs_id).map_err(|err| err.to_string())?
+        }
+    };
+    Ok(value)
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    if cell.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if cell.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// Infer the type of a column from its non-empty cells: integers, numbers
+// (integers mixed with numbers too), booleans, and text for anything else.
+fn infer_column_type<'a>(cells: impl Iterator<Item = &'a str>) -> ColumnType {
+    let mut inferred = None;
+    for cell in cells.filter(|cell| !cell.is_empty()) {
+        let column_type = if cell.parse::<i64>().is_ok() {
+            ColumnType::Integer
+        // `f64::from_str` also accepts e.g. `inf` and `NaN`.
+        } else if cell.bytes().any(|b| b.is_ascii_digit()) && cell.parse::<f64>().is_ok() {
+            ColumnType::Double
+        } else if parse_bool(cell).is_some() {
+            ColumnType::Boolean
+        } else {
+            return ColumnType::Varchar;
+        };
+        inferred = Some(match (inferred, column_type) {
+            (None, t) => t,
+            (Some(a), b) if a == b => a,
+            (
+                Some(ColumnType::Integer | ColumnType::Double),
+                ColumnType::Integer | ColumnType::Double,
+            ) => ColumnType::Double,
+            _ => return ColumnType::Varchar,
+        });
+    }
+    inferred.unwrap_or(ColumnType::Varchar)
+}
+
+// Infer the geometry type and dimension of a WKT column. Mixed (or no)
+// geometry types need a GEOMETRY layer, for which the type is `None`, and the
+// dimension is that of the first valid geometry.
+fn infer_geometry_type<'a>(
+    cells: impl Iterator<Item = &'a str>,
+) -> (Option<GeometryType>, Dimension) {
+    let mut inferred: Option<(Option<GeometryType>, Dimension)> = None;
+    let mut buf = Vec::new();
+    for cell in cells.filter(|cell| !cell.is_empty()) {
+        let Ok(geometry) = wkt::Wkt::<f64>::from_str(cell) else {
+            continue;
+        };
+        buf.clear();
+        if wkb::writer::write_geometry(&mut buf, &geometry, &Default::default()).is_err() {
+            continue;
+        }
+        let Ok(wkb) = Wkb::try_new(&buf) else {
+            continue;
+        };
+        let (geometry_type, dimension) = (wkb.geometry_type(), wkb.dimension());
+        inferred = Some(match inferred {
+            None => (Some(geometry_type), dimension),
+            Some((t, d)) if t == Some(geometry_type) => (t, d),
+            Some((_, d)) => (None, d),
+        });
+    }
+    inferred.unwrap_or((None, Dimension::Xy))
+}
+
+fn csv_error(err: csv::Error) -> GpkgError {
+    let line = err.position().map_or(0, |position| position.line());
+    GpkgError::InvalidCsv {
+        line,
+        message: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ColumnType, CsvImportOptions, GeometryFrom};
+    use crate::{Gpkg, GpkgError, Result, Value};
+    use wkb::reader::{Dimension, GeometryType};
+
+    const STATIONS: &str = "\
+name,x,y,count,score,active,note
+\"Summit, north\",138.7,35.4,3,1.5,true,\"said \"\"hi\"\"\"
+harbor,139.6,35.4,,2,FALSE,
+unplaced,,,1,,,
+broken,abc,35.0,2,1,true,x
+short,1,2
+";
+
+    fn lon_lat() -> CsvImportOptions {
+        CsvImportOptions::new(GeometryFrom::LonLat {
+            lon: "x".to_string(),
+            lat: "y".to_string(),
+        })
+    }
+
+    fn point_wkb(x: f64, y: f64) -> Vec<u8> {
+        let point = wkt::types::Point::from_coord(wkt::types::Coord {
+            x,
+            y,
+            z: None,
+            m: None,
+        });
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &point, &Default::default()).expect("wkb");
+        buf
+    }
+
+    #[test]
+    fn strict_import_reports_the_malformed_line() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let err = gpkg
+            .create_layer_from_csv(STATIONS.as_bytes(), "stations", &lon_lat())
+            .expect_err("malformed row");
+        assert!(
+            matches!(&err, GpkgError::InvalidCsv { line: 5, message }
+                if message.contains("'abc'")),
+            "{err}"
+        );
+        // The layer is rolled back with the rows.
+        assert!(gpkg.list_layers()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_import_skips_malformed_rows() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let options = CsvImportOptions {
+            strict: false,
+            ..lon_lat()
+        };
+        let layer = gpkg.create_layer_from_csv(STATIONS.as_bytes(), "stations", &options)?;
+
+        let columns: Vec<_> = layer
+            .property_columns
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.column_type))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("name", ColumnType::Varchar),
+                ("count", ColumnType::Integer),
+                ("score", ColumnType::Double),
+                ("active", ColumnType::Boolean),
+                ("note", ColumnType::Varchar),
+            ]
+        );
+        assert_eq!(layer.geometry_type, GeometryType::Point);
+        assert_eq!(layer.srs_id, 4326);
+
+        let features = layer.features()?;
+        assert_eq!(features.len(), 3);
+        assert_eq!(
+            features[0].properties(),
+            [
+                Value::Text("Summit, north".to_string()),
+                Value::Integer(3),
+                Value::Real(1.5),
+                Value::Integer(1),
+                Value::Text("said \"hi\"".to_string()),
+            ]
+        );
+        assert_eq!(
+            features[0].geometry()?.buf(),
+            point_wkb(138.7, 35.4).as_slice()
+        );
+        assert_eq!(
+            features[1].properties(),
+            [
+                Value::Text("harbor".to_string()),
+                Value::Null,
+                Value::Real(2.0),
+                Value::Integer(0),
+                Value::Null,
+            ]
+        );
+        assert!(features[2].geometry_opt()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn imports_wkt_with_type_overrides() -> Result<()> {
+        let csv = "\
+id;wkt;code
+1;POINT Z (1 2 3);007
+2;;010
+3;LINESTRING Z (0 0 0, 1 1 1);
+";
+        let gpkg = Gpkg::open_in_memory()?;
+        let mut options = CsvImportOptions::new(GeometryFrom::Wkt {
+            column: "wkt".to_string(),
+        });
+        options.delimiter = b';';
+        options.srs_id = 0;
+        options
+            .column_types
+            .insert("code".to_string(), ColumnType::Varchar);
+        let layer = gpkg.create_layer_from_csv(csv.as_bytes(), "mixed", &options)?;
+
+        assert_eq!(layer.geometry_type_name, "GEOMETRY");
+        assert_eq!(layer.geometry_dimension, Dimension::Xyz);
+        let features = layer.features()?;
+        assert_eq!(features.len(), 3);
+        assert_eq!(
+            features[0].property("code"),
+            Some(Value::Text("007".to_string()))
+        );
+        assert_eq!(features[0].property("id"), Some(Value::Integer(1)));
+        assert!(features[1].geometry_opt()?.is_none());
+        assert_eq!(
+            features[2].geometry()?.geometry_type(),
+            GeometryType::LineString
+        );
+
+        let err = gpkg
+            .create_layer_from_csv(csv.as_bytes(), "missing", &lon_lat())
+            .expect_err("no x column");
+        assert!(
+            matches!(err, GpkgError::InvalidCsv { line: 1, .. }),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn imports_mixed_wkt_as_geometry_layer() -> Result<()> {
+        let csv = "\
+name,wkt
+a,POINT (1 2)
+b,\"LINESTRING (0 0, 1 1)\"
+c,\"POLYGON ((0 0, 1 0, 1 1, 0 0))\"
+";
+        let gpkg = Gpkg::open_in_memory()?;
+        let options = CsvImportOptions::new(GeometryFrom::Wkt {
+            column: "wkt".to_string(),
+        });
+        let layer = gpkg.create_layer_from_csv(csv.as_bytes(), "mixed", &options)?;
+
+        assert_eq!(layer.geometry_type_name, "GEOMETRY");
+        assert_eq!(gpkg.get_layer("mixed")?.geometry_type_name, "GEOMETRY");
+        assert_eq!(layer.count()?, 3);
+        let report = gpkg.validate()?;
+        assert!(report.is_valid(), "{:?}", report.issues);
+        Ok(())
+    }
+}
@@ -8,12 +8,24 @@ pub enum GpkgError {
     Sql(rusqlite::Error),
     /// Wraps errors returned by the `wkb` crate.
     Wkb(wkb::error::WkbError),
+    /// Wraps I/O errors, e.g. from writing an export.
+    Io(std::io::Error),
     /// Wraps errors returned by Arrow APIs.
     #[cfg(feature = "arrow")]
     Arrow(arrow_schema::ArrowError),
+    /// Wraps errors returned by the Parquet writer.
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
     /// Wraps errors returned by GeoArrow APIs as strings.
     #[cfg(feature = "arrow")]
     GeoArrow(String),
+    /// Wraps errors returned by the FlatGeobuf writer as strings, and
+    /// features that can't be written as FlatGeobuf.
+    #[cfg(feature = "flatgeobuf")]
+    FlatGeobuf(String),
+    /// Invalid WKT, or a geometry that could not be written as WKT.
+    #[cfg(feature = "wkt")]
+    Wkt(String),
     /// A geometry type in metadata could not be mapped to a supported WKB geometry type.
     UnsupportedGeometryType(String),
     /// A column type declared in SQLite metadata is not supported by this crate.
@@ -31,9 +43,41 @@ pub enum GpkgError {
         expected: usize,
         got: usize,
     },
+    /// A geometry does not match the geometry type or dimension of the layer.
+    GeometryTypeMismatch {
+        expected: wkb::reader::GeometryType,
+        expected_dimension: wkb::reader::Dimension,
+        actual: wkb::reader::GeometryType,
+        actual_dimension: wkb::reader::Dimension,
+    },
+    /// A geometry cannot be represented by `geo_types`, which supports
+    /// neither Z/M coordinates nor empty points.
+    #[cfg(feature = "geo")]
+    UnsupportedGeoTypesGeometry {
+        geometry_type: wkb::reader::GeometryType,
+        dimension: wkb::reader::Dimension,
+    },
+    /// Invalid GeoJSON input, or GeoJSON that cannot be imported into a layer.
+    #[cfg(feature = "geojson")]
+    InvalidGeoJson(String),
+    /// A GeoJSON property has values of incompatible types and
+    /// `GeoJsonImportOptions::widen_conflicting_types` is disabled.
+    #[cfg(feature = "geojson")]
+    GeoJsonPropertyTypeConflict {
+        property: String,
+        first: crate::types::ColumnType,
+        second: crate::types::ColumnType,
+    },
+    /// A CSV row that can't be read or imported, with its 1-based line number.
+    #[cfg(feature = "csv")]
+    InvalidCsv {
+        line: u64,
+        message: String,
+    },
     /// Invalid GeoPackage geometry flags byte.
     InvalidGpkgGeometryFlags(u8),
-    /// GeoPackage geometry blob is too short for the fixed header.
+    /// GeoPackage geometry blob is too short for the fixed header, or has no
+    /// WKB after the header.
     InvalidGpkgGeometryLength {
         len: usize,
         minimum: usize,
@@ -43,11 +87,19 @@ pub enum GpkgError {
         len: usize,
         required: usize,
     },
+    /// GeoPackage geometry blob has a version other than 0 (version 1).
+    UnsupportedGpkgGeometryVersion(u8),
     /// Dynamic `Value` type did not match the expected conversion target.
     ValueTypeMismatch {
         expected: &'static str,
         actual: &'static str,
     },
+    /// A property value does not match the type of its column (strict mode only).
+    PropertyTypeMismatch {
+        column: String,
+        expected: &'static str,
+        actual: &'static str,
+    },
     /// Numeric conversion failed because the value is out of range.
     ValueOutOfRange {
         target: &'static str,
@@ -56,10 +108,73 @@ pub enum GpkgError {
     MissingProperty {
         property: String,
     },
+    /// Converting the value of a property failed; `source` is the underlying
+    /// error.
+    InvalidPropertyValue {
+        property: String,
+        source: Box<GpkgError>,
+    },
+    /// Two layers can't exchange features; `differences` describes each
+    /// mismatching column, the geometry type or the SRS.
+    IncompatibleSchema {
+        differences: Vec<String>,
+    },
+    /// A `NOT NULL` column without a default value was given no value.
+    MissingRequiredProperty {
+        column: String,
+    },
+    /// A SQL `WHERE` expression passed to a filtered query contains `;`.
+    InvalidWhereClause {
+        clause: String,
+    },
+    /// Inserting the feature at `index` of a bulk insert failed. The whole
+    /// batch was rolled back.
+    InsertManyFailed {
+        index: usize,
+        source: Box<GpkgError>,
+    },
+    /// No feature has the given primary key.
+    FeatureNotFound {
+        id: i64,
+    },
+    /// A feature with the same primary key already exists.
+    DuplicatePrimaryKey {
+        id: i64,
+    },
+    /// The GeoPackage file to open doesn't exist, and the open flags don't
+    /// allow creating it (e.g. `Gpkg::open_read_only()`).
+    FileNotFound {
+        path: std::path::PathBuf,
+    },
+    /// The destination file already exists and overwriting was not requested.
+    FileAlreadyExists {
+        path: std::path::PathBuf,
+    },
     /// A layer with the same name already exists.
     LayerAlreadyExists {
         layer_name: String,
     },
+    /// No table with the name is registered in `gpkg_contents`.
+    LayerNotFound {
+        layer_name: String,
+    },
+    /// No row of `gpkg_metadata` has the id.
+    MetadataNotFound {
+        id: i64,
+    },
+    /// The identifier is already used by another table in `gpkg_contents`.
+    DuplicateIdentifier {
+        identifier: String,
+        layer_name: String,
+    },
+    /// The layer already has an rtree spatial index.
+    SpatialIndexAlreadyExists {
+        layer_name: String,
+    },
+    /// The layer has no rtree spatial index.
+    MissingSpatialIndex {
+        layer_name: String,
+    },
     /// Attribute table must not contain geometry-typed columns.
     GeometryColumnInAttributeTable {
         column: String,
@@ -68,6 +183,10 @@ pub enum GpkgError {
     MissingSpatialRefSysId {
         srs_id: u32,
     },
+    /// The geometry or primary key column of a layer cannot be dropped.
+    CannotDropColumn {
+        column: String,
+    },
     /// Layer schema has multiple primary key columns, which is unsupported.
     CompositePrimaryKeyUnsupported {
         layer_name: String,
@@ -80,6 +199,12 @@ pub enum GpkgError {
     MissingGeometryColumn {
         layer_name: String,
     },
+    /// The column given as the id column of a layer doesn't exist, or isn't
+    /// the primary key of the table.
+    InvalidIdColumn {
+        layer_name: String,
+        column: String,
+    },
     /// Attempted to use `get_layer()` on an attribute table (use `get_attribute_table()` instead).
     NotAFeatureLayer {
         layer_name: String,
@@ -93,8 +218,37 @@ pub enum GpkgError {
         layer_name: String,
         data_type: String,
     },
+    /// SQLite did not switch to the requested journal mode (e.g. WAL on an in-memory database).
+    JournalModeNotApplied {
+        requested: &'static str,
+        actual: String,
+    },
+    /// The connection is still shared with a layer or attribute table.
+    ConnectionInUse,
+    /// An in-memory database cannot be opened by more than one connection.
+    InMemoryDatabase,
+    /// The connection reads a dump opened by `Gpkg::from_bytes_read_only()`,
+    /// which must outlive it.
+    DumpInUse,
+    /// A progress callback returned `ControlFlow::Break`; the operation was rolled back.
+    Cancelled,
+    /// A WKT2 definition was given but the `gpkg_crs_wkt` extension is not enabled.
+    CrsWktExtensionNotEnabled,
     /// A feature row has a `NULL` geometry value.
     NullGeometryValue,
+    /// A TEXT value is not valid UTF-8.
+    InvalidUtf8Text {
+        column: String,
+        /// The primary key of the row, if known.
+        id: Option<i64>,
+    },
+    /// Reading a feature of a layer failed; `source` is the underlying error.
+    Feature {
+        layer_name: String,
+        /// The primary key of the feature, if known.
+        id: Option<i64>,
+        source: Box<GpkgError>,
+    },
     /// Hybrid/custom VFS registration or usage failed.
     Vfs(String),
     /// Arrow reader observed a value type that did not match the expected Arrow builder type.
@@ -103,6 +257,18 @@ pub enum GpkgError {
         expected: &'static str,
         actual: &'static str,
     },
+    /// Arrow reader could not parse a DATE or DATETIME text value as ISO 8601.
+    #[cfg(feature = "arrow")]
+    InvalidArrowTemporalValue {
+        expected: &'static str,
+        value: String,
+    },
+    /// A DATE or DATETIME text value could not be parsed as ISO 8601.
+    #[cfg(feature = "chrono")]
+    InvalidTemporalValue {
+        expected: &'static str,
+        value: String,
+    },
     ReadOnly,
 }
 
@@ -111,10 +277,17 @@ impl fmt::Display for GpkgError {
         match self {
             Self::Sql(err) => write!(f, "{err}"),
             Self::Wkb(err) => write!(f, "{err}"),
+            Self::Io(err) => write!(f, "{err}"),
             #[cfg(feature = "arrow")]
             Self::Arrow(err) => write!(f, "{err}"),
             #[cfg(feature = "arrow")]
             Self::GeoArrow(err) => write!(f, "{err}"),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => write!(f, "Parquet error: {err}"),
+            #[cfg(feature = "flatgeobuf")]
+            Self::FlatGeobuf(err) => write!(f, "FlatGeobuf error: {err}"),
+            #[cfg(feature = "wkt")]
+            Self::Wkt(err) => write!(f, "wkt error: {err}"),
             Self::UnsupportedGeometryType(ty) => write!(f, "unsupported geometry type: {ty}"),
             Self::UnsupportedColumnType {
                 column,
@@ -129,6 +302,31 @@ impl fmt::Display for GpkgError {
             Self::InvalidPropertyCount { expected, got } => {
                 write!(f, "invalid property count: expected {expected}, got {got}")
             }
+            #[cfg(feature = "geo")]
+            Self::UnsupportedGeoTypesGeometry {
+                geometry_type,
+                dimension,
+            } => {
+                write!(
+                    f,
+                    "cannot convert {geometry_type:?} geometry ({dimension:?}) to geo-types: only non-empty XY geometries are supported"
+                )
+            }
+            #[cfg(feature = "geojson")]
+            Self::InvalidGeoJson(msg) => write!(f, "invalid GeoJSON: {msg}"),
+            #[cfg(feature = "geojson")]
+            Self::GeoJsonPropertyTypeConflict {
+                property,
+                first,
+                second,
+            } => write!(
+                f,
+                "GeoJSON property '{property}' has conflicting types: {first:?} and {second:?}"
+            ),
+            #[cfg(feature = "csv")]
+            Self::InvalidCsv { line, message } => {
+                write!(f, "invalid CSV at line {line}: {message}")
+            }
             Self::InvalidGpkgGeometryFlags(flags) => {
                 write!(f, "invalid gpkg geometry flags: {flags:#04x}")
             }
@@ -144,6 +342,9 @@ impl fmt::Display for GpkgError {
                     "invalid gpkg geometry envelope length: got {len} bytes, required {required}"
                 )
             }
+            Self::UnsupportedGpkgGeometryVersion(version) => {
+                write!(f, "unsupported gpkg geometry version: {version}")
+            }
             Self::ValueTypeMismatch { expected, actual } => {
                 write!(f, "expected {expected}, got {actual}")
             }
@@ -151,18 +352,81 @@ impl fmt::Display for GpkgError {
                 write!(f, "value out of range for {target}")
             }
             Self::MissingProperty { property } => write!(f, "missing property: {property}"),
+            Self::InvalidPropertyValue { property, source } => {
+                write!(f, "invalid value of property '{property}': {source}")
+            }
+            Self::IncompatibleSchema { differences } => {
+                write!(f, "incompatible layer schemas: {}", differences.join("; "))
+            }
+            Self::MissingRequiredProperty { column } => {
+                write!(f, "no value for NOT NULL column: {column}")
+            }
+            Self::InvalidWhereClause { clause } => {
+                write!(f, "WHERE clause must not contain ';': {clause}")
+            }
+            Self::InsertManyFailed { index, source } => {
+                write!(f, "failed to insert feature at index {index}: {source}")
+            }
+            Self::GeometryTypeMismatch {
+                expected,
+                expected_dimension,
+                actual,
+                actual_dimension,
+            } => write!(
+                f,
+                "geometry type mismatch: expected {expected:?} ({expected_dimension:?}), got {actual:?} ({actual_dimension:?})"
+            ),
+            Self::PropertyTypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "type mismatch for column {column}: expected {expected}, got {actual}"
+            ),
+            Self::FeatureNotFound { id } => write!(f, "no feature with primary key {id}"),
+            Self::DuplicatePrimaryKey { id } => {
+                write!(f, "a feature with primary key {id} already exists")
+            }
             Self::LayerAlreadyExists { layer_name } => {
                 write!(f, "layer already exists: {layer_name}")
             }
+            Self::LayerNotFound { layer_name } => write!(f, "layer not found: {layer_name}"),
+            Self::MetadataNotFound { id } => write!(f, "no metadata with id {id}"),
+            Self::DuplicateIdentifier {
+                identifier,
+                layer_name,
+            } => write!(
+                f,
+                "identifier {identifier:?} is already used by layer {layer_name}"
+            ),
+            Self::SpatialIndexAlreadyExists { layer_name } => {
+                write!(f, "layer already has a spatial index: {layer_name}")
+            }
+            Self::MissingSpatialIndex { layer_name } => {
+                write!(f, "layer has no spatial index: {layer_name}")
+            }
             Self::GeometryColumnInAttributeTable { column } => {
                 write!(
                     f,
                     "attribute tables must not contain geometry columns, but found column '{column}'"
                 )
             }
+            Self::CrsWktExtensionNotEnabled => {
+                write!(
+                    f,
+                    "the gpkg_crs_wkt extension must be enabled to store WKT2 definitions"
+                )
+            }
             Self::MissingSpatialRefSysId { srs_id } => {
                 write!(f, "srs_id {srs_id} not found in gpkg_spatial_ref_sys")
             }
+            Self::CannotDropColumn { column } => {
+                write!(
+                    f,
+                    "cannot drop column '{column}' because it is the geometry or primary key column"
+                )
+            }
             Self::CompositePrimaryKeyUnsupported { layer_name } => write!(
                 f,
                 "composite primary keys are not supported yet for layer: {layer_name}"
@@ -173,6 +437,10 @@ impl fmt::Display for GpkgError {
             Self::MissingGeometryColumn { layer_name } => {
                 write!(f, "no geometry column found for layer: {layer_name}")
             }
+            Self::InvalidIdColumn { layer_name, column } => write!(
+                f,
+                "column '{column}' can't be used as the id column of layer: {layer_name}"
+            ),
             Self::NotAFeatureLayer { layer_name } => {
                 write!(
                     f,
@@ -194,7 +462,52 @@ impl fmt::Display for GpkgError {
                     "data_type '{data_type}' is not supported yet (table '{layer_name}')"
                 )
             }
+            Self::JournalModeNotApplied { requested, actual } => {
+                write!(
+                    f,
+                    "journal_mode '{requested}' could not be applied (got '{actual}')"
+                )
+            }
+            Self::ConnectionInUse => {
+                write!(
+                    f,
+                    "connection is still in use by a layer or attribute table"
+                )
+            }
+            Self::InMemoryDatabase => write!(
+                f,
+                "an in-memory database cannot be shared between connections"
+            ),
+            Self::DumpInUse => write!(f, "connection reads a dump that must outlive it"),
+            Self::Cancelled => write!(f, "operation cancelled by the progress callback"),
+            Self::FileNotFound { path } => {
+                write!(f, "GeoPackage file not found: {}", path.display())
+            }
+            Self::FileAlreadyExists { path } => {
+                write!(f, "file already exists: {}", path.display())
+            }
             Self::NullGeometryValue => write!(f, "feature has null geometry value"),
+            Self::InvalidUtf8Text { column, id } => match id {
+                Some(id) => write!(
+                    f,
+                    "column '{column}' of the row with primary key {id} contains invalid UTF-8 text"
+                ),
+                None => write!(f, "column '{column}' contains invalid UTF-8 text"),
+            },
+            Self::Feature {
+                layer_name,
+                id,
+                source,
+            } => match id {
+                Some(id) => write!(
+                    f,
+                    "failed to read feature {id} of layer '{layer_name}': {source}"
+                ),
+                None => write!(
+                    f,
+                    "failed to read a feature of layer '{layer_name}': {source}"
+                ),
+            },
             Self::Vfs(err) => write!(f, "vfs error: {err}"),
             #[cfg(feature = "arrow")]
             Self::InvalidArrowValue { expected, actual } => {
@@ -203,6 +516,20 @@ impl fmt::Display for GpkgError {
                     "invalid value for Arrow conversion: expected {expected}, got {actual}"
                 )
             }
+            #[cfg(feature = "arrow")]
+            Self::InvalidArrowTemporalValue { expected, value } => {
+                write!(
+                    f,
+                    "invalid value for Arrow conversion: expected {expected}, got {value:?}"
+                )
+            }
+            #[cfg(feature = "chrono")]
+            Self::InvalidTemporalValue { expected, value } => {
+                write!(
+                    f,
+                    "invalid temporal value: expected {expected}, got {value:?}"
+                )
+            }
             Self::ReadOnly => write!(f, "operation not allowed on read-only connection"),
         }
     }
@@ -213,8 +540,14 @@ impl Error for GpkgError {
         match self {
             Self::Sql(err) => Some(err),
             Self::Wkb(err) => Some(err),
+            Self::Io(err) => Some(err),
             #[cfg(feature = "arrow")]
             Self::Arrow(err) => Some(err),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => Some(err),
+            Self::InsertManyFailed { source, .. }
+            | Self::Feature { source, .. }
+            | Self::InvalidPropertyValue { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -222,7 +555,25 @@ impl Error for GpkgError {
 
 impl From<rusqlite::Error> for GpkgError {
     fn from(err: rusqlite::Error) -> Self {
-        Self::Sql(err)
+        // Row conversions that run inside rusqlite callbacks pass our errors
+        // through as a conversion failure.
+        match err {
+            rusqlite::Error::FromSqlConversionFailure(idx, ty, inner) => {
+                match inner.downcast::<GpkgError>() {
+                    Ok(err) => *err,
+                    Err(inner) => {
+                        Self::Sql(rusqlite::Error::FromSqlConversionFailure(idx, ty, inner))
+                    }
+                }
+            }
+            err => Self::Sql(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for GpkgError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
     }
 }
 
@@ -247,3 +598,10 @@ impl From<arrow_schema::ArrowError> for GpkgError {
         GpkgError::Arrow(value)
     }
 }
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for GpkgError {
+    fn from(value: parquet::errors::ParquetError) -> Self {
+        GpkgError::Parquet(value)
+    }
+}
@@ -0,0 +1,423 @@
+//! FlatGeobuf export of feature layers.
+
+use crate::error::{GpkgError, Result};
+use crate::geozero_source::column_value;
+use crate::gpkg::GpkgLayer;
+use crate::types::{ColumnSpec, ColumnType, Value};
+use flatgeobuf::geozero::{GeomProcessor, GeozeroGeometry, PropertyProcessor};
+use flatgeobuf::{FgbCrs, FgbWriter, FgbWriterOptions};
+use geo_traits::{GeometryTrait, GeometryType, PointTrait};
+use std::io::Write;
+use wkb::reader::{Dimension, Wkb};
+
+/// Write the features of `layer` into `w` as a FlatGeobuf file.
+pub(crate) fn write_layer<W: Write>(layer: &GpkgLayer, w: W, spatial_index: bool) -> Result<()> {
+    let srs = layer.srs()?;
+    let has_crs = !srs.organization.eq_ignore_ascii_case("NONE") && srs.definition != "undefined";
+    let crs = if has_crs {
+        FgbCrs {
+            org: Some(&srs.organization),
+            code: srs.organization_coordsys_id,
+            name: Some(&srs.srs_name),
+            description: srs.description.as_deref(),
+            wkt: Some(&srs.definition),
+            code_string: None,
+        }
+    } else {
+        FgbCrs::default()
+    };
+    let title = layer.identifier()?;
+    let description = layer.description()?;
+    let options = FgbWriterOptions {
+        write_index: spatial_index,
+        // Keep the geometries as they are stored.
+        detect_type: false,
+        promote_to_multi: false,
+        crs,
+        has_z: matches!(layer.geometry_dimension, Dimension::Xyz | Dimension::Xyzm),
+        has_m: matches!(layer.geometry_dimension, Dimension::Xym | Dimension::Xyzm),
+        title: title.as_deref(),
+        description: description.as_deref(),
+        ..Default::default()
+    };
+    let dataset_type = geometry_type(&layer.geometry_type_name);
+    let mut fgb = FgbWriter::create_with_options(&layer.layer_name, dataset_type, options)
+        .map_err(fgb_error)?;
+    for spec in &layer.property_columns {
+        fgb.add_column(&spec.name, column_type(spec.column_type), |_, column| {
+            column.nullable = !spec.not_null;
+        });
+    }
+
+    layer.for_each_feature(|feature| {
+        let geometry = feature.geometry_opt()?;
+        let geometry = match &geometry {
+            // The FlatGeobuf writer rejects empty points in the WKB.
+            Some(wkb) if is_empty_point(wkb) => {
+                FeatureGeometry::Empty(flatgeobuf::GeometryType::Point, wkb.dimension())
+            }
+            Some(wkb) => FeatureGeometry::Wkb(wkb.buf()),
+            None => FeatureGeometry::Empty(dataset_type, layer.geometry_dimension),
+        };
+        let mut result = Ok(());
+        fgb.add_feature_geom(geometry, |writer| {
+            result = write_properties(writer, &layer.property_columns, feature.properties());
+        })
+        .and(result)
+        .map_err(fgb_error)?;
+        Ok(())
+    })?;
+
+    fgb.write(w).map_err(fgb_error)
+}
+
+fn write_properties<P: PropertyProcessor>(
+    processor: &mut P,
+    columns: &[ColumnSpec],
+    values: &[Value],
+) -> flatgeobuf::geozero::error::Result<()> {
+    for (i, (spec, value)) in columns.iter().zip(values).enumerate() {
+        // FlatGeobuf represents NULL by omitting the property.
+        if let Some(value) = column_value(spec.column_type, value) {
+            processor.property(i, &spec.name, &value)?;
+        }
+    }
+    Ok(())
+}
+
+fn geometry_type(geometry_type_name: &str) -> flatgeobuf::GeometryType {
+    use flatgeobuf::GeometryType as Fgb;
+
+    match geometry_type_name.to_ascii_uppercase().as_str() {
+        "POINT" => Fgb::Point,
+        "LINESTRING" => Fgb::LineString,
+        "POLYGON" => Fgb::Polygon,
+        "MULTIPOINT" => Fgb::MultiPoint,
+        "MULTILINESTRING" => Fgb::MultiLineString,
+        "MULTIPOLYGON" => Fgb::MultiPolygon,
+        // The other types allow subtypes (e.g. a CURVEPOLYGON layer can
+        // contain polygons), which FlatGeobuf only allows with per-feature
+        // geometry types.
+        _ => Fgb::Unknown,
+    }
+}
+
+enum FeatureGeometry<'a> {
+    Wkb(&'a [u8]),
+    Empty(flatgeobuf::GeometryType, Dimension),
+}
+
+impl GeozeroGeometry for FeatureGeometry<'_> {
+    fn process_geom<P: GeomProcessor>(
+        &self,
+        processor: &mut P,
+    ) -> flatgeobuf::geozero::error::Result<()> {
+        match self {
+            Self::Wkb(buf) => flatgeobuf::geozero::wkb::Wkb(buf).process_geom(processor),
+            Self::Empty(geometry_type, dimension) => {
+                write_empty_geometry(processor, *geometry_type, *dimension)
+            }
+        }
+    }
+}
+
+// FlatGeobuf has no NULL geometry that every reader accepts, so a NULL
+// geometry is written as an empty geometry of the layer type, or as an empty
+// point (NaN coordinates) for a layer without a single geometry type.
+fn write_empty_geometry<P: GeomProcessor>(
+    processor: &mut P,
+    dataset_type: flatgeobuf::GeometryType,
+    dimension: Dimension,
+) -> flatgeobuf::geozero::error::Result<()> {
+    use flatgeobuf::GeometryType as Fgb;
+
+    match dataset_type {
+        Fgb::LineString => {
+            processor.linestring_begin(true, 0, 0)?;
+            processor.linestring_end(true, 0)
+        }
+        Fgb::Polygon => {
+            processor.polygon_begin(true, 0, 0)?;
+            processor.polygon_end(true, 0)
+        }
+        Fgb::MultiPoint => {
+            processor.multipoint_begin(0, 0)?;
+            processor.multipoint_end(0)
+        }
+        Fgb::MultiLineString => {
+            processor.multilinestring_begin(0, 0)?;
+            processor.multilinestring_end(0)
+        }
+        // A multipolygon without parts can't be read back, so this is one
+        // without any ring.
+        Fgb::MultiPolygon => {
+            processor.multipolygon_begin(1, 0)?;
+            processor.polygon_begin(false, 0, 0)?;
+            processor.polygon_end(false, 0)?;
+            processor.multipolygon_end(0)
+        }
+        _ => {
+            let z = matches!(dimension, Dimension::Xyz | Dimension::Xyzm).then_some(f64::NAN);
+            let m = matches!(dimension, Dimension::Xym | Dimension::Xyzm).then_some(f64::NAN);
+            processor.point_begin(0)?;
+            processor.coordinate(f64::NAN, f64::NAN, z, m, None, None, 0)?;
+            processor.point_end(0)
+        }
+    }
+}
+
+fn is_empty_point(wkb: &Wkb<'_>) -> bool {
+    matches!(wkb.as_type(), GeometryType::Point(point) if point.coord().is_none())
+}
+
+fn column_type(column_type: ColumnType) -> flatgeobuf::ColumnType {
+    use flatgeobuf::ColumnType as Fgb;
+
+    match column_type {
+        ColumnType::Boolean => Fgb::Bool,
+        ColumnType::Varchar => Fgb::String,
+        ColumnType::Double => Fgb::Double,
+        ColumnType::Float => Fgb::Float,
+        ColumnType::Integer => Fgb::Long,
+        ColumnType::MediumInt => Fgb::Int,
+        ColumnType::SmallInt => Fgb::Short,
+        ColumnType::TinyInt => Fgb::Byte,
+        ColumnType::Date | ColumnType::Datetime => Fgb::DateTime,
+        ColumnType::Blob | ColumnType::Geometry => Fgb::Binary,
+    }
+}
+
+fn fgb_error(err: impl std::fmt::Display) -> GpkgError {
+    GpkgError::FlatGeobuf(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ColumnSpec, ColumnType, CreateLayerOptions};
+    use crate::{Gpkg, GpkgLayer, Result, params};
+    use flatgeobuf::geozero::{FeatureProperties, ToWkt};
+    use flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geo_types::Point;
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::str::FromStr;
+    use wkb::reader::{Dimension, GeometryType};
+
+    fn create_layer(gpkg: &Gpkg) -> Result<GpkgLayer> {
+        let column = |name: &str, column_type| ColumnSpec {
+            name: name.to_string(),
+            column_type,
+            ..Default::default()
+        };
+        let columns = vec![
+            ColumnSpec {
+                not_null: true,
+                ..column("name", ColumnType::Varchar)
+            },
+            column("count", ColumnType::Integer),
+            column("active", ColumnType::Boolean),
+        ];
+        let layer = gpkg.create_layer(
+            "stations",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.set_description("Weather stations")?;
+        Ok(layer)
+    }
+
+    // Read the WKT and the properties of each feature.
+    fn read_features(bytes: &[u8]) -> Vec<(String, HashMap<String, String>)> {
+        let reader = FgbReader::open(Cursor::new(bytes)).expect("valid FlatGeobuf");
+        let mut features = reader.select_all().expect("features");
+        let mut out = Vec::new();
+        while let Some(feature) = features.next().expect("feature") {
+            let wkt = feature.to_wkt().unwrap_or_default();
+            out.push((wkt, feature.properties().expect("properties")));
+        }
+        out
+    }
+
+    #[test]
+    fn exports_header_and_features() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+        layer.insert(Point::new(1.5, 2.5), params!["summit", 3_i64, true])?;
+        layer.insert(Point::new(-1.0, 0.0), params!["harbor", None::<i64>, false])?;
+        let mut builder = layer.feature_builder();
+        builder.set("name", "unplaced")?;
+        builder.insert()?;
+
+        let mut buf = Vec::new();
+        layer.to_flatgeobuf_writer(&mut buf, false)?;
+
+        let reader = FgbReader::open(Cursor::new(&buf)).expect("valid FlatGeobuf");
+        let header = reader.header();
+        assert_eq!(header.name(), Some("stations"));
+        assert_eq!(header.geometry_type(), flatgeobuf::GeometryType::Point);
+        assert_eq!(header.features_count(), 3);
+        assert_eq!(header.index_node_size(), 0);
+        assert_eq!(header.description(), Some("Weather stations"));
+        let crs = header.crs().expect("crs");
+        assert_eq!((crs.org(), crs.code()), (Some("EPSG"), 4326));
+        assert!(crs.wkt().expect("wkt").contains("WGS 84"));
+        let columns: Vec<_> = header
+            .columns()
+            .expect("columns")
+            .iter()
+            .map(|column| (column.name().to_string(), column.type_(), column.nullable()))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("name".to_string(), flatgeobuf::ColumnType::String, false),
+                ("count".to_string(), flatgeobuf::ColumnType::Long, true),
+                ("active".to_string(), flatgeobuf::ColumnType::Bool, true),
+            ]
+        );
+
+        let features = read_features(&buf);
+        let properties = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert_eq!(features[0].0, "POINT(1.5 2.5)");
+        assert_eq!(
+            features[0].1,
+            properties(&[("name", "summit"), ("count", "3"), ("active", "true")])
+        );
+        assert_eq!(features[1].0, "POINT(-1 0)");
+        assert_eq!(
+            features[1].1,
+            properties(&[("name", "harbor"), ("active", "false")])
+        );
+        assert_eq!(features[2].0, "POINT(EMPTY)");
+        assert_eq!(features[2].1, properties(&[("name", "unplaced")]));
+        Ok(())
+    }
+
+    #[test]
+    fn null_and_empty_geometries_are_written_as_empty() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let polygons = gpkg.create_layer(
+            "polygons",
+            "geom",
+            GeometryType::Polygon,
+            Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        polygons.feature_builder().insert()?;
+        let mixed = gpkg.create_layer_with_options(
+            "mixed",
+            "geom",
+            GeometryType::GeometryCollection,
+            Dimension::Xy,
+            4326,
+            &[],
+            &CreateLayerOptions {
+                any_geometry_type: true,
+                ..Default::default()
+            },
+        )?;
+        mixed.insert(Point::new(1.0, 2.0), params![])?;
+        mixed.insert(
+            wkt::Wkt::<f64>::from_str("POINT EMPTY").expect("wkt"),
+            params![],
+        )?;
+        mixed.feature_builder().insert()?;
+
+        let mut buf = Vec::new();
+        polygons.to_flatgeobuf_writer(&mut buf, false)?;
+        let features = read_features(&buf);
+        assert_eq!(features[0].0, "POLYGON(EMPTY)");
+
+        let mut buf = Vec::new();
+        mixed.to_flatgeobuf_writer(&mut buf, true)?;
+        let reader = FgbReader::open(Cursor::new(&buf)).expect("valid FlatGeobuf");
+        assert_eq!(
+            reader.header().geometry_type(),
+            flatgeobuf::GeometryType::Unknown
+        );
+        let features = read_features(&buf);
+        let wkts: Vec<_> = features.into_iter().map(|(wkt, _)| wkt).collect();
+        assert_eq!(wkts, ["POINT(1 2)", "POINT(EMPTY)", "POINT(EMPTY)"]);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_z_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "peaks",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xyz,
+            4326,
+            &[],
+        )?;
+        layer.insert(
+            wkt::Wkt::<f64>::from_str("POINT Z (1 2 3)").expect("wkt"),
+            params![],
+        )?;
+
+        let mut buf = Vec::new();
+        layer.to_flatgeobuf_writer(&mut buf, false)?;
+        let reader = FgbReader::open(Cursor::new(&buf)).expect("valid FlatGeobuf");
+        assert!(reader.header().has_z());
+        let mut features = reader.select_all().expect("features");
+        let feature = features.next().expect("feature").expect("one feature");
+        let geometry = feature.geometry().expect("geometry");
+        assert_eq!(
+            geometry.z().map(|z| z.iter().collect::<Vec<_>>()),
+            Some(vec![3.0])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn writes_spatial_index() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+        for i in 0..20 {
+            let name = format!("p{i}");
+            layer.insert(
+                Point::new(f64::from(i), f64::from(i % 5)),
+                params![name, i, true],
+            )?;
+        }
+        let mut builder = layer.feature_builder();
+        builder.set("name", "unplaced")?;
+        builder.insert()?;
+
+        let mut buf = Vec::new();
+        layer.to_flatgeobuf_writer(&mut buf, true)?;
+        let reader = FgbReader::open(Cursor::new(&buf)).expect("valid FlatGeobuf");
+        assert!(reader.header().index_node_size() > 0);
+        assert_eq!(reader.header().features_count(), 21);
+        assert_eq!(
+            reader
+                .header()
+                .envelope()
+                .map(|e| e.iter().collect::<Vec<_>>()),
+            Some(vec![0.0, 0.0, 19.0, 4.0])
+        );
+
+        // The empty point of the NULL geometry never matches a bbox query.
+        let mut hits = reader
+            .select_bbox(-0.5, -0.5, 2.5, 2.5)
+            .expect("indexed features");
+        let mut names = Vec::new();
+        while let Some(feature) = hits.next().expect("feature") {
+            names.push(feature.property::<String>("name").expect("name"));
+        }
+        names.sort();
+        assert_eq!(names, ["p0", "p1", "p2"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,845 @@
+//! GeoJSON export and import of feature layers.
+
+use crate::error::{GpkgError, Result};
+use crate::gpkg::GpkgLayer;
+use crate::types::{ColumnSpec, ColumnType, GeoJsonImportOptions, Value};
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+};
+use serde_json::{Map, Value as JsonValue, json};
+use std::io::{Read, Write};
+use wkb::reader::{Dimension, GeometryType, Wkb};
+
+/// Stream the features of `layer` into `w` as a GeoJSON FeatureCollection.
+pub(crate) fn write_feature_collection<W: Write>(layer: &GpkgLayer, mut w: W) -> Result<()> {
+    let crs = layer.srs_organization()?;
+
+    w.write_all(br#"{"type":"FeatureCollection","#)?;
+    // The `crs` member was removed in RFC 7946, but many readers still use it.
+    if let Some((organization, id)) = crs
+        && organization != "NONE"
+    {
+        let crs = json!({
+            "type": "name",
+            "properties": { "name": format!("urn:ogc:def:crs:{organization}::{id}") },
+        });
+        w.write_all(br#""crs":"#)?;
+        serde_json::to_writer(&mut w, &crs).map_err(std::io::Error::from)?;
+        w.write_all(b",")?;
+    }
+    w.write_all(br#""features":["#)?;
+
+    let mut first = true;
+    layer.for_each_feature(|feature| {
+        if !first {
+            w.write_all(b",")?;
+        }
+        first = false;
+
+        let geometry = match feature.geometry_opt()? {
+            Some(wkb) => geometry_to_json(&wkb),
+            None => JsonValue::Null,
+        };
+        let mut properties = Map::new();
+        for (spec, value) in layer.property_columns.iter().zip(feature.properties()) {
+            properties.insert(spec.name.clone(), value_to_json(spec.column_type, value));
+        }
+        let feature = json!({
+            "type": "Feature",
+            "id": feature.id(),
+            "geometry": geometry,
+            "properties": properties,
+        });
+        serde_json::to_writer(&mut w, &feature).map_err(std::io::Error::from)?;
+        Ok(())
+    })?;
+
+    w.write_all(b"]}")?;
+    w.flush()?;
+    Ok(())
+}
+
+fn value_to_json(column_type: ColumnType, value: &Value) -> JsonValue {
+    match value {
+        Value::Integer(v) if column_type == ColumnType::Boolean => JsonValue::Bool(*v != 0),
+        Value::Integer(v) => JsonValue::from(*v),
+        // JSON has no representation for NaN or infinity, so they become null.
+        Value::Real(v) => serde_json::Number::from_f64(*v)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        Value::Text(v) => JsonValue::String(v.clone()),
+        Value::Null | Value::Blob(_) | Value::Geometry(_) => JsonValue::Null,
+    }
+}
+
+fn geometry_to_json<G: GeometryTrait<T = f64>>(geometry: &G) -> JsonValue {
+    use geo_traits::GeometryType as GeoType;
+
+    match geometry.as_type() {
+        GeoType::Point(point) => json!({
+            "type": "Point",
+            "coordinates": point.coord().map(|c| position(&c)).unwrap_or_default(),
+        }),
+        GeoType::LineString(line) => json!({
+            "type": "LineString",
+            "coordinates": line_string_positions(line),
+        }),
+        GeoType::Polygon(polygon) => json!({
+            "type": "Polygon",
+            "coordinates": polygon_positions(polygon),
+        }),
+        GeoType::MultiPoint(multi) => json!({
+            "type": "MultiPoint",
+            "coordinates": multi
+                .points()
+                .filter_map(|p| p.coord().map(|c| position(&c)))
+                .collect::<Vec<_>>(),
+        }),
+        GeoType::MultiLineString(multi) => json!({
+            "type": "MultiLineString",
+            "coordinates": multi
+                .line_strings()
+                .map(|l| line_string_positions(&l))
+                .collect::<Vec<_>>(),
+        }),
+        GeoType::MultiPolygon(multi) => json!({
+            "type": "MultiPolygon",
+            "coordinates": multi
+                .polygons()
+                .map(|p| polygon_positions(&p))
+                .collect::<Vec<_>>(),
+        }),
+        GeoType::GeometryCollection(collection) => json!({
+            "type": "GeometryCollection",
+            "geometries": collection
+                .geometries()
+                .map(|g| geometry_to_json(&g))
+                .collect::<Vec<_>>(),
+        }),
+        GeoType::Rect(_) | GeoType::Triangle(_) | GeoType::Line(_) => {
+            // No GeoPackage geometry types should reach here.
+            unreachable!()
+        }
+    }
+}
+
+// GeoJSON positions are `[x, y]` or `[x, y, z]`; M values are dropped.
+fn position<C: CoordTrait<T = f64>>(coord: &C) -> Vec<f64> {
+    let (x, y) = coord.x_y();
+    match coord.dim() {
+        Dimensions::Xyz | Dimensions::Xyzm => vec![x, y, coord.nth_or_panic(2)],
+        _ => vec![x, y],
+    }
+}
+
+fn line_string_positions<L: LineStringTrait<T = f64>>(line: &L) -> Vec<Vec<f64>> {
+    line.coords().map(|c| position(&c)).collect()
+}
+
+fn polygon_positions<P: PolygonTrait<T = f64>>(polygon: &P) -> Vec<Vec<Vec<f64>>> {
+    polygon
+        .exterior()
+        .into_iter()
+        .map(|ring| line_string_positions(&ring))
+        .chain(polygon.interiors().map(|ring| line_string_positions(&ring)))
+        .collect()
+}
+
+/// Column and geometry definitions inferred from GeoJSON features.
+pub(crate) struct GeoJsonSchema {
+    /// `None` if the geometry types are mixed, for a `GEOMETRY` layer.
+    pub(crate) geometry_type: Option<GeometryType>,
+    pub(crate) dimension: Dimension,
+    pub(crate) columns: Vec<ColumnSpec>,
+}
+
+/// Read the features of a GeoJSON FeatureCollection, or a single Feature.
+pub(crate) fn read_features<R: Read>(reader: R) -> Result<Vec<JsonValue>> {
+    let mut doc: JsonValue = serde_json::from_reader(reader)
+        .map_err(|err| GpkgError::InvalidGeoJson(err.to_string()))?;
+    match doc.get("type").and_then(JsonValue::as_str) {
+        Some("FeatureCollection") => match doc["features"].take() {
+            JsonValue::Array(features) => Ok(features),
+            _ => Err(invalid("FeatureCollection has no features array")),
+        },
+        Some("Feature") => Ok(vec![doc]),
+        _ => Err(invalid("expected a FeatureCollection or a Feature")),
+    }
+}
+
+/// Infer the columns, geometry type and dimension of a layer from the first
+/// `options.sample_size` features.
+pub(crate) fn infer_schema(
+    features: &[JsonValue],
+    options: &GeoJsonImportOptions,
+) -> Result<GeoJsonSchema> {
+    let sample = match options.sample_size {
+        Some(n) => &features[..n.min(features.len())],
+        None => features,
+    };
+
+    // Columns in the order they first appear, with `None` until a non-null
+    // value is seen.
+    let mut columns: Vec<(String, Option<ColumnType>)> = Vec::new();
+    let mut geometry_types: Vec<GeometryType> = Vec::new();
+    let mut has_z = None;
+    for feature in sample {
+        if let Some(properties) = feature.get("properties").and_then(JsonValue::as_object) {
+            for (name, value) in properties {
+                let column_type = json_column_type(value);
+                match columns.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, existing)) => {
+                        *existing = merge_column_type(name, *existing, column_type, options)?;
+                    }
+                    None => columns.push((name.clone(), column_type)),
+                }
+            }
+        }
+
+        let geometry = &feature["geometry"];
+        if geometry.is_null() {
+            continue;
+        }
+        let geometry_type = json_geometry_type(geometry)?;
+        if !geometry_types.contains(&geometry_type) {
+            geometry_types.push(geometry_type);
+        }
+        // The layer has Z only if every geometry has it.
+        if let Some(z) = first_position(geometry).map(|p| p.len() >= 3) {
+            has_z = Some(has_z.unwrap_or(true) && z);
+        }
+    }
+
+    let geometry_type = match geometry_types.as_slice() {
+        [single] => Some(*single),
+        [a, b] if multi_of(*a) == Some(*b) => Some(*b),
+        [a, b] if multi_of(*b) == Some(*a) => Some(*a),
+        // Mixed (or no) geometry types need a GEOMETRY layer.
+        _ => None,
+    };
+    let dimension = if has_z == Some(true) {
+        Dimension::Xyz
+    } else {
+        Dimension::Xy
+    };
+    let columns = columns
+        .into_iter()
+        .map(|(name, column_type)| ColumnSpec {
+            name,
+            column_type: column_type.unwrap_or(ColumnType::Varchar),
+            ..Default::default()
+        })
+        .collect();
+
+    Ok(GeoJsonSchema {
+        geometry_type,
+        dimension,
+        columns,
+    })
+}
+
+/// Insert GeoJSON features into `layer`, matching properties to columns by
+/// name. Missing properties are inserted as `NULL`, and properties without a
+/// column are ignored.
+pub(crate) fn insert_features(layer: &GpkgLayer, features: &[JsonValue]) -> Result<u64> {
+    let has_z = match layer.geometry_dimension {
+        Dimension::Xy => false,
+        Dimension::Xyz => true,
+        _ => {
+            return Err(invalid(
+                "GeoJSON cannot be imported into a layer with M values",
+            ));
+        }
+    };
+
+    let empty = Map::new();
+    let mut buf = Vec::new();
+    for (index, feature) in features.iter().enumerate() {
+        let properties = feature
+            .get("properties")
+            .and_then(JsonValue::as_object)
+            .unwrap_or(&empty);
+        let values: Vec<Value> = layer
+            .property_columns
+            .iter()
+            .map(|spec| json_to_value(spec.column_type, properties.get(&spec.name)))
+            .collect();
+
+        let geometry = &feature["geometry"];
+        let result = if geometry.is_null() {
+            layer.insert_without_geometry(&values)
+        } else {
+            buf.clear();
+            write_wkb(&mut buf, geometry, has_z, layer.geometry_type).and_then(|_| {
+                let wkb = Wkb::try_new(&buf)?;
+                layer.insert(wkb, &values)
+            })
+        };
+        result.map_err(|err| GpkgError::InsertManyFailed {
+            index,
+            source: Box::new(err),
+        })?;
+    }
+    Ok(features.len() as u64)
+}
+
+fn invalid(msg: &str) -> GpkgError {
+    GpkgError::InvalidGeoJson(msg.to_string())
+}
+
+fn json_column_type(value: &JsonValue) -> Option<ColumnType> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(_) => Some(ColumnType::Boolean),
+        JsonValue::Number(n) if n.is_i64() => Some(ColumnType::Integer),
+        JsonValue::Number(_) => Some(ColumnType::Double),
+        // Arrays and objects are stored as JSON text.
+        JsonValue::String(_) | JsonValue::Array(_) | JsonValue::Object(_) => {
+            Some(ColumnType::Varchar)
+        }
+    }
+}
+
+fn merge_column_type(
+    name: &str,
+    existing: Option<ColumnType>,
+    new: Option<ColumnType>,
+    options: &GeoJsonImportOptions,
+) -> Result<Option<ColumnType>> {
+    use ColumnType::{Double, Integer, Varchar};
+
+    match (existing, new) {
+        (a, None) => Ok(a),
+        (None, b) => Ok(b),
+        (Some(a), Some(b)) if a == b => Ok(Some(a)),
+        (Some(Integer), Some(Double)) | (Some(Double), Some(Integer)) => Ok(Some(Double)),
+        (Some(a), Some(b)) => {
+            if options.widen_conflicting_types {
+                Ok(Some(Varchar))
+            } else {
+                Err(GpkgError::GeoJsonPropertyTypeConflict {
+                    property: name.to_string(),
+                    first: a,
+                    second: b,
+                })
+            }
+        }
+    }
+}
+
+fn json_to_value(column_type: ColumnType, value: Option<&JsonValue>) -> Value {
+    let Some(value) = value else {
+        return Value::Null;
+    };
+    match value {
+        JsonValue::Null => Value::Null,
+        // A column widened to TEXT stores the other values as their JSON text.
+        _ if column_type == ColumnType::Varchar => match value {
+            JsonValue::String(s) => Value::Text(s.clone()),
+            other => Value::Text(other.to_string()),
+        },
+        JsonValue::Bool(b) => Value::from(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) if column_type != ColumnType::Double => Value::Integer(i),
+            _ => n.as_f64().map(Value::Real).unwrap_or(Value::Null),
+        },
+        JsonValue::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn json_geometry_type(geometry: &JsonValue) -> Result<GeometryType> {
+    match geometry.get("type").and_then(JsonValue::as_str) {
+        Some("Point") => Ok(GeometryType::Point),
+        Some("LineString") => Ok(GeometryType::LineString),
+        Some("Polygon") => Ok(GeometryType::Polygon),
+        Some("MultiPoint") => Ok(GeometryType::MultiPoint),
+        Some("MultiLineString") => Ok(GeometryType::MultiLineString),
+        Some("MultiPolygon") => Ok(GeometryType::MultiPolygon),
+        Some("GeometryCollection") => Ok(GeometryType::GeometryCollection),
+        Some(other) => Err(GpkgError::InvalidGeoJson(format!(
+            "unknown geometry type: {other}"
+        ))),
+        None => Err(invalid("geometry has no type")),
+    }
+}
+
+fn multi_of(geometry_type: GeometryType) -> Option<GeometryType> {
+    match geometry_type {
+        GeometryType::Point => Some(GeometryType::MultiPoint),
+        GeometryType::LineString => Some(GeometryType::MultiLineString),
+        GeometryType::Polygon => Some(GeometryType::MultiPolygon),
+        _ => None,
+    }
+}
+
+fn first_position(geometry: &JsonValue) -> Option<&Vec<JsonValue>> {
+    if let Some(geometries) = geometry.get("geometries").and_then(JsonValue::as_array) {
+        return geometries.iter().find_map(first_position);
+    }
+    let mut coordinates = geometry.get("coordinates")?.as_array()?;
+    // Descend into the nested arrays until reaching an array of numbers.
+    while let Some(JsonValue::Array(inner)) = coordinates.first() {
+        coordinates = inner;
+    }
+    if coordinates.is_empty() {
+        None
+    } else {
+        Some(coordinates)
+    }
+}
+
+// Write a GeoJSON geometry as little endian WKB. A single geometry is wrapped
+// into its Multi- variant when the layer has that type.
+fn write_wkb(
+    buf: &mut Vec<u8>,
+    geometry: &JsonValue,
+    has_z: bool,
+    layer_type: GeometryType,
+) -> Result<()> {
+    let geometry_type = json_geometry_type(geometry)?;
+    if geometry_type == GeometryType::GeometryCollection {
+        let geometries = geometry
+            .get("geometries")
+            .and_then(JsonValue::as_array)
+            .ok_or_else(|| invalid("GeometryCollection has no geometries array"))?;
+        write_wkb_header(buf, 7, has_z, geometries.len());
+        for geometry in geometries {
+            write_wkb(buf, geometry, has_z, GeometryType::GeometryCollection)?;
+        }
+        return Ok(());
+    }
+
+    let coordinates = geometry
+        .get("coordinates")
+        .ok_or_else(|| invalid("geometry has no coordinates"))?;
+    if multi_of(geometry_type) == Some(layer_type) {
+        write_wkb_header(buf, wkb_type_code(layer_type), has_z, 1);
+        buf.push(0x01);
+        buf.extend_from_slice(&wkb_type_id(wkb_type_code(geometry_type), has_z).to_le_bytes());
+        return write_wkb_coordinates(buf, geometry_type, coordinates, has_z);
+    }
+    buf.push(0x01);
+    buf.extend_from_slice(&wkb_type_id(wkb_type_code(geometry_type), has_z).to_le_bytes());
+    write_wkb_coordinates(buf, geometry_type, coordinates, has_z)
+}
+
+fn write_wkb_header(buf: &mut Vec<u8>, code: u32, has_z: bool, len: usize) {
+    buf.push(0x01);
+    buf.extend_from_slice(&wkb_type_id(code, has_z).to_le_bytes());
+    buf.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn wkb_type_code(geometry_type: GeometryType) -> u32 {
+    match geometry_type {
+        GeometryType::Point => 1,
+        GeometryType::LineString => 2,
+        GeometryType::Polygon => 3,
+        GeometryType::MultiPoint => 4,
+        GeometryType::MultiLineString => 5,
+        GeometryType::MultiPolygon => 6,
+        _ => 7,
+    }
+}
+
+fn wkb_type_id(code: u32, has_z: bool) -> u32 {
+    if has_z { code + 1000 } else { code }
+}
+
+// Write the body of a non-collection geometry, i.e. everything after the
+// byte order and type id.
+fn write_wkb_coordinates(
+    buf: &mut Vec<u8>,
+    geometry_type: GeometryType,
+    coordinates: &JsonValue,
+    has_z: bool,
+) -> Result<()> {
+    let items = coordinates
+        .as_array()
+        .ok_or_else(|| invalid("coordinates must be an array"))?;
+    match geometry_type {
+        // An empty array is POINT EMPTY, which WKB writes as NaN coordinates.
+        GeometryType::Point if items.is_empty() => {
+            let n = if has_z { 3 } else { 2 };
+            for _ in 0..n {
+                buf.extend_from_slice(&f64::NAN.to_le_bytes());
+            }
+            Ok(())
+        }
+        GeometryType::Point => write_wkb_position(buf, items, has_z),
+        GeometryType::LineString => {
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for position in items {
+                write_wkb_position(buf, as_array(position)?, has_z)?;
+            }
+            Ok(())
+        }
+        GeometryType::Polygon => {
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for ring in items {
+                write_wkb_coordinates(buf, GeometryType::LineString, ring, has_z)?;
+            }
+            Ok(())
+        }
+        GeometryType::MultiPoint | GeometryType::MultiLineString | GeometryType::MultiPolygon => {
+            let (code, member_type) = match geometry_type {
+                GeometryType::MultiPoint => (1, GeometryType::Point),
+                GeometryType::MultiLineString => (2, GeometryType::LineString),
+                _ => (3, GeometryType::Polygon),
+            };
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for member in items {
+                buf.push(0x01);
+                buf.extend_from_slice(&wkb_type_id(code, has_z).to_le_bytes());
+                write_wkb_coordinates(buf, member_type, member, has_z)?;
+            }
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn write_wkb_position(buf: &mut Vec<u8>, position: &[JsonValue], has_z: bool) -> Result<()> {
+    let n = if has_z { 3 } else { 2 };
+    if position.len() < n {
+        return Err(GpkgError::InvalidGeoJson(format!(
+            "expected a position with {n} coordinates, got {}",
+            position.len()
+        )));
+    }
+    for value in &position[..n] {
+        let value = value
+            .as_f64()
+            .ok_or_else(|| invalid("coordinates must be numbers"))?;
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+    Ok(())
+}
+
+fn as_array(value: &JsonValue) -> Result<&Vec<JsonValue>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid("coordinates must be an array"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Result;
+    use crate::error::GpkgError;
+    use crate::gpkg::Gpkg;
+    use crate::params;
+    use crate::types::{ColumnSpec, ColumnType, GeoJsonImportOptions, Value};
+    use geo_traits::GeometryTrait;
+    use geo_types::Point;
+    use serde_json::{Value as JsonValue, json};
+    use wkb::reader::{Dimension, GeometryType};
+
+    #[test]
+    fn writes_generated_gpkg_as_geojson() -> Result<()> {
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        for layer_name in gpkg.list_layers()? {
+            let layer = gpkg.get_layer(&layer_name)?;
+            let mut buf = Vec::new();
+            layer.to_geojson_writer(&mut buf)?;
+            let geojson: JsonValue = serde_json::from_slice(&buf).expect("valid json");
+
+            assert_eq!(geojson["type"], "FeatureCollection");
+            assert_eq!(
+                geojson["crs"]["properties"]["name"],
+                "urn:ogc:def:crs:EPSG::4326"
+            );
+            let features = geojson["features"].as_array().expect("features");
+            assert_eq!(features.len() as u64, layer.count()?);
+        }
+
+        let layer = gpkg.get_layer("points")?;
+        let mut buf = Vec::new();
+        layer.to_geojson_writer(&mut buf)?;
+        let geojson: JsonValue = serde_json::from_slice(&buf).expect("valid json");
+        let first = &geojson["features"][0];
+        assert_eq!(first["id"], 1);
+        assert_eq!(first["geometry"]["type"], "Point");
+        assert_eq!(first["geometry"]["coordinates"], json!([139.7, 35.6895]));
+        assert_eq!(first["properties"]["name"], "alpha");
+        Ok(())
+    }
+
+    #[test]
+    fn writes_null_geometry_and_property_types() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "flag".to_string(),
+                column_type: ColumnType::Boolean,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "ratio".to_string(),
+                column_type: ColumnType::Double,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            0,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![true, f64::NAN])?;
+        gpkg.conn.execute(
+            "INSERT INTO points (geom, flag, ratio) VALUES (NULL, 0, 0.5)",
+            [],
+        )?;
+
+        let mut buf = Vec::new();
+        layer.to_geojson_writer(&mut buf)?;
+        let geojson: JsonValue = serde_json::from_slice(&buf).expect("valid json");
+
+        // The undefined SRS has no crs member.
+        assert!(geojson.get("crs").is_none());
+        let features = &geojson["features"];
+        assert_eq!(
+            features[0]["properties"],
+            json!({ "flag": true, "ratio": null })
+        );
+        assert_eq!(features[1]["geometry"], JsonValue::Null);
+        assert_eq!(
+            features[1]["properties"],
+            json!({ "flag": false, "ratio": 0.5 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn creates_layer_from_geojson_with_inferred_schema() -> Result<()> {
+        let geojson = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [1.0, 2.0] },
+                    "properties": { "name": "alpha", "count": 1, "ratio": 1, "note": null }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "MultiPoint", "coordinates": [[3.0, 4.0], [5.0, 6.0]] },
+                    "properties": { "name": "beta", "count": 2, "ratio": 0.5, "flag": true }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": null,
+                    "properties": { "name": "gamma", "tags": ["a", "b"] }
+                }
+            ]
+        });
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer =
+            gpkg.create_layer_from_geojson(geojson.to_string().as_bytes(), "points", 4326)?;
+
+        // Point and MultiPoint are merged into MultiPoint.
+        assert_eq!(layer.geometry_column, "geom");
+        assert_eq!(layer.geometry_type, GeometryType::MultiPoint);
+        assert_eq!(layer.geometry_dimension, Dimension::Xy);
+        let columns: Vec<(&str, ColumnType)> = layer
+            .property_columns
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.column_type))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("name", ColumnType::Varchar),
+                ("count", ColumnType::Integer),
+                ("ratio", ColumnType::Double),
+                ("note", ColumnType::Varchar),
+                ("flag", ColumnType::Boolean),
+                ("tags", ColumnType::Varchar),
+            ]
+        );
+
+        let features = layer.features()?;
+        assert_eq!(features.len(), 3);
+        assert!(matches!(
+            features[0].geometry()?.as_type(),
+            geo_traits::GeometryType::MultiPoint(_)
+        ));
+        assert_eq!(features[0].property("ratio"), Some(Value::Real(1.0)));
+        assert_eq!(features[0].property("flag"), Some(Value::Null));
+        assert_eq!(features[1].property("flag"), Some(Value::Integer(1)));
+        assert!(features[2].geometry_opt()?.is_none());
+        assert_eq!(
+            features[2].property("tags"),
+            Some(Value::Text(r#"["a","b"]"#.to_string()))
+        );
+        assert_eq!(layer.extent()?, Some((1.0, 2.0, 5.0, 6.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_conflicting_property_types() -> Result<()> {
+        let geojson = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [1.0, 2.0] },
+                    "properties": { "code": 1 }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "LineString", "coordinates": [[0.0, 0.0], [1.0, 1.0]] },
+                    "properties": { "code": "A1" }
+                }
+            ]
+        })
+        .to_string();
+        let gpkg = Gpkg::open_in_memory()?;
+
+        let options = GeoJsonImportOptions {
+            widen_conflicting_types: false,
+            ..Default::default()
+        };
+        let err = gpkg
+            .create_layer_from_geojson_with_options(geojson.as_bytes(), "strict", 4326, &options)
+            .expect_err("conflicting types");
+        assert!(matches!(
+            err,
+            GpkgError::GeoJsonPropertyTypeConflict {
+                ref property,
+                first: ColumnType::Integer,
+                second: ColumnType::Varchar,
+            } if property == "code"
+        ));
+
+        // Mixed geometry types need a GEOMETRY layer.
+        let layer = gpkg.create_layer_from_geojson(geojson.as_bytes(), "widened", 4326)?;
+        assert_eq!(layer.geometry_type_name, "GEOMETRY");
+        assert_eq!(gpkg.get_layer("widened")?.geometry_type_name, "GEOMETRY");
+        assert!(gpkg.validate()?.is_valid());
+        assert_eq!(layer.property_columns[0].column_type, ColumnType::Varchar);
+        let codes: Vec<Option<Value>> = layer
+            .features()?
+            .iter()
+            .map(|feature| feature.property("code"))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                Some(Value::Text("1".to_string())),
+                Some(Value::Text("A1".to_string()))
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_import_does_not_create_layer() -> Result<()> {
+        // The second feature is outside of the sample and has no Y coordinate.
+        let geojson = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [1.0, 2.0] },
+                    "properties": {}
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [1.0] },
+                    "properties": {}
+                }
+            ]
+        })
+        .to_string();
+        let gpkg = Gpkg::open_in_memory()?;
+        let options = GeoJsonImportOptions {
+            sample_size: Some(1),
+            ..Default::default()
+        };
+        let err = gpkg
+            .create_layer_from_geojson_with_options(geojson.as_bytes(), "points", 4326, &options)
+            .expect_err("invalid position");
+        assert!(matches!(
+            err,
+            GpkgError::InsertManyFailed { index: 1, ref source }
+                if matches!(**source, GpkgError::InvalidGeoJson(_))
+        ));
+        assert!(gpkg.list_layers()?.is_empty());
+
+        let err = gpkg
+            .create_layer_from_geojson(&b"{\"type\": \"Point\"}"[..], "points", 4326)
+            .expect_err("not a feature");
+        assert!(matches!(err, GpkgError::InvalidGeoJson(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_geojson_into_existing_layer() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Double,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "polygons",
+            "geom",
+            GeometryType::MultiPolygon,
+            Dimension::Xyz,
+            4326,
+            &columns,
+        )?;
+        let geojson = json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 1.0], [0.0, 0.0, 1.0]]]
+            },
+            "properties": { "value": 3, "unknown": "ignored" }
+        });
+        assert_eq!(layer.insert_geojson(geojson.to_string().as_bytes())?, 1);
+
+        let features = layer.features()?;
+        let geometry = features[0].geometry()?;
+        assert_eq!(geometry.dim(), geo_traits::Dimensions::Xyz);
+        assert!(matches!(
+            geometry.as_type(),
+            geo_traits::GeometryType::MultiPolygon(_)
+        ));
+        assert_eq!(features[0].property("name"), Some(Value::Null));
+        assert_eq!(features[0].property("value"), Some(Value::Real(3.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_generated_gpkg_through_geojson() -> Result<()> {
+        let src = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let dst = Gpkg::open_in_memory()?;
+        for layer_name in src.list_layers()? {
+            let layer = src.get_layer(&layer_name)?;
+            let mut exported = Vec::new();
+            layer.to_geojson_writer(&mut exported)?;
+
+            let imported = dst.create_layer_from_geojson(&exported[..], &layer_name, 4326)?;
+            assert_eq!(imported.geometry_type, layer.geometry_type);
+            assert_eq!(imported.count()?, layer.count()?);
+
+            let mut reexported = Vec::new();
+            imported.to_geojson_writer(&mut reexported)?;
+            let expected: JsonValue = serde_json::from_slice(&exported).expect("valid json");
+            let actual: JsonValue = serde_json::from_slice(&reexported).expect("valid json");
+            assert_eq!(actual["features"], expected["features"]);
+        }
+        Ok(())
+    }
+}
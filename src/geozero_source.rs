@@ -0,0 +1,229 @@
+//! geozero datasource for feature layers.
+
+use crate::gpkg::{GpkgFeature, GpkgLayer};
+use crate::types::{ColumnSpec, ColumnType, Value};
+use geozero::error::{GeozeroError, Result};
+use geozero::{ColumnValue, FeatureProcessor, GeozeroDatasource, GeozeroGeometry};
+
+// The number of features read from the layer at a time.
+const BATCH_SIZE: u32 = 1024;
+
+/// Streams the features of the layer, in batches and in primary key order,
+/// into a geozero `FeatureProcessor` such as `geozero::geojson::GeoJsonWriter`.
+///
+/// The geometries are passed through geozero's WKB reader. A `NULL` geometry
+/// is omitted from the feature, as is a `NULL` property or one that doesn't
+/// fit the type of its column; the index passed with each property is the
+/// position of its column in the layer schema. A failure to read the layer is
+/// reported as `GeozeroError::Dataset`.
+impl GeozeroDatasource for GpkgLayer {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        processor.dataset_begin(Some(&self.layer_name))?;
+
+        let batches = self
+            .features_batch(BATCH_SIZE)
+            .map_err(|err| GeozeroError::Dataset(err.to_string()))?;
+        let mut idx = 0;
+        for batch in batches {
+            let batch = batch.map_err(|err| GeozeroError::Dataset(err.to_string()))?;
+            for feature in &batch {
+                process_feature(processor, &self.property_columns, feature, idx)?;
+                idx += 1;
+            }
+        }
+
+        processor.dataset_end()
+    }
+}
+
+fn process_feature<P: FeatureProcessor>(
+    processor: &mut P,
+    columns: &[ColumnSpec],
+    feature: &GpkgFeature,
+    idx: u64,
+) -> Result<()> {
+    processor.feature_begin(idx)?;
+
+    processor.properties_begin()?;
+    for (i, (spec, value)) in columns.iter().zip(feature.properties()).enumerate() {
+        if let Some(value) = column_value(spec.column_type, value) {
+            processor.property(i, &spec.name, &value)?;
+        }
+    }
+    processor.properties_end()?;
+
+    let geometry = feature
+        .geometry_opt()
+        .map_err(|err| GeozeroError::FeatureGeometry(err.to_string()))?;
+    if let Some(wkb) = geometry {
+        processor.geometry_begin()?;
+        geozero::wkb::Wkb(wkb.buf()).process_geom(processor)?;
+        processor.geometry_end()?;
+    }
+
+    processor.feature_end(idx)
+}
+
+// SQLite doesn't enforce column types, so a value that doesn't fit the type
+// of its column is written as NULL.
+pub(crate) fn column_value(column_type: ColumnType, value: &Value) -> Option<ColumnValue<'_>> {
+    match (column_type, value) {
+        (_, Value::Null) => None,
+        (ColumnType::Boolean, Value::Integer(v)) => Some(ColumnValue::Bool(*v != 0)),
+        (ColumnType::Integer, Value::Integer(v)) => Some(ColumnValue::Long(*v)),
+        (ColumnType::MediumInt, Value::Integer(v)) => i32::try_from(*v).ok().map(ColumnValue::Int),
+        (ColumnType::SmallInt, Value::Integer(v)) => i16::try_from(*v).ok().map(ColumnValue::Short),
+        (ColumnType::TinyInt, Value::Integer(v)) => i8::try_from(*v).ok().map(ColumnValue::Byte),
+        (ColumnType::Double, Value::Real(v)) => Some(ColumnValue::Double(*v)),
+        (ColumnType::Double, Value::Integer(v)) => Some(ColumnValue::Double(*v as f64)),
+        (ColumnType::Float, Value::Real(v)) => Some(ColumnValue::Float(*v as f32)),
+        (ColumnType::Float, Value::Integer(v)) => Some(ColumnValue::Float(*v as f32)),
+        (ColumnType::Varchar, Value::Text(v)) => Some(ColumnValue::String(v)),
+        (ColumnType::Date | ColumnType::Datetime, Value::Text(v)) => Some(ColumnValue::DateTime(v)),
+        (ColumnType::Blob | ColumnType::Geometry, Value::Blob(v) | Value::Geometry(v)) => {
+            Some(ColumnValue::Binary(v))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Result;
+    use crate::gpkg::Gpkg;
+    use crate::params;
+    use crate::types::{ColumnSpec, ColumnType};
+    use geo_types::Point;
+    use geozero::GeozeroDatasource;
+    use geozero::geojson::GeoJsonWriter;
+    use serde_json::json;
+    use wkb::reader::{Dimension, GeometryType};
+
+    #[test]
+    fn processes_layer_into_geojson_writer() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "count".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "visited".to_string(),
+                column_type: ColumnType::Boolean,
+                ..Default::default()
+            },
+        ];
+        let mut layer = gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.5, 2.5), params!["alpha", 3, true])?;
+        layer.insert(
+            Point::new(-3.5, 4.25),
+            params!["beta", Option::<i64>::None, false],
+        )?;
+
+        let mut out = Vec::new();
+        layer
+            .process(&mut GeoJsonWriter::new(&mut out))
+            .expect("process");
+        let actual: serde_json::Value = serde_json::from_slice(&out).expect("valid GeoJSON");
+        let expected = json!({
+            "type": "FeatureCollection",
+            "name": "places",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": { "name": "alpha", "count": 3, "visited": true },
+                    "geometry": { "type": "Point", "coordinates": [1.5, 2.5] },
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "name": "beta", "visited": false },
+                    "geometry": { "type": "Point", "coordinates": [-3.5, 4.25] },
+                },
+            ],
+        });
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[cfg(feature = "flatgeobuf")]
+    #[test]
+    fn processes_layer_into_fgb_writer() -> Result<()> {
+        use flatgeobuf::geozero::FeatureProperties;
+        use flatgeobuf::{ColumnType as FgbColumnType, FallibleStreamingIterator, FgbReader};
+        use flatgeobuf::{FgbWriter, FgbWriterOptions, GeometryType as FgbGeometryType};
+        use std::io::Cursor;
+
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "count".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "note".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+        ];
+        let mut layer = gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.5, 2.5), params!["alpha", 3, "first"])?;
+        layer.insert(
+            Point::new(-3.5, 4.25),
+            params!["beta", Option::<i64>::None, "second"],
+        )?;
+
+        let mut fgb = FgbWriter::create_with_options(
+            "places",
+            FgbGeometryType::Point,
+            FgbWriterOptions::default(),
+        )
+        .expect("writer");
+        fgb.add_column("name", FgbColumnType::String, |_, _| {});
+        fgb.add_column("count", FgbColumnType::Long, |_, _| {});
+        fgb.add_column("note", FgbColumnType::String, |_, _| {});
+        layer.process(&mut fgb).expect("process");
+        let mut buf = Vec::new();
+        fgb.write(&mut buf).expect("write");
+
+        let reader = FgbReader::open(Cursor::new(&buf)).expect("valid FlatGeobuf");
+        let mut features = reader.select_all().expect("features");
+        let mut properties = Vec::new();
+        while let Some(feature) = features.next().expect("feature") {
+            properties.push(feature.properties().expect("properties"));
+        }
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties[0]["name"], "alpha");
+        assert_eq!(properties[0]["count"], "3");
+        assert_eq!(properties[0]["note"], "first");
+        assert_eq!(properties[1]["name"], "beta");
+        assert!(!properties[1].contains_key("count"));
+        assert_eq!(properties[1]["note"], "second");
+        Ok(())
+    }
+}
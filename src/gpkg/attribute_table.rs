@@ -1,8 +1,9 @@
 use crate::Value;
 use crate::error::{GpkgError, Result};
 use crate::ogc_sql::{sql_delete_all, sql_insert_feature, sql_select_attribute_rows};
-use crate::types::ColumnSpec;
+use crate::types::{ColumnSpec, params_from_properties, value_from_row};
 use rusqlite::types::Type;
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -12,6 +13,8 @@ use super::attribute_row::GpkgAttributeRow;
 /// A GeoPackage attribute table (non-spatial, no geometry column).
 pub struct GpkgAttributeTable {
     pub(super) conn: Rc<rusqlite::Connection>,
+    /// The dump read by the connection, which must be dropped after it.
+    pub(super) _dump: Option<Rc<dyn std::any::Any>>,
     pub(super) is_read_only: bool,
     pub table_name: String,
     pub primary_key_column: String,
@@ -51,11 +54,12 @@ impl GpkgAttributeTable {
     }
 
     /// Insert a row with ordered property values (no geometry).
-    pub fn insert<'p, P>(&self, properties: P) -> Result<()>
+    pub fn insert<P>(&self, properties: P) -> Result<()>
     where
-        P: IntoIterator<Item = &'p Value>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
     {
-        let properties: Vec<&Value> = properties.into_iter().collect();
+        let properties: Vec<P::Item> = properties.into_iter().collect();
         let expected = self.property_columns.len();
         let got = properties.len();
         if expected != got {
@@ -71,11 +75,12 @@ impl GpkgAttributeTable {
     }
 
     /// Update the row with the given primary key.
-    pub fn update<'p, P>(&self, properties: P, id: i64) -> Result<()>
+    pub fn update<P>(&self, properties: P, id: i64) -> Result<()>
     where
-        P: IntoIterator<Item = &'p Value>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
     {
-        let properties: Vec<&Value> = properties.into_iter().collect();
+        let properties: Vec<P::Item> = properties.into_iter().collect();
         let expected = self.property_columns.len();
         let got = properties.len();
         if expected != got {
@@ -166,8 +171,7 @@ fn row_to_attribute_row(
     let row_len = property_columns.len() + 1;
 
     for idx in 0..row_len {
-        let value_ref = row.get_ref(idx)?;
-        let value = Value::from(value_ref);
+        let value = value_from_row(row, idx, id)?;
 
         if idx == PRIMARY_INDEX {
             match &value {
@@ -176,7 +180,7 @@ fn row_to_attribute_row(
                     return Err(rusqlite::Error::InvalidColumnType(
                         idx,
                         primary_key_column.to_string(),
-                        value_ref.data_type(),
+                        row.get_ref(idx)?.data_type(),
                     ));
                 }
             }
@@ -200,36 +204,11 @@ fn row_to_attribute_row(
     })
 }
 
-fn params_from_properties<'p, P>(properties: P, id: Option<i64>) -> impl rusqlite::Params
-where
-    P: IntoIterator<Item = &'p Value>,
-{
-    let params = properties
-        .into_iter()
-        .map(SqlParam::Borrowed)
-        .chain(id.into_iter().map(|i| SqlParam::Owned(Value::Integer(i))));
-    rusqlite::params_from_iter(params)
-}
-
-enum SqlParam<'a> {
-    Owned(Value),
-    Borrowed(&'a Value),
-}
-
-impl<'a> rusqlite::ToSql for SqlParam<'a> {
-    #[inline]
-    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
-        match self {
-            SqlParam::Owned(value) => value.to_sql(),
-            SqlParam::Borrowed(value) => value.to_sql(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::GpkgError;
     use crate::Result;
+    use crate::Value;
     use crate::gpkg::Gpkg;
     use crate::params;
     use crate::types::{ColumnSpec, ColumnType};
@@ -241,10 +220,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -270,12 +251,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn attribute_table_accepts_owned_and_borrowed_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let table = gpkg.create_attribute_table("observations", &columns)?;
+
+        let values = vec![Value::from("alpha")];
+        table.insert(values.clone())?;
+        table.insert(&values)?;
+        table.insert(crate::params_from(["beta"]))?;
+        table.update(values.iter(), 3)?;
+        table.update(vec![Value::from("gamma")], 1)?;
+
+        let names = table
+            .rows()?
+            .iter()
+            .map(|row| row.property("name").unwrap().try_into())
+            .collect::<Result<Vec<String>>>()?;
+        assert_eq!(names, ["gamma", "alpha", "alpha"]);
+        Ok(())
+    }
+
     #[test]
     fn attribute_table_metadata_in_gpkg_contents() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
 
         gpkg.create_attribute_table("observations", &columns)?;
@@ -318,6 +326,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
         gpkg.create_attribute_table("observations", &columns)?;
 
@@ -338,6 +347,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
         gpkg.create_attribute_table("observations", &columns)?;
 
@@ -376,10 +386,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -406,6 +418,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
 
         let table = gpkg.create_attribute_table("observations", &columns)?;
@@ -431,6 +444,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
         gpkg.create_attribute_table("observations", &columns)?;
 
@@ -449,10 +463,12 @@ mod tests {
             ColumnSpec {
                 name: "a".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "b".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -477,10 +493,12 @@ mod tests {
             ColumnSpec {
                 name: "a".to_string(),
                 column_type: ColumnType::Double,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "b".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -506,10 +524,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -578,6 +598,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "geom".to_string(),
             column_type: ColumnType::Geometry,
+            ..Default::default()
         }];
 
         let err = gpkg
@@ -1,7 +1,6 @@
-use crate::gpkg::GpkgFeature;
+use crate::gpkg::{GpkgFeature, PropertyIndex};
 use crate::types::ColumnSpec;
-use crate::{GpkgLayer, Result};
-use std::collections::HashMap;
+use crate::{GpkgLayer, Result, Value};
 use std::rc::Rc;
 
 /// Iterator that yields batches of features from a layer.
@@ -9,27 +8,112 @@ use std::rc::Rc;
 /// Each call to `next()` returns a `Result<Vec<GpkgFeature>>` containing up to
 /// `batch_size` features. This provides a chunked alternative to `features()`,
 /// which always allocates a single vector for the whole layer.
+///
+/// An iterator created by `GpkgLayer::features_batch_owned()` keeps the
+/// connection alive by itself, so it is `GpkgFeatureBatchIterator<'static>`.
+///
+/// Batches are read in primary key order, each one starting after the last
+/// primary key of the previous batch, and no transaction is held between
+/// batches. So rows can be inserted, updated or deleted while iterating, from
+/// the same connection or another one: every row that exists for the whole
+/// iteration is returned exactly once, a deleted row not yet read is not
+/// returned, and an inserted row is returned only if its primary key is
+/// greater than the last one read. A batch reflects the database at the time
+/// it's read.
+///
+/// An iterator created by `GpkgLayer::features_batch_ordered()` pages the same
+/// way, but after the sort key of the last row read instead of its primary
+/// key, so the same holds for rows whose sort key isn't modified.
 pub struct GpkgFeatureBatchIterator<'a> {
-    pub(super) stmt: rusqlite::Statement<'a>,
+    pub(super) stmt: BatchStatement<'a>,
+    pub(super) layer_name: String,
     pub(super) property_columns: Vec<ColumnSpec>,
     pub(super) geometry_column: String,
     pub(super) primary_key_column: String,
-    pub(super) property_index_by_name: Rc<HashMap<String, usize>>,
+    pub(super) property_index_by_name: Rc<PropertyIndex>,
     pub(super) batch_size: u32,
-    pub(super) offset: u32,
+    pub(super) params: Vec<Value>,
+    pub(super) last_id: i64,
+    /// The columns of the sort key (`None` for the primary key), for an
+    /// iterator paging after the last-seen sort key instead of the primary key.
+    pub(super) sort_key_columns: Option<Vec<Option<String>>>,
+    pub(super) last_sort_key: Option<Vec<Value>>,
     pub(super) end_or_invalid_state: bool,
 }
 
+pub(super) enum BatchStatement<'a> {
+    Borrowed(rusqlite::CachedStatement<'a>),
+    /// Shares the layer's connection and prepares the (cached) statement for
+    /// every batch.
+    Owned {
+        conn: Rc<rusqlite::Connection>,
+        sql: String,
+        /// The dump read by the connection, which must be dropped after it.
+        _dump: Option<Rc<dyn std::any::Any>>,
+    },
+}
+
 impl<'a> GpkgFeatureBatchIterator<'a> {
-    pub(crate) fn new(stmt: rusqlite::Statement<'a>, layer: &GpkgLayer, batch_size: u32) -> Self {
+    pub(crate) fn new(
+        stmt: rusqlite::CachedStatement<'a>,
+        layer: &GpkgLayer,
+        batch_size: u32,
+        params: Vec<Value>,
+    ) -> Self {
+        Self::with_statement(BatchStatement::Borrowed(stmt), layer, batch_size, params)
+    }
+
+    // Page after the last-seen sort key, for a statement built by
+    // `sql_select_features_ordered()`.
+    pub(crate) fn new_with_sort_key(
+        stmt: rusqlite::CachedStatement<'a>,
+        layer: &GpkgLayer,
+        batch_size: u32,
+        sort_key_columns: Vec<Option<String>>,
+    ) -> Self {
+        Self {
+            sort_key_columns: Some(sort_key_columns),
+            ..Self::with_statement(
+                BatchStatement::Borrowed(stmt),
+                layer,
+                batch_size,
+                Vec::new(),
+            )
+        }
+    }
+
+    pub(crate) fn new_owned(
+        sql: String,
+        layer: &GpkgLayer,
+        batch_size: u32,
+        params: Vec<Value>,
+    ) -> Self {
+        let stmt = BatchStatement::Owned {
+            conn: layer.conn.clone(),
+            sql,
+            _dump: layer.dump.clone(),
+        };
+        Self::with_statement(stmt, layer, batch_size, params)
+    }
+
+    fn with_statement(
+        stmt: BatchStatement<'a>,
+        layer: &GpkgLayer,
+        batch_size: u32,
+        params: Vec<Value>,
+    ) -> Self {
         Self {
             stmt,
             batch_size,
+            params,
+            layer_name: layer.layer_name.clone(),
             property_columns: layer.property_columns.clone(),
             geometry_column: layer.geometry_column.clone(),
             primary_key_column: layer.primary_key_column.clone(),
             property_index_by_name: layer.property_index_by_name.clone(),
-            offset: 0,
+            last_id: i64::MIN,
+            sort_key_columns: None,
+            last_sort_key: None,
             end_or_invalid_state: false,
         }
     }
@@ -43,26 +127,39 @@ impl<'a> Iterator for GpkgFeatureBatchIterator<'a> {
             return None;
         }
 
-        let result = self.stmt.query_map([self.offset], |row| {
-            super::layer::row_to_feature(
-                row,
-                &self.property_columns,
-                &self.geometry_column,
-                &self.primary_key_column,
-                &self.property_index_by_name,
-            )
-        });
-
-        let collected_result = match result {
-            Ok(mapped_rows) => mapped_rows.collect::<rusqlite::Result<Vec<GpkgFeature>>>(),
-            Err(e) => {
-                // I don't know in what case some error happens, but I bet it's unrecoverable.
-                self.end_or_invalid_state = true;
-                return Some(Err(e.into()));
-            }
+        // The last-seen primary key follows the parameters of the WHERE
+        // expression. A sort key is preceded by whether it's the first page.
+        let page = match (&self.sort_key_columns, &self.last_sort_key) {
+            (None, _) => vec![Value::Integer(self.last_id)],
+            (Some(columns), None) => std::iter::once(Value::Integer(1))
+                .chain(columns.iter().map(|_| Value::Null))
+                .collect(),
+            (Some(_), Some(key)) => std::iter::once(Value::Integer(0))
+                .chain(key.iter().cloned())
+                .collect(),
+        };
+        let params = self.params.iter().cloned().chain(page);
+        let query = |stmt: &mut rusqlite::Statement<'_>| {
+            stmt.query_map(rusqlite::params_from_iter(params), |row| {
+                super::layer::row_to_feature(
+                    row,
+                    &self.layer_name,
+                    &self.property_columns,
+                    &self.geometry_column,
+                    &self.primary_key_column,
+                    &self.property_index_by_name,
+                )
+            })?
+            .collect::<rusqlite::Result<Vec<GpkgFeature>>>()
+        };
+        let result = match &mut self.stmt {
+            BatchStatement::Borrowed(stmt) => query(stmt),
+            BatchStatement::Owned { conn, sql, .. } => conn
+                .prepare_cached(sql)
+                .and_then(|mut stmt| query(&mut stmt)),
         };
 
-        let features = match collected_result {
+        let features = match result {
             Ok(features) => features,
             Err(e) => {
                 // I don't know in what case some error happens, but I bet it's unrecoverable.
@@ -80,7 +177,16 @@ impl<'a> Iterator for GpkgFeatureBatchIterator<'a> {
             }
         }
 
-        self.offset += result_size as u32;
+        if let Some(last) = features.last() {
+            self.last_id = last.id();
+            if let Some(columns) = &self.sort_key_columns {
+                let key = columns.iter().map(|column| match column {
+                    Some(column) => last.property(column).unwrap_or(Value::Null),
+                    None => Value::Integer(last.id()),
+                });
+                self.last_sort_key = Some(key.collect());
+            }
+        }
 
         Some(Ok(features))
     }
@@ -174,4 +280,96 @@ mod tests {
         assert_batch_iteration(4, 1)?;
         Ok(())
     }
+
+    #[test]
+    fn batch_iterator_handles_gaps_in_primary_key() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "gappy_points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        gpkg.with_transaction(|_| {
+            for i in 0..3000 {
+                layer.insert(Point::new(i as f64, i as f64), std::iter::empty::<&Value>())?;
+            }
+            Ok(())
+        })?;
+        gpkg.conn.execute(
+            "DELETE FROM gappy_points WHERE fid % 3 = 0 OR fid % 11 = 0",
+            [],
+        )?;
+
+        let expected: Vec<i64> = layer.features()?.iter().map(|f| f.id()).collect();
+        let mut seen = Vec::new();
+        for batch in layer.features_batch(7)? {
+            let features = batch?;
+            assert!(features.len() <= 7);
+            seen.extend(features.iter().map(|f| f.id()));
+        }
+        assert_eq!(seen, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_iterator_tolerates_changes_between_batches() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "changing_points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        for i in 0..10 {
+            layer.insert(Point::new(i as f64, i as f64), std::iter::empty::<&Value>())?;
+        }
+
+        let mut seen = Vec::new();
+        for (index, batch) in layer.features_batch(3)?.enumerate() {
+            let features = batch?;
+            seen.extend(features.iter().map(|f| f.id()));
+            if index == 0 {
+                // Delete a row already read and one not read yet, and add a
+                // row at the end.
+                layer.delete(2)?;
+                layer.delete(5)?;
+                layer.insert(Point::new(10.0, 10.0), std::iter::empty::<&Value>())?;
+            }
+        }
+        assert_eq!(seen, vec![1, 2, 3, 4, 6, 7, 8, 9, 10, 11]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn owned_batch_iterator_outlives_gpkg_and_layer() -> Result<()> {
+        let expected: Vec<i64> = {
+            let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+            let layer = gpkg.get_layer("points")?;
+            layer.features()?.iter().map(|f| f.id()).collect()
+        };
+
+        let iter = {
+            let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+            gpkg.get_layer("points")?.features_batch_owned(2)?
+        };
+        let mut seen = Vec::new();
+        for batch in iter {
+            let features = batch?;
+            assert!(features.len() <= 2);
+            seen.extend(features.iter().map(|f| f.id()));
+        }
+        assert_eq!(seen, expected);
+
+        Ok(())
+    }
 }
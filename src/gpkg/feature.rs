@@ -1,15 +1,54 @@
 use crate::Value;
 use crate::error::{GpkgError, Result};
+use crate::sql_functions::{Bounds, bounds_from_geometry};
+use crate::types::Envelope;
 use std::collections::HashMap;
 use std::rc::Rc;
 use wkb::reader::Wkb;
 
+use super::transform::transform_wkb;
+
 /// A single feature with geometry bytes and owned properties.
 pub struct GpkgFeature {
     pub(super) id: i64,
     pub(super) geometry: Option<Vec<u8>>,
     pub(super) properties: Vec<Value>,
-    pub(super) property_index_by_name: Rc<HashMap<String, usize>>,
+    pub(super) property_index_by_name: Rc<PropertyIndex>,
+}
+
+/// The property column names of a layer in schema order, and their indices
+/// by name. Built once per layer and shared with its features.
+#[derive(Debug, Default)]
+pub(crate) struct PropertyIndex {
+    names: Vec<String>,
+    index_by_name: HashMap<String, usize>,
+}
+
+impl PropertyIndex {
+    pub(crate) fn new<'a, I: IntoIterator<Item = &'a str>>(names: I) -> Self {
+        let names: Vec<String> = names.into_iter().map(str::to_string).collect();
+        let index_by_name = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect();
+        Self {
+            names,
+            index_by_name,
+        }
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&usize> {
+        self.index_by_name.get(name)
+    }
+
+    pub(crate) fn contains_key(&self, name: &str) -> bool {
+        self.index_by_name.contains_key(name)
+    }
+
+    pub(crate) fn names(&self) -> &[String] {
+        &self.names
+    }
 }
 
 impl GpkgFeature {
@@ -48,8 +87,173 @@ impl GpkgFeature {
         gpkg_geometry_to_wkb(bytes)
     }
 
+    /// Decode the geometry column into WKB, returning `None` for a `NULL` geometry.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     if let Some(_geom) = feature.geometry_opt()? {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn geometry_opt(&self) -> Result<Option<Wkb<'_>>> {
+        self.geometry
+            .as_deref()
+            .map(gpkg_geometry_to_wkb)
+            .transpose()
+    }
+
+    /// Read the envelope stored in the GeoPackage header of the geometry
+    /// without decoding the WKB.
+    ///
+    /// Returns `None` when the geometry is `NULL` or was written without an
+    /// envelope. In that case, compute the bounding box from `geometry()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     if let Some(envelope) = feature.envelope()? {
+    ///         println!("{} {} {} {}", envelope.min_x, envelope.min_y, envelope.max_x, envelope.max_y);
+    ///     }
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn envelope(&self) -> Result<Option<Envelope>> {
+        match self.geometry.as_deref() {
+            Some(bytes) => gpkg_geometry_envelope(bytes),
+            None => Ok(None),
+        }
+    }
+
+    /// Decode the geometry column into an owned `geo_types::Geometry`.
+    ///
+    /// Returns `GpkgError::NullGeometryValue` for a `NULL` geometry, and
+    /// `GpkgError::UnsupportedGeoTypesGeometry` for geometries that geo-types
+    /// cannot represent, i.e. ones with Z or M coordinates and empty points.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let features = layer.features()?;
+    /// let feature = features.first().expect("feature");
+    /// if let geo_types::Geometry::Point(point) = feature.geometry_as_geo()? {
+    ///     println!("{} {}", point.x(), point.y());
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "geo")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+    pub fn geometry_as_geo(&self) -> Result<geo_types::Geometry<f64>> {
+        crate::conversions::wkb_to_geo(&self.geometry()?)
+    }
+
+    /// Format the geometry as WKT.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     println!("{}", feature.geometry_wkt()?);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "wkt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "wkt")))]
+    pub fn geometry_wkt(&self) -> Result<String> {
+        crate::conversions::geometry_to_wkt(&self.geometry()?, None)
+    }
+
+    /// Format the geometry as WKT, writing each coordinate with `precision`
+    /// decimal places.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     // e.g. POINT(139.700 35.690)
+    ///     println!("{}", feature.geometry_wkt_with_precision(3)?);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "wkt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "wkt")))]
+    pub fn geometry_wkt_with_precision(&self, precision: usize) -> Result<String> {
+        crate::conversions::geometry_to_wkt(&self.geometry()?, Some(precision))
+    }
+
+    /// Return the srs_id stored in the GeoPackage header of the geometry, or
+    /// `None` for a `NULL` geometry.
+    ///
+    /// This is usually the same as the layer's `srs_id`, but the header is
+    /// what other tools see for this particular geometry. The value is signed
+    /// because the spec reserves `-1` and `0` for undefined systems.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let features = layer.features()?;
+    /// let feature = features.first().expect("feature");
+    /// let _srs_id = feature.geometry_srs_id()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn geometry_srs_id(&self) -> Result<Option<i32>> {
+        self.geometry
+            .as_deref()
+            .map(gpkg_geometry_srs_id)
+            .transpose()
+    }
+
+    /// Whether the geometry is flagged as empty in its GeoPackage header.
+    ///
+    /// GDAL, for example, writes `POINT EMPTY` as a point with NaN
+    /// coordinates and sets this flag.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     if feature.is_empty_geometry()? {
+    ///         continue;
+    ///     }
+    ///     let _geom = feature.geometry()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn is_empty_geometry(&self) -> Result<bool> {
+        let bytes = self.geometry.as_ref().ok_or(GpkgError::NullGeometryValue)?;
+        gpkg_geometry_is_empty(bytes)
+    }
+
     /// Read a property by name as an owned `Value`.
     ///
+    /// This clones the value; `property_ref()` borrows it instead, which
+    /// avoids copying large `TEXT` or `BLOB` values.
+    ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
@@ -67,17 +271,184 @@ impl GpkgFeature {
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
     pub fn property(&self, name: &str) -> Option<Value> {
-        match self.property_index_by_name.get(name) {
-            Some(idx) => self.properties.get(*idx).cloned(),
-            None => None,
+        self.property_ref(name).cloned()
+    }
+
+    /// Borrow a property by name.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     if let Some(value) = feature.property_ref("name") {
+    ///         let name: &str = value.try_into()?;
+    ///         println!("{name}");
+    ///     }
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn property_ref(&self, name: &str) -> Option<&Value> {
+        let idx = self.property_index_by_name.get(name)?;
+        self.properties.get(*idx)
+    }
+
+    /// Move a property out of the feature by name, leaving `Value::Null` in
+    /// its place.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("documents")?;
+    /// let attachments: Vec<Value> = layer
+    ///     .features()?
+    ///     .into_iter()
+    ///     .filter_map(|mut feature| feature.take_property("attachment"))
+    ///     .collect();
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn take_property(&mut self, name: &str) -> Option<Value> {
+        let idx = self.property_index_by_name.get(name)?;
+        let value = self.properties.get_mut(*idx)?;
+        Some(std::mem::replace(value, Value::Null))
+    }
+
+    /// Read a property by name and convert it to `T`.
+    ///
+    /// Fails with `GpkgError::MissingProperty` if the layer has no such
+    /// column, and with `GpkgError::InvalidPropertyValue` if the value can't be
+    /// converted, including a SQL `NULL`. Use `get_opt()` for nullable columns.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     let name: String = feature.get("name")?;
+    ///     let value: i64 = feature.get("value")?;
+    ///     println!("{name}: {value}");
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get<T>(&self, name: &str) -> Result<T>
+    where
+        T: TryFrom<Value, Error = GpkgError>,
+    {
+        T::try_from(self.required_property(name)?).map_err(|e| invalid_property_value(name, e))
+    }
+
+    /// Read a property by name and convert it to `T`, mapping SQL `NULL` to
+    /// `None`.
+    ///
+    /// Fails with `GpkgError::MissingProperty` if the layer has no such
+    /// column, and with `GpkgError::InvalidPropertyValue` if a non-`NULL`
+    /// value can't be converted.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     let name: Option<String> = feature.get_opt("name")?;
+    ///     println!("{}", name.as_deref().unwrap_or("(unnamed)"));
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get_opt<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Value, Error = GpkgError>,
+    {
+        match self.required_property(name)? {
+            Value::Null => Ok(None),
+            value => T::try_from(value)
+                .map(Some)
+                .map_err(|e| invalid_property_value(name, e)),
         }
     }
 
+    fn required_property(&self, name: &str) -> Result<Value> {
+        self.property(name)
+            .ok_or_else(|| GpkgError::MissingProperty {
+                property: name.to_string(),
+            })
+    }
+
+    // Apply `transform` to the coordinates of the geometry, recomputing the
+    // envelope of the header if it has one.
+    pub(super) fn transform_geometry<F: Fn(&mut [f64; 2]) + ?Sized>(
+        &mut self,
+        transform: &F,
+    ) -> Result<()> {
+        let Some(blob) = &self.geometry else {
+            return Ok(());
+        };
+        let srs_id = gpkg_geometry_srs_id(blob)?;
+        let has_envelope = envelope_len(blob)? > 0;
+        let mut buf = gpkg_geometry_to_wkb_bytes(blob)?.to_vec();
+        transform_wkb(&mut buf, transform)?;
+        let wkb = Wkb::try_new(&buf)?;
+        let envelope = if has_envelope {
+            bounds_from_geometry(&wkb)
+        } else {
+            None
+        };
+        self.geometry = Some(wkb_to_gpkg_geometry(wkb, srs_id as u32, envelope.as_ref())?);
+        Ok(())
+    }
+
     /// Return the ordered property values as stored in the feature.
     pub fn properties(&self) -> &[Value] {
         &self.properties
     }
 
+    /// Return the property column names in schema order, matching
+    /// `properties()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let features = layer.features()?;
+    /// let feature = features.first().expect("feature");
+    /// let header: Vec<&str> = feature.property_names().collect();
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn property_names(&self) -> impl Iterator<Item = &str> {
+        self.property_index_by_name
+            .names()
+            .iter()
+            .map(String::as_str)
+    }
+
+    /// Iterate over `(name, value)` pairs of the properties in schema order.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features()? {
+    ///     for (name, value) in feature.iter() {
+    ///         println!("{name} = {value:?}");
+    ///     }
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.property_names().zip(&self.properties)
+    }
+
     #[cfg(test)]
     fn new<G, I>(id: i64, geometry: G, properties: I, property_names: &[&str]) -> Result<Self>
     where
@@ -86,22 +457,178 @@ impl GpkgFeature {
     {
         let mut buf = Vec::new();
         wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
-        let mut property_index_by_name = HashMap::with_capacity(property_names.len());
-        for (idx, name) in property_names.iter().enumerate() {
-            property_index_by_name.insert((*name).to_string(), idx);
-        }
         Ok(Self {
             id,
             geometry: Some(buf),
             properties: properties.into_iter().collect(),
-            property_index_by_name: Rc::new(property_index_by_name),
+            property_index_by_name: Rc::new(PropertyIndex::new(property_names.iter().copied())),
         })
     }
 }
 
+// Qualify a conversion error of a typed accessor with the property name.
+fn invalid_property_value(name: &str, source: GpkgError) -> GpkgError {
+    GpkgError::InvalidPropertyValue {
+        property: name.to_string(),
+        source: Box::new(source),
+    }
+}
+
+/// Serializes the feature as `{"id": ..., "properties": {...}}`, with the
+/// properties in column order. The geometry is not included; use
+/// `GpkgLayer::to_geojson_writer()` (the `geojson` feature) for that.
+///
+/// Example:
+/// ```no_run
+/// use rusqlite_gpkg::Gpkg;
+///
+/// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+/// let layer = gpkg.get_layer("points")?;
+/// for feature in layer.features()? {
+///     let json = serde_json::to_string(&feature).unwrap();
+///     println!("{json}");
+/// }
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl serde::Serialize for GpkgFeature {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("GpkgFeature", 2)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("properties", &SerializeProperties(self))?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+struct SerializeProperties<'a>(&'a GpkgFeature);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SerializeProperties<'_> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let feature = self.0;
+        let mut map = serializer.serialize_map(Some(feature.properties.len()))?;
+        for (name, value) in feature.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
 /// Strip GeoPackage header and envelope bytes to access raw WKB.
 // cf. https://www.geopackage.org/spec140/index.html#gpb_format
 pub(crate) fn gpkg_geometry_to_wkb_bytes(b: &[u8]) -> Result<&[u8]> {
+    let offset = 8 + 8 * envelope_len(b)?;
+    if b.len() == offset {
+        return Err(GpkgError::InvalidGpkgGeometryLength {
+            len: b.len(),
+            minimum: offset + 1,
+        });
+    }
+    Ok(&b[offset..])
+}
+
+/// Parse the envelope from the header of a GeoPackage geometry blob.
+pub(crate) fn gpkg_geometry_envelope(b: &[u8]) -> Result<Option<Envelope>> {
+    let len = envelope_len(b)?;
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let little_endian = is_little_endian(b[3]);
+    let values: Vec<f64> = b[8..8 + 8 * len]
+        .chunks_exact(8)
+        .map(|chunk| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            if little_endian {
+                f64::from_le_bytes(bytes)
+            } else {
+                f64::from_be_bytes(bytes)
+            }
+        })
+        .collect();
+
+    let mut envelope = Envelope {
+        min_x: values[0],
+        max_x: values[1],
+        min_y: values[2],
+        max_y: values[3],
+        min_z: None,
+        max_z: None,
+        min_m: None,
+        max_m: None,
+    };
+    match b[3] & 0b00001110 {
+        0b00000100 => {
+            envelope.min_z = Some(values[4]);
+            envelope.max_z = Some(values[5]);
+        }
+        0b00000110 => {
+            envelope.min_m = Some(values[4]);
+            envelope.max_m = Some(values[5]);
+        }
+        0b00001000 => {
+            envelope.min_z = Some(values[4]);
+            envelope.max_z = Some(values[5]);
+            envelope.min_m = Some(values[6]);
+            envelope.max_m = Some(values[7]);
+        }
+        _ => {}
+    }
+    Ok(Some(envelope))
+}
+
+/// Whether the empty geometry flag is set in the header of a GeoPackage
+/// geometry blob.
+pub(crate) fn gpkg_geometry_is_empty(b: &[u8]) -> Result<bool> {
+    envelope_len(b)?;
+    // Bit 4 of the flags marks an empty geometry.
+    Ok(b[3] & 0b00010000 != 0)
+}
+
+/// Read the srs_id stored in the header of a GeoPackage geometry blob.
+pub(crate) fn gpkg_geometry_srs_id(b: &[u8]) -> Result<i32> {
+    envelope_len(b)?;
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&b[4..8]);
+    if is_little_endian(b[3]) {
+        Ok(i32::from_le_bytes(bytes))
+    } else {
+        Ok(i32::from_be_bytes(bytes))
+    }
+}
+
+/// Overwrite the srs_id stored in the header of a GeoPackage geometry blob.
+pub(crate) fn set_gpkg_geometry_srs_id(b: &mut [u8], srs_id: i32) -> Result<()> {
+    envelope_len(b)?;
+    let bytes = if is_little_endian(b[3]) {
+        srs_id.to_le_bytes()
+    } else {
+        srs_id.to_be_bytes()
+    };
+    b[4..8].copy_from_slice(&bytes);
+    Ok(())
+}
+
+// Bit 0 of the flags is the byte order of the header: 1 for little endian.
+fn is_little_endian(flags: u8) -> bool {
+    flags & 0b00000001 != 0
+}
+
+// Validate the header and return the number of doubles in the envelope.
+fn envelope_len(b: &[u8]) -> Result<usize> {
     if b.len() < 8 {
         return Err(GpkgError::InvalidGpkgGeometryLength {
             len: b.len(),
@@ -109,42 +636,117 @@ pub(crate) fn gpkg_geometry_to_wkb_bytes(b: &[u8]) -> Result<&[u8]> {
         });
     }
 
+    if b[2] != 0 {
+        return Err(GpkgError::UnsupportedGpkgGeometryVersion(b[2]));
+    }
+
     let flags = b[3];
-    let envelope_size: usize = match flags & 0b00001110 {
-        0b00000000 => 0,  // no envelope
-        0b00000010 => 32, // envelope is [minx, maxx, miny, maxy], 32 bytes
-        0b00000100 => 48, // envelope is [minx, maxx, miny, maxy, minz, maxz], 48 bytes
-        0b00000110 => 48, // envelope is [minx, maxx, miny, maxy, minm, maxm], 48 bytes
-        0b00001000 => 64, // envelope is [minx, maxx, miny, maxy, minz, maxz, minm, maxm], 64 bytes
+    let envelope_len: usize = match flags & 0b00001110 {
+        0b00000000 => 0, // no envelope
+        0b00000010 => 4, // envelope is [minx, maxx, miny, maxy], 32 bytes
+        0b00000100 => 6, // envelope is [minx, maxx, miny, maxy, minz, maxz], 48 bytes
+        0b00000110 => 6, // envelope is [minx, maxx, miny, maxy, minm, maxm], 48 bytes
+        0b00001000 => 8, // envelope is [minx, maxx, miny, maxy, minz, maxz, minm, maxm], 64 bytes
         _ => {
             return Err(GpkgError::InvalidGpkgGeometryFlags(flags));
         }
     };
-    let offset = 8 + envelope_size;
-    if b.len() < offset {
+    let required = 8 + 8 * envelope_len;
+    if b.len() < required {
         return Err(GpkgError::InvalidGpkgGeometryEnvelope {
             len: b.len(),
-            required: offset,
+            required,
         });
     }
 
-    Ok(&b[offset..])
+    Ok(envelope_len)
 }
 
 pub(crate) fn gpkg_geometry_to_wkb<'a>(b: &'a [u8]) -> Result<Wkb<'a>> {
-    Ok(Wkb::try_new(gpkg_geometry_to_wkb_bytes(b)?)?)
+    let bytes = gpkg_geometry_to_wkb_bytes(b)?;
+    reject_non_linear_wkb(bytes)?;
+    Ok(Wkb::try_new(bytes)?)
 }
 
+// The WKB reader only looks at the lowest 3 bits of the type code, so it would
+// misread e.g. a CurvePolygon (10) as a LineString (2). Fail clearly instead.
+// cf. https://www.geopackage.org/spec140/index.html#extension_geometry_types
+fn reject_non_linear_wkb(wkb: &[u8]) -> Result<()> {
+    if wkb.len() < 5 {
+        return Ok(());
+    }
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&wkb[1..5]);
+    let code = match wkb[0] {
+        0 => u32::from_be_bytes(bytes),
+        _ => u32::from_le_bytes(bytes),
+    };
+    // Strip the EWKB dimension flags and the ISO dimension offset.
+    let name = match (code & 0x0FFF_FFFF) % 1000 {
+        8 => "CIRCULARSTRING",
+        9 => "COMPOUNDCURVE",
+        10 => "CURVEPOLYGON",
+        11 => "MULTICURVE",
+        12 => "MULTISURFACE",
+        13 => "CURVE",
+        14 => "SURFACE",
+        15 => "POLYHEDRALSURFACE",
+        16 => "TIN",
+        17 => "TRIANGLE",
+        _ => return Ok(()),
+    };
+    Err(GpkgError::UnsupportedGeometryType(name.to_string()))
+}
+
+/// Prepend the GeoPackage header to WKB. If `envelope` is given, it's written
+/// into the header, including the Z and M ranges when they are present. The
+/// empty geometry flag is set when the geometry has no coordinates, in which
+/// case no envelope is written.
 // cf. https://www.geopackage.org/spec140/index.html#gpb_format
-pub(crate) fn wkb_to_gpkg_geometry<'a>(wkb: Wkb<'a>, srs_id: u32) -> Result<Vec<u8>> {
-    let mut geom = Vec::with_capacity(wkb.buf().len() + 8);
+pub(crate) fn wkb_to_gpkg_geometry<'a>(
+    wkb: Wkb<'a>,
+    srs_id: u32,
+    envelope: Option<&Bounds>,
+) -> Result<Vec<u8>> {
+    let is_empty = bounds_from_geometry(&wkb).is_none();
+    let envelope = if is_empty { None } else { envelope };
+
+    // The envelope contents indicator code is stored in bits 1-3 of the flags.
+    let (envelope_code, envelope_values) = match envelope {
+        None => (0u8, vec![]),
+        Some(b) => {
+            let mut values = vec![b.minx, b.maxx, b.miny, b.maxy];
+            let code = match (b.z, b.m) {
+                (None, None) => 1,
+                (Some((minz, maxz)), None) => {
+                    values.extend([minz, maxz]);
+                    2
+                }
+                (None, Some((minm, maxm))) => {
+                    values.extend([minm, maxm]);
+                    3
+                }
+                (Some((minz, maxz)), Some((minm, maxm))) => {
+                    values.extend([minz, maxz, minm, maxm]);
+                    4
+                }
+            };
+            (code, values)
+        }
+    };
+
+    let empty_flag = if is_empty { 0b00010000 } else { 0 };
+    let mut geom = Vec::with_capacity(wkb.buf().len() + 8 + envelope_values.len() * 8);
     geom.extend_from_slice(&[
-        0x47u8, // magic
-        0x50u8, // magic
-        0x00u8, // version
-        0x01u8, // flags (little endian SRS ID, no envelope)
+        0x47u8,                                   // magic
+        0x50u8,                                   // magic
+        0x00u8,                                   // version
+        0x01u8 | envelope_code << 1 | empty_flag, // flags (little endian)
     ]);
     geom.extend_from_slice(&srs_id.to_le_bytes());
+    for value in envelope_values {
+        geom.extend_from_slice(&value.to_le_bytes());
+    }
     geom.extend_from_slice(wkb.buf());
 
     Ok(geom)
@@ -152,12 +754,161 @@ pub(crate) fn wkb_to_gpkg_geometry<'a>(wkb: Wkb<'a>, srs_id: u32) -> Result<Vec<
 
 #[cfg(test)]
 mod tests {
-    use super::{gpkg_geometry_to_wkb, wkb_to_gpkg_geometry};
+    use super::{
+        gpkg_geometry_envelope, gpkg_geometry_is_empty, gpkg_geometry_srs_id, gpkg_geometry_to_wkb,
+        wkb_to_gpkg_geometry,
+    };
     use crate::Result;
     use crate::Value;
+    use crate::error::GpkgError;
+    use crate::sql_functions::Bounds;
+    use crate::types::Envelope;
     use geo_types::Point;
+    use std::str::FromStr;
     use wkb::reader::Wkb;
 
+    #[test]
+    fn borrows_and_takes_properties() -> Result<()> {
+        let names = ["text", "blob"];
+        let mut feature = super::GpkgFeature::new(
+            1,
+            Point::new(0.0, 0.0),
+            vec![Value::Text("alpha".to_string()), Value::Blob(vec![1, 2, 3])],
+            &names,
+        )?;
+
+        let text: &str = feature.property_ref("text").expect("text").try_into()?;
+        assert_eq!(text, "alpha");
+        let blob: &[u8] = feature.property_ref("blob").expect("blob").try_into()?;
+        assert_eq!(blob, [1, 2, 3]);
+        assert_eq!(feature.property_ref("missing"), None);
+
+        assert_eq!(
+            feature.take_property("blob"),
+            Some(Value::Blob(vec![1, 2, 3]))
+        );
+        assert_eq!(feature.property_ref("blob"), Some(&Value::Null));
+        assert_eq!(feature.take_property("missing"), None);
+        assert_eq!(
+            feature.property("text"),
+            Some(Value::Text("alpha".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn typed_property_accessors() -> Result<()> {
+        let names = ["int", "real", "text", "flag", "null"];
+        let feature = super::GpkgFeature::new(
+            1,
+            Point::new(0.0, 0.0),
+            vec![
+                Value::Integer(42),
+                Value::Real(1.5),
+                Value::Text("alpha".to_string()),
+                Value::Integer(1),
+                Value::Null,
+            ],
+            &names,
+        )?;
+
+        assert_eq!(feature.get::<i64>("int")?, 42);
+        assert_eq!(feature.get::<i32>("int")?, 42);
+        assert_eq!(feature.get::<i16>("int")?, 42);
+        assert_eq!(feature.get::<i8>("int")?, 42);
+        assert_eq!(feature.get::<isize>("int")?, 42);
+        assert_eq!(feature.get::<u64>("int")?, 42);
+        assert_eq!(feature.get::<u32>("int")?, 42);
+        assert_eq!(feature.get::<u16>("int")?, 42);
+        assert_eq!(feature.get::<u8>("int")?, 42);
+        assert_eq!(feature.get::<usize>("int")?, 42);
+        assert_eq!(feature.get::<f64>("real")?, 1.5);
+        assert_eq!(feature.get::<f32>("real")?, 1.5);
+        assert_eq!(feature.get::<f64>("int")?, 42.0);
+        assert_eq!(feature.get::<String>("text")?, "alpha");
+        assert!(feature.get::<bool>("flag")?);
+        assert_eq!(feature.get::<Option<i64>>("null")?, None);
+        assert_eq!(feature.get::<Option<i64>>("int")?, Some(42));
+        assert_eq!(
+            feature.get::<Option<String>>("text")?.as_deref(),
+            Some("alpha")
+        );
+
+        assert_eq!(feature.get_opt::<i64>("int")?, Some(42));
+        assert_eq!(feature.get_opt::<u8>("int")?, Some(42));
+        assert_eq!(feature.get_opt::<f64>("real")?, Some(1.5));
+        assert_eq!(feature.get_opt::<String>("text")?.as_deref(), Some("alpha"));
+        assert_eq!(feature.get_opt::<bool>("flag")?, Some(true));
+        assert_eq!(feature.get_opt::<i64>("null")?, None);
+        assert_eq!(feature.get_opt::<String>("null")?, None);
+
+        assert!(matches!(
+            feature.get::<i64>("missing"),
+            Err(GpkgError::MissingProperty { property }) if property == "missing"
+        ));
+        assert!(matches!(
+            feature.get_opt::<i64>("missing"),
+            Err(GpkgError::MissingProperty { .. })
+        ));
+        let is_type_mismatch = |err: GpkgError, expected_property: &str| match err {
+            GpkgError::InvalidPropertyValue { property, source } => {
+                property == expected_property
+                    && matches!(*source, GpkgError::ValueTypeMismatch { .. })
+            }
+            _ => false,
+        };
+        let err = feature.get::<i64>("text").expect_err("TEXT as i64");
+        assert!(matches!(
+            &err,
+            GpkgError::InvalidPropertyValue { property, source } if property == "text"
+                && matches!(
+                    source.as_ref(),
+                    GpkgError::ValueTypeMismatch {
+                        expected: "i64",
+                        actual: "TEXT"
+                    }
+                )
+        ));
+        assert!(is_type_mismatch(
+            feature.get::<i64>("null").expect_err("NULL as i64"),
+            "null"
+        ));
+        assert!(is_type_mismatch(
+            feature
+                .get_opt::<String>("int")
+                .expect_err("INTEGER as String"),
+            "int"
+        ));
+        assert!(is_type_mismatch(
+            feature.get::<i8>("real").expect_err("REAL as i8"),
+            "real"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn property_names_follow_schema_order() -> Result<()> {
+        let names = ["zeta", "alpha", "mid"];
+        let feature = super::GpkgFeature::new(
+            1,
+            Point::new(0.0, 0.0),
+            vec![Value::Integer(1), Value::Text("a".to_string()), Value::Null],
+            &names,
+        )?;
+
+        assert_eq!(feature.property_names().collect::<Vec<_>>(), names);
+        let pairs: Vec<(&str, &Value)> = feature.iter().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("zeta", &Value::Integer(1)),
+                ("alpha", &Value::Text("a".to_string())),
+                ("mid", &Value::Null),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn gpkg_geometry_roundtrip() -> Result<()> {
         let point = Point::new(3.0, -1.0);
@@ -165,13 +916,207 @@ mod tests {
         wkb::writer::write_geometry(&mut buf, &point, &Default::default())?;
         let wkb = Wkb::try_new(&buf)?;
         let expected = wkb.buf().to_vec();
-        let gpkg_blob = wkb_to_gpkg_geometry(wkb, 4326)?;
+        let gpkg_blob = wkb_to_gpkg_geometry(wkb, 4326, None)?;
 
         let recovered = gpkg_geometry_to_wkb(&gpkg_blob)?;
         assert_eq!(recovered.buf(), expected.as_slice());
         Ok(())
     }
 
+    #[test]
+    fn gpkg_geometry_roundtrip_with_envelope() -> Result<()> {
+        let point = Point::new(3.0, -1.0);
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &point, &Default::default())?;
+
+        let xy = Bounds {
+            minx: 3.0,
+            maxx: 3.0,
+            miny: -1.0,
+            maxy: -1.0,
+            z: None,
+            m: None,
+        };
+        let cases = [
+            (xy, 0x03, 32),
+            (
+                Bounds {
+                    z: Some((1.0, 2.0)),
+                    ..xy
+                },
+                0x05,
+                48,
+            ),
+            (
+                Bounds {
+                    m: Some((1.0, 2.0)),
+                    ..xy
+                },
+                0x07,
+                48,
+            ),
+            (
+                Bounds {
+                    z: Some((1.0, 2.0)),
+                    m: Some((3.0, 4.0)),
+                    ..xy
+                },
+                0x09,
+                64,
+            ),
+        ];
+        for (bounds, flags, envelope_size) in cases {
+            let blob = wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, Some(&bounds))?;
+            assert_eq!(blob[3], flags);
+            assert_eq!(blob.len(), 8 + envelope_size + buf.len());
+            assert_eq!(&blob[8..16], &3.0f64.to_le_bytes());
+            assert_eq!(&blob[24..32], &(-1.0f64).to_le_bytes());
+
+            let recovered = gpkg_geometry_to_wkb(&blob)?;
+            assert_eq!(recovered.buf(), buf.as_slice());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gpkg_geometry_envelope_reads_written_envelope() -> Result<()> {
+        let point = Point::new(3.0, -1.0);
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &point, &Default::default())?;
+
+        let blob = wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, None)?;
+        assert_eq!(gpkg_geometry_envelope(&blob)?, None);
+
+        let bounds = Bounds {
+            minx: 1.0,
+            maxx: 2.0,
+            miny: 3.0,
+            maxy: 4.0,
+            z: Some((5.0, 6.0)),
+            m: Some((7.0, 8.0)),
+        };
+        let blob = wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, Some(&bounds))?;
+        assert_eq!(
+            gpkg_geometry_envelope(&blob)?,
+            Some(Envelope {
+                min_x: 1.0,
+                max_x: 2.0,
+                min_y: 3.0,
+                max_y: 4.0,
+                min_z: Some(5.0),
+                max_z: Some(6.0),
+                min_m: Some(7.0),
+                max_m: Some(8.0),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn gpkg_geometry_envelope_honors_big_endian_header() -> Result<()> {
+        // Flags 0x06 => big endian header with an XYM envelope.
+        let mut blob = vec![0x47, 0x50, 0x00, 0x06];
+        blob.extend_from_slice(&4326u32.to_be_bytes());
+        for v in [-1.0f64, 1.0, -2.0, 2.0, 10.0, 20.0] {
+            blob.extend_from_slice(&v.to_be_bytes());
+        }
+        let envelope = gpkg_geometry_envelope(&blob)?.expect("envelope");
+        assert_eq!(
+            envelope,
+            Envelope {
+                min_x: -1.0,
+                max_x: 1.0,
+                min_y: -2.0,
+                max_y: 2.0,
+                min_z: None,
+                max_z: None,
+                min_m: Some(10.0),
+                max_m: Some(20.0),
+            }
+        );
+
+        blob.truncate(40);
+        assert!(matches!(
+            gpkg_geometry_envelope(&blob),
+            Err(crate::error::GpkgError::InvalidGpkgGeometryEnvelope {
+                len: 40,
+                required: 56
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn gpkg_geometry_reads_big_endian_header() -> Result<()> {
+        // Big endian WKB for POINT (3 -1).
+        let mut wkb = vec![0x00];
+        wkb.extend_from_slice(&1u32.to_be_bytes());
+        wkb.extend_from_slice(&3.0f64.to_be_bytes());
+        wkb.extend_from_slice(&(-1.0f64).to_be_bytes());
+
+        // Flags 0x00 => big endian header without envelope.
+        let mut blob = vec![0x47, 0x50, 0x00, 0x00];
+        blob.extend_from_slice(&3857i32.to_be_bytes());
+        blob.extend_from_slice(&wkb);
+        assert_eq!(gpkg_geometry_srs_id(&blob)?, 3857);
+        assert_eq!(gpkg_geometry_to_wkb(&blob)?.buf(), wkb.as_slice());
+
+        // Flags 0x02 => big endian header with an XY envelope.
+        let mut blob = vec![0x47, 0x50, 0x00, 0x02];
+        blob.extend_from_slice(&(-1i32).to_be_bytes());
+        for v in [3.0f64, 3.0, -1.0, -1.0] {
+            blob.extend_from_slice(&v.to_be_bytes());
+        }
+        blob.extend_from_slice(&wkb);
+        assert_eq!(gpkg_geometry_srs_id(&blob)?, -1);
+        assert_eq!(gpkg_geometry_to_wkb(&blob)?.buf(), wkb.as_slice());
+        let envelope = gpkg_geometry_envelope(&blob)?.expect("envelope");
+        assert_eq!((envelope.min_x, envelope.max_y), (3.0, -1.0));
+
+        let mut feature = super::GpkgFeature::new(1, Point::new(0.0, 0.0), vec![], &[])?;
+        feature.geometry = Some(blob);
+        assert_eq!(feature.geometry_srs_id()?, Some(-1));
+        feature.geometry = None;
+        assert_eq!(feature.geometry_srs_id()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn gpkg_geometry_rejects_unsupported_version() {
+        let mut blob = vec![0x47, 0x50, 0x01, 0x01, 0, 0, 0, 0];
+        blob.extend_from_slice(&[0; 21]);
+        assert!(matches!(
+            gpkg_geometry_to_wkb(&blob),
+            Err(crate::error::GpkgError::UnsupportedGpkgGeometryVersion(1))
+        ));
+        assert!(gpkg_geometry_srs_id(&blob).is_err());
+    }
+
+    #[test]
+    fn gpkg_geometry_sets_empty_flag() -> Result<()> {
+        let bounds = Bounds {
+            minx: 0.0,
+            maxx: 0.0,
+            miny: 0.0,
+            maxy: 0.0,
+            z: None,
+            m: None,
+        };
+        for wkt in ["POINT EMPTY", "LINESTRING EMPTY", "POINT (1 2)"] {
+            let geometry = wkt::Wkt::<f64>::from_str(wkt).expect("wkt");
+            let mut buf = Vec::new();
+            wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
+            let blob = wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, Some(&bounds))?;
+
+            let is_empty = wkt != "POINT (1 2)";
+            assert_eq!(gpkg_geometry_is_empty(&blob)?, is_empty, "{wkt}");
+            // Empty geometries don't get an envelope.
+            assert_eq!(gpkg_geometry_envelope(&blob)?.is_none(), is_empty, "{wkt}");
+            assert_eq!(gpkg_geometry_to_wkb(&blob)?.buf(), buf.as_slice());
+        }
+        Ok(())
+    }
+
     #[test]
     fn gpkg_geometry_rejects_invalid_flags() {
         let mut blob = vec![0x47, 0x50, 0x00, 0x0A, 0, 0, 0, 0];
@@ -208,6 +1153,173 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn gpkg_geometry_rejects_missing_wkb_payload() {
+        let blob = vec![0x47, 0x50, 0x00, 0x01, 0, 0, 0, 0];
+        assert!(matches!(
+            gpkg_geometry_to_wkb(&blob),
+            Err(crate::error::GpkgError::InvalidGpkgGeometryLength { len: 8, minimum: 9 })
+        ));
+
+        let mut blob = vec![0x47, 0x50, 0x00, 0x03, 0, 0, 0, 0];
+        blob.extend_from_slice(&[0; 32]);
+        assert!(matches!(
+            gpkg_geometry_to_wkb(&blob),
+            Err(crate::error::GpkgError::InvalidGpkgGeometryLength {
+                len: 40,
+                minimum: 41
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_gpkg_geometry_does_not_panic() -> Result<()> {
+        let point = Point::new(3.0, -1.0);
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &point, &Default::default())?;
+        let bounds = Bounds {
+            minx: 3.0,
+            maxx: 3.0,
+            miny: -1.0,
+            maxy: -1.0,
+            z: None,
+            m: None,
+        };
+        let blobs = [
+            wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, None)?,
+            wkb_to_gpkg_geometry(Wkb::try_new(&buf)?, 4326, Some(&bounds))?,
+        ];
+
+        for blob in &blobs {
+            for len in 0..blob.len() {
+                let truncated = &blob[..len];
+                assert!(gpkg_geometry_to_wkb(truncated).is_err(), "len {len}");
+                let _ = gpkg_geometry_envelope(truncated);
+                let _ = gpkg_geometry_srs_id(truncated);
+                let _ = gpkg_geometry_is_empty(truncated);
+                let value = Value::Geometry(truncated.to_vec());
+                assert!(Wkb::try_from(&value).is_err(), "len {len}");
+            }
+        }
+
+        // Arbitrary blobs that are not GeoPackage geometries at all.
+        for len in 0..12 {
+            let blob: Vec<u8> = (0..len).map(|i: u8| i.wrapping_mul(37)).collect();
+            let _ = gpkg_geometry_to_wkb(&blob);
+            let _ = gpkg_geometry_envelope(&blob);
+            let _ = gpkg_geometry_srs_id(&blob);
+            let _ = gpkg_geometry_is_empty(&blob);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn geometry_as_geo_roundtrip() -> Result<()> {
+        use geo_types::{
+            Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+            Polygon,
+        };
+
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]);
+        let polygon = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            vec![],
+        );
+        let geometries: Vec<Geometry> = vec![
+            Point::new(3.0, -1.0).into(),
+            line.clone().into(),
+            polygon.clone().into(),
+            MultiPoint::from(vec![Point::new(1.0, 2.0), Point::new(3.0, 4.0)]).into(),
+            MultiLineString::new(vec![line.clone()]).into(),
+            MultiPolygon::new(vec![polygon]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(5.0, 6.0).into(),
+                line.into(),
+            ])),
+        ];
+
+        let mut feature = super::GpkgFeature::new(1, Point::new(0.0, 0.0), vec![], &[])?;
+        for geometry in geometries {
+            let Value::Geometry(blob) = Value::from_geometry(&geometry, 4326)? else {
+                panic!("expected Value::Geometry");
+            };
+            feature.geometry = Some(blob);
+            assert_eq!(feature.geometry_as_geo()?, geometry);
+        }
+
+        feature.geometry = None;
+        assert!(matches!(
+            feature.geometry_as_geo(),
+            Err(crate::error::GpkgError::NullGeometryValue)
+        ));
+
+        for wkt in [
+            "POINT Z (1 2 3)",
+            "LINESTRING M (0 0 1, 1 1 2)",
+            "POINT EMPTY",
+        ] {
+            let geometry = wkt::Wkt::<f64>::from_str(wkt).expect("wkt");
+            let Value::Geometry(blob) = Value::from_geometry(&geometry, 4326)? else {
+                panic!("expected Value::Geometry");
+            };
+            feature.geometry = Some(blob);
+            assert!(
+                matches!(
+                    feature.geometry_as_geo(),
+                    Err(crate::error::GpkgError::UnsupportedGeoTypesGeometry { .. })
+                ),
+                "{wkt}"
+            );
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "wkt")]
+    #[test]
+    fn geometry_wkt_formats_geometry() -> Result<()> {
+        let mut feature = super::GpkgFeature::new(1, Point::new(0.0, 0.0), vec![], &[])?;
+        let line = geo_types::LineString::from(vec![(1.0, -2.5), (1.0 / 3.0, 100.0)]);
+        let Value::Geometry(blob) = Value::from_geometry(&line, 4326)? else {
+            panic!("expected Value::Geometry");
+        };
+        feature.geometry = Some(blob);
+
+        assert_eq!(
+            feature.geometry_wkt()?,
+            "LINESTRING(1 -2.5,0.3333333333333333 100)"
+        );
+        assert_eq!(
+            feature.geometry_wkt_with_precision(2)?,
+            "LINESTRING(1.00 -2.50,0.33 100.00)"
+        );
+        assert_eq!(
+            feature.geometry_wkt_with_precision(0)?,
+            "LINESTRING(1 -2,0 100)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn geometry_opt_handles_null_geometry() -> Result<()> {
+        let mut feature = super::GpkgFeature::new(1, Point::new(1.0, 2.0), vec![], &[])?;
+        // `new()` stores raw WKB, so wrap it into a GeoPackage blob.
+        let wkb = Wkb::try_new(feature.geometry.as_deref().expect("geometry"))?;
+        feature.geometry = Some(wkb_to_gpkg_geometry(wkb, 4326, None)?);
+        assert!(feature.geometry_opt()?.is_some());
+
+        feature.geometry = None;
+        assert!(feature.geometry_opt()?.is_none());
+        assert!(matches!(
+            feature.geometry(),
+            Err(crate::error::GpkgError::NullGeometryValue)
+        ));
+
+        feature.geometry = Some(vec![0x47, 0x50, 0x00]);
+        assert!(feature.geometry_opt().is_err());
+        Ok(())
+    }
+
     #[test]
     fn property_invalid_index_reports_error() -> Result<()> {
         let feature =
@@ -216,4 +1328,25 @@ mod tests {
         assert!(value.is_none());
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn feature_serializes_id_and_properties_in_column_order() -> Result<()> {
+        let feature = super::GpkgFeature::new(
+            7,
+            Point::new(0.0, 0.0),
+            vec![
+                Value::Text("alpha".to_string()),
+                Value::Integer(3),
+                Value::Null,
+                Value::Real(0.5),
+            ],
+            &["name", "count", "missing", "ratio"],
+        )?;
+        assert_eq!(
+            serde_json::to_string(&feature).unwrap(),
+            r#"{"id":7,"properties":{"name":"alpha","count":3,"missing":null,"ratio":0.5}}"#
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,378 @@
+use crate::Value;
+use crate::error::{GpkgError, Result};
+use crate::ogc_sql::sql_update_columns;
+use crate::sql_functions::Bounds;
+use crate::types::params_from_properties;
+use geo_traits::GeometryTrait;
+
+use super::GpkgLayer;
+
+/// Builder of a feature to insert into or update in a layer, with the
+/// properties set by name.
+///
+/// Created by [`GpkgLayer::feature_builder`]. `set()` fails immediately for
+/// a name that is not a property column of the layer, and, when strict types
+/// are enabled, for a value that doesn't match the column type.
+///
+/// The values are kept after `insert()` and `update()`, so the builder can be
+/// reused for many features: call `clear()` to start over without
+/// reallocating, or just overwrite the values that change.
+///
+/// Example:
+/// ```no_run
+/// use geo_types::Point;
+/// use rusqlite_gpkg::Gpkg;
+///
+/// let gpkg = Gpkg::open("data/example.gpkg")?;
+/// let layer = gpkg.get_layer("points")?;
+/// let mut builder = layer.feature_builder();
+/// for i in 0..10 {
+///     builder.clear();
+///     builder
+///         .geometry(Point::new(i as f64, 0.0))?
+///         .set("name", format!("point {i}"))?;
+///     builder.insert()?;
+/// }
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub struct GpkgFeatureBuilder<'a> {
+    layer: &'a GpkgLayer,
+    geometry: Option<(Value, Option<Bounds>)>,
+    values: Vec<Option<Value>>,
+}
+
+impl<'a> GpkgFeatureBuilder<'a> {
+    pub(super) fn new(layer: &'a GpkgLayer) -> Self {
+        Self {
+            layer,
+            geometry: None,
+            values: vec![None; layer.property_columns.len()],
+        }
+    }
+
+    /// Set the value of the named property column.
+    ///
+    /// Fails with `GpkgError::MissingProperty` if the name is not a property
+    /// column of the layer, and with `GpkgError::PropertyTypeMismatch` if
+    /// strict types are enabled and the value doesn't match the column type.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let mut builder = layer.feature_builder();
+    /// builder.set("name", "alpha")?.set("value", 1)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) -> Result<&mut Self> {
+        let idx = *self.layer.property_index_by_name.get(name).ok_or_else(|| {
+            GpkgError::MissingProperty {
+                property: name.to_string(),
+            }
+        })?;
+        let value = value.into();
+        self.layer
+            .check_property_type(&self.layer.property_columns[idx], &value)?;
+        self.values[idx] = Some(value);
+        Ok(self)
+    }
+
+    /// Set the geometry of the feature.
+    ///
+    /// The geometry is encoded right away, so this fails with
+    /// `GpkgError::GeometryTypeMismatch` if it doesn't match the layer's
+    /// geometry type and dimension.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let mut builder = layer.feature_builder();
+    /// builder.geometry(Point::new(1.0, 2.0))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn geometry<G: GeometryTrait<T = f64>>(&mut self, geometry: G) -> Result<&mut Self> {
+        let (geom, bounds) = self.layer.encode_geometry(geometry, true)?;
+        self.geometry = Some((Value::Geometry(geom), bounds));
+        Ok(self)
+    }
+
+    /// Unset the geometry and all the property values, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.geometry = None;
+        self.values.fill(None);
+    }
+
+    /// Insert a new feature with the geometry and the property values set so
+    /// far.
+    ///
+    /// A property that was not set gets the `DEFAULT` value of its column,
+    /// or `NULL` if there is none; a missing geometry is written as `NULL`.
+    /// Fails with `GpkgError::MissingRequiredProperty` if a `NOT NULL` column
+    /// without a default value was not set or was set to `NULL`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let mut builder = layer.feature_builder();
+    /// builder.geometry(Point::new(1.0, 2.0))?.set("name", "alpha")?;
+    /// builder.insert()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert(&self) -> Result<()> {
+        self.layer.ensure_writable()?;
+
+        let mut properties = Vec::with_capacity(self.values.len());
+        for (spec, value) in self.layer.property_columns.iter().zip(&self.values) {
+            let value = value
+                .as_ref()
+                .or(spec.default.as_ref())
+                .unwrap_or(&Value::Null);
+            if spec.not_null && matches!(value, Value::Null) {
+                return Err(GpkgError::MissingRequiredProperty {
+                    column: spec.name.clone(),
+                });
+            }
+            properties.push(value);
+        }
+
+        let (geom, bounds) = match &self.geometry {
+            Some((geom, bounds)) => (geom, *bounds),
+            None => (&Value::Null, None),
+        };
+        let params = params_from_properties(std::iter::once(geom).chain(properties), None);
+        let mut stmt = self.layer.conn.prepare_cached(&self.layer.insert_sql)?;
+        stmt.execute(params)?;
+        self.layer.expand_extent(bounds)?;
+        self.layer.touch_last_change()
+    }
+
+    /// Update the feature with the given id, writing only the geometry (if
+    /// set) and the property values set so far.
+    ///
+    /// Fails with `GpkgError::FeatureNotFound` if no feature has the given id.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let mut builder = layer.feature_builder();
+    /// builder.set("name", "beta")?;
+    /// builder.update(1)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update(&self, id: i64) -> Result<()> {
+        self.layer.ensure_writable()?;
+
+        let mut columns = Vec::new();
+        let mut params = Vec::new();
+        if let Some((geom, _)) = &self.geometry {
+            columns.push(self.layer.geometry_column.as_str());
+            params.push(geom);
+        }
+        for (spec, value) in self.layer.property_columns.iter().zip(&self.values) {
+            if let Some(value) = value {
+                columns.push(spec.name.as_str());
+                params.push(value);
+            }
+        }
+
+        // There is nothing to SET, but a missing feature is still an error.
+        if columns.is_empty() {
+            return match self.layer.get_feature(id)? {
+                Some(_) => Ok(()),
+                None => Err(GpkgError::FeatureNotFound { id }),
+            };
+        }
+
+        let sql = sql_update_columns(
+            &self.layer.layer_name,
+            &self.layer.primary_key_column,
+            columns,
+        );
+        let mut stmt = self.layer.conn.prepare_cached(&sql)?;
+        if stmt.execute(params_from_properties(params, Some(id)))? == 0 {
+            return Err(GpkgError::FeatureNotFound { id });
+        }
+        // As with `GpkgLayer::update()`, the stored extent is only grown here.
+        self.layer
+            .expand_extent(self.geometry.as_ref().and_then(|(_, bounds)| *bounds))?;
+        self.layer.touch_last_change()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ColumnSpec, ColumnType};
+    use crate::{Gpkg, GpkgError, GpkgLayer, Result, Value};
+    use geo_types::{LineString, Point};
+    use wkb::reader::{Dimension, GeometryType};
+
+    fn create_layer(gpkg: &Gpkg) -> Result<GpkgLayer> {
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                not_null: true,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "kind".to_string(),
+                column_type: ColumnType::Varchar,
+                not_null: true,
+                default: Some(Value::from("unknown")),
+                ..Default::default()
+            },
+        ];
+        gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )
+    }
+
+    #[test]
+    fn builder_inserts_named_values_and_defaults() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+
+        let mut builder = layer.feature_builder();
+        builder
+            .set("value", 7)?
+            .geometry(Point::new(1.0, 2.0))?
+            .set("name", "alpha")?;
+        builder.insert()?;
+
+        let feature = layer.get_feature(1)?.expect("inserted feature");
+        assert_eq!(feature.get::<String>("name")?, "alpha");
+        assert_eq!(feature.get::<i64>("value")?, 7);
+        assert_eq!(feature.get::<String>("kind")?, "unknown");
+        assert_eq!(layer.extent()?, Some((1.0, 2.0, 1.0, 2.0)));
+
+        // Values are kept after insert(), so only the changed ones are set.
+        builder.set("name", "beta")?.set("kind", "tree")?;
+        builder.insert()?;
+        let feature = layer.get_feature(2)?.expect("inserted feature");
+        assert_eq!(feature.get::<String>("name")?, "beta");
+        assert_eq!(feature.get::<i64>("value")?, 7);
+        assert_eq!(feature.get::<String>("kind")?, "tree");
+
+        // After clear(), unset properties are NULL and the geometry is NULL.
+        builder.clear();
+        builder.set("name", "gamma")?;
+        builder.insert()?;
+        let feature = layer.get_feature(3)?.expect("inserted feature");
+        assert_eq!(feature.get_opt::<i64>("value")?, None);
+        assert!(matches!(
+            feature.geometry(),
+            Err(GpkgError::NullGeometryValue)
+        ));
+        assert_eq!(layer.count()?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_validates_names_types_and_not_null_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let mut layer = create_layer(&gpkg)?;
+
+        let mut builder = layer.feature_builder();
+        let err = builder.set("missing", 1).err().expect("unknown column");
+        assert!(matches!(err, GpkgError::MissingProperty { property } if property == "missing"));
+
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]);
+        let err = builder.geometry(line).err().expect("wrong geometry type");
+        assert!(matches!(err, GpkgError::GeometryTypeMismatch { .. }));
+
+        builder.set("value", 1)?;
+        let err = builder.insert().expect_err("name is NOT NULL");
+        assert!(matches!(err, GpkgError::MissingRequiredProperty { column } if column == "name"));
+
+        builder.set("name", Value::Null)?;
+        let err = builder.insert().expect_err("name is NULL");
+        assert!(matches!(err, GpkgError::MissingRequiredProperty { column } if column == "name"));
+
+        // Explicit NULL overrides the default of a NOT NULL column too.
+        builder.set("name", "alpha")?.set("kind", Value::Null)?;
+        let err = builder.insert().expect_err("kind is NULL");
+        assert!(matches!(err, GpkgError::MissingRequiredProperty { column } if column == "kind"));
+        assert_eq!(layer.count()?, 0);
+
+        layer.set_strict_types(true);
+        let mut builder = layer.feature_builder();
+        let err = builder.set("value", "x").err().expect("strict types");
+        assert!(matches!(err, GpkgError::PropertyTypeMismatch { column, .. } if column == "value"));
+        Ok(())
+    }
+
+    #[test]
+    fn builder_updates_only_the_set_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+        layer.insert(
+            Point::new(0.0, 0.0),
+            [Value::from("alpha"), Value::from(1), Value::from("a")],
+        )?;
+
+        let mut builder = layer.feature_builder();
+        builder.set("value", 2)?;
+        builder.update(1)?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.get::<String>("name")?, "alpha");
+        assert_eq!(feature.get::<i64>("value")?, 2);
+
+        builder.geometry(Point::new(5.0, 6.0))?;
+        builder.update(1)?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.get::<i64>("value")?, 2);
+        assert_eq!(layer.features_in_bbox(4.0, 5.0, 6.0, 7.0)?.len(), 1);
+        assert_eq!(layer.extent()?, Some((0.0, 0.0, 5.0, 6.0)));
+
+        assert!(matches!(
+            builder.update(10),
+            Err(GpkgError::FeatureNotFound { id: 10 })
+        ));
+        builder.clear();
+        builder.update(1)?;
+        assert!(matches!(
+            builder.update(10),
+            Err(GpkgError::FeatureNotFound { id: 10 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn builder_rejects_writes_to_read_only_layer() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let mut layer = create_layer(&gpkg)?;
+        layer.is_read_only = true;
+        let mut builder = layer.feature_builder();
+        assert!(matches!(
+            builder.geometry(Point::new(0.0, 0.0)),
+            Err(GpkgError::ReadOnly)
+        ));
+        builder.set("name", "alpha")?;
+        assert!(matches!(builder.insert(), Err(GpkgError::ReadOnly)));
+        assert!(matches!(builder.update(1), Err(GpkgError::ReadOnly)));
+        Ok(())
+    }
+}
@@ -1,32 +1,78 @@
 use crate::conversions::{
-    column_type_from_str, column_type_to_str, dimension_from_zm, dimension_to_zm,
-    geometry_type_from_str, geometry_type_to_str,
+    column_definition_sql, column_type_from_str, default_value_from_sql, dimension_from_zm,
+    dimension_from_zm_flags, geometry_type_from_str, geometry_type_to_str,
+    is_non_linear_geometry_type, zm_flag_to_i8, zm_flags_from_dimension, zm_flags_from_i8,
 };
 use crate::error::{GpkgError, Result};
+#[cfg(not(target_family = "wasm"))]
+use crate::native_vfs::HybridVfsBuilder;
 use crate::ogc_sql::{
+    GPKG_APPLICATION_ID, SQL_ANALYZE, SQL_CRS_WKT_COLUMN_EXISTS, SQL_DELETE_CONTENTS,
+    SQL_DELETE_DATA_COLUMNS, SQL_DELETE_EXTENSIONS, SQL_DELETE_GEOMETRY_COLUMNS,
+    SQL_DELETE_METADATA_REFERENCE, SQL_ENABLE_CRS_WKT, SQL_ENABLE_METADATA,
     SQL_INSERT_GPKG_CONTENTS, SQL_INSERT_GPKG_CONTENTS_ATTRIBUTES,
-    SQL_INSERT_GPKG_GEOMETRY_COLUMNS, SQL_LIST_ATTRIBUTE_TABLES, SQL_LIST_LAYERS,
-    SQL_SELECT_DATA_TYPE, SQL_SELECT_GEOMETRY_COLUMN_META, execute_rtree_sqls, gpkg_rtree_drop_sql,
-    initialize_gpkg, sql_create_table, sql_drop_table, sql_table_columns,
+    SQL_INSERT_GPKG_GEOMETRY_COLUMNS, SQL_INSERT_METADATA, SQL_INSERT_METADATA_REFERENCE,
+    SQL_LIST_ATTRIBUTE_TABLES, SQL_LIST_LAYERS, SQL_LIST_LAYERS_DETAILED, SQL_METADATA_EXISTS,
+    SQL_RENAME_CONTENTS, SQL_RENAME_EXTENSIONS, SQL_RENAME_GEOMETRY_COLUMNS,
+    SQL_RENAME_METADATA_REFERENCE, SQL_SCHEMA_OBJECT_EXISTS, SQL_SELECT_COLUMN_TYPE,
+    SQL_SELECT_DATA_TYPE, SQL_SELECT_GEOMETRY_COLUMN_META, SQL_SRS_EXISTS, SQL_TABLE_EXISTS,
+    SQL_UPDATE_CONTENTS_LAST_CHANGE, SQL_UPDATE_GEOMETRY_COLUMN_DEFINITION, SQL_VACUUM,
+    SQL_VACUUM_INTO, SQL_VIEW_EXISTS, execute_rtree_sqls, gpkg_rtree_drop_sql,
+    gpkg_rtree_rename_sql, gpkg_rtree_triggers_drop_sql, gpkg_rtree_triggers_sql, initialize_gpkg,
+    sql_analyze_table, sql_copy_rows, sql_copy_rows_after, sql_create_table, sql_create_view,
+    sql_drop_table, sql_drop_view, sql_max_primary_key, sql_rename_table,
+    sql_select_first_geometry, sql_select_spatial_ref_sys, sql_table_columns, sql_unique_columns,
 };
 use crate::sql_functions::register_spatial_functions;
-use crate::types::{ColumnSpec, GpkgLayerMetadata};
+#[cfg(feature = "csv")]
+use crate::types::CsvImportOptions;
+#[cfg(feature = "geojson")]
+use crate::types::GeoJsonImportOptions;
+#[cfg(feature = "parquet")]
+use crate::types::ParquetExportOptions;
+use crate::types::{
+    ColumnSpec, CreateLayerOptions, GpkgLayerMetadata, JournalMode, MetadataEntry, SpatialRefSys,
+    Value, ZmFlag, params_from_properties, value_from_row,
+};
 #[cfg(target_family = "wasm")]
 use crate::vfs::HybridVfsBuilder;
-use rusqlite::OpenFlags;
-#[cfg(target_family = "wasm")]
-use std::io::{Seek, Write};
+use rusqlite::{OpenFlags, OptionalExtension};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::io::{Read, Seek, Write};
+use std::ops::ControlFlow;
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Duration;
 
 use super::attribute_table::GpkgAttributeTable;
-use super::layer::GpkgLayer;
+use super::layer::{
+    GpkgLayer, ensure_identifier_available, select_contents_metadata, select_referenced_metadata,
+};
+use super::{Progress, no_progress};
+
+// A row of gpkg_geometry_columns.
+struct GeometryColumnMeta {
+    geometry_column: String,
+    geometry_type_name: String,
+    geometry_type: wkb::reader::GeometryType,
+    z_flag: ZmFlag,
+    m_flag: ZmFlag,
+    srs_id: i32,
+}
+
+// The number of pages copied per step of `Gpkg::save_to()`.
+const BACKUP_PAGES_PER_STEP: std::os::raw::c_int = 1024;
 
 #[derive(Debug)]
 /// GeoPackage connection wrapper for reading (and later writing) layers.
 pub struct Gpkg {
     pub(crate) conn: Rc<rusqlite::Connection>,
     pub(crate) read_only: bool,
+    /// The buffer read by the connection of `Gpkg::from_bytes_read_only()`.
+    /// It's declared after `conn`, and shared with every layer and attribute
+    /// table, so that it's dropped after the connection is closed.
+    pub(crate) dump: Option<Rc<dyn std::any::Any>>,
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -46,23 +92,20 @@ fn rusqlite_open_path<P: AsRef<Path>>(
     rusqlite::Connection::open_with_flags_and_vfs(path, flags, "opfs-sahpool")
 }
 
-#[cfg(target_family = "wasm")]
-fn rusqlite_open_path_with_vfs<P: AsRef<Path>>(
-    path: P,
-    flags: rusqlite::OpenFlags,
-    vfs_name: &str,
-) -> rusqlite::Result<rusqlite::Connection> {
-    rusqlite::Connection::open_with_flags_and_vfs(path, flags, vfs_name)
-}
-
 impl Gpkg {
     pub(crate) fn new_from_conn(conn: Rc<rusqlite::Connection>, read_only: bool) -> Result<Self> {
         register_spatial_functions(&conn)?;
-        Ok(Self { conn, read_only })
+        Ok(Self {
+            conn,
+            read_only,
+            dump: None,
+        })
     }
 
     /// Open a GeoPackage in read-only mode.
     ///
+    /// Fails with `GpkgError::FileNotFound` if the file doesn't exist.
+    ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
@@ -71,8 +114,7 @@ impl Gpkg {
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
     pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let conn = rusqlite_open_path(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
-        Self::new_from_conn(Rc::new(conn), true)
+        Self::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
     }
 
     /// Open a new or existing GeoPackage in read-write mode.
@@ -85,31 +127,140 @@ impl Gpkg {
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with_flags(path, OpenFlags::default())
+    }
+
+    /// Open a GeoPackage with the given SQLite open flags.
+    ///
+    /// The GeoPackage is treated as read-only if the flags contain
+    /// `SQLITE_OPEN_READ_ONLY`. A new file created by opening is initialized
+    /// as a GeoPackage, the same as [`Gpkg::open`]. If the file doesn't exist
+    /// and the flags don't contain `SQLITE_OPEN_CREATE`, this fails with
+    /// `GpkgError::FileNotFound`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, OpenFlags};
+    ///
+    /// let flags = OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    /// let gpkg = Gpkg::open_with_flags("data/example.gpkg", flags)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open_with_flags<P: AsRef<Path>>(path: P, flags: OpenFlags) -> Result<Self> {
         let path = path.as_ref();
         let is_existing = path.exists();
+        let read_only = flags.contains(OpenFlags::SQLITE_OPEN_READ_ONLY);
+
+        // A URI filename is not a path on the file system.
+        #[cfg(not(target_family = "wasm"))]
+        if !is_existing
+            && !flags.contains(OpenFlags::SQLITE_OPEN_CREATE)
+            && !flags.contains(OpenFlags::SQLITE_OPEN_URI)
+        {
+            return Err(GpkgError::FileNotFound {
+                path: path.to_path_buf(),
+            });
+        }
 
-        let conn = rusqlite_open_path(path, rusqlite::OpenFlags::default())?;
+        let conn = rusqlite_open_path(path, flags)?;
 
         // In the case of new file, initialize it
-        if !is_existing {
+        if !is_existing && !read_only {
             initialize_gpkg(&conn)?;
         }
 
-        Self::new_from_conn(Rc::new(conn), false)
+        Self::new_from_conn(Rc::new(conn), read_only)
     }
 
-    /// Open a new or existing GeoPackage in read-write mode with an explicit VFS.
+    /// Open a remote GeoPackage read-only with HTTP range requests.
     ///
-    /// This is available only on wasm targets where custom SQLite VFS usage is
-    /// required (for example, a user-registered hybrid VFS).
-    #[cfg(target_family = "wasm")]
-    pub(crate) fn open_with_vfs<P: AsRef<Path>>(path: P, vfs_name: &str) -> Result<Self> {
-        let path = path.as_ref();
-        let is_existing = path.exists();
+    /// Only the parts of the file that queries read are downloaded. This uses
+    /// the built-in client, which supports plain `http://` URLs; use
+    /// [`HttpVfsBuilder`](crate::HttpVfsBuilder) to plug in another client or
+    /// to tune the cache. Errors are reported as `GpkgError::Vfs`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_http("http://example.com/data.gpkg")?;
+    /// let _layers = gpkg.list_layers()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(all(feature = "http", not(target_family = "wasm")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+    pub fn open_http(url: &str) -> Result<Self> {
+        crate::HttpVfsBuilder::new(url).open()
+    }
+
+    /// Set how long to wait for a lock held by another connection before
+    /// failing with `database is locked`.
+    ///
+    /// This is useful when the same GeoPackage file is shared between
+    /// processes. Passing a zero duration disables the busy handler.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    /// use std::time::Duration;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.busy_timeout(Duration::from_secs(5))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn busy_timeout(&self, timeout: Duration) -> Result<()> {
+        self.conn.busy_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Set how many prepared statements the connection keeps for reuse.
+    ///
+    /// Reading and writing features reuse the statements prepared for the
+    /// same SQL, so a server querying many layers (each `features_in_bbox()`
+    /// of a layer is one statement) may want more than the default of 16.
+    /// Passing zero disables the cache.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// gpkg.set_prepared_statement_cache_capacity(64);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_prepared_statement_cache_capacity(&self, capacity: usize) {
+        self.conn.set_prepared_statement_cache_capacity(capacity);
+    }
 
-        let conn = rusqlite_open_path_with_vfs(path, rusqlite::OpenFlags::default(), vfs_name)?;
+    /// Open a new or existing GeoPackage in read-write mode with an explicit VFS.
+    ///
+    /// The VFS must already be registered with SQLite, e.g. by
+    /// [`HybridVfsBuilder::register_reusable`](crate::HybridVfsBuilder::register_reusable).
+    /// Fails with `GpkgError::Vfs` if no VFS named `vfs_name` is registered.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, HybridVfsBuilder};
+    /// use std::io::Cursor;
+    ///
+    /// HybridVfsBuilder::new(Cursor::new(Vec::new())).register_reusable("my-vfs", false)?;
+    /// let gpkg = Gpkg::open_with_vfs("demo.gpkg", "my-vfs")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open_with_vfs<P: AsRef<Path>>(path: P, vfs_name: &str) -> Result<Self> {
+        let not_registered = || GpkgError::Vfs(format!("VFS not registered: {vfs_name}"));
+        let c_name = std::ffi::CString::new(vfs_name).map_err(|_| not_registered())?;
+        if unsafe { rusqlite::ffi::sqlite3_vfs_find(c_name.as_ptr()) }.is_null() {
+            return Err(not_registered());
+        }
+        let conn =
+            rusqlite::Connection::open_with_flags_and_vfs(path, OpenFlags::default(), vfs_name)?;
 
-        if !is_existing {
+        // The VFS decides what's behind the path, so check the database
+        // itself instead of the file system.
+        let initialized: i64 =
+            conn.query_row(SQL_TABLE_EXISTS, ["gpkg_contents"], |row| row.get(0))?;
+        if initialized == 0 {
             initialize_gpkg(&conn)?;
         }
 
@@ -118,21 +269,236 @@ impl Gpkg {
 
     /// Open a new or existing GeoPackage in read-write mode with a custom writer.
     ///
-    /// This is available only on wasm targets. It uses the Hybrid VFS internally
-    /// and reuses a default VFS registration across calls.
+    /// This uses the Hybrid VFS internally and reuses a default VFS
+    /// registration across calls.
     ///
     /// The writer must implement `Seek` because SQLite writes pages at arbitrary
     /// offsets (page splits, journal replay, etc.) — a stream-only writer would
-    /// receive pages in write-order and produce a corrupt file.
+    /// receive pages in write-order and produce a corrupt file. It must also
+    /// implement `Read`, as SQLite reads the pages back from it; if it already
+    /// contains a GeoPackage (e.g. an existing OPFS file), that one is opened.
     #[cfg(target_family = "wasm")]
-    #[cfg_attr(docsrs, doc(cfg(target_family = "wasm")))]
-    pub fn open_with_writer<P: AsRef<Path>, W: Write + Seek + 'static>(
+    pub fn open_with_writer<P: AsRef<Path>, W: Read + Write + Seek + 'static>(
+        path: P,
+        writer: W,
+    ) -> Result<Self> {
+        HybridVfsBuilder::new(writer).open_gpkg(path)
+    }
+
+    /// Open a new or existing GeoPackage in read-write mode with a custom writer.
+    ///
+    /// This is the native counterpart of the wasm `open_with_writer()`; the
+    /// writer must also be `Send`. See [`HybridVfsBuilder`](crate::HybridVfsBuilder).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    /// use std::io::Cursor;
+    ///
+    /// let gpkg = Gpkg::open_with_writer("demo.gpkg", Cursor::new(Vec::new()))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(not(target_family = "wasm"))]
+    pub fn open_with_writer<P: AsRef<Path>, W: Read + Write + Seek + Send + 'static>(
         path: P,
         writer: W,
     ) -> Result<Self> {
         HybridVfsBuilder::new(writer).open_gpkg(path)
     }
 
+    /// Return the `application_id` pragma of the database.
+    ///
+    /// A GeoPackage has `0x47504B47` (ASCII `"GPKG"`).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// println!("{:#x}", gpkg.application_id()?);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn application_id(&self) -> Result<i32> {
+        Ok(self
+            .conn
+            .pragma_query_value(None, "application_id", |row| row.get(0))?)
+    }
+
+    /// Return the `user_version` pragma of the database.
+    ///
+    /// For a GeoPackage, this is the spec version in `MMNNPP` format (e.g.
+    /// `10400` for 1.4.0). GeoPackages created by this crate use `10400`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// println!("{}", gpkg.user_version()?);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn user_version(&self) -> Result<i32> {
+        Ok(self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    /// Return whether the database has the GeoPackage `application_id`.
+    ///
+    /// `Gpkg::open` accepts any SQLite database, so this can be used to check
+    /// that a file is actually a GeoPackage.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// assert!(gpkg.is_geopackage()?);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn is_geopackage(&self) -> Result<bool> {
+        Ok(self.application_id()? == GPKG_APPLICATION_ID)
+    }
+
+    /// Set the SQLite journal mode of the GeoPackage.
+    ///
+    /// `JournalMode::Wal` lets readers run concurrently with a writer, and also
+    /// sets `PRAGMA synchronous = NORMAL`, which is safe in WAL mode. Returns an
+    /// error on a read-only connection, or if SQLite does not apply the mode
+    /// (e.g. WAL on an in-memory database).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, JournalMode};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.set_journal_mode(JournalMode::Wal)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_journal_mode(&self, mode: JournalMode) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        let requested = mode.as_str();
+        let actual: String =
+            self.conn
+                .pragma_update_and_check(None, "journal_mode", requested, |row| row.get(0))?;
+        if !actual.eq_ignore_ascii_case(requested) {
+            return Err(GpkgError::JournalModeNotApplied { requested, actual });
+        }
+
+        if mode == JournalMode::Wal {
+            self.conn.pragma_update(None, "synchronous", "NORMAL")?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild the database file with `VACUUM`, returning the space freed by
+    /// deleted features and layers to the file system.
+    ///
+    /// The prepared statements cached by layers are cleared first. This fails
+    /// with `GpkgError::ReadOnly` on a read-only connection, and with
+    /// `GpkgError::Sql` if a statement is still running (e.g. while an
+    /// `ArrowGpkgReader` or a features batch iterator is in use) or inside a
+    /// transaction. Use `vacuum_into()` to leave the original file untouched.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.delete_layer("points")?;
+    /// gpkg.vacuum()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn vacuum(&self) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        self.conn.flush_prepared_statement_cache();
+        self.conn.execute_batch(SQL_VACUUM)?;
+        Ok(())
+    }
+
+    /// Write a compacted copy of the GeoPackage to `path` with `VACUUM INTO`.
+    ///
+    /// The original database is not modified, so this also works on read-only
+    /// and in-memory connections. `path` must not exist yet.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// gpkg.vacuum_into("data/compacted.gpkg")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn vacuum_into<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let path = path.to_str().ok_or_else(|| {
+            GpkgError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("path is not valid UTF-8: {}", path.display()),
+            ))
+        })?;
+
+        self.conn.flush_prepared_statement_cache();
+        self.conn.execute(SQL_VACUUM_INTO, [path])?;
+        Ok(())
+    }
+
+    /// Refresh the statistics the SQLite query planner uses, with `ANALYZE`.
+    ///
+    /// This is worth running after large inserts or deletes. Fails with
+    /// `GpkgError::ReadOnly` on a read-only connection.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.analyze()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn analyze(&self) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        self.conn.execute_batch(SQL_ANALYZE)?;
+        Ok(())
+    }
+
+    /// Refresh the query planner statistics of a single layer or attribute
+    /// table and its indexes.
+    ///
+    /// Fails with `GpkgError::LayerNotFound` if the table is not in
+    /// `gpkg_contents`, and with `GpkgError::ReadOnly` on a read-only connection.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.analyze_layer("points")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn analyze_layer(&self, layer_name: &str) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        if !self.table_exists_in_contents(layer_name)? {
+            return Err(GpkgError::LayerNotFound {
+                layer_name: layer_name.to_string(),
+            });
+        }
+
+        self.conn.execute_batch(&sql_analyze_table(layer_name))?;
+        Ok(())
+    }
+
     /// Create a new GeoPackage in memory.
     ///
     /// Example:
@@ -150,6 +516,58 @@ impl Gpkg {
         Self::new_from_conn(Rc::new(conn), false)
     }
 
+    /// Wrap an existing rusqlite connection.
+    ///
+    /// The spatial SQL functions are registered on the connection, and the
+    /// GeoPackage tables are created if they don't exist yet. The GeoPackage is
+    /// treated as read-only if `read_only` is `true` or if the main database of
+    /// the connection is read-only.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let conn = rusqlite::Connection::open("data/example.gpkg")?;
+    /// let gpkg = Gpkg::from_connection(conn, false)?;
+    /// let _conn = gpkg.into_connection()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn from_connection(conn: rusqlite::Connection, read_only: bool) -> Result<Self> {
+        let read_only = read_only || conn.is_readonly(rusqlite::MAIN_DB)?;
+
+        if !read_only {
+            let initialized: i64 =
+                conn.query_row(SQL_TABLE_EXISTS, ["gpkg_contents"], |row| row.get(0))?;
+            if initialized == 0 {
+                initialize_gpkg(&conn)?;
+            }
+        }
+
+        Self::new_from_conn(Rc::new(conn), read_only)
+    }
+
+    /// Return the underlying rusqlite connection.
+    ///
+    /// This fails with `GpkgError::ConnectionInUse` if any layer or attribute
+    /// table obtained from this `Gpkg` is still alive, as they share the
+    /// connection, and with `GpkgError::DumpInUse` for a `Gpkg` opened by
+    /// `Gpkg::from_bytes_read_only()`, as the dump must outlive the connection.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let conn = gpkg.into_connection()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn into_connection(self) -> Result<rusqlite::Connection> {
+        if self.dump.is_some() {
+            return Err(GpkgError::DumpInUse);
+        }
+        Rc::try_unwrap(self.conn).map_err(|_| GpkgError::ConnectionInUse)
+    }
+
     /// Expert-only: register a spatial reference system in gpkg_spatial_ref_sys.
     ///
     /// GeoPackage layers must reference a valid `srs_id` that already exists in
@@ -163,6 +581,8 @@ impl Gpkg {
     /// This method performs a direct insert with all required columns and does
     /// no validation of the WKT or authority fields. Use only if you understand
     /// the GeoPackage SRS requirements and have authoritative metadata.
+    /// With the `epsg-common` feature, `register_epsg()` covers a few common
+    /// EPSG codes without a hand-written definition.
     ///
     /// Example: register EPSG:3857 (Web Mercator / Pseudo-Mercator).
     /// ```no_run
@@ -187,44 +607,431 @@ impl Gpkg {
         definition: &str,
         description: &str,
     ) -> Result<()> {
+        self.register_srs_wkt2(&SpatialRefSys {
+            srs_name: srs_name.to_string(),
+            srs_id,
+            organization: organization.to_string(),
+            organization_coordsys_id,
+            definition: definition.to_string(),
+            description: Some(description.to_string()),
+            definition_12_063: None,
+        })
+    }
+
+    /// Register a spatial reference system together with its WKT2 definition.
+    ///
+    /// `srs.definition_12_063` is written to the column added by the
+    /// `gpkg_crs_wkt` extension, so setting it requires
+    /// `enable_crs_wkt_extension()` first. When the extension is enabled and
+    /// `srs.definition_12_063` is `None`, `"undefined"` is stored.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, SpatialRefSys};
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// gpkg.enable_crs_wkt_extension()?;
+    /// gpkg.register_srs_wkt2(&SpatialRefSys {
+    ///     srs_name: "WGS 84 / Pseudo-Mercator".to_string(),
+    ///     srs_id: 3857,
+    ///     organization: "EPSG".to_string(),
+    ///     organization_coordsys_id: 3857,
+    ///     definition: "undefined".to_string(),
+    ///     description: None,
+    ///     definition_12_063: Some(r#"PROJCRS["WGS 84 / Pseudo-Mercator", ...]"#.to_string()),
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn register_srs_wkt2(&self, srs: &SpatialRefSys) -> Result<()> {
         if self.read_only {
             return Err(GpkgError::ReadOnly);
         }
 
-        self.conn.execute(
-            "INSERT INTO gpkg_spatial_ref_sys \
-            (srs_name, srs_id, organization, organization_coordsys_id, definition, description) \
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![
-                srs_name,
-                srs_id,
-                organization,
-                organization_coordsys_id,
-                definition,
-                description
-            ],
-        )?;
+        if self.has_crs_wkt_extension()? {
+            self.conn.execute(
+                "INSERT INTO gpkg_spatial_ref_sys \
+                (srs_name, srs_id, organization, organization_coordsys_id, definition, description, definition_12_063) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    srs.srs_name,
+                    srs.srs_id,
+                    srs.organization,
+                    srs.organization_coordsys_id,
+                    srs.definition,
+                    srs.description,
+                    srs.definition_12_063.as_deref().unwrap_or("undefined")
+                ],
+            )?;
+        } else {
+            if srs.definition_12_063.is_some() {
+                return Err(GpkgError::CrsWktExtensionNotEnabled);
+            }
+            self.conn.execute(
+                "INSERT INTO gpkg_spatial_ref_sys \
+                (srs_name, srs_id, organization, organization_coordsys_id, definition, description) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    srs.srs_name,
+                    srs.srs_id,
+                    srs.organization,
+                    srs.organization_coordsys_id,
+                    srs.definition,
+                    srs.description
+                ],
+            )?;
+        }
         Ok(())
     }
 
-    /// List the names of the feature layers (tables with `data_type = 'features'`).
+    /// Return whether the GeoPackage uses the `gpkg_crs_wkt` extension, i.e.
+    /// whether `gpkg_spatial_ref_sys` has the `definition_12_063` column.
     ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
     ///
     /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
-    /// let layers = gpkg.list_layers()?;
+    /// println!("{}", gpkg.has_crs_wkt_extension()?);
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn list_layers(&self) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare(SQL_LIST_LAYERS)?;
+    pub fn has_crs_wkt_extension(&self) -> Result<bool> {
+        Ok(crs_wkt_enabled(&self.conn)?)
+    }
+
+    /// Enable the `gpkg_crs_wkt` extension, which adds the
+    /// `definition_12_063` column for WKT2 definitions to
+    /// `gpkg_spatial_ref_sys` and registers the extension in
+    /// `gpkg_extensions`.
+    ///
+    /// Existing SRSs get `"undefined"` as their WKT2 definition. This does
+    /// nothing if the extension is already enabled.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.enable_crs_wkt_extension()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn enable_crs_wkt_extension(&self) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        if self.has_crs_wkt_extension()? {
+            return Ok(());
+        }
+
+        super::layer::with_savepoint(&self.conn, || {
+            self.conn.execute_batch(SQL_ENABLE_CRS_WKT)?;
+            Ok(())
+        })
+    }
+
+    /// Register an EPSG spatial reference system from the definitions bundled
+    /// with the `epsg-common` feature.
+    ///
+    /// This does nothing if `code` is already registered. A code that is not
+    /// bundled fails with `GpkgError::MissingSpatialRefSysId`; use
+    /// `register_srs()` for it instead.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// gpkg.register_epsg(3857)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "epsg-common")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "epsg-common")))]
+    pub fn register_epsg(&self, code: u32) -> Result<()> {
+        let def =
+            crate::epsg::lookup(code).ok_or(GpkgError::MissingSpatialRefSysId { srs_id: code })?;
+        if self.srs_exists(code as i32)? {
+            return Ok(());
+        }
+        self.register_srs(
+            def.name,
+            code as i32,
+            "EPSG",
+            code as i32,
+            def.definition,
+            def.name,
+        )
+    }
+
+    /// List the spatial reference systems in `gpkg_spatial_ref_sys`, ordered
+    /// by `srs_id`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// for srs in gpkg.list_srs()? {
+    ///     println!("{}: {}", srs.srs_id, srs.srs_name);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn list_srs(&self) -> Result<Vec<SpatialRefSys>> {
+        let sql = format!(
+            "{} ORDER BY srs_id",
+            sql_select_spatial_ref_sys(self.has_crs_wkt_extension()?)
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let srs = stmt
+            .query_map([], spatial_ref_sys_from_row)?
+            .collect::<std::result::Result<Vec<SpatialRefSys>, _>>()?;
+        Ok(srs)
+    }
+
+    /// Return the spatial reference system with the given `srs_id`, or `None`
+    /// if it is not registered.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// if let Some(srs) = gpkg.get_srs(4326)? {
+    ///     println!("{}", srs.definition);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get_srs(&self, srs_id: i32) -> Result<Option<SpatialRefSys>> {
+        Ok(select_spatial_ref_sys(&self.conn, srs_id)?)
+    }
+
+    /// Return whether a spatial reference system with the given `srs_id` is
+    /// registered in `gpkg_spatial_ref_sys`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// assert!(gpkg.srs_exists(4326)?);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn srs_exists(&self, srs_id: i32) -> Result<bool> {
+        Ok(self
+            .conn
+            .query_row(SQL_SRS_EXISTS, [srs_id], |row| row.get(0))?)
+    }
+
+    /// Run `f` inside a single transaction.
+    ///
+    /// All writes performed through this `Gpkg` (and the layers obtained from it)
+    /// while `f` runs are committed at once when `f` returns `Ok`. If `f` returns
+    /// an error, the transaction is rolled back and the error is passed through.
+    /// This is much faster than letting each `insert` auto-commit.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// gpkg.with_transaction(|_| {
+    ///     for i in 0..1000 {
+    ///         layer.insert(Point::new(i as f64, 0.0), params!["alpha", i])?;
+    ///     }
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn with_transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Gpkg) -> Result<T>,
+    {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        // The connection is shared with the layers via Rc, so we cannot get a
+        // &mut Connection here. Dropping the transaction without committing
+        // rolls it back.
+        let tx = self.conn.unchecked_transaction()?;
+        let result = f(self)?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Run an arbitrary SQL query and return its rows as `Value`s.
+    ///
+    /// This is an escape hatch for joins and aggregates the high-level API
+    /// doesn't cover. The query runs on this connection, so the spatial SQL
+    /// functions (`ST_*`) are available. Geometry columns are returned as
+    /// `Value::Blob`; use [`Value::is_gpkg_geometry`] to detect them. On a
+    /// read-only `Gpkg`, a statement that writes fails with
+    /// `GpkgError::ReadOnly`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let rows = gpkg.query(
+    ///     r#"SELECT "region", count(*), ST_MinX(geom) FROM points WHERE "value" > ?1 GROUP BY "region""#,
+    ///     params![10],
+    /// )?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn query<P>(&self, sql: &str, params: P) -> Result<Vec<Vec<Value>>>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let mut rows = Vec::new();
+        self.query_each(sql, params, |row| {
+            rows.push(row);
+            Ok(())
+        })?;
+        Ok(rows)
+    }
+
+    /// Run an arbitrary SQL query like [`query`](Self::query), passing each
+    /// row to `f` instead of collecting them.
+    ///
+    /// An error returned by `f` stops the query and is passed through.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// gpkg.query_each("SELECT fid, geom FROM points", rusqlite_gpkg::params![], |row| {
+    ///     println!("{:?}: {}", row[0], row[1].is_gpkg_geometry());
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn query_each<P, F>(&self, sql: &str, params: P, mut f: F) -> Result<()>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+        F: FnMut(Vec<Value>) -> Result<()>,
+    {
+        let mut stmt = self.conn.prepare(sql)?;
+        if self.read_only && !stmt.readonly() {
+            return Err(GpkgError::ReadOnly);
+        }
+        let column_count = stmt.column_count();
+        let mut rows = stmt.query(params_from_properties(params, None))?;
+        while let Some(row) = rows.next()? {
+            let values = (0..column_count)
+                .map(|idx| value_from_row(row, idx, None))
+                .collect::<rusqlite::Result<Vec<Value>>>()?;
+            f(values)?;
+        }
+        Ok(())
+    }
+
+    /// Run an arbitrary SQL statement and return the number of changed rows.
+    ///
+    /// This bypasses the GeoPackage bookkeeping: `gpkg_contents` (e.g. the
+    /// extent and last change), `gpkg_geometry_columns` and the rest of the
+    /// metadata are not updated, and nothing checks that the result is still a
+    /// valid GeoPackage. Use it at your own risk. Fails with
+    /// `GpkgError::ReadOnly` on a read-only `Gpkg`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let n = gpkg.execute(r#"UPDATE points SET "value" = 0 WHERE "value" < ?1"#, params![0])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn execute<P>(&self, sql: &str, params: P) -> Result<usize>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        Ok(self
+            .conn
+            .execute(sql, params_from_properties(params, None))?)
+    }
+
+    /// List the names of the feature layers (tables with `data_type = 'features'`).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layers = gpkg.list_layers()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn list_layers(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(SQL_LIST_LAYERS)?;
         let layers = stmt
             .query_map([], |row| row.get(0))?
             .collect::<std::result::Result<Vec<String>, _>>()?;
         Ok(layers)
     }
 
+    /// List the feature layers along with their metadata.
+    ///
+    /// Unlike calling `layer_metadata()` for each name from `list_layers()`,
+    /// this reads `gpkg_contents` and `gpkg_geometry_columns` in a single query
+    /// and only looks up the table columns per layer. A layer with an unknown
+    /// geometry type name is still listed, with `geometry_type` set to `None`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// for info in gpkg.list_layers_detailed()? {
+    ///     println!("{}: {} (srs_id {})", info.table_name, info.geometry_type_name, info.srs_id);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn list_layers_detailed(&self) -> Result<Vec<GpkgLayerMetadata>> {
+        let mut stmt = self.conn.prepare(SQL_LIST_LAYERS_DETAILED)?;
+        let mut layers = stmt
+            .query_map([], |row| {
+                let geometry_type_name: String = row.get(5)?;
+                let z: i8 = row.get(6)?;
+                let m: i8 = row.get(7)?;
+                let extent = match (row.get(9)?, row.get(10)?, row.get(11)?, row.get(12)?) {
+                    (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                        Some((min_x, min_y, max_x, max_y))
+                    }
+                    _ => None,
+                };
+                Ok(GpkgLayerMetadata {
+                    table_name: row.get(0)?,
+                    identifier: row.get(1)?,
+                    description: row.get(2)?,
+                    data_type: row.get(3)?,
+                    primary_key_column: String::new(),
+                    geometry_column: row.get(4)?,
+                    geometry_type: geometry_type_from_str(&geometry_type_name).ok(),
+                    geometry_type_name,
+                    geometry_dimension: dimension_from_zm(z, m).ok(),
+                    srs_id: row.get(8)?,
+                    other_columns: Vec::new(),
+                    extent,
+                    last_change: row.get(13)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<GpkgLayerMetadata>, _>>()?;
+
+        for layer in &mut layers {
+            let is_view = self.is_view(&layer.table_name)?;
+            (layer.primary_key_column, layer.other_columns) =
+                self.get_column_specs(&layer.table_name, &layer.geometry_column, is_view, None)?;
+        }
+        Ok(layers)
+    }
+
     /// List the names of the attribute tables (tables with `data_type = 'attributes'`).
     ///
     /// Example:
@@ -254,59 +1061,168 @@ impl Gpkg {
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
     pub fn get_layer(&self, layer_name: &str) -> Result<GpkgLayer> {
-        // Guard: if this table exists but is not a feature layer, give a clear error.
-        if let Ok(data_type) = self.get_data_type(layer_name)
-            && data_type != "features"
-        {
-            if data_type == "attributes" {
-                return Err(GpkgError::NotAFeatureLayer {
-                    layer_name: layer_name.to_string(),
-                });
-            }
-            return Err(GpkgError::UnsupportedDataType {
-                layer_name: layer_name.to_string(),
-                data_type,
-            });
-        }
+        self.load_layer(layer_name, None)
+    }
 
-        let (geometry_column, geometry_type, geometry_dimension, srs_id) =
-            self.get_geometry_column_and_srs_id(layer_name)?;
-        let column_specs = self.get_column_specs(
-            layer_name,
-            &geometry_column,
+    /// Load a layer, using `id_column` as the feature id. This is for layers
+    /// backed by a SQL view whose id column isn't named `fid`, `id` or
+    /// `OGC_FID`; for a table, `id_column` must be its primary key.
+    ///
+    /// Layers backed by a view are read-only, so writes fail with
+    /// `GpkgError::ReadOnly`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer_with_id_column("points_by_name", "point_id")?;
+    /// let features = layer.features()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get_layer_with_id_column(&self, layer_name: &str, id_column: &str) -> Result<GpkgLayer> {
+        self.load_layer(layer_name, Some(id_column))
+    }
+
+    fn load_layer(&self, layer_name: &str, id_column: Option<&str>) -> Result<GpkgLayer> {
+        self.ensure_feature_layer(layer_name)?;
+        let is_view = self.is_view(layer_name)?;
+
+        let GeometryColumnMeta {
+            geometry_column,
+            geometry_type_name,
             geometry_type,
-            geometry_dimension,
+            z_flag,
+            m_flag,
             srs_id,
-        )?;
-        let primary_key_column = column_specs.primary_key_column.clone();
-        let other_columns = column_specs.other_columns;
+        } = self.get_geometry_column_meta(layer_name)?;
+        // A layer is only opened with a non-negative srs_id, as it is written
+        // back to new geometries; the metadata still accepts -1 and others.
+        let srs_id = u32::try_from(srs_id)
+            .map_err(|_| rusqlite::Error::IntegralValueOutOfRange(4, srs_id.into()))?;
+        let geometry_dimension = dimension_from_zm_flags(z_flag, m_flag);
+        let (primary_key_column, other_columns) =
+            self.get_column_specs(layer_name, &geometry_column, is_view, id_column)?;
 
         let insert_sql = GpkgLayer::build_insert_sql(layer_name, &geometry_column, &other_columns);
+        let insert_with_id_sql = GpkgLayer::build_insert_with_id_sql(
+            layer_name,
+            &geometry_column,
+            &primary_key_column,
+            &other_columns,
+        );
         let update_sql = GpkgLayer::build_update_sql(
             layer_name,
             &geometry_column,
             &primary_key_column,
             &other_columns,
         );
+        let update_properties_sql =
+            GpkgLayer::build_update_properties_sql(layer_name, &primary_key_column, &other_columns);
+        let update_geometry_sql =
+            GpkgLayer::build_update_geometry_sql(layer_name, &geometry_column, &primary_key_column);
+        let upsert_sql = GpkgLayer::build_upsert_sql(
+            layer_name,
+            &geometry_column,
+            &primary_key_column,
+            &other_columns,
+        );
         let property_index_by_name =
             Rc::new(GpkgLayer::build_property_index_by_name(&other_columns));
 
         Ok(GpkgLayer {
             conn: self.conn.clone(),
-            is_read_only: self.read_only,
+            dump: self.dump.clone(),
+            is_read_only: self.read_only || is_view,
             layer_name: layer_name.to_string(),
             geometry_column,
             primary_key_column,
+            geometry_type_name,
             geometry_type,
             geometry_dimension,
+            z_flag,
+            m_flag,
             srs_id,
             property_columns: other_columns,
             property_index_by_name,
             insert_sql,
+            insert_with_id_sql,
             update_sql,
+            update_properties_sql,
+            update_geometry_sql,
+            upsert_sql,
+            strict_types: false,
+            write_envelope: false,
+            write_transform: None,
+        })
+    }
+
+    /// Load the metadata of a layer by name without loading the layer
+    /// itself. This is the same as `get_layer(layer_name)?.metadata()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let metadata = gpkg.layer_metadata("points")?;
+    /// for column in &metadata.other_columns {
+    ///     println!("{}: {:?}", column.name, column.column_type);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn layer_metadata(&self, layer_name: &str) -> Result<GpkgLayerMetadata> {
+        self.ensure_feature_layer(layer_name)?;
+
+        let GeometryColumnMeta {
+            geometry_column,
+            geometry_type_name,
+            geometry_type,
+            z_flag,
+            m_flag,
+            srs_id,
+        } = self.get_geometry_column_meta(layer_name)?;
+        let geometry_dimension = dimension_from_zm_flags(z_flag, m_flag);
+        let is_view = self.is_view(layer_name)?;
+        let (primary_key_column, other_columns) =
+            self.get_column_specs(layer_name, &geometry_column, is_view, None)?;
+        let contents = select_contents_metadata(&self.conn, layer_name)?;
+
+        Ok(GpkgLayerMetadata {
+            table_name: layer_name.to_string(),
+            data_type: "features".to_string(),
+            primary_key_column,
+            geometry_column,
+            geometry_type_name,
+            geometry_type: Some(geometry_type),
+            geometry_dimension: Some(geometry_dimension),
+            srs_id,
+            other_columns,
+            identifier: contents.identifier,
+            description: contents.description,
+            last_change: contents.last_change,
+            extent: contents.extent,
         })
     }
 
+    // Fail with a clear error if the table exists but is not a feature layer.
+    fn ensure_feature_layer(&self, layer_name: &str) -> Result<()> {
+        if let Ok(data_type) = self.get_data_type(layer_name)
+            && data_type != "features"
+        {
+            if data_type == "attributes" {
+                return Err(GpkgError::NotAFeatureLayer {
+                    layer_name: layer_name.to_string(),
+                });
+            }
+            return Err(GpkgError::UnsupportedDataType {
+                layer_name: layer_name.to_string(),
+                data_type,
+            });
+        }
+        Ok(())
+    }
+
     // Create a new layer.
     ///
     /// Example:
@@ -318,6 +1234,7 @@ impl Gpkg {
     /// let columns = vec![ColumnSpec {
     ///     name: "name".to_string(),
     ///     column_type: ColumnType::Varchar,
+    ///     ..Default::default()
     /// }];
     /// let layer = gpkg.create_layer(
     ///     "points",
@@ -338,6 +1255,52 @@ impl Gpkg {
         geometry_dimension: wkb::reader::Dimension,
         srs_id: u32,
         other_column_specs: &[ColumnSpec],
+    ) -> Result<GpkgLayer> {
+        self.create_layer_with_options(
+            layer_name,
+            geometry_column,
+            geometry_type,
+            geometry_dimension,
+            srs_id,
+            other_column_specs,
+            &CreateLayerOptions::default(),
+        )
+    }
+
+    /// Create a new layer with options for the primary key column.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ColumnSpec, CreateLayerOptions, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let columns: Vec<ColumnSpec> = Vec::new();
+    /// let options = CreateLayerOptions {
+    ///     primary_key_column: "id".to_string(),
+    ///     autoincrement: false,
+    ///     ..Default::default()
+    /// };
+    /// let layer = gpkg.create_layer_with_options(
+    ///     "points",
+    ///     "geom",
+    ///     wkb::reader::GeometryType::Point,
+    ///     wkb::reader::Dimension::Xy,
+    ///     4326,
+    ///     &columns,
+    ///     &options,
+    /// )?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_layer_with_options(
+        &self,
+        layer_name: &str,
+        geometry_column: &str,
+        geometry_type: wkb::reader::GeometryType,
+        geometry_dimension: wkb::reader::Dimension,
+        srs_id: u32,
+        other_column_specs: &[ColumnSpec],
+        options: &CreateLayerOptions,
     ) -> Result<GpkgLayer> {
         if self.read_only {
             return Err(GpkgError::ReadOnly);
@@ -348,25 +1311,40 @@ impl Gpkg {
                 layer_name: layer_name.to_string(),
             });
         }
+        let identifier = options.identifier.as_deref().unwrap_or(layer_name);
+        ensure_identifier_available(&self.conn, layer_name, identifier)?;
 
-        let srs_exists: i64 = self.conn.query_row(
-            "SELECT EXISTS(SELECT 1 FROM gpkg_spatial_ref_sys WHERE srs_id = ?1)",
-            rusqlite::params![srs_id],
-            |row| row.get(0),
-        )?;
-        if srs_exists == 0 {
+        #[cfg(feature = "epsg-common")]
+        if options.register_epsg && crate::epsg::lookup(srs_id).is_some() {
+            self.register_epsg(srs_id)?;
+        }
+
+        let srs_exists: i64 =
+            self.conn
+                .query_row(SQL_SRS_EXISTS, rusqlite::params![srs_id], |row| row.get(0))?;
+        if srs_exists == 0 {
             return Err(GpkgError::MissingSpatialRefSysId { srs_id });
         }
 
-        let geometry_type_name = geometry_type_to_str(geometry_type);
-        let (z, m) = dimension_to_zm(geometry_dimension);
+        let (geometry_type, geometry_type_name) = if options.any_geometry_type {
+            (wkb::reader::GeometryType::GeometryCollection, "GEOMETRY")
+        } else {
+            (geometry_type, geometry_type_to_str(geometry_type))
+        };
+        let (z_flag, m_flag) = zm_flags_from_dimension(geometry_dimension);
 
+        let primary_key_column = options.primary_key_column.as_str();
         let mut column_defs = Vec::with_capacity(other_column_specs.len() + 2);
-        column_defs.push("fid INTEGER PRIMARY KEY AUTOINCREMENT".to_string());
+        if options.autoincrement {
+            column_defs.push(format!(
+                r#""{primary_key_column}" INTEGER PRIMARY KEY AUTOINCREMENT"#
+            ));
+        } else {
+            column_defs.push(format!(r#""{primary_key_column}" INTEGER PRIMARY KEY"#));
+        }
         column_defs.push(format!(r#""{}" {geometry_type_name}"#, geometry_column));
         for spec in other_column_specs {
-            let col_type = column_type_to_str(spec.column_type);
-            column_defs.push(format!(r#""{}" {col_type}"#, spec.name));
+            column_defs.push(column_definition_sql(spec));
         }
 
         let create_sql = sql_create_table(layer_name, &column_defs.join(", "));
@@ -374,7 +1352,12 @@ impl Gpkg {
 
         self.conn.execute(
             SQL_INSERT_GPKG_CONTENTS,
-            rusqlite::params![layer_name, layer_name, srs_id],
+            rusqlite::params![
+                layer_name,
+                identifier,
+                options.description.as_deref().unwrap_or(""),
+                srs_id
+            ],
         )?;
         self.conn.execute(
             SQL_INSERT_GPKG_GEOMETRY_COLUMNS,
@@ -383,38 +1366,349 @@ impl Gpkg {
                 geometry_column,
                 geometry_type_name,
                 srs_id,
-                z,
-                m
+                zm_flag_to_i8(z_flag),
+                zm_flag_to_i8(m_flag)
             ],
         )?;
 
-        execute_rtree_sqls(&self.conn, layer_name, geometry_column, "fid")?;
+        execute_rtree_sqls(&self.conn, layer_name, geometry_column, primary_key_column)?;
 
         let insert_sql =
             GpkgLayer::build_insert_sql(layer_name, geometry_column, other_column_specs);
-        let update_sql =
-            GpkgLayer::build_update_sql(layer_name, geometry_column, "fid", other_column_specs);
+        let insert_with_id_sql = GpkgLayer::build_insert_with_id_sql(
+            layer_name,
+            geometry_column,
+            primary_key_column,
+            other_column_specs,
+        );
+        let update_sql = GpkgLayer::build_update_sql(
+            layer_name,
+            geometry_column,
+            primary_key_column,
+            other_column_specs,
+        );
+        let update_properties_sql = GpkgLayer::build_update_properties_sql(
+            layer_name,
+            primary_key_column,
+            other_column_specs,
+        );
+        let update_geometry_sql =
+            GpkgLayer::build_update_geometry_sql(layer_name, geometry_column, primary_key_column);
+        let upsert_sql = GpkgLayer::build_upsert_sql(
+            layer_name,
+            geometry_column,
+            primary_key_column,
+            other_column_specs,
+        );
         let property_index_by_name =
             Rc::new(GpkgLayer::build_property_index_by_name(other_column_specs));
 
         Ok(GpkgLayer {
             conn: self.conn.clone(),
+            dump: self.dump.clone(),
             is_read_only: self.read_only,
             layer_name: layer_name.to_string(),
             geometry_column: geometry_column.to_string(),
-            primary_key_column: "fid".to_string(),
+            primary_key_column: primary_key_column.to_string(),
+            geometry_type_name: geometry_type_name.to_string(),
             geometry_type,
             geometry_dimension,
+            z_flag,
+            m_flag,
             srs_id,
             property_columns: other_column_specs.to_vec(),
             property_index_by_name,
             insert_sql,
+            insert_with_id_sql,
             update_sql,
+            update_properties_sql,
+            update_geometry_sql,
+            upsert_sql,
+            strict_types: false,
+            write_envelope: options.write_envelope,
+            write_transform: None,
+        })
+    }
+
+    /// Create a new layer from a GeoJSON FeatureCollection (or a single
+    /// Feature) and insert its features.
+    ///
+    /// The property columns, the geometry type and the dimension are inferred
+    /// from the first 1000 features; see `create_layer_from_geojson_with_options()`
+    /// to change this. The geometry column is named `geom`. If any feature
+    /// fails to insert, the layer is not created.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let file = std::fs::File::open("points.geojson")?;
+    /// let layer = gpkg.create_layer_from_geojson(file, "points", 4326)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "geojson")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+    pub fn create_layer_from_geojson<R: std::io::Read>(
+        &self,
+        reader: R,
+        layer_name: &str,
+        srs_id: u32,
+    ) -> Result<GpkgLayer> {
+        self.create_layer_from_geojson_with_options(
+            reader,
+            layer_name,
+            srs_id,
+            &GeoJsonImportOptions::default(),
+        )
+    }
+
+    /// Create a new layer from GeoJSON with options for schema inference.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{GeoJsonImportOptions, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let file = std::fs::File::open("points.geojson")?;
+    /// let options = GeoJsonImportOptions {
+    ///     sample_size: None,
+    ///     widen_conflicting_types: false,
+    /// };
+    /// let layer = gpkg.create_layer_from_geojson_with_options(file, "points", 4326, &options)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "geojson")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+    pub fn create_layer_from_geojson_with_options<R: std::io::Read>(
+        &self,
+        reader: R,
+        layer_name: &str,
+        srs_id: u32,
+        options: &GeoJsonImportOptions,
+    ) -> Result<GpkgLayer> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        let features = crate::geojson::read_features(reader)?;
+        let schema = crate::geojson::infer_schema(&features, options)?;
+        super::layer::with_savepoint(&self.conn, || {
+            let layer = self.create_layer_with_options(
+                layer_name,
+                "geom",
+                schema
+                    .geometry_type
+                    .unwrap_or(wkb::reader::GeometryType::GeometryCollection),
+                schema.dimension,
+                srs_id,
+                &schema.columns,
+                &CreateLayerOptions {
+                    any_geometry_type: schema.geometry_type.is_none(),
+                    ..Default::default()
+                },
+            )?;
+            crate::geojson::insert_features(&layer, &features)?;
+            Ok(layer)
+        })
+    }
+
+    /// Create a new layer from a CSV file, with the geometry read from a
+    /// longitude and a latitude column or from a WKT column.
+    ///
+    /// The first row is the header. The type of each other column is inferred
+    /// from the first `options.sample_size` rows (`INTEGER`, `DOUBLE`,
+    /// `BOOLEAN` for `true`/`false`, and `TEXT` otherwise) unless given in
+    /// `options.column_types`, and so are the geometry type and dimension of a
+    /// WKT column (a `GEOMETRY` layer if the types are mixed). Empty cells are
+    /// inserted as `NULL`, and a row whose geometry cells are empty gets a
+    /// `NULL` geometry. The geometry column is named `geom`.
+    ///
+    /// All rows are inserted in one transaction. A malformed row fails with
+    /// `GpkgError::InvalidCsv`, which has its line number, and the layer is
+    /// not created; with `options.strict` disabled, such rows are skipped.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{CsvImportOptions, GeometryFrom, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let file = std::fs::File::open("stations.csv")?;
+    /// let options = CsvImportOptions::new(GeometryFrom::LonLat {
+    ///     lon: "x".to_string(),
+    ///     lat: "y".to_string(),
+    /// });
+    /// let layer = gpkg.create_layer_from_csv(file, "stations", &options)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "csv")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+    pub fn create_layer_from_csv<R: std::io::Read>(
+        &self,
+        reader: R,
+        layer_name: &str,
+        options: &CsvImportOptions,
+    ) -> Result<GpkgLayer> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        super::layer::with_savepoint(&self.conn, || {
+            crate::csv_import::create_layer(self, reader, layer_name, options)
+        })
+    }
+
+    /// Create a layer backed by a SQL view of `select_sql`, e.g. to filter or
+    /// join layers without copying their features.
+    ///
+    /// The result of `select_sql` must have the columns `geometry_column` and
+    /// `id_column`. The geometry type is taken from the declared type of the
+    /// geometry column (`GEOMETRY` for an expression), and the srs_id and
+    /// dimension from the first geometry; a view without geometries gets the
+    /// srs_id 0 and XY. The returned layer is read-only.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.create_view_layer(
+    ///     "large_cities",
+    ///     "SELECT fid, geom, name FROM cities WHERE population > 1000000",
+    ///     "geom",
+    ///     "fid",
+    /// )?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn create_view_layer(
+        &self,
+        layer_name: &str,
+        select_sql: &str,
+        geometry_column: &str,
+        id_column: &str,
+    ) -> Result<GpkgLayer> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        let exists: i64 = self
+            .conn
+            .query_row(SQL_SCHEMA_OBJECT_EXISTS, [layer_name], |row| row.get(0))?;
+        if exists == 1 || self.table_exists_in_contents(layer_name)? {
+            return Err(GpkgError::LayerAlreadyExists {
+                layer_name: layer_name.to_string(),
+            });
+        }
+        ensure_identifier_available(&self.conn, layer_name, layer_name)?;
+
+        let column_names: Vec<String> = {
+            let stmt = self.conn.prepare(select_sql)?;
+            stmt.column_names().into_iter().map(String::from).collect()
+        };
+        if !column_names.iter().any(|name| name == geometry_column) {
+            return Err(GpkgError::MissingGeometryColumn {
+                layer_name: layer_name.to_string(),
+            });
+        }
+        if !column_names.iter().any(|name| name == id_column) {
+            return Err(GpkgError::InvalidIdColumn {
+                layer_name: layer_name.to_string(),
+                column: id_column.to_string(),
+            });
+        }
+
+        super::layer::with_savepoint(&self.conn, || {
+            self.conn
+                .execute_batch(&sql_create_view(layer_name, select_sql))?;
+
+            let declared_type: String = self.conn.query_row(
+                SQL_SELECT_COLUMN_TYPE,
+                [layer_name, geometry_column],
+                |row| row.get(0),
+            )?;
+            let geometry_type_name = if geometry_type_from_str(&declared_type).is_ok()
+                || is_non_linear_geometry_type(&declared_type)
+            {
+                declared_type.to_ascii_uppercase()
+            } else {
+                "GEOMETRY".to_string()
+            };
+
+            let first_geometry: Option<Vec<u8>> = self
+                .conn
+                .query_row(
+                    &sql_select_first_geometry(layer_name, geometry_column),
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let (srs_id, dimension) = match first_geometry {
+                Some(blob) => {
+                    let srs_id = super::gpkg_geometry_srs_id(&blob)?;
+                    (srs_id, super::gpkg_geometry_to_wkb(&blob)?.dimension())
+                }
+                None => (0, wkb::reader::Dimension::Xy),
+            };
+            let (z_flag, m_flag) = zm_flags_from_dimension(dimension);
+
+            self.conn.execute(
+                SQL_INSERT_GPKG_CONTENTS,
+                rusqlite::params![layer_name, layer_name, "", srs_id],
+            )?;
+            self.conn.execute(
+                SQL_INSERT_GPKG_GEOMETRY_COLUMNS,
+                rusqlite::params![
+                    layer_name,
+                    geometry_column,
+                    geometry_type_name,
+                    srs_id,
+                    zm_flag_to_i8(z_flag),
+                    zm_flag_to_i8(m_flag)
+                ],
+            )?;
+            self.load_layer(layer_name, Some(id_column))
         })
     }
 
+    /// Write the layer `layer_name` into a GeoParquet file at `path`, and
+    /// return the number of rows written.
+    ///
+    /// The features are streamed through `ArrowGpkgReader` in batches of
+    /// `options.batch_size`, with the geometry column encoded as WKB. The
+    /// `geo` file metadata records the geometry column, the geometry types and
+    /// the bbox of the layer, and the CRS as PROJJSON when the SRS is a known
+    /// EPSG code. The `srs_id` and the WKT definition from
+    /// `gpkg_spatial_ref_sys` are also written as the `gpkg:srs_id` and
+    /// `gpkg:srs_definition` key-value metadata.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, ParquetCompression, ParquetExportOptions};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data.gpkg")?;
+    /// let options = ParquetExportOptions {
+    ///     compression: ParquetCompression::Zstd(3),
+    ///     ..Default::default()
+    /// };
+    /// let rows = gpkg.export_parquet("points", "points.parquet", &options)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "parquet")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+    pub fn export_parquet(
+        &self,
+        layer_name: &str,
+        path: impl AsRef<std::path::Path>,
+        options: &ParquetExportOptions,
+    ) -> Result<u64> {
+        crate::arrow::geoparquet::export_layer(self, layer_name, path.as_ref(), options)
+    }
+
     /// Delete a layer.
     ///
+    /// This drops the table together with its rtree spatial index and
+    /// triggers, if any, or the view of a layer backed by a view, and removes
+    /// the layer's rows from `gpkg_contents`, `gpkg_geometry_columns`,
+    /// `gpkg_extensions` and `gpkg_metadata_reference`.
+    ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
@@ -443,151 +1737,719 @@ impl Gpkg {
             });
         }
 
-        let (geometry_column, _, _, _) = self.get_geometry_column_and_srs_id(layer_name)?;
+        let geometry_column = self.get_geometry_column_meta(layer_name)?.geometry_column;
+        let has_metadata_reference: i64 =
+            self.conn
+                .query_row(SQL_TABLE_EXISTS, ["gpkg_metadata_reference"], |row| {
+                    row.get(0)
+                })?;
+        let has_data_columns: i64 =
+            self.conn
+                .query_row(SQL_TABLE_EXISTS, ["gpkg_data_columns"], |row| row.get(0))?;
 
-        self.conn
-            .execute_batch(&gpkg_rtree_drop_sql(layer_name, &geometry_column))?;
+        let is_view = self.is_view(layer_name)?;
 
-        self.conn.execute_batch(&sql_drop_table(layer_name))?;
-        Ok(())
+        super::layer::with_savepoint(&self.conn, || {
+            if is_view {
+                self.conn.execute_batch(&sql_drop_view(layer_name))?;
+            } else {
+                self.conn
+                    .execute_batch(&gpkg_rtree_triggers_drop_sql(layer_name, &geometry_column))?;
+                self.conn
+                    .execute_batch(&gpkg_rtree_drop_sql(layer_name, &geometry_column))?;
+                self.conn.execute_batch(&sql_drop_table(layer_name))?;
+            }
+            if has_metadata_reference == 1 {
+                self.conn
+                    .execute(SQL_DELETE_METADATA_REFERENCE, [layer_name])?;
+            }
+            if has_data_columns == 1 {
+                self.conn.execute(SQL_DELETE_DATA_COLUMNS, [layer_name])?;
+            }
+            self.conn.execute(SQL_DELETE_EXTENSIONS, [layer_name])?;
+            self.conn
+                .execute(SQL_DELETE_GEOMETRY_COLUMNS, [layer_name])?;
+            self.conn.execute(SQL_DELETE_CONTENTS, [layer_name])?;
+            Ok(())
+        })
     }
 
-    /// Dump the GeoPackage data to `Vec<u8>`.
+    /// Copy a layer, including its features and spatial index, to a new layer
+    /// in the same GeoPackage.
     ///
-    /// This is intended for environments without filesystem access (for example,
-    /// running in a web browser). You can serialize an in-memory GeoPackage and
-    /// move the bytes over the wire or store them in browser storage.
+    /// The primary key column and its values are preserved.
     ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
     ///
-    /// let gpkg = Gpkg::open_in_memory()?;
-    /// let bytes = gpkg.to_bytes()?;
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let backup = gpkg.copy_layer("points", "points_backup")?;
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn to_bytes(&self) -> Result<Vec<u8>> {
-        let data: &[u8] = &self.conn.serialize("main")?;
-        Ok(data.to_vec())
+    pub fn copy_layer(&self, src: &str, dst: &str) -> Result<GpkgLayer> {
+        self.copy_layer_with_progress(src, dst, 0, no_progress)
     }
 
-    /// Load the GeoPackage data from a dump.
+    /// Copy a layer like [`copy_layer`](Self::copy_layer), calling `progress`
+    /// with the number of copied rows every `every_n` rows and once at the end.
     ///
-    /// This is intended for environments without filesystem access (for example,
-    /// running in a web browser). Provide the bytes from `Gpkg::to_bytes()` to
-    /// recreate an in-memory GeoPackage.
+    /// With `every_n > 0`, the rows are copied in chunks of `every_n` rows in
+    /// primary key order. If `progress` returns `ControlFlow::Break`, the new
+    /// layer is not created and `GpkgError::Cancelled` is returned.
     ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
+    /// use std::ops::ControlFlow;
     ///
-    /// let gpkg = Gpkg::open_in_memory()?;
-    /// let bytes = gpkg.to_bytes()?;
-    /// let restored = Gpkg::from_bytes(&bytes)?;
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let backup = gpkg.copy_layer_with_progress("points", "points_backup", 10_000, |done| {
+    ///     println!("{done} rows");
+    ///     ControlFlow::Continue(())
+    /// })?;
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn from_bytes<D: AsRef<[u8]>>(data: D) -> Result<Self> {
-        let mut conn = rusqlite::Connection::open_in_memory()?;
+    pub fn copy_layer_with_progress<F>(
+        &self,
+        src: &str,
+        dst: &str,
+        every_n: u64,
+        progress: F,
+    ) -> Result<GpkgLayer>
+    where
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
 
-        let data_ref = data.as_ref();
-        let reader = std::io::Cursor::new(data_ref);
-        conn.deserialize_read_exact("main", reader, data_ref.len(), false)?;
-        Ok(Self {
-            conn: Rc::new(conn),
-            read_only: false,
-        })
+        let src_layer = self.get_layer(src)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut dst_layer = self.create_layer_with_options(
+            dst,
+            &src_layer.geometry_column,
+            src_layer.geometry_type,
+            src_layer.geometry_dimension,
+            src_layer.srs_id,
+            &src_layer.property_columns,
+            &CreateLayerOptions {
+                primary_key_column: src_layer.primary_key_column.clone(),
+                ..Default::default()
+            },
+        )?;
+        self.copy_geometry_definition(&src_layer, &mut dst_layer)?;
+
+        let property_columns = src_layer
+            .property_columns
+            .iter()
+            .map(|spec| format!(r#", "{}""#, spec.name))
+            .collect::<String>();
+        let src_columns = format!(
+            r#""{}", "{}"{property_columns}"#,
+            src_layer.primary_key_column, src_layer.geometry_column
+        );
+        let dst_columns = format!(
+            r#""{}", "{}"{property_columns}"#,
+            dst_layer.primary_key_column, dst_layer.geometry_column
+        );
+        // The rtree triggers of the new layer populate its spatial index.
+        let mut progress = Progress::new(every_n, progress);
+        let mut copied = 0;
+        if every_n == 0 {
+            copied = tx.execute(&sql_copy_rows(src, &src_columns, dst, &dst_columns), [])? as u64;
+        } else {
+            let copy_sql = sql_copy_rows_after(
+                src,
+                &src_columns,
+                &src_layer.primary_key_column,
+                dst,
+                &dst_columns,
+            );
+            let max_sql = sql_max_primary_key(dst, &dst_layer.primary_key_column);
+            let limit = i64::try_from(every_n).unwrap_or(i64::MAX);
+            let mut last_id = i64::MIN;
+            loop {
+                let n = tx.execute(&copy_sql, rusqlite::params![last_id, limit])? as u64;
+                if n == 0 {
+                    break;
+                }
+                copied += n;
+                progress.update(copied)?;
+                if n < every_n {
+                    break;
+                }
+                last_id = tx.query_row(&max_sql, [], |row| row.get(0))?;
+            }
+        }
+        progress.finish(copied)?;
+        dst_layer.update_extent()?;
+        tx.commit()?;
+
+        Ok(dst_layer)
     }
 
-    /// Load an attribute table definition by name.
+    /// Create an empty layer with the same schema as `template`, which may
+    /// be a layer of another GeoPackage.
+    ///
+    /// The geometry column, geometry type and dimension, srs_id, primary key
+    /// column and property columns are copied, but no features. If the
+    /// template's SRS is not registered here, its `gpkg_spatial_ref_sys` row
+    /// is copied from the template's GeoPackage.
     ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
     ///
-    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
-    /// let table = gpkg.get_attribute_table("observations")?;
+    /// let src = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let template = src.get_layer("points")?;
+    /// let dst = Gpkg::open("data/regions.gpkg")?;
+    /// for region in ["north", "south"] {
+    ///     dst.create_layer_like(&template, &format!("points_{region}"))?;
+    /// }
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn get_attribute_table(&self, table_name: &str) -> Result<GpkgAttributeTable> {
-        let data_type = self.get_data_type(table_name)?;
-        if data_type != "attributes" {
-            if data_type == "features" {
-                return Err(GpkgError::NotAnAttributeTable {
-                    layer_name: table_name.to_string(),
-                });
+    pub fn create_layer_like(&self, template: &GpkgLayer, new_name: &str) -> Result<GpkgLayer> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let srs_id = template.srs_id as i32;
+        if !self.srs_exists(srs_id)? {
+            let mut srs = select_spatial_ref_sys(&template.conn, srs_id)?.ok_or(
+                GpkgError::MissingSpatialRefSysId {
+                    srs_id: template.srs_id,
+                },
+            )?;
+            // Enable the gpkg_crs_wkt extension only for an actual WKT2
+            // definition, not for the placeholder of its other rows.
+            match srs.definition_12_063.as_deref() {
+                Some("undefined") | None => srs.definition_12_063 = None,
+                Some(_) => self.enable_crs_wkt_extension()?,
             }
-            return Err(GpkgError::UnsupportedDataType {
-                layer_name: table_name.to_string(),
-                data_type,
-            });
+            self.register_srs_wkt2(&srs)?;
         }
 
-        let (primary_key_column, other_columns) = self.get_attribute_column_specs(table_name)?;
+        let mut layer = self.create_layer_with_options(
+            new_name,
+            &template.geometry_column,
+            template.geometry_type,
+            template.geometry_dimension,
+            template.srs_id,
+            &template.property_columns,
+            &CreateLayerOptions {
+                primary_key_column: template.primary_key_column.clone(),
+                write_envelope: template.write_envelope,
+                ..Default::default()
+            },
+        )?;
+        self.copy_geometry_definition(template, &mut layer)?;
+        tx.commit()?;
 
-        let insert_sql = GpkgAttributeTable::build_insert_sql(table_name, &other_columns);
-        let update_sql =
-            GpkgAttributeTable::build_update_sql(table_name, &primary_key_column, &other_columns);
-        let property_index_by_name = Rc::new(GpkgAttributeTable::build_property_index_by_name(
-            &other_columns,
-        ));
+        Ok(layer)
+    }
 
-        Ok(GpkgAttributeTable {
-            conn: self.conn.clone(),
-            is_read_only: self.read_only,
-            table_name: table_name.to_string(),
-            primary_key_column,
-            property_columns: other_columns,
-            property_index_by_name,
-            insert_sql,
-            update_sql,
-        })
+    // Optional Z/M flags and the geometry types of the non-linear geometry
+    // extension can't be expressed by a Dimension and a GeometryType, so
+    // `create_layer_with_options()` can't create them from `src_layer`.
+    fn copy_geometry_definition(
+        &self,
+        src_layer: &GpkgLayer,
+        dst_layer: &mut GpkgLayer,
+    ) -> Result<()> {
+        if (
+            &src_layer.geometry_type_name,
+            src_layer.z_flag,
+            src_layer.m_flag,
+        ) == (
+            &dst_layer.geometry_type_name,
+            dst_layer.z_flag,
+            dst_layer.m_flag,
+        ) {
+            return Ok(());
+        }
+        self.conn.execute(
+            SQL_UPDATE_GEOMETRY_COLUMN_DEFINITION,
+            rusqlite::params![
+                dst_layer.layer_name,
+                src_layer.geometry_type_name,
+                zm_flag_to_i8(src_layer.z_flag),
+                zm_flag_to_i8(src_layer.m_flag)
+            ],
+        )?;
+        dst_layer.geometry_type_name = src_layer.geometry_type_name.clone();
+        dst_layer.z_flag = src_layer.z_flag;
+        dst_layer.m_flag = src_layer.m_flag;
+        Ok(())
     }
 
-    /// Create a new attribute table (non-spatial, no geometry column).
+    /// Rename a layer.
+    ///
+    /// Besides the table itself, this updates `gpkg_contents`,
+    /// `gpkg_geometry_columns`, `gpkg_extensions`, and the rtree spatial index
+    /// along with its triggers.
     ///
     /// Example:
     /// ```no_run
-    /// use rusqlite_gpkg::{ColumnSpec, ColumnType, Gpkg, params};
+    /// use rusqlite_gpkg::Gpkg;
     ///
-    /// let gpkg = Gpkg::open_in_memory()?;
-    /// let columns = vec![
-    ///     ColumnSpec { name: "name".to_string(), column_type: ColumnType::Varchar },
-    ///     ColumnSpec { name: "value".to_string(), column_type: ColumnType::Integer },
-    /// ];
-    /// let table = gpkg.create_attribute_table("observations", &columns)?;
-    /// table.insert(params!["alpha", 7_i64])?;
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.rename_layer("points", "places")?;
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn create_attribute_table(
-        &self,
-        table_name: &str,
-        column_specs: &[ColumnSpec],
-    ) -> Result<GpkgAttributeTable> {
+    pub fn rename_layer(&self, old_name: &str, new_name: &str) -> Result<()> {
         if self.read_only {
             return Err(GpkgError::ReadOnly);
         }
 
-        if self.table_exists_in_contents(table_name)? {
+        let layer = self.get_layer(old_name)?;
+
+        let table_exists: i64 = self
+            .conn
+            .query_row(SQL_TABLE_EXISTS, [new_name], |row| row.get(0))?;
+        if table_exists == 1 || self.table_exists_in_contents(new_name)? {
             return Err(GpkgError::LayerAlreadyExists {
-                layer_name: table_name.to_string(),
+                layer_name: new_name.to_string(),
             });
         }
 
-        // Attribute tables must not have geometry columns.
-        if let Some(spec) = column_specs
-            .iter()
-            .find(|s| s.column_type == crate::types::ColumnType::Geometry)
-        {
-            return Err(GpkgError::GeometryColumnInAttributeTable {
-                column: spec.name.clone(),
-            });
-        }
+        let rtree_name = format!("rtree_{old_name}_{}", layer.geometry_column);
+        let has_rtree: i64 = self
+            .conn
+            .query_row(SQL_TABLE_EXISTS, [rtree_name], |row| row.get(0))?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        // gpkg_geometry_columns references gpkg_contents, so the foreign keys
+        // only hold again after both tables are updated.
+        tx.pragma_update(None, "defer_foreign_keys", true)?;
+        tx.execute_batch(&gpkg_rtree_triggers_drop_sql(
+            old_name,
+            &layer.geometry_column,
+        ))?;
+        tx.execute_batch(&sql_rename_table(old_name, new_name))?;
+        tx.execute(SQL_RENAME_CONTENTS, [old_name, new_name])?;
+        tx.execute(SQL_RENAME_GEOMETRY_COLUMNS, [old_name, new_name])?;
+        tx.execute(SQL_RENAME_EXTENSIONS, [old_name, new_name])?;
+        let has_metadata_reference: i64 =
+            tx.query_row(SQL_TABLE_EXISTS, ["gpkg_metadata_reference"], |row| {
+                row.get(0)
+            })?;
+        if has_metadata_reference == 1 {
+            tx.execute(SQL_RENAME_METADATA_REFERENCE, [old_name, new_name])?;
+        }
+        if has_rtree == 1 {
+            tx.execute_batch(&gpkg_rtree_rename_sql(
+                old_name,
+                new_name,
+                &layer.geometry_column,
+            ))?;
+            tx.execute_batch(&gpkg_rtree_triggers_sql(
+                new_name,
+                &layer.geometry_column,
+                &layer.primary_key_column,
+            ))?;
+        }
+        tx.execute(SQL_UPDATE_CONTENTS_LAST_CHANGE, [new_name])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store a metadata document in `gpkg_metadata` and return its id.
+    ///
+    /// `scope` is the `md_scope` of the document (e.g. `dataset`), and
+    /// `standard_uri` is the URI of its schema (e.g.
+    /// `http://schemas.opengis.net/iso/19139/` for ISO 19115 XML). The tables
+    /// of the `gpkg_metadata` extension are created on first use. Link the
+    /// document with `link_metadata_to_layer()` or
+    /// `link_metadata_to_geopackage()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let id = gpkg.add_metadata(
+    ///     "dataset",
+    ///     "http://schemas.opengis.net/iso/19139/",
+    ///     "text/xml",
+    ///     "<gmd:MD_Metadata/>",
+    /// )?;
+    /// gpkg.link_metadata_to_layer(id, "points")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn add_metadata(
+        &self,
+        scope: &str,
+        standard_uri: &str,
+        mime_type: &str,
+        metadata: &str,
+    ) -> Result<i64> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        super::layer::with_savepoint(&self.conn, || {
+            self.conn.execute_batch(SQL_ENABLE_METADATA)?;
+            self.conn.execute(
+                SQL_INSERT_METADATA,
+                [scope, standard_uri, mime_type, metadata],
+            )?;
+            Ok(self.conn.last_insert_rowid())
+        })
+    }
+
+    /// Link a metadata document to a layer (or an attribute table), so that
+    /// it's returned by `GpkgLayer::metadata_entries()`.
+    ///
+    /// Fails with `GpkgError::MetadataNotFound` if there is no document with
+    /// the id, and with `GpkgError::LayerNotFound` if there is no such layer.
+    /// Linking the same document twice has no effect.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.link_metadata_to_layer(1, "points")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn link_metadata_to_layer(&self, metadata_id: i64, layer_name: &str) -> Result<()> {
+        if !self.table_exists_in_contents(layer_name)? {
+            return Err(GpkgError::LayerNotFound {
+                layer_name: layer_name.to_string(),
+            });
+        }
+        self.link_metadata(metadata_id, "table", Some(layer_name))
+    }
+
+    /// Link a metadata document to the GeoPackage as a whole, so that it's
+    /// returned by `metadata_entries()`.
+    ///
+    /// Fails with `GpkgError::MetadataNotFound` if there is no document with
+    /// the id. Linking the same document twice has no effect.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// gpkg.link_metadata_to_geopackage(1)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn link_metadata_to_geopackage(&self, metadata_id: i64) -> Result<()> {
+        self.link_metadata(metadata_id, "geopackage", None)
+    }
+
+    fn link_metadata(
+        &self,
+        metadata_id: i64,
+        reference_scope: &str,
+        table_name: Option<&str>,
+    ) -> Result<()> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        let has_metadata: i64 =
+            self.conn
+                .query_row(SQL_TABLE_EXISTS, ["gpkg_metadata"], |row| row.get(0))?;
+        let exists: i64 = if has_metadata == 1 {
+            self.conn
+                .query_row(SQL_METADATA_EXISTS, [metadata_id], |row| row.get(0))?
+        } else {
+            0
+        };
+        if exists == 0 {
+            return Err(GpkgError::MetadataNotFound { id: metadata_id });
+        }
+
+        super::layer::with_savepoint(&self.conn, || {
+            self.conn.execute_batch(SQL_ENABLE_METADATA)?;
+            self.conn.execute(
+                SQL_INSERT_METADATA_REFERENCE,
+                rusqlite::params![reference_scope, table_name, metadata_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Return the metadata documents linked to the GeoPackage as a whole.
+    ///
+    /// Returns an empty `Vec` if the GeoPackage doesn't use the
+    /// `gpkg_metadata` extension.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// for entry in gpkg.metadata_entries()? {
+    ///     println!("{} ({})", entry.metadata, entry.mime_type);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn metadata_entries(&self) -> Result<Vec<MetadataEntry>> {
+        select_referenced_metadata(&self.conn, "geopackage", None)
+    }
+
+    /// Dump the GeoPackage data to `Vec<u8>`.
+    ///
+    /// This is intended for environments without filesystem access (for example,
+    /// running in a web browser). You can serialize an in-memory GeoPackage and
+    /// move the bytes over the wire or store them in browser storage.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let bytes = gpkg.to_bytes()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let data: &[u8] = &self.conn.serialize("main")?;
+        Ok(data.to_vec())
+    }
+
+    /// Write a copy of the GeoPackage to the file at `path`.
+    ///
+    /// This uses SQLite's online backup API, so the data is copied page by
+    /// page without being held in memory, and works for both in-memory and
+    /// file-backed GeoPackages. If `path` already exists, this fails with
+    /// `GpkgError::FileAlreadyExists` unless `overwrite` is `true`, in which
+    /// case the file is replaced.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// gpkg.save_to("data/example.gpkg", false)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn save_to<P: AsRef<Path>>(&self, path: P, overwrite: bool) -> Result<()> {
+        self.save_to_with_progress(path, overwrite, |_, _| {})
+    }
+
+    /// Same as `save_to()`, but calls `progress` with the number of remaining
+    /// pages and the total number of pages after each step of the copy.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// gpkg.save_to_with_progress("data/example.gpkg", true, |remaining, total| {
+    ///     println!("{}/{total} pages copied", total - remaining);
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn save_to_with_progress<P, F>(
+        &self,
+        path: P,
+        overwrite: bool,
+        mut progress: F,
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(usize, usize),
+    {
+        use rusqlite::backup::{Backup, StepResult};
+
+        let path = path.as_ref();
+        if path.exists() {
+            if !overwrite {
+                return Err(GpkgError::FileAlreadyExists {
+                    path: path.to_path_buf(),
+                });
+            }
+            std::fs::remove_file(path)?;
+        }
+
+        let mut dst = rusqlite_open_path(path, OpenFlags::default())?;
+        let backup = Backup::new(&self.conn, &mut dst)?;
+        loop {
+            let step = backup.step(BACKUP_PAGES_PER_STEP)?;
+            let rusqlite::backup::Progress {
+                remaining,
+                pagecount,
+            } = backup.progress();
+            progress(remaining.max(0) as usize, pagecount.max(0) as usize);
+
+            let code = match step {
+                StepResult::Done => return Ok(()),
+                StepResult::More => continue,
+                StepResult::Busy => rusqlite::ffi::SQLITE_BUSY,
+                _ => rusqlite::ffi::SQLITE_LOCKED,
+            };
+            return Err(
+                rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(code), None).into(),
+            );
+        }
+    }
+
+    /// Load the GeoPackage data from a dump.
+    ///
+    /// This is intended for environments without filesystem access (for example,
+    /// running in a web browser). Provide the bytes from `Gpkg::to_bytes()` to
+    /// recreate an in-memory GeoPackage.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let bytes = gpkg.to_bytes()?;
+    /// let restored = Gpkg::from_bytes(&bytes)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn from_bytes<D: AsRef<[u8]>>(data: D) -> Result<Self> {
+        let mut conn = rusqlite::Connection::open_in_memory()?;
+
+        let data_ref = data.as_ref();
+        let reader = std::io::Cursor::new(data_ref);
+        conn.deserialize_read_exact("main", reader, data_ref.len(), false)?;
+        Ok(Self {
+            conn: Rc::new(conn),
+            read_only: false,
+            dump: None,
+        })
+    }
+
+    /// Open a dump as a read-only GeoPackage without copying it.
+    ///
+    /// Unlike `Gpkg::from_bytes()`, SQLite reads directly from `data`, so a
+    /// large dump isn't allocated twice. `data` is any owned buffer, e.g. a
+    /// `Vec<u8>`, `Box<[u8]>` or `Arc<[u8]>`, or a `&'static [u8]` from
+    /// `include_bytes!()`. The `Gpkg` takes ownership of it and frees it after
+    /// the connection is closed, i.e. after the `Gpkg` and all the layers
+    /// sharing its connection are dropped, so `Gpkg::into_connection()` fails
+    /// with `GpkgError::DumpInUse`. The write APIs fail with
+    /// `GpkgError::ReadOnly`.
+    /// The dump must not be in WAL mode.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let bytes = std::fs::read("data/example.gpkg")?;
+    /// let gpkg = Gpkg::from_bytes_read_only(bytes)?;
+    /// let _layers = gpkg.list_layers()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn from_bytes_read_only<D>(data: D) -> Result<Self>
+    where
+        D: AsRef<[u8]> + Send + 'static,
+    {
+        // Move the buffer into the `Rc` first so that the slice doesn't move
+        // along with `data`. It's declared before the connection, so it's
+        // dropped after it on an error too.
+        let data = Rc::new(data);
+        let bytes = (*data).as_ref();
+        // SAFETY: The buffer is shared by the `Gpkg` and every layer and
+        // attribute table, which declare it after the connection, so it's
+        // dropped only after the connection is closed, and no public API hands
+        // out the connection. The slice outlives every read of the database.
+        let bytes: &'static [u8] =
+            unsafe { std::slice::from_raw_parts(bytes.as_ptr(), bytes.len()) };
+        let mut conn = rusqlite::Connection::open_in_memory()?;
+        conn.deserialize_bytes("main", bytes)?;
+        Ok(Self {
+            dump: Some(data),
+            ..Self::new_from_conn(Rc::new(conn), true)?
+        })
+    }
+
+    /// Load an attribute table definition by name.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let table = gpkg.get_attribute_table("observations")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get_attribute_table(&self, table_name: &str) -> Result<GpkgAttributeTable> {
+        let data_type = self.get_data_type(table_name)?;
+        if data_type != "attributes" {
+            if data_type == "features" {
+                return Err(GpkgError::NotAnAttributeTable {
+                    layer_name: table_name.to_string(),
+                });
+            }
+            return Err(GpkgError::UnsupportedDataType {
+                layer_name: table_name.to_string(),
+                data_type,
+            });
+        }
+
+        let (primary_key_column, other_columns) = self.get_attribute_column_specs(table_name)?;
+
+        let insert_sql = GpkgAttributeTable::build_insert_sql(table_name, &other_columns);
+        let update_sql =
+            GpkgAttributeTable::build_update_sql(table_name, &primary_key_column, &other_columns);
+        let property_index_by_name = Rc::new(GpkgAttributeTable::build_property_index_by_name(
+            &other_columns,
+        ));
+
+        Ok(GpkgAttributeTable {
+            conn: self.conn.clone(),
+            _dump: self.dump.clone(),
+            is_read_only: self.read_only,
+            table_name: table_name.to_string(),
+            primary_key_column,
+            property_columns: other_columns,
+            property_index_by_name,
+            insert_sql,
+            update_sql,
+        })
+    }
+
+    /// Create a new attribute table (non-spatial, no geometry column).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ColumnSpec, ColumnType, Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open_in_memory()?;
+    /// let columns = vec![
+    ///     ColumnSpec { name: "name".to_string(), column_type: ColumnType::Varchar, ..Default::default() },
+    ///     ColumnSpec { name: "value".to_string(), column_type: ColumnType::Integer, ..Default::default() },
+    /// ];
+    /// let table = gpkg.create_attribute_table("observations", &columns)?;
+    /// table.insert(params!["alpha", 7_i64])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn create_attribute_table(
+        &self,
+        table_name: &str,
+        column_specs: &[ColumnSpec],
+    ) -> Result<GpkgAttributeTable> {
+        if self.read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+
+        if self.table_exists_in_contents(table_name)? {
+            return Err(GpkgError::LayerAlreadyExists {
+                layer_name: table_name.to_string(),
+            });
+        }
+
+        // Attribute tables must not have geometry columns.
+        if let Some(spec) = column_specs
+            .iter()
+            .find(|s| s.column_type == crate::types::ColumnType::Geometry)
+        {
+            return Err(GpkgError::GeometryColumnInAttributeTable {
+                column: spec.name.clone(),
+            });
+        }
 
         let mut column_defs = Vec::with_capacity(column_specs.len() + 1);
         column_defs.push("fid INTEGER PRIMARY KEY AUTOINCREMENT".to_string());
         for spec in column_specs {
-            let col_type = crate::conversions::column_type_to_str(spec.column_type);
-            column_defs.push(format!(r#""{}" {col_type}"#, spec.name));
+            column_defs.push(column_definition_sql(spec));
         }
 
         let create_sql = sql_create_table(table_name, &column_defs.join(", "));
@@ -606,6 +2468,7 @@ impl Gpkg {
 
         Ok(GpkgAttributeTable {
             conn: self.conn.clone(),
+            _dump: self.dump.clone(),
             is_read_only: self.read_only,
             table_name: table_name.to_string(),
             primary_key_column: "fid".to_string(),
@@ -645,10 +2508,7 @@ impl Gpkg {
         }
 
         self.conn.execute_batch(&sql_drop_table(table_name))?;
-        self.conn.execute(
-            "DELETE FROM gpkg_contents WHERE table_name = ?1",
-            rusqlite::params![table_name],
-        )?;
+        self.conn.execute(SQL_DELETE_CONTENTS, [table_name])?;
         Ok(())
     }
 
@@ -662,6 +2522,21 @@ impl Gpkg {
         Ok(exists == 1)
     }
 
+    fn unique_columns(&self, table_name: &str) -> Result<HashSet<String>> {
+        let mut stmt = self.conn.prepare(&sql_unique_columns(table_name))?;
+        let columns = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<HashSet<String>>>()?;
+        Ok(columns)
+    }
+
+    // Whether the table of a layer is a SQL view.
+    fn is_view(&self, table_name: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .query_row(SQL_VIEW_EXISTS, [table_name], |row| row.get(0))?)
+    }
+
     /// Look up the `data_type` for a table in `gpkg_contents`.
     pub(crate) fn get_data_type(&self, table_name: &str) -> Result<String> {
         let mut stmt = self.conn.prepare(SQL_SELECT_DATA_TYPE)?;
@@ -669,195 +2544,1984 @@ impl Gpkg {
         Ok(data_type)
     }
 
-    /// Resolve column specs for an attribute table (no geometry column expected).
-    pub(crate) fn get_attribute_column_specs(
-        &self,
-        table_name: &str,
-    ) -> Result<(String, Vec<ColumnSpec>)> {
-        let query = sql_table_columns(table_name);
-        let mut stmt = self.conn.prepare(&query)?;
+    /// Resolve column specs for an attribute table (no geometry column expected).
+    pub(crate) fn get_attribute_column_specs(
+        &self,
+        table_name: &str,
+    ) -> Result<(String, Vec<ColumnSpec>)> {
+        let query = sql_table_columns(table_name);
+        let mut stmt = self.conn.prepare(&query)?;
+
+        let column_specs = stmt.query_map([], |row| {
+            let name: String = row.get(0)?;
+            let column_type_str: String = row.get(1)?;
+            let primary_key: i32 = row.get(2)?;
+            let primary_key = primary_key != 0;
+            let not_null: i32 = row.get(3)?;
+            let default: Option<String> = row.get(4)?;
+            Ok(TableColumn {
+                name,
+                column_type_str,
+                primary_key,
+                not_null: not_null != 0,
+                default,
+            })
+        })?;
+
+        let result: std::result::Result<Vec<TableColumn>, _> = column_specs.collect();
+        let unique_columns = self.unique_columns(table_name)?;
+        let mut primary_key_column: Option<String> = None;
+        let mut other_columns = Vec::new();
+        for TableColumn {
+            name,
+            column_type_str,
+            primary_key: is_primary_key,
+            not_null,
+            default,
+        } in result?
+        {
+            let column_type = crate::conversions::column_type_from_str(&column_type_str)
+                .ok_or_else(|| GpkgError::UnsupportedColumnType {
+                    column: name.clone(),
+                    declared_type: column_type_str,
+                })?;
+
+            if is_primary_key {
+                if primary_key_column.is_some() {
+                    return Err(GpkgError::CompositePrimaryKeyUnsupported {
+                        layer_name: table_name.to_string(),
+                    });
+                }
+                primary_key_column = Some(name);
+                continue;
+            }
+            if column_type == crate::types::ColumnType::Geometry {
+                return Err(GpkgError::GeometryColumnInAttributeTable { column: name });
+            }
+            other_columns.push(ColumnSpec {
+                unique: unique_columns.contains(&name),
+                default: default.as_deref().and_then(default_value_from_sql),
+                name,
+                column_type,
+                not_null,
+            });
+        }
+
+        let primary_key_column =
+            primary_key_column.ok_or_else(|| GpkgError::MissingPrimaryKeyColumn {
+                layer_name: table_name.to_string(),
+            })?;
+
+        Ok((primary_key_column, other_columns))
+    }
+
+    /// Resolve the table columns and map SQLite types.
+    ///
+    /// A view has no primary key, so its id column is `id_column`, or else
+    /// the first column named `fid`, `id` or `OGC_FID`. Columns of a view
+    /// computed by an expression have no declared type and are read as
+    /// `BLOB`, i.e. as they are.
+    pub(crate) fn get_column_specs(
+        &self,
+        layer_name: &str,
+        geometry_column: &str,
+        is_view: bool,
+        id_column: Option<&str>,
+    ) -> Result<(String, Vec<ColumnSpec>)> {
+        let query = sql_table_columns(layer_name);
+        let mut stmt = self.conn.prepare(&query)?;
+
+        let mut primary_key_column: Option<String> = None;
+        let mut has_geometry_column = false;
+        let column_specs = stmt.query_map([], |row| {
+            let name: String = row.get(0)?;
+            let column_type_str: String = row.get(1)?;
+            let primary_key: i32 = row.get(2)?;
+            let primary_key = primary_key != 0;
+            let not_null: i32 = row.get(3)?;
+            let default: Option<String> = row.get(4)?;
+
+            Ok(TableColumn {
+                name,
+                column_type_str,
+                primary_key,
+                not_null: not_null != 0,
+                default,
+            })
+        })?;
+
+        let mut columns = column_specs.collect::<std::result::Result<Vec<TableColumn>, _>>()?;
+        if is_view {
+            let id_column = match id_column {
+                Some(id_column) => columns.iter().position(|column| column.name == id_column),
+                None => ["fid", "id", "ogc_fid"].iter().find_map(|candidate| {
+                    columns
+                        .iter()
+                        .position(|column| column.name.eq_ignore_ascii_case(candidate))
+                }),
+            };
+            if let Some(i) = id_column {
+                columns[i].primary_key = true;
+            }
+        }
+        if let Some(id_column) = id_column
+            && !columns
+                .iter()
+                .any(|column| column.primary_key && column.name == id_column)
+        {
+            return Err(GpkgError::InvalidIdColumn {
+                layer_name: layer_name.to_string(),
+                column: id_column.to_string(),
+            });
+        }
+
+        let unique_columns = self.unique_columns(layer_name)?;
+        let mut other_columns = Vec::new();
+        for TableColumn {
+            name,
+            mut column_type_str,
+            primary_key: is_primary_key,
+            not_null,
+            default,
+        } in columns
+        {
+            if is_view && column_type_str.is_empty() {
+                column_type_str = "BLOB".to_string();
+            }
+            // cf. https://www.geopackage.org/spec140/index.html#_sqlite_container
+            let column_type = column_type_from_str(&column_type_str).ok_or_else(|| {
+                GpkgError::UnsupportedColumnType {
+                    column: name.clone(),
+                    declared_type: column_type_str,
+                }
+            })?;
+
+            if is_primary_key {
+                if primary_key_column.is_some() {
+                    return Err(GpkgError::CompositePrimaryKeyUnsupported {
+                        layer_name: layer_name.to_string(),
+                    });
+                }
+                primary_key_column = Some(name.clone());
+                continue;
+            }
+            if name == geometry_column {
+                has_geometry_column = true;
+            } else {
+                other_columns.push(ColumnSpec {
+                    unique: unique_columns.contains(&name),
+                    default: default.as_deref().and_then(default_value_from_sql),
+                    name,
+                    column_type,
+                    not_null,
+                });
+            }
+        }
+
+        let primary_key_column =
+            primary_key_column.ok_or_else(|| GpkgError::MissingPrimaryKeyColumn {
+                layer_name: layer_name.to_string(),
+            })?;
+
+        if !has_geometry_column {
+            return Err(GpkgError::MissingGeometryColumn {
+                layer_name: layer_name.to_string(),
+            });
+        }
+
+        Ok((primary_key_column, other_columns))
+    }
+
+    /// Resolve the geometry column metadata and SRS information for a layer.
+    fn get_geometry_column_meta(&self, layer_name: &str) -> Result<GeometryColumnMeta> {
+        let mut stmt = self.conn.prepare(SQL_SELECT_GEOMETRY_COLUMN_META)?;
+
+        let (geometry_column, geometry_type_name, z, m, srs_id) =
+            stmt.query_one([layer_name], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i8>(2)?,
+                    row.get::<_, i8>(3)?,
+                    row.get::<_, i32>(4)?,
+                ))
+            })?;
+
+        // A layer of curves is opened like a GEOMETRY layer; decoding a curve
+        // geometry fails per feature.
+        let geometry_type = if is_non_linear_geometry_type(&geometry_type_name) {
+            wkb::reader::GeometryType::GeometryCollection
+        } else {
+            geometry_type_from_str(&geometry_type_name)?
+        };
+        let (z_flag, m_flag) = zm_flags_from_i8(z, m)?;
+
+        Ok(GeometryColumnMeta {
+            geometry_column,
+            geometry_type_name,
+            geometry_type,
+            z_flag,
+            m_flag,
+            srs_id,
+        })
+    }
+}
+
+pub(crate) fn crs_wkt_enabled(conn: &rusqlite::Connection) -> rusqlite::Result<bool> {
+    conn.query_row(SQL_CRS_WKT_COLUMN_EXISTS, [], |row| row.get(0))
+}
+
+pub(crate) fn select_spatial_ref_sys(
+    conn: &rusqlite::Connection,
+    srs_id: i32,
+) -> rusqlite::Result<Option<SpatialRefSys>> {
+    let sql = format!(
+        "{} WHERE srs_id = ?1",
+        sql_select_spatial_ref_sys(crs_wkt_enabled(conn)?)
+    );
+    conn.query_row(&sql, [srs_id], spatial_ref_sys_from_row)
+        .optional()
+}
+
+// A row of `sql_select_spatial_ref_sys()`.
+fn spatial_ref_sys_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SpatialRefSys> {
+    Ok(SpatialRefSys {
+        srs_name: row.get(0)?,
+        srs_id: row.get(1)?,
+        organization: row.get(2)?,
+        organization_coordsys_id: row.get(3)?,
+        definition: row.get(4)?,
+        description: row.get(5)?,
+        definition_12_063: row.get(6)?,
+    })
+}
+
+// A row of `sql_table_columns()`.
+struct TableColumn {
+    name: String,
+    column_type_str: String,
+    primary_key: bool,
+    not_null: bool,
+    default: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gpkg;
+    use crate::Value;
+    use crate::error::GpkgError;
+    use crate::params;
+    use crate::types::{
+        ColumnSpec, ColumnType, CreateLayerOptions, JournalMode, MetadataEntry, SpatialRefSys,
+    };
+    use geo_types::Point;
+    use rusqlite::OpenFlags;
+    use std::fs;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use wkb::reader::{Dimension, GeometryType};
+
+    #[test]
+    fn create_layer_requires_existing_srs() {
+        let gpkg = Gpkg::open_in_memory().expect("new gpkg");
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let err = gpkg
+            .create_layer(
+                "missing_srs",
+                "geom",
+                wkb::reader::GeometryType::Point,
+                wkb::reader::Dimension::Xy,
+                9999,
+                &columns,
+            )
+            .expect_err("missing srs should fail");
+
+        assert!(matches!(
+            err,
+            GpkgError::MissingSpatialRefSysId { srs_id: 9999 }
+        ));
+    }
+
+    #[test]
+    fn lists_and_gets_spatial_ref_sys() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let ids: Vec<i32> = gpkg.list_srs()?.iter().map(|srs| srs.srs_id).collect();
+        assert_eq!(ids, vec![-1, 0, 4326]);
+
+        let wgs84 = gpkg.get_srs(4326)?.expect("EPSG:4326");
+        assert_eq!(wgs84.srs_name, "WGS 84");
+        assert_eq!(wgs84.organization, "EPSG");
+        assert_eq!(wgs84.organization_coordsys_id, 4326);
+        assert!(wgs84.definition.starts_with("GEOGCS["));
+        assert_eq!(wgs84.description.as_deref(), Some("WGS 84"));
+        let undefined = gpkg.get_srs(-1)?.expect("undefined Cartesian SRS");
+        assert_eq!(undefined.definition, "undefined");
+
+        assert!(gpkg.srs_exists(0)?);
+        assert!(!gpkg.srs_exists(3857)?);
+        assert_eq!(gpkg.get_srs(3857)?, None);
+
+        gpkg.register_srs(
+            "WGS 84 / Pseudo-Mercator",
+            3857,
+            "EPSG",
+            3857,
+            "PROJCS[\"WGS 84 / Pseudo-Mercator\"]",
+            "Web Mercator",
+        )?;
+        assert!(gpkg.srs_exists(3857)?);
+        assert_eq!(gpkg.list_srs()?.len(), 4);
+
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "mercator",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            3857,
+            &columns,
+        )?;
+        let srs = layer.srs()?;
+        assert_eq!(srs.srs_id, 3857);
+        assert_eq!(srs.definition, "PROJCS[\"WGS 84 / Pseudo-Mercator\"]");
+        assert_eq!(Some(srs), gpkg.get_srs(3857)?);
+        Ok(())
+    }
+
+    #[test]
+    fn crs_wkt_extension_stores_wkt2_definitions() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        assert!(!gpkg.has_crs_wkt_extension()?);
+        assert_eq!(
+            gpkg.get_srs(4326)?.expect("EPSG:4326").definition_12_063,
+            None
+        );
+
+        let mut mercator = SpatialRefSys {
+            srs_name: "WGS 84 / Pseudo-Mercator".to_string(),
+            srs_id: 3857,
+            organization: "EPSG".to_string(),
+            organization_coordsys_id: 3857,
+            definition: "undefined".to_string(),
+            description: None,
+            definition_12_063: Some("PROJCRS[\"WGS 84 / Pseudo-Mercator\"]".to_string()),
+        };
+        let err = gpkg
+            .register_srs_wkt2(&mercator)
+            .expect_err("extension is not enabled");
+        assert!(matches!(err, GpkgError::CrsWktExtensionNotEnabled));
+
+        gpkg.enable_crs_wkt_extension()?;
+        gpkg.enable_crs_wkt_extension()?;
+        assert!(gpkg.has_crs_wkt_extension()?);
+        let registered: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM gpkg_extensions WHERE extension_name = 'gpkg_crs_wkt'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(registered, 1);
+        assert_eq!(
+            gpkg.get_srs(4326)?
+                .expect("EPSG:4326")
+                .definition_12_063
+                .as_deref(),
+            Some("undefined")
+        );
+
+        gpkg.register_srs_wkt2(&mercator)?;
+        assert_eq!(gpkg.get_srs(3857)?.as_ref(), Some(&mercator));
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "mercator",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            3857,
+            &columns,
+        )?;
+        assert_eq!(layer.srs()?, mercator);
+
+        mercator.srs_id = 3395;
+        mercator.definition_12_063 = None;
+        gpkg.register_srs_wkt2(&mercator)?;
+        assert_eq!(
+            gpkg.get_srs(3395)?
+                .expect("registered")
+                .definition_12_063
+                .as_deref(),
+            Some("undefined")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn register_srs_fills_not_null_crs_wkt_column() -> Result<(), GpkgError> {
+        // Recreate gpkg_spatial_ref_sys the way GDAL writes it with the
+        // gpkg_crs_wkt extension: a NOT NULL column without a default.
+        let gpkg = Gpkg::open_in_memory()?;
+        gpkg.conn.execute_batch(
+            "CREATE TABLE srs_with_wkt2 (
+               srs_name TEXT NOT NULL,
+               srs_id INTEGER PRIMARY KEY,
+               organization TEXT NOT NULL,
+               organization_coordsys_id INTEGER NOT NULL,
+               definition  TEXT NOT NULL,
+               description TEXT,
+               definition_12_063 TEXT NOT NULL
+             );
+             INSERT INTO srs_with_wkt2 SELECT *, 'undefined' FROM gpkg_spatial_ref_sys;
+             DROP TABLE gpkg_spatial_ref_sys;
+             ALTER TABLE srs_with_wkt2 RENAME TO gpkg_spatial_ref_sys;
+             INSERT INTO gpkg_extensions VALUES ('gpkg_spatial_ref_sys', 'definition_12_063', 'gpkg_crs_wkt', 'http://www.geopackage.org/spec120/#extension_crs_wkt', 'read-write');",
+        )?;
+        assert!(gpkg.has_crs_wkt_extension()?);
+        gpkg.enable_crs_wkt_extension()?;
+
+        gpkg.register_srs(
+            "WGS 84 / Pseudo-Mercator",
+            3857,
+            "EPSG",
+            3857,
+            "PROJCS[\"WGS 84 / Pseudo-Mercator\"]",
+            "Web Mercator",
+        )?;
+        let srs = gpkg.get_srs(3857)?.expect("registered");
+        assert_eq!(srs.definition, "PROJCS[\"WGS 84 / Pseudo-Mercator\"]");
+        assert_eq!(srs.definition_12_063.as_deref(), Some("undefined"));
+        Ok(())
+    }
+
+    #[cfg(feature = "epsg-common")]
+    #[test]
+    fn create_layer_registers_bundled_epsg_code() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "mercator",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            3857,
+            &columns,
+        )?;
+        let srs = layer.srs()?;
+        assert_eq!(srs.srs_name, "WGS 84 / Pseudo-Mercator");
+        assert_eq!(srs.organization, "EPSG");
+        assert!(srs.definition.starts_with("PROJCS["));
+
+        // Already registered.
+        gpkg.register_epsg(3857)?;
+        gpkg.register_epsg(25832)?;
+        assert!(gpkg.srs_exists(25832)?);
+
+        let err = gpkg.register_epsg(32654).expect_err("not bundled");
+        assert!(matches!(
+            err,
+            GpkgError::MissingSpatialRefSysId { srs_id: 32654 }
+        ));
+
+        let options = CreateLayerOptions {
+            register_epsg: false,
+            ..Default::default()
+        };
+        let err = gpkg
+            .create_layer_with_options(
+                "lambert",
+                "geom",
+                GeometryType::Point,
+                Dimension::Xy,
+                2154,
+                &columns,
+                &options,
+            )
+            .expect_err("auto-registration is disabled");
+        assert!(matches!(
+            err,
+            GpkgError::MissingSpatialRefSysId { srs_id: 2154 }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn delete_layer_rejects_read_only() {
+        let gpkg =
+            Gpkg::open_read_only("src/test/test_generated.gpkg").expect("open read-only gpkg");
+        let err = gpkg
+            .delete_layer("points")
+            .expect_err("read-only should fail");
+        assert!(matches!(err, GpkgError::ReadOnly));
+    }
+
+    #[test]
+    fn save_to_writes_in_memory_gpkg_to_file() -> Result<(), GpkgError> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("rusqlite_gpkg_save_to_{nanos}.gpkg"));
+
+        let gpkg = Gpkg::open_in_memory()?;
+        for (name, n) in [("first", 3), ("second", 500)] {
+            let layer =
+                gpkg.create_layer(name, "geom", GeometryType::Point, Dimension::Xy, 4326, &[])?;
+            layer.insert_many((0..n).map(|i| (Point::new(i as f64, 1.0), Vec::<Value>::new())))?;
+        }
+        gpkg.save_to(&path, false)?;
+
+        let saved = Gpkg::open_read_only(&path)?;
+        assert!(saved.is_geopackage()?);
+        assert_eq!(saved.list_layers()?, vec!["first", "second"]);
+        assert_eq!(saved.get_layer("first")?.count()?, 3);
+        assert_eq!(saved.get_layer("second")?.count()?, 500);
+        drop(saved);
+
+        let err = gpkg.save_to(&path, false).expect_err("file exists");
+        assert!(matches!(&err, GpkgError::FileAlreadyExists { path: p } if p == &path));
+
+        gpkg.delete_layer("second")?;
+        let mut steps = Vec::new();
+        gpkg.save_to_with_progress(&path, true, |remaining, total| {
+            steps.push((remaining, total))
+        })?;
+        let (remaining, total) = *steps.last().expect("progress");
+        assert_eq!(remaining, 0);
+        assert!(total > 0);
+
+        let saved = Gpkg::open_read_only(&path)?;
+        assert_eq!(saved.list_layers()?, vec!["first"]);
+        drop(saved);
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn vacuum_shrinks_file_after_deleting_features() -> Result<(), GpkgError> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("rusqlite_gpkg_vacuum_{nanos}.gpkg"));
+        let copy_path = dir.join(format!("rusqlite_gpkg_vacuum_copy_{nanos}.gpkg"));
+
+        let gpkg = Gpkg::open(&path)?;
+        let columns = vec![ColumnSpec {
+            name: "note".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let note = "x".repeat(1000);
+        layer.insert_many(
+            (0..2000).map(|i| (Point::new(i as f64, 0.0), vec![Value::from(note.as_str())])),
+        )?;
+        let original_size = fs::metadata(&path)?.len();
+        gpkg.conn.execute("DELETE FROM points WHERE fid > 10", [])?;
+        assert_eq!(fs::metadata(&path)?.len(), original_size);
+
+        // The statements cached by the layer don't block VACUUM.
+        gpkg.vacuum_into(&copy_path)?;
+        assert_eq!(fs::metadata(&path)?.len(), original_size);
+        assert!(fs::metadata(&copy_path)?.len() < original_size / 10);
+        assert_eq!(
+            Gpkg::open_read_only(&copy_path)?
+                .get_layer("points")?
+                .count()?,
+            10
+        );
+
+        gpkg.vacuum()?;
+        assert!(fs::metadata(&path)?.len() < original_size / 10);
+        assert_eq!(layer.count()?, 10);
+        layer.insert(Point::new(0.0, 0.0), params!["after vacuum"])?;
+
+        gpkg.analyze()?;
+        gpkg.analyze_layer("points")?;
+        let analyzed: i64 = gpkg.conn.query_row(
+            "SELECT count(*) FROM sqlite_stat1 WHERE tbl = 'points'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert!(analyzed > 0);
+        let err = gpkg.analyze_layer("missing").expect_err("missing layer");
+        assert!(matches!(err, GpkgError::LayerNotFound { .. }));
+
+        drop(layer);
+        drop(gpkg);
+        let read_only = Gpkg::open_read_only(&path)?;
+        assert!(matches!(read_only.vacuum(), Err(GpkgError::ReadOnly)));
+        assert!(matches!(read_only.analyze(), Err(GpkgError::ReadOnly)));
+
+        drop(read_only);
+        fs::remove_file(&path).ok();
+        fs::remove_file(&copy_path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn open_missing_file_fails_with_file_not_found() -> Result<(), GpkgError> {
+        let mut path = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        path.push(format!("rusqlite_gpkg_missing_{nanos}.gpkg"));
+
+        let err = Gpkg::open_read_only(&path).expect_err("missing file");
+        assert!(matches!(&err, GpkgError::FileNotFound { path: p } if p == &path));
+        let err = Gpkg::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .expect_err("missing file");
+        assert!(matches!(err, GpkgError::FileNotFound { .. }));
+        assert!(!path.exists());
+
+        // The default flags create the file.
+        let gpkg = Gpkg::open(&path)?;
+        assert!(gpkg.is_geopackage()?);
+        drop(gpkg);
+        Gpkg::open_read_only(&path)?;
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn open_with_flags_read_only_rejects_writes() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_with_flags(
+            "src/test/test_generated.gpkg",
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )?;
+        let err = gpkg
+            .delete_layer("points")
+            .expect_err("read-only should fail");
+        assert!(matches!(err, GpkgError::ReadOnly));
+        Ok(())
+    }
+
+    #[test]
+    fn busy_timeout_waits_for_other_writer() -> Result<(), GpkgError> {
+        let mut path = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        path.push(format!("rusqlite_gpkg_busy_{nanos}.gpkg"));
+
+        let gpkg = Gpkg::open(&path)?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        // Hold the write lock from the first connection.
+        gpkg.conn.execute_batch("BEGIN IMMEDIATE")?;
+
+        // Gpkg is not Send, so the second connection is opened in the thread.
+        let thread_path = path.clone();
+        let writer = std::thread::spawn(move || -> Result<(), GpkgError> {
+            let other = Gpkg::open(&thread_path)?;
+            other.busy_timeout(Duration::from_secs(10))?;
+            let layer = other.get_layer("points")?;
+            layer.insert(Point::new(1.0, 2.0), std::iter::empty::<&Value>())
+        });
+
+        std::thread::sleep(Duration::from_millis(200));
+        gpkg.conn.execute_batch("COMMIT")?;
+        writer.join().expect("writer thread")?;
+
+        assert_eq!(gpkg.get_layer("points")?.count()?, 1);
+
+        drop(gpkg);
+        let _ = fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[test]
+    fn sets_wal_journal_mode() -> Result<(), GpkgError> {
+        let mut path = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        path.push(format!("rusqlite_gpkg_wal_{nanos}.gpkg"));
+
+        let gpkg = Gpkg::open(&path)?;
+        gpkg.set_journal_mode(JournalMode::Wal)?;
+        let mode: String = gpkg
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))?;
+        assert_eq!(mode, "wal");
+        let synchronous: i64 = gpkg
+            .conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))?;
+        assert_eq!(synchronous, 1); // NORMAL
+
+        drop(gpkg);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn set_journal_mode_rejects_in_memory_and_read_only() {
+        let gpkg = Gpkg::open_in_memory().expect("new gpkg");
+        let err = gpkg
+            .set_journal_mode(JournalMode::Wal)
+            .expect_err("in-memory should fail");
+        assert!(matches!(
+            err,
+            GpkgError::JournalModeNotApplied {
+                requested: "wal",
+                ..
+            }
+        ));
+
+        let gpkg =
+            Gpkg::open_read_only("src/test/test_generated.gpkg").expect("open read-only gpkg");
+        let err = gpkg
+            .set_journal_mode(JournalMode::Wal)
+            .expect_err("read-only should fail");
+        assert!(matches!(err, GpkgError::ReadOnly));
+    }
+
+    #[test]
+    fn new_gpkg_has_application_id_and_user_version() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        assert_eq!(gpkg.application_id()?, 0x47504B47);
+        assert_eq!(gpkg.user_version()?, 10400);
+        assert!(gpkg.is_geopackage()?);
+
+        let conn = rusqlite::Connection::open_in_memory()?;
+        conn.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let plain = Gpkg::new_from_conn(std::rc::Rc::new(conn), false)?;
+        assert_eq!(plain.application_id()?, 0);
+        assert!(!plain.is_geopackage()?);
+        Ok(())
+    }
+
+    #[test]
+    fn wraps_existing_connection() -> Result<(), GpkgError> {
+        let conn = rusqlite::Connection::open_in_memory()?;
+        let gpkg = Gpkg::from_connection(conn, false)?;
+        assert!(gpkg.is_geopackage()?);
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), std::iter::empty::<&Value>())?;
+        drop(layer);
+
+        let conn = gpkg.into_connection()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM points", [], |row| row.get(0))?;
+        assert_eq!(count, 1);
+
+        // Wrapping an existing GeoPackage doesn't initialize it again.
+        let gpkg = Gpkg::from_connection(conn, false)?;
+        let layer = gpkg.get_layer("points")?;
+        assert_eq!(layer.count()?, 1);
+
+        // The layer still shares the connection.
+        let err = gpkg.into_connection().expect_err("layer is alive");
+        assert!(matches!(err, GpkgError::ConnectionInUse));
+        Ok(())
+    }
+
+    fn count_rows(gpkg: &Gpkg, sql: &str) -> Result<i64, GpkgError> {
+        Ok(gpkg.conn.query_row(sql, [], |row| row.get(0))?)
+    }
+
+    #[test]
+    fn create_layer_registers_rtree_extension() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let rows = gpkg
+            .conn
+            .prepare(
+                "SELECT table_name, column_name, definition, scope FROM gpkg_extensions \
+                 WHERE extension_name = 'gpkg_rtree_index'",
+            )?
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(
+            rows,
+            vec![(
+                "places".to_string(),
+                "geom".to_string(),
+                "http://www.geopackage.org/spec/#extension_rtree".to_string(),
+                "write-only".to_string(),
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete_layer_removes_rtree_and_metadata_rows() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["a"])?;
+        drop(layer);
+
+        gpkg.delete_layer("places")?;
+        assert!(gpkg.list_layers()?.is_empty());
+        for sql in [
+            "SELECT COUNT(*) FROM sqlite_master WHERE name LIKE '%places%'",
+            "SELECT COUNT(*) FROM gpkg_contents WHERE table_name = 'places'",
+            "SELECT COUNT(*) FROM gpkg_geometry_columns WHERE table_name = 'places'",
+            "SELECT COUNT(*) FROM gpkg_extensions WHERE table_name = 'places'",
+        ] {
+            assert_eq!(count_rows(&gpkg, sql)?, 0, "{sql}");
+        }
+
+        let layer = gpkg.create_layer(
+            "places",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(3.0, 4.0), params!["b"])?;
+        assert_eq!(layer.count()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn delete_layer_without_rtree() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        gpkg.conn.execute_batch(
+            "CREATE TABLE plain (fid INTEGER PRIMARY KEY AUTOINCREMENT, geom POINT);
+             INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id)
+             VALUES ('plain', 'features', 'plain', 4326);
+             INSERT INTO gpkg_geometry_columns (table_name, column_name, geometry_type_name, srs_id, z, m)
+             VALUES ('plain', 'geom', 'POINT', 4326, 0, 0);
+             CREATE TABLE gpkg_metadata_reference (
+               reference_scope TEXT NOT NULL,
+               table_name TEXT,
+               column_name TEXT,
+               row_id_value INTEGER,
+               timestamp DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+               md_file_id INTEGER NOT NULL,
+               md_parent_id INTEGER
+             );
+             INSERT INTO gpkg_metadata_reference (reference_scope, table_name, md_file_id)
+             VALUES ('table', 'plain', 1);",
+        )?;
+        assert_eq!(gpkg.list_layers()?, vec!["plain".to_string()]);
+
+        gpkg.delete_layer("plain")?;
+        assert!(gpkg.list_layers()?.is_empty());
+        assert_eq!(
+            count_rows(&gpkg, "SELECT COUNT(*) FROM gpkg_metadata_reference")?,
+            0
+        );
+
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "plain",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        assert_eq!(layer.count()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn from_connection_detects_read_only() -> Result<(), GpkgError> {
+        let conn = rusqlite::Connection::open_with_flags(
+            "src/test/test_generated.gpkg",
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )?;
+        let gpkg = Gpkg::from_connection(conn, false)?;
+        let err = gpkg
+            .delete_layer("points")
+            .expect_err("read-only should fail");
+        assert!(matches!(err, GpkgError::ReadOnly));
+        Ok(())
+    }
+
+    #[test]
+    fn lists_layers_with_metadata() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xyz,
+            4326,
+            &columns,
+        )?;
+        layer.insert_unchecked(Point::new(1.0, 2.0), std::iter::empty::<&Value>())?;
+        gpkg.create_layer(
+            "odd",
+            "shape",
+            GeometryType::Polygon,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        gpkg.create_attribute_table("codes", &columns)?;
+        gpkg.conn.execute(
+            "UPDATE gpkg_geometry_columns SET geometry_type_name = 'CIRCULARSTRING' WHERE table_name = 'odd'",
+            [],
+        )?;
+        gpkg.conn.execute_batch(
+            "UPDATE gpkg_contents SET srs_id = -1 WHERE table_name = 'odd';
+             UPDATE gpkg_geometry_columns SET srs_id = -1 WHERE table_name = 'odd';",
+        )?;
+
+        let layers = gpkg.list_layers_detailed()?;
+        assert_eq!(layers.len(), 2);
+
+        let points = &layers[0];
+        assert_eq!(points.table_name, "points");
+        assert_eq!(points.data_type, "features");
+        assert_eq!(points.primary_key_column, "fid");
+        assert_eq!(points.geometry_column, "geom");
+        assert_eq!(points.geometry_type_name, "POINT");
+        assert_eq!(points.geometry_type, Some(GeometryType::Point));
+        assert_eq!(points.geometry_dimension, Some(Dimension::Xyz));
+        assert_eq!(points.srs_id, 4326);
+        assert_eq!(points.extent, Some((1.0, 2.0, 1.0, 2.0)));
+        assert!(!points.last_change.is_empty());
+
+        let odd = &layers[1];
+        assert_eq!(odd.table_name, "odd");
+        assert_eq!(odd.geometry_type_name, "CIRCULARSTRING");
+        assert_eq!(odd.geometry_type, None);
+        assert_eq!(odd.srs_id, -1);
+        assert_eq!(odd.extent, None);
+        assert_eq!(gpkg.layer_metadata("odd")?.srs_id, -1);
+        Ok(())
+    }
+
+    #[test]
+    fn layer_metadata_matches_layer() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            not_null: true,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["alpha"])?;
+        gpkg.conn.execute(
+            "UPDATE gpkg_contents SET identifier = 'Points', description = 'some points' WHERE table_name = 'points'",
+            [],
+        )?;
+
+        let metadata = gpkg.layer_metadata("points")?;
+        assert_eq!(metadata.primary_key_column, "fid");
+        assert_eq!(metadata.geometry_column, "geom");
+        assert_eq!(metadata.geometry_type, Some(GeometryType::Point));
+        assert_eq!(metadata.geometry_dimension, Some(Dimension::Xy));
+        assert_eq!(metadata.srs_id, 4326);
+        assert_eq!(metadata.other_columns.len(), 1);
+        assert_eq!(metadata.other_columns[0].name, "name");
+        assert_eq!(metadata.other_columns[0].column_type, ColumnType::Varchar);
+        assert!(metadata.other_columns[0].not_null);
+        assert_eq!(metadata.identifier.as_deref(), Some("Points"));
+        assert_eq!(metadata.description.as_deref(), Some("some points"));
+        assert_eq!(metadata.extent, Some((1.0, 2.0, 1.0, 2.0)));
+        assert!(!metadata.last_change.is_empty());
+
+        let from_layer = layer.metadata()?;
+        assert_eq!(from_layer.primary_key_column, metadata.primary_key_column);
+        assert_eq!(from_layer.other_columns.len(), 1);
+        assert_eq!(from_layer.identifier, metadata.identifier);
+        assert_eq!(from_layer.last_change, metadata.last_change);
+        assert_eq!(from_layer.extent, metadata.extent);
+
+        gpkg.create_attribute_table("codes", &columns)?;
+        assert!(matches!(
+            gpkg.layer_metadata("codes"),
+            Err(GpkgError::NotAFeatureLayer { .. })
+        ));
+        assert!(gpkg.layer_metadata("missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn layer_identifier_and_description_persist() -> Result<(), GpkgError> {
+        let mut path = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        path.push(format!("rusqlite_gpkg_identifier_{nanos}.gpkg"));
+
+        {
+            let gpkg = Gpkg::open(&path)?;
+            let options = CreateLayerOptions {
+                identifier: Some("Sample points".to_string()),
+                description: Some("points sampled in 2024".to_string()),
+                ..Default::default()
+            };
+            let points = gpkg.create_layer_with_options(
+                "points",
+                "geom",
+                GeometryType::Point,
+                Dimension::Xy,
+                4326,
+                &[],
+                &options,
+            )?;
+            assert_eq!(points.identifier()?.as_deref(), Some("Sample points"));
+            assert_eq!(
+                points.description()?.as_deref(),
+                Some("points sampled in 2024")
+            );
+
+            let lines = gpkg.create_layer(
+                "lines",
+                "geom",
+                GeometryType::LineString,
+                Dimension::Xy,
+                4326,
+                &[],
+            )?;
+            assert_eq!(lines.identifier()?.as_deref(), Some("lines"));
+            assert_eq!(lines.description()?.as_deref(), Some(""));
+
+            let err = lines.set_identifier("Sample points").unwrap_err();
+            assert!(matches!(
+                &err,
+                GpkgError::DuplicateIdentifier { identifier, layer_name }
+                    if identifier == "Sample points" && layer_name == "points"
+            ));
+            let options = CreateLayerOptions {
+                identifier: Some("lines".to_string()),
+                ..Default::default()
+            };
+            let err = gpkg
+                .create_layer_with_options(
+                    "polygons",
+                    "geom",
+                    GeometryType::Polygon,
+                    Dimension::Xy,
+                    4326,
+                    &[],
+                    &options,
+                )
+                .expect_err("duplicate identifier");
+            assert!(matches!(
+                err,
+                GpkgError::DuplicateIdentifier { layer_name, .. } if layer_name == "lines"
+            ));
+            assert!(!gpkg.list_layers()?.contains(&"polygons".to_string()));
+
+            // Setting the layer's own identifier again is not a conflict.
+            points.set_identifier("Sample points")?;
+            lines.set_identifier("Sample lines")?;
+            lines.set_description("some lines")?;
+        }
+
+        let reopened = Gpkg::open_read_only(&path)?;
+        let lines = reopened.get_layer("lines")?;
+        assert_eq!(lines.identifier()?.as_deref(), Some("Sample lines"));
+        assert_eq!(lines.description()?.as_deref(), Some("some lines"));
+        let points = reopened.get_layer("points")?;
+        assert_eq!(points.identifier()?.as_deref(), Some("Sample points"));
+        assert!(matches!(
+            points.set_description("read only"),
+            Err(GpkgError::ReadOnly)
+        ));
+        drop(reopened);
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn metadata_roundtrips_and_links_to_layers() -> Result<(), GpkgError> {
+        const ISO_19139: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gmd:MD_Metadata xmlns:gmd="http://www.isotc211.org/2005/gmd" xmlns:gco="http://www.isotc211.org/2005/gco">
+  <gmd:fileIdentifier><gco:CharacterString>points-2024</gco:CharacterString></gmd:fileIdentifier>
+  <gmd:abstract><gco:CharacterString>Points &amp; lines "sampled" in 2024</gco:CharacterString></gmd:abstract>
+</gmd:MD_Metadata>"#;
+        const ISO_URI: &str = "http://schemas.opengis.net/iso/19139/";
+
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        assert!(layer.metadata_entries()?.is_empty());
+        assert!(gpkg.metadata_entries()?.is_empty());
+        assert!(matches!(
+            gpkg.link_metadata_to_layer(1, "points"),
+            Err(GpkgError::MetadataNotFound { id: 1 })
+        ));
+
+        let dataset_id = gpkg.add_metadata("dataset", ISO_URI, "text/xml", ISO_19139)?;
+        let file_id = gpkg.add_metadata("series", ISO_URI, "text/xml", "<gmd:MD_Metadata/>")?;
+        gpkg.link_metadata_to_layer(dataset_id, "points")?;
+        gpkg.link_metadata_to_layer(dataset_id, "points")?;
+        gpkg.link_metadata_to_geopackage(file_id)?;
+
+        let entries = layer.metadata_entries()?;
+        assert_eq!(
+            entries,
+            vec![MetadataEntry {
+                id: dataset_id,
+                scope: "dataset".to_string(),
+                standard_uri: ISO_URI.to_string(),
+                mime_type: "text/xml".to_string(),
+                metadata: ISO_19139.to_string(),
+            }]
+        );
+        let file_entries = gpkg.metadata_entries()?;
+        assert_eq!(file_entries.len(), 1);
+        assert_eq!(file_entries[0].id, file_id);
+        assert_eq!(file_entries[0].scope, "series");
+
+        let extension_rows: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM gpkg_extensions WHERE extension_name = 'gpkg_metadata'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(extension_rows, 2);
+
+        assert!(matches!(
+            gpkg.link_metadata_to_layer(dataset_id, "missing"),
+            Err(GpkgError::LayerNotFound { layer_name }) if layer_name == "missing"
+        ));
+        assert!(matches!(
+            gpkg.link_metadata_to_geopackage(999),
+            Err(GpkgError::MetadataNotFound { id: 999 })
+        ));
+
+        gpkg.rename_layer("points", "samples")?;
+        let renamed = gpkg.get_layer("samples")?;
+        assert_eq!(renamed.metadata_entries()?, entries);
+
+        gpkg.delete_layer("samples")?;
+        let references: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM gpkg_metadata_reference WHERE table_name IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(references, 0);
+        assert_eq!(gpkg.metadata_entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn creates_layer_with_custom_primary_key() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let options = CreateLayerOptions {
+            primary_key_column: "id".to_string(),
+            autoincrement: false,
+            ..Default::default()
+        };
+        let layer = gpkg.create_layer_with_options(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+            &options,
+        )?;
+        assert_eq!(layer.primary_key_column, "id");
+        layer.insert(Point::new(1.0, 2.0), params![1])?;
+        layer.insert(Point::new(3.0, 4.0), params![2])?;
+
+        let has_sequence: i64 = gpkg.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'sqlite_sequence')",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(has_sequence, 0);
+
+        let reopened = gpkg.get_layer("points")?;
+        assert_eq!(reopened.primary_key_column, "id");
+        let features = reopened.features()?;
+        assert_eq!(
+            features.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        // The rtree triggers use the custom primary key column.
+        let in_bbox = reopened.features_in_bbox(2.5, 3.5, 3.5, 4.5)?;
+        assert_eq!(in_bbox.len(), 1);
+        assert_eq!(in_bbox[0].id(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_layer_backed_by_view() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+        let points = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        points.insert(Point::new(1.0, 2.0), params!["a", 1])?;
+        points.insert(Point::new(3.0, 4.0), params!["b", 2])?;
+        points.insert(Point::new(5.0, 6.0), params!["c", 3])?;
+
+        gpkg.conn.execute_batch(
+            r#"
+CREATE VIEW big_points AS
+  SELECT fid, geom, name, value * 10 AS scaled FROM points WHERE value > 1;
+CREATE VIEW renamed_points AS SELECT fid AS point_id, geom FROM points;
+INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id)
+  VALUES ('big_points', 'features', 'big_points', 4326),
+         ('renamed_points', 'features', 'renamed_points', 4326);
+INSERT INTO gpkg_geometry_columns (table_name, column_name, geometry_type_name, srs_id, z, m)
+  VALUES ('big_points', 'geom', 'POINT', 4326, 0, 0),
+         ('renamed_points', 'geom', 'POINT', 4326, 0, 0);
+"#,
+        )?;
+
+        let layer = gpkg.get_layer("big_points")?;
+        assert_eq!(layer.primary_key_column, "fid");
+        let columns: Vec<_> = layer
+            .property_columns
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.column_type))
+            .collect();
+        assert_eq!(
+            columns,
+            [("name", ColumnType::Varchar), ("scaled", ColumnType::Blob)]
+        );
+        let features = layer.features()?;
+        assert_eq!(
+            features.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(features[0].property("scaled"), Some(Value::Integer(20)));
+        assert_eq!(features[1].geometry()?.geometry_type(), GeometryType::Point);
+        assert!(matches!(
+            layer.insert(Point::new(0.0, 0.0), params!["d", 0]),
+            Err(GpkgError::ReadOnly)
+        ));
+        assert_eq!(gpkg.layer_metadata("big_points")?.primary_key_column, "fid");
+
+        assert!(matches!(
+            gpkg.get_layer("renamed_points"),
+            Err(GpkgError::MissingPrimaryKeyColumn { .. })
+        ));
+        let layer = gpkg.get_layer_with_id_column("renamed_points", "point_id")?;
+        assert_eq!(layer.features()?.len(), 3);
+        assert!(matches!(layer.delete(1), Err(GpkgError::ReadOnly)));
+
+        // For a table, the id column must be the primary key.
+        assert!(matches!(
+            gpkg.get_layer_with_id_column("points", "name"),
+            Err(GpkgError::InvalidIdColumn { column, .. }) if column == "name"
+        ));
+        assert_eq!(
+            gpkg.get_layer_with_id_column("points", "fid")?
+                .features()?
+                .len(),
+            3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn creates_and_deletes_view_layer() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let points = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        points.insert(Point::new(1.0, 2.0), params![1])?;
+        points.insert(Point::new(3.0, 4.0), params![2])?;
+        points.insert(Point::new(5.0, 6.0), params![3])?;
+
+        let layer = gpkg.create_view_layer(
+            "big_points",
+            "SELECT fid, geom, value FROM points WHERE value > 1",
+            "geom",
+            "fid",
+        )?;
+        assert_eq!(layer.geometry_type_name, "POINT");
+        assert_eq!(layer.geometry_dimension, Dimension::Xy);
+        assert_eq!(layer.srs_id, 4326);
+        assert_eq!(layer.features()?.len(), 2);
+        assert!(matches!(
+            layer.insert(Point::new(0.0, 0.0), params![4]),
+            Err(GpkgError::ReadOnly)
+        ));
+        assert_eq!(gpkg.get_layer("big_points")?.features()?.len(), 2);
+
+        // A geometry computed by an expression has no declared type, and an
+        // empty view has no geometry to take the srs_id from.
+        let layer = gpkg.create_view_layer(
+            "shapes",
+            "SELECT fid AS id, CAST(geom AS BLOB) AS shape FROM points WHERE value > 10",
+            "shape",
+            "id",
+        )?;
+        assert_eq!(layer.geometry_type_name, "GEOMETRY");
+        assert_eq!(layer.srs_id, 0);
+        assert!(layer.features()?.is_empty());
+
+        assert!(matches!(
+            gpkg.create_view_layer("points", "SELECT fid, geom FROM points", "geom", "fid"),
+            Err(GpkgError::LayerAlreadyExists { .. })
+        ));
+        assert!(matches!(
+            gpkg.create_view_layer(
+                "values_only",
+                "SELECT fid, value FROM points",
+                "geom",
+                "fid"
+            ),
+            Err(GpkgError::MissingGeometryColumn { .. })
+        ));
+        assert!(matches!(
+            gpkg.create_view_layer("no_id", "SELECT geom FROM points", "geom", "fid"),
+            Err(GpkgError::InvalidIdColumn { .. })
+        ));
+
+        gpkg.delete_layer("big_points")?;
+        gpkg.delete_layer("shapes")?;
+        assert_eq!(gpkg.list_layers()?, vec!["points".to_string()]);
+        let views: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'view'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(views, 0);
+        // The spatial index of the underlying layer is untouched.
+        assert_eq!(points.features_in_bbox(2.5, 3.5, 3.5, 4.5)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn renames_layer() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![1])?;
+        layer.insert(Point::new(3.0, 4.0), params![2])?;
+        drop(layer);
+        gpkg.create_layer(
+            "lines",
+            "geom",
+            GeometryType::LineString,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let err = gpkg
+            .rename_layer("points", "lines")
+            .expect_err("target exists");
+        assert!(matches!(err, GpkgError::LayerAlreadyExists { .. }));
+
+        gpkg.rename_layer("points", "places")?;
+
+        let mut layers = gpkg.list_layers()?;
+        layers.sort();
+        assert_eq!(layers, vec!["lines".to_string(), "places".to_string()]);
+
+        let layer = gpkg.get_layer("places")?;
+        assert_eq!(layer.count()?, 2);
+        let features = layer.features_in_bbox(0.0, 0.0, 2.0, 2.0)?;
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].id(), 1);
+
+        let rtree_rows: i64 =
+            gpkg.conn
+                .query_row("SELECT COUNT(*) FROM rtree_places_geom", [], |row| {
+                    row.get(0)
+                })?;
+        assert_eq!(rtree_rows, 2);
+        let extensions: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM gpkg_extensions WHERE table_name = 'places'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(extensions, 1);
+        let old_objects: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name LIKE '%points%'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(old_objects, 0);
+
+        // The triggers now maintain the renamed rtree.
+        layer.insert(Point::new(1.5, 1.5), params![3])?;
+        assert_eq!(layer.features_in_bbox(0.0, 0.0, 2.0, 2.0)?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn copies_layer() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xyzm,
+            4326,
+            &columns,
+        )?;
+        layer.insert_unchecked(Point::new(0.0, 0.0), params!["placeholder".to_string()])?;
+        layer.insert_unchecked(Point::new(1.0, 2.0), params!["a".to_string()])?;
+        layer.insert_unchecked(Point::new(3.0, 4.0), params!["b".to_string()])?;
+        // Leave a gap in the primary keys to check they are preserved.
+        layer.delete(1)?;
+
+        let err = gpkg
+            .copy_layer("points", "points")
+            .expect_err("target exists");
+        assert!(matches!(err, GpkgError::LayerAlreadyExists { .. }));
+
+        let copy = gpkg.copy_layer("points", "points_copy")?;
+        assert_eq!(copy.geometry_type, GeometryType::Point);
+        assert_eq!(copy.geometry_dimension, Dimension::Xyzm);
+        assert_eq!(copy.srs_id, 4326);
+        assert_eq!(copy.property_columns.len(), 1);
+
+        let features = copy.features()?;
+        assert_eq!(
+            features.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        let name: String = features[1]
+            .property("name")
+            .ok_or_else(|| GpkgError::MissingProperty {
+                property: "name".to_string(),
+            })?
+            .try_into()?;
+        assert_eq!(name, "b");
+
+        let in_bbox = copy.features_in_bbox(2.5, 3.5, 3.5, 4.5)?;
+        assert_eq!(in_bbox.len(), 1);
+        assert_eq!(in_bbox[0].id(), 3);
+        assert_eq!(copy.extent()?, Some((1.0, 2.0, 3.0, 4.0)));
+
+        // The source layer is untouched.
+        assert_eq!(layer.count()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn runs_raw_sql_queries_and_statements() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "region".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 0.0), params!["north"])?;
+        layer.insert(Point::new(5.0, 0.0), params!["south"])?;
+        layer.insert(Point::new(3.0, 0.0), params!["north"])?;
+
+        // The spatial SQL functions are available.
+        let rows = gpkg.query(
+            r#"SELECT "region", count(*), max(ST_MinX(geom)) FROM points WHERE fid > ?1 GROUP BY "region" ORDER BY "region""#,
+            params![0],
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::from("north"), Value::Integer(2), Value::Real(3.0)],
+                vec![Value::from("south"), Value::Integer(1), Value::Real(5.0)],
+            ]
+        );
+
+        let mut geometries = 0;
+        gpkg.query_each("SELECT geom, region FROM points", params![], |row| {
+            assert!(row[0].is_gpkg_geometry());
+            assert!(!row[1].is_gpkg_geometry());
+            geometries += 1;
+            Ok(())
+        })?;
+        assert_eq!(geometries, 3);
+
+        let changed = gpkg.execute(
+            r#"UPDATE points SET "region" = ?1 WHERE "region" = ?2"#,
+            params!["east", "south"],
+        )?;
+        assert_eq!(changed, 1);
+        assert_eq!(layer.distinct_values("region", None)?.len(), 2);
+
+        let bytes = gpkg.to_bytes()?;
+        let read_only = Gpkg::from_bytes_read_only(bytes)?;
+        assert_eq!(
+            read_only
+                .query("SELECT count(*) FROM points", params![])?
+                .len(),
+            1
+        );
+        let err = read_only
+            .execute("DELETE FROM points", params![])
+            .expect_err("read-only");
+        assert!(matches!(err, GpkgError::ReadOnly), "{err}");
+        let err = read_only
+            .query("DELETE FROM points RETURNING fid", params![])
+            .expect_err("read-only");
+        assert!(matches!(err, GpkgError::ReadOnly), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn creates_layer_like_template_from_another_gpkg() -> Result<(), GpkgError> {
+        let src = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let template = src.get_layer("points")?;
+        let template_count = template.count()?;
+
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer_like(&template, "points_north")?;
+        assert_eq!(layer.layer_name, "points_north");
+        assert_eq!(layer.geometry_column, template.geometry_column);
+        assert_eq!(layer.primary_key_column, template.primary_key_column);
+        assert_eq!(layer.geometry_type, template.geometry_type);
+        assert_eq!(layer.geometry_dimension, template.geometry_dimension);
+        assert_eq!(layer.srs_id, template.srs_id);
+        assert_eq!(
+            format!("{:?}", layer.property_columns),
+            format!("{:?}", template.property_columns)
+        );
+        assert_eq!(layer.count()?, 0);
+
+        let feature = template.get_feature(1)?.expect("template feature");
+        layer.insert(Point::new(1.0, 2.0), feature.properties())?;
+        assert_eq!(layer.count()?, 1);
+        assert_eq!(
+            gpkg.get_layer("points_north")?.features()?[0].properties(),
+            feature.properties()
+        );
+        assert_eq!(template.count()?, template_count);
+
+        let err = gpkg
+            .create_layer_like(&template, "points_north")
+            .expect_err("layer exists");
+        assert!(matches!(err, GpkgError::LayerAlreadyExists { .. }), "{err}");
+        let err = src
+            .create_layer_like(&template, "points_copy")
+            .expect_err("read-only");
+        assert!(matches!(err, GpkgError::ReadOnly), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn create_layer_like_copies_missing_srs_and_zm_flags() -> Result<(), GpkgError> {
+        let src = Gpkg::open_in_memory()?;
+        src.enable_crs_wkt_extension()?;
+        let srs = SpatialRefSys {
+            srs_name: "Local grid".to_string(),
+            srs_id: 990_001,
+            organization: "local".to_string(),
+            organization_coordsys_id: 1,
+            definition: "LOCAL_CS[\"grid\"]".to_string(),
+            description: Some("site grid".to_string()),
+            definition_12_063: Some("ENGCRS[\"grid\"]".to_string()),
+        };
+        src.register_srs_wkt2(&srs)?;
+        src.create_layer(
+            "wells",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xyz,
+            990_001,
+            &[],
+        )?;
+        src.conn.execute(
+            "UPDATE gpkg_geometry_columns SET z = 2 WHERE table_name = 'wells'",
+            [],
+        )?;
+        let template = src.get_layer("wells")?;
+
+        let gpkg = Gpkg::open_in_memory()?;
+        assert!(!gpkg.srs_exists(990_001)?);
+        let layer = gpkg.create_layer_like(&template, "wells_copy")?;
+        assert_eq!(gpkg.get_srs(990_001)?, Some(srs));
+        assert_eq!(layer.z_flag, template.z_flag);
+        let reopened = gpkg.get_layer("wells_copy")?;
+        assert_eq!(reopened.z_flag, template.z_flag);
+        assert_eq!(reopened.srs_id, 990_001);
+
+        // The SRS is only copied once.
+        gpkg.create_layer_like(&template, "wells_copy2")?;
+        assert_eq!(gpkg.list_srs()?.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn copies_layer_with_progress_and_cancels() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &Vec::<ColumnSpec>::new(),
+        )?;
+        layer.insert_many((0..1000).map(|i| (Point::new(i as f64, 0.0), Vec::<Value>::new())))?;
+        // Leave a gap in the primary keys across a chunk boundary.
+        layer.delete(100)?;
+        layer.delete(101)?;
 
-        let column_specs = stmt.query_map([], |row| {
-            let name: String = row.get(0)?;
-            let column_type_str: String = row.get(1)?;
-            let primary_key: i32 = row.get(2)?;
-            let primary_key = primary_key != 0;
-            Ok((name, column_type_str, primary_key))
+        let mut calls = Vec::new();
+        let copy = gpkg.copy_layer_with_progress("points", "points_copy", 400, |done| {
+            calls.push(done);
+            std::ops::ControlFlow::Continue(())
         })?;
+        assert_eq!(calls, vec![400, 800, 998]);
+        assert_eq!(copy.count()?, 998);
+        assert_eq!(copy.extent()?, Some((0.0, 0.0, 999.0, 0.0)));
+        assert_eq!(copy.features_in_bbox(98.5, -1.0, 100.5, 1.0)?.len(), 0);
 
-        let result: std::result::Result<Vec<(String, String, bool)>, _> = column_specs.collect();
-        let mut primary_key_column: Option<String> = None;
-        let mut other_columns = Vec::new();
-        for (name, column_type_str, is_primary_key) in result? {
-            let column_type = crate::conversions::column_type_from_str(&column_type_str)
-                .ok_or_else(|| GpkgError::UnsupportedColumnType {
-                    column: name.clone(),
-                    declared_type: column_type_str,
-                })?;
-
-            if is_primary_key {
-                if primary_key_column.is_some() {
-                    return Err(GpkgError::CompositePrimaryKeyUnsupported {
-                        layer_name: table_name.to_string(),
-                    });
+        let err = gpkg
+            .copy_layer_with_progress("points", "points_cancelled", 10, |done| {
+                if done >= 100 {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
                 }
-                primary_key_column = Some(name);
-                continue;
-            }
-            if column_type == crate::types::ColumnType::Geometry {
-                return Err(GpkgError::GeometryColumnInAttributeTable {
-                    column: name,
-                });
-            }
-            other_columns.push(ColumnSpec { name, column_type });
-        }
+            })
+            .expect_err("cancelled");
+        assert!(matches!(err, GpkgError::Cancelled), "{err}");
+        assert!(
+            !gpkg
+                .list_layers()?
+                .contains(&"points_cancelled".to_string())
+        );
+        Ok(())
+    }
 
-        let primary_key_column =
-            primary_key_column.ok_or_else(|| GpkgError::MissingPrimaryKeyColumn {
-                layer_name: table_name.to_string(),
-            })?;
+    #[test]
+    fn preserves_narrow_column_types() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = [
+            ("tiny", ColumnType::TinyInt),
+            ("small", ColumnType::SmallInt),
+            ("medium", ColumnType::MediumInt),
+            ("ratio", ColumnType::Float),
+        ]
+        .into_iter()
+        .map(|(name, column_type)| ColumnSpec {
+            name: name.to_string(),
+            column_type,
+            ..Default::default()
+        })
+        .collect();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(
+            Point::new(0.0, 0.0),
+            params![-8_i64, 300_i64, 70000_i64, 0.5],
+        )?;
 
-        Ok((primary_key_column, other_columns))
-    }
+        let declared_types = |table: &str| -> Result<Vec<String>, GpkgError> {
+            let mut stmt = gpkg.conn.prepare(&format!(
+                r#"SELECT type FROM pragma_table_info('{table}') WHERE name NOT IN ('fid', 'geom')"#
+            ))?;
+            let types = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok(types)
+        };
+        let expected = vec!["TINYINT", "SMALLINT", "MEDIUMINT", "FLOAT"];
+        assert_eq!(declared_types("points")?, expected);
 
-    /// Resolve the table columns and map SQLite types.
-    pub(crate) fn get_column_specs(
-        &self,
-        layer_name: &str,
-        geometry_column: &str,
-        geometry_type: wkb::reader::GeometryType,
-        geometry_dimension: wkb::reader::Dimension,
-        srs_id: u32,
-    ) -> Result<GpkgLayerMetadata> {
-        let query = sql_table_columns(layer_name);
-        let mut stmt = self.conn.prepare(&query)?;
+        let reloaded = gpkg.get_layer("points")?;
+        assert_eq!(
+            reloaded
+                .property_columns
+                .iter()
+                .map(|c| c.column_type)
+                .collect::<Vec<_>>(),
+            columns.iter().map(|c| c.column_type).collect::<Vec<_>>()
+        );
 
-        let mut primary_key_column: Option<String> = None;
-        let mut geometry_column_name: Option<String> = None;
-        let column_specs = stmt.query_map([], |row| {
-            let name: String = row.get(0)?;
-            let column_type_str: String = row.get(1)?;
-            let primary_key: i32 = row.get(2)?;
-            let primary_key = primary_key != 0;
+        // Copying a layer must not widen the declared types.
+        gpkg.copy_layer("points", "points_copy")?;
+        assert_eq!(declared_types("points_copy")?, expected);
 
-            Ok((name, column_type_str, primary_key))
-        })?;
+        let feature = &reloaded.features()?[0];
+        let small: i64 = feature.property("small").unwrap().try_into()?;
+        let ratio: f64 = feature.property("ratio").unwrap().try_into()?;
+        assert_eq!(small, 300);
+        assert_eq!(ratio, 0.5);
+        Ok(())
+    }
 
-        let result: std::result::Result<Vec<(String, String, bool)>, _> = column_specs.collect();
-        let mut other_columns = Vec::new();
-        for (name, column_type_str, is_primary_key) in result? {
-            // cf. https://www.geopackage.org/spec140/index.html#_sqlite_container
-            let column_type = column_type_from_str(&column_type_str).ok_or_else(|| {
-                GpkgError::UnsupportedColumnType {
-                    column: name.clone(),
-                    declared_type: column_type_str,
-                }
-            })?;
+    #[test]
+    fn creates_and_reads_column_constraints() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                not_null: true,
+                default: Some(Value::from("it's unknown")),
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "code".to_string(),
+                column_type: ColumnType::Integer,
+                unique: true,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "ratio".to_string(),
+                column_type: ColumnType::Double,
+                default: Some(Value::Real(1.0)),
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "data".to_string(),
+                column_type: ColumnType::Blob,
+                default: Some(Value::Blob(vec![0x00, 0xff])),
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
 
-            if is_primary_key {
-                if primary_key_column.is_some() {
-                    return Err(GpkgError::CompositePrimaryKeyUnsupported {
-                        layer_name: layer_name.to_string(),
-                    });
-                }
-                primary_key_column = Some(name.clone());
-                continue;
-            }
-            if name == geometry_column {
-                geometry_column_name = Some(name.clone());
-            } else {
-                other_columns.push(ColumnSpec { name, column_type });
-            }
-        }
+        let reloaded = gpkg.get_layer("points")?;
+        let specs = &reloaded.property_columns;
+        assert!(specs[0].not_null && !specs[0].unique);
+        assert_eq!(specs[0].default, Some(Value::from("it's unknown")));
+        assert!(!specs[1].not_null && specs[1].unique);
+        assert_eq!(specs[1].default, None);
+        assert_eq!(specs[2].default, Some(Value::Real(1.0)));
+        assert_eq!(specs[3].default, Some(Value::Blob(vec![0x00, 0xff])));
 
-        let primary_key_column =
-            primary_key_column.ok_or_else(|| GpkgError::MissingPrimaryKeyColumn {
-                layer_name: layer_name.to_string(),
-            })?;
+        // Defaults are applied to omitted columns.
+        gpkg.conn
+            .execute(r#"INSERT INTO points (geom, code) VALUES (NULL, 1)"#, [])?;
+        let feature = &layer.features()?[0];
+        assert_eq!(feature.property("name"), Some(Value::from("it's unknown")));
+        assert_eq!(feature.property("ratio"), Some(Value::Real(1.0)));
 
-        let geometry_column =
-            geometry_column_name.ok_or_else(|| GpkgError::MissingGeometryColumn {
-                layer_name: layer_name.to_string(),
-            })?;
+        let err = layer
+            .insert(
+                Point::new(0.0, 0.0),
+                params![Value::Null, 2, 0.5, Value::Null],
+            )
+            .expect_err("NOT NULL violation");
+        assert!(matches!(
+            err,
+            GpkgError::Sql(rusqlite::Error::SqliteFailure(e, _))
+                if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_NOTNULL
+        ));
+        let err = layer
+            .insert(Point::new(0.0, 0.0), params!["a", 1, 0.5, Value::Null])
+            .expect_err("UNIQUE violation");
+        assert!(matches!(
+            err,
+            GpkgError::Sql(rusqlite::Error::SqliteFailure(e, _))
+                if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+        ));
 
-        Ok(GpkgLayerMetadata {
-            primary_key_column,
-            geometry_column,
-            geometry_type,
-            geometry_dimension,
-            srs_id,
-            other_columns,
-        })
+        // Attribute tables keep their constraints too.
+        gpkg.create_attribute_table("attrs", &columns)?;
+        let table = gpkg.get_attribute_table("attrs")?;
+        assert!(table.property_columns[0].not_null);
+        assert!(table.property_columns[1].unique);
+        Ok(())
     }
 
-    /// Resolve the geometry column metadata and SRS information for a layer.
-    pub(crate) fn get_geometry_column_and_srs_id(
-        &self,
-        layer_name: &str,
-    ) -> Result<(
-        String,
-        wkb::reader::GeometryType,
-        wkb::reader::Dimension,
-        u32,
-    )> {
-        let mut stmt = self.conn.prepare(SQL_SELECT_GEOMETRY_COLUMN_META)?;
+    #[test]
+    fn rename_layer_rejects_read_only() {
+        let gpkg =
+            Gpkg::open_read_only("src/test/test_generated.gpkg").expect("open read-only gpkg");
+        let err = gpkg
+            .rename_layer("points", "places")
+            .expect_err("read-only should fail");
+        assert!(matches!(err, GpkgError::ReadOnly));
+    }
 
-        let (geometry_column, geometry_type_str, z, m, srs_id) =
-            stmt.query_one([layer_name], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, i8>(2)?,
-                    row.get::<_, i8>(3)?,
-                    row.get::<_, u32>(4)?,
-                ))
-            })?;
+    #[test]
+    fn with_transaction_commits_all_inserts() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
 
-        let geometry_type = geometry_type_from_str(&geometry_type_str)?;
-        let geometry_dimension = dimension_from_zm(z, m)?;
+        gpkg.with_transaction(|_| {
+            for i in 0..1000 {
+                layer.insert(Point::new(i as f64, i as f64), params![i])?;
+            }
+            Ok(())
+        })?;
 
-        Ok((geometry_column, geometry_type, geometry_dimension, srs_id))
+        assert_eq!(layer.features()?.len(), 1000);
+        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::Gpkg;
-    use crate::error::GpkgError;
-    use crate::params;
-    use crate::types::{ColumnSpec, ColumnType};
-    use geo_types::Point;
-    use std::fs;
-    use std::time::{SystemTime, UNIX_EPOCH};
-    use wkb::reader::{Dimension, GeometryType};
 
     #[test]
-    fn create_layer_requires_existing_srs() {
-        let gpkg = Gpkg::open_in_memory().expect("new gpkg");
-        let columns: Vec<ColumnSpec> = Vec::new();
-        let err = gpkg
-            .create_layer(
-                "missing_srs",
-                "geom",
-                wkb::reader::GeometryType::Point,
-                wkb::reader::Dimension::Xy,
-                9999,
-                &columns,
-            )
-            .expect_err("missing srs should fail");
+    fn with_transaction_rolls_back_on_error() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
 
+        let result: Result<(), GpkgError> = gpkg.with_transaction(|_| {
+            layer.insert(Point::new(1.0, 2.0), params![1])?;
+            // Wrong number of properties
+            layer.insert(Point::new(3.0, 4.0), params![2, 3])?;
+            Ok(())
+        });
         assert!(matches!(
-            err,
-            GpkgError::MissingSpatialRefSysId { srs_id: 9999 }
+            result,
+            Err(GpkgError::InvalidPropertyCount { .. })
         ));
+
+        assert!(layer.features()?.is_empty());
+        Ok(())
     }
 
     #[test]
-    fn delete_layer_rejects_read_only() {
+    fn with_transaction_rejects_read_only() {
         let gpkg =
             Gpkg::open_read_only("src/test/test_generated.gpkg").expect("open read-only gpkg");
         let err = gpkg
-            .delete_layer("points")
+            .with_transaction(|_| Ok(()))
             .expect_err("read-only should fail");
         assert!(matches!(err, GpkgError::ReadOnly));
     }
@@ -870,10 +4534,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
         let layer = gpkg.create_layer(
@@ -947,6 +4613,97 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn from_bytes_read_only_reads_dump_without_copying() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["alpha"])?;
+        layer.insert(Point::new(-3.0, 4.5), params!["beta"])?;
+
+        let dump: std::sync::Arc<[u8]> = gpkg.to_bytes()?.into();
+        let borrowed = Gpkg::from_bytes_read_only(dump.clone())?;
+        let borrowed_layer = borrowed.get_layer("points")?;
+
+        let expected = layer.features()?;
+        let actual = borrowed_layer.features()?;
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(&expected) {
+            assert_eq!(actual.id(), expected.id());
+            assert_eq!(actual.properties(), expected.properties());
+            assert_eq!(actual.geometry()?.buf(), expected.geometry()?.buf());
+        }
+        assert_eq!(
+            borrowed_layer.features_in_bbox(0.0, 0.0, 2.0, 3.0)?.len(),
+            1
+        );
+
+        let err = borrowed_layer
+            .insert(Point::new(0.0, 0.0), params!["gamma"])
+            .expect_err("read-only");
+        assert!(matches!(err, GpkgError::ReadOnly), "{err}");
+        assert!(matches!(
+            borrowed.delete_layer("points"),
+            Err(GpkgError::ReadOnly)
+        ));
+
+        // The layer keeps the dump alive after the `Gpkg` is dropped, and the
+        // dump is freed with the connection.
+        drop(borrowed);
+        assert_eq!(std::sync::Arc::strong_count(&dump), 2);
+        assert_eq!(borrowed_layer.count()?, 2);
+        drop(borrowed_layer);
+        assert_eq!(std::sync::Arc::strong_count(&dump), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn from_bytes_read_only_keeps_dump_alive_with_connection() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+        layer.insert(Point::new(-3.0, 4.5), params![])?;
+        let dump: std::sync::Arc<[u8]> = gpkg.to_bytes()?.into();
+
+        // The raw connection isn't handed out, as it could outlive the dump.
+        let borrowed = Gpkg::from_bytes_read_only(dump.clone())?;
+        assert!(matches!(
+            borrowed.into_connection(),
+            Err(GpkgError::DumpInUse)
+        ));
+        assert_eq!(std::sync::Arc::strong_count(&dump), 1);
+
+        // An owned batch iterator outlives the layer and the `Gpkg`.
+        let borrowed = Gpkg::from_bytes_read_only(dump.clone())?;
+        let batches = borrowed.get_layer("points")?.features_batch_owned(1)?;
+        drop(borrowed);
+        assert_eq!(std::sync::Arc::strong_count(&dump), 2);
+        let ids = batches
+            .map(|batch| Ok(batch?.iter().map(|f| f.id()).collect::<Vec<_>>()))
+            .collect::<Result<Vec<_>, GpkgError>>()?;
+        assert_eq!(ids, vec![vec![1], vec![2]]);
+        assert_eq!(std::sync::Arc::strong_count(&dump), 1);
+        Ok(())
+    }
+
     #[test]
     fn dump_roundtrips_in_memory_gpkg_from_bytes() -> Result<(), GpkgError> {
         let gpkg = Gpkg::open_in_memory()?;
@@ -955,10 +4712,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
         let layer = gpkg.create_layer(
@@ -1,14 +1,45 @@
 use crate::Value;
+use crate::conversions::column_type_to_str;
+use crate::conversions::{
+    column_definition_sql, dimension_allowed_by_zm_flags, geometry_type_to_str,
+};
 use crate::error::{GpkgError, Result};
-use crate::ogc_sql::{sql_delete_all, sql_insert_feature, sql_select_features};
-use crate::types::{ColumnSpec, params_from_geom_and_properties};
+use crate::ogc_sql::{
+    SQL_DELETE_DATA_COLUMN_CONSTRAINT, SQL_DELETE_RTREE_EXTENSION, SQL_ENABLE_SCHEMA,
+    SQL_EXPAND_CONTENTS_EXTENT, SQL_INSERT_DATA_COLUMN_CONSTRAINT, SQL_RELEASE_SAVEPOINT,
+    SQL_ROLLBACK_TO_SAVEPOINT, SQL_SAVEPOINT, SQL_SELECT_CONTENTS_EXTENT,
+    SQL_SELECT_CONTENTS_METADATA, SQL_SELECT_DATA_COLUMN, SQL_SELECT_DATA_COLUMN_CONSTRAINT,
+    SQL_SELECT_REFERENCED_METADATA, SQL_SELECT_TABLE_BY_IDENTIFIER, SQL_TABLE_EXISTS,
+    SQL_UPDATE_CONTENTS_DESCRIPTION, SQL_UPDATE_CONTENTS_EXTENT, SQL_UPDATE_CONTENTS_IDENTIFIER,
+    SQL_UPDATE_CONTENTS_LAST_CHANGE, SQL_UPSERT_DATA_COLUMN, execute_rtree_sqls,
+    gpkg_rtree_clear_sql, gpkg_rtree_drop_sql, gpkg_rtree_load_sql, gpkg_rtree_triggers_drop_sql,
+    gpkg_rtree_triggers_sql, sql_add_column, sql_column_stats, sql_compute_extent, sql_count,
+    sql_delete_all, sql_delete_by_id, sql_distinct_values, sql_drop_column, sql_feature_exists,
+    sql_insert_feature, sql_max_primary_key, sql_rtree_bounds_mismatches, sql_rtree_missing_ids,
+    sql_rtree_orphan_ids, sql_select_feature_by_id, sql_select_features,
+    sql_select_features_in_bbox, sql_select_features_ordered, sql_select_geometry_by_id,
+    sql_update_columns, sql_upsert_feature,
+};
+use crate::sql_functions::{Bounds, bounds_from_geometry, is_assignable, merge_bounds};
+use crate::types::{
+    Collation, ColumnSpec, ColumnStats, DataColumnConstraint, DataColumnInfo, FeatureReadOptions,
+    GpkgLayerMetadata, MetadataEntry, SortKey, SortOrder, SpatialIndexReport, SpatialRefSys,
+    UpsertOutcome, ZmFlag, check_value_type, feature_row_error, params_from_geom_and_properties,
+    params_from_properties, value_from_row,
+};
 use geo_traits::GeometryTrait;
-use rusqlite::types::Type;
-use std::collections::HashMap;
+use rusqlite::OptionalExtension;
+use rusqlite::types::ValueRef;
+use std::borrow::Borrow;
+use std::ops::ControlFlow;
 use std::rc::Rc;
 use wkb::reader::Wkb;
 
-use super::{GpkgFeature, wkb_to_gpkg_geometry};
+use super::transform::transform_wkb;
+use super::{
+    CoordTransform, FromFeature, GpkgFeature, GpkgFeatureBuilder, Progress, PropertyIndex,
+    ToFeature, gpkg_geometry_to_wkb, no_progress, set_gpkg_geometry_srs_id, wkb_to_gpkg_geometry,
+};
 
 use crate::GpkgFeatureBatchIterator;
 
@@ -16,17 +47,39 @@ use crate::GpkgFeatureBatchIterator;
 /// A GeoPackage layer with geometry metadata and column specs.
 pub struct GpkgLayer {
     pub(super) conn: Rc<rusqlite::Connection>,
+    /// The dump read by the connection, which must be dropped after it.
+    pub(super) dump: Option<Rc<dyn std::any::Any>>,
     pub(super) is_read_only: bool,
     pub layer_name: String,
     pub geometry_column: String,
     pub primary_key_column: String,
+    /// The geometry type name as stored in `gpkg_geometry_columns`, e.g.
+    /// `POINT` or `CURVEPOLYGON`.
+    pub geometry_type_name: String,
+    /// The geometry type. The types of the non-linear geometry extension
+    /// (e.g. `CURVEPOLYGON`) have no `GeometryType`, and are read as
+    /// `GeometryCollection` like `GEOMETRY`; see `geometry_type_name` for the
+    /// actual type.
     pub geometry_type: wkb::reader::GeometryType,
     pub geometry_dimension: wkb::reader::Dimension,
+    /// Whether the geometries have Z values. When this is
+    /// `ZmFlag::Optional`, `geometry_dimension` includes Z and the layer
+    /// accepts geometries with and without Z values.
+    pub z_flag: ZmFlag,
+    /// Whether the geometries have M values, like `z_flag`.
+    pub m_flag: ZmFlag,
     pub srs_id: u32,
     pub property_columns: Vec<ColumnSpec>,
-    pub(super) property_index_by_name: Rc<HashMap<String, usize>>,
+    pub(super) property_index_by_name: Rc<PropertyIndex>,
     pub(super) insert_sql: String,
+    pub(super) insert_with_id_sql: String,
     pub(super) update_sql: String,
+    pub(super) update_properties_sql: String,
+    pub(super) update_geometry_sql: String,
+    pub(super) upsert_sql: String,
+    pub(super) strict_types: bool,
+    pub(super) write_envelope: bool,
+    pub(super) write_transform: Option<CoordTransform>,
 }
 
 // When issueing the SELECT query, always place these columns first so that
@@ -34,6 +87,9 @@ pub struct GpkgLayer {
 const GEOMETRY_INDEX: usize = 0;
 const PRIMARY_INDEX: usize = 1;
 
+// The number of features `features_in_bbox_iter()` reads per query.
+const BBOX_BATCH_SIZE: u32 = 1000;
+
 impl GpkgLayer {
     /// Return all the features in the layer.
     ///
@@ -55,25 +111,54 @@ impl GpkgLayer {
     /// I was hoping we could avoid allocation here, but it seems rusqlite's
     /// API requires allocation.
     pub fn features(&self) -> Result<Vec<GpkgFeature>> {
-        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        self.features_with_options(&FeatureReadOptions::default())
+    }
+
+    /// Return all the features in the layer, read as `options` specifies.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{FeatureReadOptions, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let options = FeatureReadOptions {
+    ///     skip_properties: true,
+    /// };
+    /// for feature in layer.features_with_options(&options)? {
+    ///     let _geom = feature.geometry_opt()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_with_options(&self, options: &FeatureReadOptions) -> Result<Vec<GpkgFeature>> {
+        let (property_columns, property_index_by_name) = if options.skip_properties {
+            (&[][..], Rc::new(PropertyIndex::default()))
+        } else {
+            (
+                self.property_columns.as_slice(),
+                self.property_index_by_name.clone(),
+            )
+        };
+        let columns = property_columns.iter().map(|spec| spec.name.as_str());
         let sql = sql_select_features(
             &self.layer_name,
             &self.geometry_column,
             &self.primary_key_column,
             columns,
             None,
+            None,
         );
 
-        let sql: &str = &sql;
-        let mut stmt = self.conn.prepare(sql)?;
+        let mut stmt = self.conn.prepare_cached(&sql)?;
         let features = stmt
             .query_map([], |row| {
                 row_to_feature(
                     row,
-                    &self.property_columns,
+                    &self.layer_name,
+                    property_columns,
                     &self.geometry_column,
                     &self.primary_key_column,
-                    &self.property_index_by_name,
+                    &property_index_by_name,
                 )
             })?
             .collect::<rusqlite::Result<Vec<GpkgFeature>>>()?;
@@ -81,12 +166,321 @@ impl GpkgLayer {
         Ok(features)
     }
 
+    /// Return all the features in the layer with `transform` applied to the
+    /// X and Y of each coordinate of their geometries, e.g. to reproject them
+    /// from the layer's SRS.
+    ///
+    /// Z and M values are kept as they are. A geometry with an envelope in
+    /// its header gets the envelope of the transformed coordinates.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let features = layer.features_transformed(|xy: &mut [f64; 2]| {
+    ///     xy[0] /= 111_320.0;
+    ///     xy[1] /= 110_540.0;
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_transformed<F>(&self, transform: F) -> Result<Vec<GpkgFeature>>
+    where
+        F: Fn(&mut [f64; 2]),
+    {
+        let mut features = self.features()?;
+        for feature in &mut features {
+            feature
+                .transform_geometry(&transform)
+                .map_err(|source| GpkgError::Feature {
+                    layer_name: self.layer_name.clone(),
+                    id: Some(feature.id()),
+                    source: Box::new(source),
+                })?;
+        }
+        Ok(features)
+    }
+
+    /// Call `f` for each feature in the layer, in primary key order.
+    ///
+    /// Unlike `features()`, this reads the rows one by one, so only a single
+    /// feature is held in memory at a time. Iteration stops at the first error,
+    /// either from reading a row or returned by `f`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let mut n = 0;
+    /// layer.for_each_feature(|feature| {
+    ///     let _geom = feature.geometry()?;
+    ///     n += 1;
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn for_each_feature<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(GpkgFeature) -> Result<()>,
+    {
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            None,
+            None,
+        );
+
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let feature = row_to_feature(
+                row,
+                &self.layer_name,
+                &self.property_columns,
+                &self.geometry_column,
+                &self.primary_key_column,
+                &self.property_index_by_name,
+            )?;
+            f(feature)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return all the features in the layer converted to `T`.
+    ///
+    /// A conversion error is returned as `GpkgError::Feature` with the id of
+    /// the feature, wrapping the error of `T::from_feature()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{FromFeature, Gpkg, GpkgFeature};
+    ///
+    /// struct Station {
+    ///     name: String,
+    /// }
+    ///
+    /// impl FromFeature for Station {
+    ///     fn from_feature(feature: &GpkgFeature) -> rusqlite_gpkg::Result<Self> {
+    ///         Ok(Self {
+    ///             name: feature.get("name")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let stations: Vec<Station> = gpkg.get_layer("stations")?.features_as()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_as<T: FromFeature>(&self) -> Result<Vec<T>> {
+        let mut out = Vec::new();
+        self.for_each_feature(|feature| {
+            let value = T::from_feature(&feature).map_err(|source| GpkgError::Feature {
+                layer_name: self.layer_name.clone(),
+                id: Some(feature.id()),
+                source: Box::new(source),
+            })?;
+            out.push(value);
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// Write the layer to `w` as a GeoJSON FeatureCollection.
+    ///
+    /// Features are written one at a time, so the whole layer is never held in
+    /// memory. The primary key becomes the feature `id` and a `NULL` geometry
+    /// becomes `"geometry": null`. Coordinates are written as stored, without
+    /// reprojection; the SRS of the layer is written in the (pre-RFC 7946)
+    /// `crs` member, e.g. `urn:ogc:def:crs:EPSG::4326`. Blob properties and
+    /// non-finite numbers are written as `null`, and M values are dropped.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let file = std::fs::File::create("points.geojson")?;
+    /// layer.to_geojson_writer(std::io::BufWriter::new(file))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "geojson")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+    pub fn to_geojson_writer(&self, w: impl std::io::Write) -> Result<()> {
+        crate::geojson::write_feature_collection(self, w)
+    }
+
+    /// Write the layer to `w` as a FlatGeobuf file.
+    ///
+    /// The header carries the layer name, geometry type, dimension, column
+    /// schema, the CRS from `gpkg_spatial_ref_sys`, and the identifier and
+    /// description from `gpkg_contents` as the title and description. NULL
+    /// properties are omitted from the feature, and a `NULL` geometry is
+    /// written as an empty geometry (e.g. a point with NaN coordinates), as
+    /// FlatGeobuf readers don't agree on a missing one. With `spatial_index`,
+    /// the features are sorted along a Hilbert curve and a packed R-tree is
+    /// written after the header.
+    ///
+    /// The features are buffered in a temporary file until the header is
+    /// complete, so the whole layer is never held in memory.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let file = std::fs::File::create("points.fgb")?;
+    /// layer.to_flatgeobuf_writer(std::io::BufWriter::new(file), true)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "flatgeobuf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flatgeobuf")))]
+    pub fn to_flatgeobuf_writer(&self, w: impl std::io::Write, spatial_index: bool) -> Result<()> {
+        crate::fgb::write_layer(self, w, spatial_index)
+    }
+
+    /// Return the feature with the given primary key, or `None` if it doesn't exist.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// if let Some(feature) = layer.get_feature(1)? {
+    ///     let _geom = feature.geometry()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get_feature(&self, id: i64) -> Result<Option<GpkgFeature>> {
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_feature_by_id(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+        );
+
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let feature = stmt
+            .query_row([id], |row| {
+                row_to_feature(
+                    row,
+                    &self.layer_name,
+                    &self.property_columns,
+                    &self.geometry_column,
+                    &self.primary_key_column,
+                    &self.property_index_by_name,
+                )
+            })
+            .optional()?;
+
+        Ok(feature)
+    }
+
+    /// Return the features whose bounding box intersects the given bbox, ordered
+    /// by primary key.
+    ///
+    /// The query uses the layer's rtree spatial index when it exists and falls
+    /// back to a full scan otherwise. Features with a `NULL` or empty geometry
+    /// are never returned, and an inverted bbox (`min > max`) yields no features.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features_in_bbox(0.0, 0.0, 10.0, 10.0)? {
+    ///     let _geom = feature.geometry()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_in_bbox(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<Vec<GpkgFeature>> {
+        self.features_in_bbox_iter(min_x, min_y, max_x, max_y)?
+            .collect()
+    }
+
+    /// Return an iterator over the features whose bounding box intersects the
+    /// given bbox, ordered by primary key.
+    ///
+    /// This is the streaming variant of `features_in_bbox()`. The features
+    /// are read in batches of 1000 as the iterator advances, so the memory
+    /// used doesn't grow with the number of matches. As with
+    /// `features_batch()`, the layer may be modified during the iteration.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for feature in layer.features_in_bbox_iter(0.0, 0.0, 10.0, 10.0)? {
+    ///     let _geom = feature?.geometry()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_in_bbox_iter(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<impl Iterator<Item = Result<GpkgFeature>> + '_> {
+        let batches = if min_x > max_x || min_y > max_y {
+            None
+        } else {
+            let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+            let sql = sql_select_features_in_bbox(
+                &self.layer_name,
+                &self.geometry_column,
+                &self.primary_key_column,
+                columns,
+                self.has_spatial_index()?,
+                Some(BBOX_BATCH_SIZE),
+            );
+            let stmt = self.conn.prepare_cached(&sql)?;
+            let params = [min_x, min_y, max_x, max_y].map(Value::Real).to_vec();
+            Some(GpkgFeatureBatchIterator::new(
+                stmt,
+                self,
+                BBOX_BATCH_SIZE,
+                params,
+            ))
+        };
+
+        Ok(batches.into_iter().flatten().flat_map(|batch| {
+            let (features, error) = match batch {
+                Ok(features) => (features, None),
+                Err(e) => (Vec::new(), Some(Err(e))),
+            };
+            features.into_iter().map(Ok).chain(error)
+        }))
+    }
+
     /// Return an iterator that yields features in batches.
     ///
     /// This is intended for large layers where allocating a single `Vec<GpkgFeature>`
     /// could be expensive. Each iterator item is a `Vec<GpkgFeature>` with up to
     /// `batch_size` features.
     ///
+    /// The layer may be modified during the iteration; see
+    /// [`GpkgFeatureBatchIterator`] for which rows are returned then.
+    ///
     /// Example:
     /// ```no_run
     /// use rusqlite_gpkg::Gpkg;
@@ -109,381 +503,4447 @@ impl GpkgLayer {
             &self.geometry_column,
             &self.primary_key_column,
             columns,
+            None,
             Some(batch_size),
         );
 
-        let stmt = self.conn.prepare(&sql)?;
+        let stmt = self.conn.prepare_cached(&sql)?;
 
-        Ok(GpkgFeatureBatchIterator::new(stmt, self, batch_size))
+        Ok(GpkgFeatureBatchIterator::new(
+            stmt,
+            self,
+            batch_size,
+            Vec::new(),
+        ))
     }
 
-    /// Remove all rows from the layer.
+    /// Same as `features_batch()`, but the iterator keeps the connection alive
+    /// by itself instead of borrowing the layer, so it can be returned from a
+    /// function or stored next to the `Gpkg`.
     ///
     /// Example:
     /// ```no_run
-    /// use rusqlite_gpkg::Gpkg;
+    /// use rusqlite_gpkg::{Gpkg, GpkgFeatureBatchIterator};
     ///
-    /// let gpkg = Gpkg::open("data/example.gpkg")?;
-    /// let layer = gpkg.get_layer("points")?;
-    /// layer.truncate()?;
+    /// fn point_batches(path: &str) -> rusqlite_gpkg::Result<GpkgFeatureBatchIterator<'static>> {
+    ///     let gpkg = Gpkg::open_read_only(path)?;
+    ///     gpkg.get_layer("points")?.features_batch_owned(100)
+    /// }
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn truncate(&self) -> Result<usize> {
-        self.ensure_writable()?;
-        let sql = sql_delete_all(&self.layer_name);
-        Ok(self.conn.execute(&sql, [])?)
+    pub fn features_batch_owned(
+        &self,
+        batch_size: u32,
+    ) -> Result<GpkgFeatureBatchIterator<'static>> {
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            None,
+            Some(batch_size),
+        );
+
+        // Fail early on a broken statement, as `features_batch()` does.
+        self.conn.prepare_cached(&sql)?;
+
+        Ok(GpkgFeatureBatchIterator::new_owned(
+            sql,
+            self,
+            batch_size,
+            Vec::new(),
+        ))
     }
 
-    /// Insert a feature with geometry and ordered property values.
+    /// Return the features matching a SQL `WHERE` expression, ordered by
+    /// primary key.
+    ///
+    /// `where_clause` is inserted verbatim after `WHERE`, so only pass trusted
+    /// SQL and bind user input via `?` placeholders and `params`. Column names
+    /// must be quoted by the caller (e.g. `"name"`). A clause containing `;` is
+    /// rejected with `GpkgError::InvalidWhereClause`.
     ///
     /// Example:
     /// ```no_run
-    /// use geo_types::Point;
     /// use rusqlite_gpkg::{Gpkg, params};
     ///
-    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
     /// let layer = gpkg.get_layer("points")?;
-    ///
-    /// layer.insert(Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// let features = layer.features_where(r#""region" = ? AND "points" >= ?"#, params!["Hokkaido", 5])?;
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn insert<'p, G, P>(&self, geometry: G, properties: P) -> Result<()>
+    pub fn features_where<P>(&self, where_clause: &str, params: P) -> Result<Vec<GpkgFeature>>
     where
-        G: GeometryTrait<T = f64>,
-        P: IntoIterator<Item = &'p Value>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
     {
-        let properties: Vec<&Value> = properties.into_iter().collect();
-        let expected = self.property_columns.len();
-        let got = properties.len();
-        if expected != got {
-            return Err(GpkgError::InvalidPropertyCount { expected, got });
-        }
+        validate_where_clause(where_clause)?;
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            Some(where_clause),
+            None,
+        );
 
-        let geom = self.geom_from_geometry(geometry)?;
-        let params = params_from_geom_and_properties(geom, properties, None);
-        let mut stmt = self.conn.prepare_cached(&self.insert_sql)?;
-        stmt.execute(params)?;
-        Ok(())
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let features = stmt
+            .query_map(params_from_properties(params, None), |row| {
+                row_to_feature(
+                    row,
+                    &self.layer_name,
+                    &self.property_columns,
+                    &self.geometry_column,
+                    &self.primary_key_column,
+                    &self.property_index_by_name,
+                )
+            })?
+            .collect::<rusqlite::Result<Vec<GpkgFeature>>>()?;
+
+        Ok(features)
     }
 
-    /// Update the feature with geometry and ordered property values.
+    /// Return an iterator that yields the features matching a SQL `WHERE`
+    /// expression in batches.
+    ///
+    /// This is the batched variant of `features_where()`; the same rules for
+    /// `where_clause` apply.
     ///
     /// Example:
     /// ```no_run
-    /// use geo_types::Point;
     /// use rusqlite_gpkg::{Gpkg, params};
     ///
-    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
     /// let layer = gpkg.get_layer("points")?;
-    /// layer.update(Point::new(3.0, 4.0), params!["beta", false], 1)?;
+    /// for batch in layer.features_batch_where(100, r#""region" = ?"#, params!["Hokkaido"])? {
+    ///     let _features = batch?;
+    /// }
     /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
     /// ```
-    pub fn update<'p, G, P>(&self, geometry: G, properties: P, id: i64) -> Result<()>
+    pub fn features_batch_where<'a, P>(
+        &'a self,
+        batch_size: u32,
+        where_clause: &str,
+        params: P,
+    ) -> Result<GpkgFeatureBatchIterator<'a>>
     where
-        G: GeometryTrait<T = f64>,
-        P: IntoIterator<Item = &'p Value>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
     {
-        let properties: Vec<&Value> = properties.into_iter().collect();
-        let expected = self.property_columns.len();
-        let got = properties.len();
-        if expected != got {
-            return Err(GpkgError::InvalidPropertyCount { expected, got });
-        }
+        validate_where_clause(where_clause)?;
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            Some(where_clause),
+            Some(batch_size),
+        );
 
-        let geom = self.geom_from_geometry(geometry)?;
-        let params = params_from_geom_and_properties(geom, properties, Some(id));
-        let mut stmt = self.conn.prepare_cached(&self.update_sql)?;
-        stmt.execute(params)?;
-        Ok(())
-    }
+        let stmt = self.conn.prepare_cached(&sql)?;
 
-    fn ensure_writable(&self) -> Result<()> {
-        if self.is_read_only {
-            return Err(GpkgError::ReadOnly);
-        }
-        Ok(())
+        Ok(GpkgFeatureBatchIterator::new(
+            stmt,
+            self,
+            batch_size,
+            params
+                .into_iter()
+                .map(|value| value.borrow().clone())
+                .collect(),
+        ))
     }
 
-    pub(crate) fn build_insert_sql(
-        layer_name: &str,
-        geometry_column: &str,
-        property_columns: &[ColumnSpec],
+    /// Return all the features in the layer ordered by `order_by`, a list of
+    /// `(column, order)` pairs, or `(column, order, collation)` triples to
+    /// compare text with e.g. `Collation::NoCase`. Each column must be the
+    /// primary key or a property column, otherwise this fails with
+    /// `GpkgError::MissingProperty`. Features that compare equal are ordered
+    /// by the primary key, so the order is stable.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Collation, Gpkg, SortOrder};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let features =
+    ///     layer.features_ordered(&[("region", SortOrder::Asc), ("points", SortOrder::Desc)])?;
+    /// let features = layer.features_ordered(&[("name", SortOrder::Desc, Collation::NoCase)])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_ordered<'k, K>(&self, order_by: &[K]) -> Result<Vec<GpkgFeature>>
+    where
+        K: Into<SortKey<'k>> + Copy,
+    {
+        let (order_by, _) = self.order_by_terms(order_by)?;
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features_ordered(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            &order_by,
+            None,
+        );
+
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let features = stmt
+            .query_map([], |row| {
+                row_to_feature(
+                    row,
+                    &self.layer_name,
+                    &self.property_columns,
+                    &self.geometry_column,
+                    &self.primary_key_column,
+                    &self.property_index_by_name,
+                )
+            })?
+            .collect::<rusqlite::Result<Vec<GpkgFeature>>>()?;
+
+        Ok(features)
+    }
+
+    /// Return an iterator that yields the features ordered by `order_by` in
+    /// batches.
+    ///
+    /// This is the batched variant of `features_ordered()`. Each batch starts
+    /// after the sort key of the last feature of the previous batch, so like
+    /// `features_batch()`, every feature whose sort key isn't modified while
+    /// iterating is returned exactly once.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, SortOrder};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for batch in layer.features_batch_ordered(100, &[("region", SortOrder::Asc)])? {
+    ///     let _features = batch?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn features_batch_ordered<'a, 'k, K>(
+        &'a self,
+        batch_size: u32,
+        order_by: &[K],
+    ) -> Result<GpkgFeatureBatchIterator<'a>>
+    where
+        K: Into<SortKey<'k>> + Copy,
+    {
+        let (order_by, sort_key_columns) = self.order_by_terms(order_by)?;
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let sql = sql_select_features_ordered(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            columns,
+            &order_by,
+            Some(batch_size),
+        );
+
+        let stmt = self.conn.prepare_cached(&sql)?;
+
+        Ok(GpkgFeatureBatchIterator::new_with_sort_key(
+            stmt,
+            self,
+            batch_size,
+            sort_key_columns,
+        ))
+    }
+
+    // Build the `ORDER BY` terms from known column names only, ending with the
+    // primary key to break ties. Also return the column of each term, `None`
+    // for the primary key, to read the sort key of a feature.
+    #[allow(clippy::type_complexity)]
+    fn order_by_terms<'k, K>(
+        &self,
+        order_by: &[K],
+    ) -> Result<(Vec<(String, SortOrder)>, Vec<Option<String>>)>
+    where
+        K: Into<SortKey<'k>> + Copy,
+    {
+        let mut terms = Vec::with_capacity(order_by.len() + 1);
+        let mut columns = Vec::with_capacity(order_by.len() + 1);
+        let mut has_primary_key = false;
+        for key in order_by {
+            let SortKey {
+                column,
+                order,
+                collation,
+            } = (*key).into();
+            if column == self.primary_key_column {
+                has_primary_key = true;
+                columns.push(None);
+            } else if self.property_index_by_name.contains_key(column) {
+                columns.push(Some(column.to_string()));
+            } else {
+                return Err(GpkgError::MissingProperty {
+                    property: column.to_string(),
+                });
+            }
+            let collation = match collation {
+                None => "",
+                Some(Collation::Binary) => " COLLATE BINARY",
+                Some(Collation::NoCase) => " COLLATE NOCASE",
+                Some(Collation::RTrim) => " COLLATE RTRIM",
+            };
+            terms.push((format!(r#""{column}"{collation}"#), order));
+        }
+        if !has_primary_key {
+            terms.push((format!(r#""{}""#, self.primary_key_column), SortOrder::Asc));
+            columns.push(None);
+        }
+        Ok((terms, columns))
+    }
+
+    /// Return the number of features in the layer without loading them.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _n = layer.count()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn count(&self) -> Result<u64> {
+        let sql = sql_count(&self.layer_name, None);
+        let count: i64 = self.conn.query_row(&sql, [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
+    /// Return the number of features matching a SQL `WHERE` expression.
+    ///
+    /// `where_clause` is inserted verbatim after `WHERE`, so only pass trusted
+    /// SQL and bind user input via `?` placeholders and `params`. A clause
+    /// containing `;` is rejected with `GpkgError::InvalidWhereClause`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _n = layer.count_where(r#""name" = ?1"#, params!["alpha"])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn count_where<P>(&self, where_clause: &str, params: P) -> Result<u64>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        validate_where_clause(where_clause)?;
+        let sql = sql_count(&self.layer_name, Some(where_clause));
+        let count: i64 =
+            self.conn
+                .query_row(&sql, params_from_properties(params, None), |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
+    /// Return the minimum, maximum, mean, `NULL` count and distinct count of a
+    /// column, computed with a single SQL query.
+    ///
+    /// `column` is a property column or the primary key column. Fails with
+    /// `GpkgError::MissingProperty` if the layer has no such column, and with
+    /// `GpkgError::UnsupportedColumnType` for the geometry column.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let stats = layer.column_stats("value")?;
+    /// println!("{:?}..{:?}, mean {:?}", stats.min, stats.max, stats.mean);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn column_stats(&self, column: &str) -> Result<ColumnStats> {
+        self.check_stats_column(column)?;
+        let sql = sql_column_stats(&self.layer_name, column);
+        let stats = self.conn.query_row(&sql, [], |row| {
+            let value = |idx| {
+                value_from_row(row, idx, None)
+                    .map(|value| (!matches!(value, Value::Null)).then_some(value))
+            };
+            Ok(ColumnStats {
+                min: value(0)?,
+                max: value(1)?,
+                mean: row.get(2)?,
+                null_count: row.get::<_, i64>(3)? as u64,
+                distinct_count: row.get::<_, i64>(4)? as u64,
+            })
+        })?;
+        Ok(stats)
+    }
+
+    /// Return the distinct non-`NULL` values of a column in ascending order,
+    /// e.g. to enumerate the categories of a classification.
+    ///
+    /// At most `limit` values are returned if given. The column is validated as
+    /// in [`column_stats`](Self::column_stats).
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _regions = layer.distinct_values("region", Some(100))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn distinct_values(&self, column: &str, limit: Option<u32>) -> Result<Vec<Value>> {
+        self.check_stats_column(column)?;
+        let sql = sql_distinct_values(&self.layer_name, column, limit);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let values = stmt
+            .query_map([], |row| value_from_row(row, 0, None))?
+            .collect::<rusqlite::Result<Vec<Value>>>()?;
+        Ok(values)
+    }
+
+    fn check_stats_column(&self, column: &str) -> Result<()> {
+        if column == self.geometry_column {
+            return Err(GpkgError::UnsupportedColumnType {
+                column: column.to_string(),
+                declared_type: self.geometry_type_name.clone(),
+            });
+        }
+        if column != self.primary_key_column && !self.property_index_by_name.contains_key(column) {
+            return Err(GpkgError::MissingProperty {
+                property: column.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Return a snapshot of the layer's definition, together with its
+    /// identifier, description, last change and extent from `gpkg_contents`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let metadata = layer.metadata()?;
+    /// println!("{:?} last changed at {}", metadata.identifier, metadata.last_change);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn metadata(&self) -> Result<GpkgLayerMetadata> {
+        let contents = select_contents_metadata(&self.conn, &self.layer_name)?;
+        Ok(GpkgLayerMetadata {
+            table_name: self.layer_name.clone(),
+            data_type: "features".to_string(),
+            primary_key_column: self.primary_key_column.clone(),
+            geometry_column: self.geometry_column.clone(),
+            geometry_type_name: self.geometry_type_name.clone(),
+            geometry_type: Some(self.geometry_type),
+            geometry_dimension: Some(self.geometry_dimension),
+            srs_id: self.srs_id as i32,
+            other_columns: self.property_columns.clone(),
+            identifier: contents.identifier,
+            description: contents.description,
+            last_change: contents.last_change,
+            extent: contents.extent,
+        })
+    }
+
+    /// Return the layer's `identifier` in `gpkg_contents`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _identifier = layer.identifier()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn identifier(&self) -> Result<Option<String>> {
+        Ok(select_contents_metadata(&self.conn, &self.layer_name)?.identifier)
+    }
+
+    /// Set the layer's `identifier` in `gpkg_contents`.
+    ///
+    /// Identifiers are unique, so this fails with
+    /// `GpkgError::DuplicateIdentifier` if another table already uses it.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.set_identifier("Sample points")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_identifier(&self, identifier: &str) -> Result<()> {
+        self.ensure_writable()?;
+        ensure_identifier_available(&self.conn, &self.layer_name, identifier)?;
+        self.conn.execute(
+            SQL_UPDATE_CONTENTS_IDENTIFIER,
+            [self.layer_name.as_str(), identifier],
+        )?;
+        Ok(())
+    }
+
+    /// Return the layer's `description` in `gpkg_contents`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _description = layer.description()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn description(&self) -> Result<Option<String>> {
+        Ok(select_contents_metadata(&self.conn, &self.layer_name)?.description)
+    }
+
+    /// Set the layer's `description` in `gpkg_contents`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.set_description("Points sampled in 2024")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_description(&self, description: &str) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            SQL_UPDATE_CONTENTS_DESCRIPTION,
+            [self.layer_name.as_str(), description],
+        )?;
+        Ok(())
+    }
+
+    /// Return the title, description and constraint of a column stored with
+    /// the `gpkg_schema` extension, as written by GDAL for field aliases and
+    /// domains.
+    ///
+    /// Returns `None` if the GeoPackage doesn't use the extension or the
+    /// column has no entry.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// if let Some(info) = layer.column_info("name")? {
+    ///     println!("alias: {:?}", info.title);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn column_info(&self, column: &str) -> Result<Option<DataColumnInfo>> {
+        let has_data_columns: bool =
+            self.conn
+                .query_row(SQL_TABLE_EXISTS, ["gpkg_data_columns"], |row| row.get(0))?;
+        if !has_data_columns {
+            return Ok(None);
+        }
+
+        let row = self
+            .conn
+            .query_row(
+                SQL_SELECT_DATA_COLUMN,
+                [self.layer_name.as_str(), column],
+                |row| {
+                    let info = DataColumnInfo {
+                        name: row.get(0)?,
+                        title: row.get(1)?,
+                        description: row.get(2)?,
+                        mime_type: row.get(3)?,
+                        constraint: None,
+                    };
+                    Ok((info, row.get::<_, Option<String>>(4)?))
+                },
+            )
+            .optional()?;
+        let Some((mut info, constraint_name)) = row else {
+            return Ok(None);
+        };
+        if let Some(constraint_name) = constraint_name {
+            info.constraint = select_data_column_constraint(&self.conn, &constraint_name)?;
+        }
+        Ok(Some(info))
+    }
+
+    /// Store the title, description and constraint of a column with the
+    /// `gpkg_schema` extension, replacing the existing entry.
+    ///
+    /// The extension tables are created on first use. The rows of the
+    /// constraint in `gpkg_data_column_constraints` are replaced too, which
+    /// also affects the other columns sharing the constraint name. Fails with
+    /// `GpkgError::MissingProperty` if the layer has no such column.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{DataColumnConstraint, DataColumnInfo, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.set_column_info(
+    ///     "value",
+    ///     &DataColumnInfo {
+    ///         title: Some("Value (%)".to_string()),
+    ///         constraint: Some(DataColumnConstraint::Range {
+    ///             name: "percentage".to_string(),
+    ///             min: Some(0.0),
+    ///             min_is_inclusive: true,
+    ///             max: Some(100.0),
+    ///             max_is_inclusive: true,
+    ///         }),
+    ///         ..Default::default()
+    ///     },
+    /// )?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_column_info(&self, column: &str, info: &DataColumnInfo) -> Result<()> {
+        self.ensure_writable()?;
+        if column != self.primary_key_column
+            && column != self.geometry_column
+            && !self.property_index_by_name.contains_key(column)
+        {
+            return Err(GpkgError::MissingProperty {
+                property: column.to_string(),
+            });
+        }
+
+        self.with_savepoint(|| {
+            self.conn.execute_batch(SQL_ENABLE_SCHEMA)?;
+            if let Some(constraint) = &info.constraint {
+                write_data_column_constraint(&self.conn, constraint)?;
+            }
+            self.conn.execute(
+                SQL_UPSERT_DATA_COLUMN,
+                rusqlite::params![
+                    self.layer_name,
+                    column,
+                    info.name,
+                    info.title,
+                    info.description,
+                    info.mime_type,
+                    info.constraint.as_ref().map(DataColumnConstraint::name),
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Return the metadata documents linked to the layer with
+    /// `Gpkg::link_metadata_to_layer()`.
+    ///
+    /// Returns an empty `Vec` if the GeoPackage doesn't use the
+    /// `gpkg_metadata` extension.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// for entry in layer.metadata_entries()? {
+    ///     println!("{}: {}", entry.scope, entry.metadata);
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn metadata_entries(&self) -> Result<Vec<MetadataEntry>> {
+        select_referenced_metadata(&self.conn, "table", Some(&self.layer_name))
+    }
+
+    /// Return the spatial reference system of the layer, e.g. to read the WKT
+    /// definition of its CRS.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// println!("{}", layer.srs()?.definition);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn srs(&self) -> Result<SpatialRefSys> {
+        super::gpkg::select_spatial_ref_sys(&self.conn, self.srs_id as i32)?.ok_or(
+            GpkgError::MissingSpatialRefSysId {
+                srs_id: self.srs_id,
+            },
+        )
+    }
+
+    /// Return the bounding box of the layer as `(min_x, min_y, max_x, max_y)`.
+    ///
+    /// The extent stored in `gpkg_contents` is used when present. Otherwise it
+    /// is computed from the rtree spatial index, or by scanning the geometries
+    /// if the layer has no index. Returns `None` for a layer without any
+    /// non-empty geometry.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// if let Some((min_x, min_y, max_x, max_y)) = layer.extent()? {
+    ///     println!("{min_x} {min_y} {max_x} {max_y}");
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn extent(&self) -> Result<Option<(f64, f64, f64, f64)>> {
+        let stored = self
+            .conn
+            .query_row(
+                SQL_SELECT_CONTENTS_EXTENT,
+                [&self.layer_name],
+                extent_from_row,
+            )
+            .optional()?
+            .flatten();
+        if stored.is_some() {
+            return Ok(stored);
+        }
+
+        self.compute_extent()
+    }
+
+    /// Recompute the extent of the layer and store it in `gpkg_contents`.
+    ///
+    /// `insert`, `update`, `delete`, and `truncate` keep the stored extent up
+    /// to date, so this is mainly useful for layers written by other tools.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.update_extent()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update_extent(&self) -> Result<()> {
+        self.ensure_writable()?;
+        let extent = self.compute_extent()?;
+        self.store_extent(extent)
+    }
+
+    fn compute_extent(&self) -> Result<Option<(f64, f64, f64, f64)>> {
+        let sql = sql_compute_extent(
+            &self.layer_name,
+            &self.geometry_column,
+            self.has_spatial_index()?,
+        );
+        Ok(self.conn.query_row(&sql, [], extent_from_row)?)
+    }
+
+    fn store_extent(&self, extent: Option<(f64, f64, f64, f64)>) -> Result<()> {
+        let (min_x, min_y, max_x, max_y) = match extent {
+            Some((min_x, min_y, max_x, max_y)) => {
+                (Some(min_x), Some(min_y), Some(max_x), Some(max_y))
+            }
+            None => (None, None, None, None),
+        };
+        self.conn.execute(
+            SQL_UPDATE_CONTENTS_EXTENT,
+            rusqlite::params![min_x, min_y, max_x, max_y, self.layer_name],
+        )?;
+        Ok(())
+    }
+
+    // Grow the stored extent to cover the bounds. If no extent is stored yet,
+    // fall back to recomputing it from the whole layer.
+    pub(crate) fn expand_extent(&self, bounds: Option<Bounds>) -> Result<()> {
+        let Some(bounds) = bounds else {
+            return Ok(());
+        };
+        let updated = self.conn.execute(
+            SQL_EXPAND_CONTENTS_EXTENT,
+            rusqlite::params![
+                bounds.minx,
+                bounds.miny,
+                bounds.maxx,
+                bounds.maxy,
+                self.layer_name
+            ],
+        )?;
+        if updated == 0 {
+            self.update_extent()?;
+        }
+        Ok(())
+    }
+
+    // Record in `gpkg_contents` that the content of the layer has changed.
+    pub(crate) fn touch_last_change(&self) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(SQL_UPDATE_CONTENTS_LAST_CHANGE)?;
+        stmt.execute([&self.layer_name])?;
+        Ok(())
+    }
+
+    /// Return whether the layer has an rtree spatial index.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// println!("{}", layer.has_spatial_index()?);
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn has_spatial_index(&self) -> Result<bool> {
+        let rtree_name = format!("rtree_{}_{}", self.layer_name, self.geometry_column);
+        let exists: i64 = self
+            .conn
+            .prepare_cached(SQL_TABLE_EXISTS)?
+            .query_row([rtree_name], |row| row.get(0))?;
+        Ok(exists == 1)
+    }
+
+    /// Create the rtree spatial index of the layer, fill it with the existing
+    /// features and register it in `gpkg_extensions`.
+    ///
+    /// `create_layer()` already creates the index, so this is for layers
+    /// written without one, or after `drop_spatial_index()`. Fails with
+    /// `GpkgError::SpatialIndexAlreadyExists` if the layer has an index.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// if !layer.has_spatial_index()? {
+    ///     layer.create_spatial_index()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn create_spatial_index(&self) -> Result<()> {
+        self.ensure_writable()?;
+        if self.has_spatial_index()? {
+            return Err(GpkgError::SpatialIndexAlreadyExists {
+                layer_name: self.layer_name.clone(),
+            });
+        }
+        self.with_savepoint(|| {
+            execute_rtree_sqls(
+                &self.conn,
+                &self.layer_name,
+                &self.geometry_column,
+                &self.primary_key_column,
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Drop the rtree spatial index of the layer, its triggers and its row in
+    /// `gpkg_extensions`. This does nothing if the layer has no index.
+    ///
+    /// Inserting many features is faster without the index, so a bulk load
+    /// can drop it first and call `create_spatial_index()` afterwards.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.drop_spatial_index()?;
+    /// let features = (0..100_000).map(|i| {
+    ///     let properties = vec![Value::from("alpha"), Value::from(i)];
+    ///     (Point::new(i as f64, 0.0), properties)
+    /// });
+    /// layer.insert_many(features)?;
+    /// layer.create_spatial_index()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn drop_spatial_index(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.with_savepoint(|| {
+            self.conn.execute_batch(&gpkg_rtree_triggers_drop_sql(
+                &self.layer_name,
+                &self.geometry_column,
+            ))?;
+            self.conn.execute_batch(&gpkg_rtree_drop_sql(
+                &self.layer_name,
+                &self.geometry_column,
+            ))?;
+            self.conn.execute(
+                SQL_DELETE_RTREE_EXTENSION,
+                [&self.layer_name, &self.geometry_column],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Compare the rtree spatial index with the features of the layer.
+    ///
+    /// The index can get out of sync when the GeoPackage is edited by software
+    /// that doesn't define the `ST_*` functions the rtree triggers rely on.
+    /// Use `rebuild_spatial_index()` to fix it. Fails with
+    /// `GpkgError::MissingSpatialIndex` if the layer has no index.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let report = layer.verify_spatial_index()?;
+    /// if !report.is_consistent() {
+    ///     println!("missing: {:?}", report.missing_ids);
+    ///     layer.rebuild_spatial_index()?;
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn verify_spatial_index(&self) -> Result<SpatialIndexReport> {
+        self.ensure_spatial_index()?;
+        let (t, c, i) = (
+            self.layer_name.as_str(),
+            self.geometry_column.as_str(),
+            self.primary_key_column.as_str(),
+        );
+        let ids = |sql: String| -> Result<Vec<i64>> {
+            let mut stmt = self.conn.prepare(&sql)?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            Ok(ids)
+        };
+        let count = |sql: String| -> Result<u64> {
+            let count: i64 = self.conn.query_row(&sql, [], |row| row.get(0))?;
+            Ok(count as u64)
+        };
+        Ok(SpatialIndexReport {
+            feature_count: count(sql_count(
+                t,
+                Some(&format!("{c} NOT NULL AND NOT ST_IsEmpty({c})")),
+            ))?,
+            rtree_count: count(sql_count(&format!("rtree_{t}_{c}"), None))?,
+            missing_ids: ids(sql_rtree_missing_ids(t, c, i))?,
+            orphan_ids: ids(sql_rtree_orphan_ids(t, c, i))?,
+            bounds_mismatches: ids(sql_rtree_bounds_mismatches(t, c, i))?,
+        })
+    }
+
+    /// Delete all rows of the rtree spatial index and recompute them from the
+    /// features of the layer. Fails with `GpkgError::MissingSpatialIndex` if
+    /// the layer has no index.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.rebuild_spatial_index()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn rebuild_spatial_index(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.ensure_spatial_index()?;
+        self.with_savepoint(|| {
+            self.conn.execute_batch(&gpkg_rtree_clear_sql(
+                &self.layer_name,
+                &self.geometry_column,
+            ))?;
+            self.conn.execute_batch(&gpkg_rtree_load_sql(
+                &self.layer_name,
+                &self.geometry_column,
+                &self.primary_key_column,
+            ))?;
+            Ok(())
+        })
+    }
+
+    /// Replace the rtree triggers of the layer with the set defined by
+    /// GeoPackage 1.4 (`insert`, `update2`, `update4` to `update7` and
+    /// `delete`).
+    ///
+    /// Files written by older software may still have the deprecated
+    /// `update1` and `update3` triggers, which mishandle some updates of
+    /// empty geometries. This drops those along with the current ones before
+    /// installing the new set. Fails with `GpkgError::MissingSpatialIndex` if
+    /// the layer has no index.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.upgrade_rtree_triggers()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn upgrade_rtree_triggers(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.ensure_spatial_index()?;
+        self.with_savepoint(|| {
+            self.conn.execute_batch(&gpkg_rtree_triggers_drop_sql(
+                &self.layer_name,
+                &self.geometry_column,
+            ))?;
+            self.conn.execute_batch(&gpkg_rtree_triggers_sql(
+                &self.layer_name,
+                &self.geometry_column,
+                &self.primary_key_column,
+            ))?;
+            Ok(())
+        })
+    }
+
+    fn ensure_spatial_index(&self) -> Result<()> {
+        if !self.has_spatial_index()? {
+            return Err(GpkgError::MissingSpatialIndex {
+                layer_name: self.layer_name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Add a property column to the layer.
+    ///
+    /// Subsequent calls to `insert()` and `update()` expect a value for the
+    /// new column, which is placed after the existing property columns.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{ColumnSpec, ColumnType, Gpkg};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let mut layer = gpkg.get_layer("points")?;
+    /// layer.add_column(&ColumnSpec {
+    ///     name: "population".to_string(),
+    ///     column_type: ColumnType::Integer,
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn add_column(&mut self, spec: &ColumnSpec) -> Result<()> {
+        self.ensure_writable()?;
+        let sql = sql_add_column(&self.layer_name, &column_definition_sql(spec));
+        self.conn.execute_batch(&sql)?;
+
+        let mut property_columns = self.property_columns.clone();
+        property_columns.push(spec.clone());
+        self.set_property_columns(property_columns);
+        Ok(())
+    }
+
+    /// Drop a property column from the layer.
+    ///
+    /// The geometry column and the primary key column cannot be dropped.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let mut layer = gpkg.get_layer("points")?;
+    /// layer.drop_column("population")?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn drop_column(&mut self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
+        if name == self.geometry_column || name == self.primary_key_column {
+            return Err(GpkgError::CannotDropColumn {
+                column: name.to_string(),
+            });
+        }
+        if !self.property_index_by_name.contains_key(name) {
+            return Err(GpkgError::MissingProperty {
+                property: name.to_string(),
+            });
+        }
+
+        self.conn
+            .execute_batch(&sql_drop_column(&self.layer_name, name))?;
+
+        let property_columns = self
+            .property_columns
+            .iter()
+            .filter(|spec| spec.name != name)
+            .cloned()
+            .collect();
+        self.set_property_columns(property_columns);
+        Ok(())
+    }
+
+    // Replace the property columns and rebuild everything derived from them.
+    fn set_property_columns(&mut self, property_columns: Vec<ColumnSpec>) {
+        self.insert_sql =
+            Self::build_insert_sql(&self.layer_name, &self.geometry_column, &property_columns);
+        self.insert_with_id_sql = Self::build_insert_with_id_sql(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            &property_columns,
+        );
+        self.update_sql = Self::build_update_sql(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            &property_columns,
+        );
+        self.upsert_sql = Self::build_upsert_sql(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+            &property_columns,
+        );
+        self.update_properties_sql = Self::build_update_properties_sql(
+            &self.layer_name,
+            &self.primary_key_column,
+            &property_columns,
+        );
+        self.property_index_by_name =
+            Rc::new(Self::build_property_index_by_name(&property_columns));
+        self.property_columns = property_columns;
+    }
+
+    /// Remove all rows from the layer.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.truncate()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn truncate(&self) -> Result<usize> {
+        self.ensure_writable()?;
+        let sql = sql_delete_all(&self.layer_name);
+        let deleted = self.conn.execute(&sql, [])?;
+        self.store_extent(None)?;
+        self.touch_last_change()?;
+        Ok(deleted)
+    }
+
+    /// Delete the feature with the given primary key.
+    ///
+    /// Returns the number of deleted rows, which is 0 if no feature matched.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.delete(1)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn delete(&self, id: i64) -> Result<usize> {
+        self.ensure_writable()?;
+        // Only a geometry on the edge of the stored extent can shrink it, so
+        // the extent isn't recomputed after deleting any other feature.
+        let shrinks_extent = self.is_on_extent_edge(id)?;
+        let sql = sql_delete_by_id(&self.layer_name, &self.primary_key_column);
+        let deleted = self.conn.prepare_cached(&sql)?.execute([id])?;
+        if deleted > 0 {
+            if shrinks_extent {
+                self.update_extent()?;
+            }
+            self.touch_last_change()?;
+        }
+        Ok(deleted)
+    }
+
+    // Whether the geometry of the feature touches the edge of the extent
+    // stored in `gpkg_contents`. A geometry that can't be decoded is assumed
+    // to touch it.
+    fn is_on_extent_edge(&self, id: i64) -> Result<bool> {
+        let stored = self
+            .conn
+            .prepare_cached(SQL_SELECT_CONTENTS_EXTENT)?
+            .query_row([&self.layer_name], extent_from_row)
+            .optional()?
+            .flatten();
+        let Some((min_x, min_y, max_x, max_y)) = stored else {
+            return Ok(false);
+        };
+
+        let sql = sql_select_geometry_by_id(
+            &self.layer_name,
+            &self.geometry_column,
+            &self.primary_key_column,
+        );
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let geometry = match stmt
+            .query_row([id], |row| Ok(row.get::<_, Option<Vec<u8>>>(0)))
+            .optional()?
+        {
+            // No such feature, or a NULL geometry.
+            None | Some(Ok(None)) => return Ok(false),
+            Some(Ok(Some(geometry))) => geometry,
+            Some(Err(_)) => return Ok(true),
+        };
+        let Ok(wkb) = gpkg_geometry_to_wkb(&geometry) else {
+            return Ok(true);
+        };
+        Ok(bounds_from_geometry(&wkb).is_some_and(|bounds| {
+            bounds.minx <= min_x
+                || bounds.miny <= min_y
+                || bounds.maxx >= max_x
+                || bounds.maxy >= max_y
+        }))
+    }
+
+    /// Insert a feature with geometry and ordered property values.
+    ///
+    /// The geometry must match the layer's `geometry_type` and
+    /// `geometry_dimension`, otherwise `GpkgError::GeometryTypeMismatch` is
+    /// returned. This applies to all the writing methods except
+    /// `insert_unchecked()`. A layer of type `GeometryCollection`, which is
+    /// also how a layer declared as `GEOMETRY` is read, accepts any geometry
+    /// type, but the dimension must still match.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// layer.insert(Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert<G, P>(&self, geometry: G, properties: P) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        self.insert_impl(geometry, properties, true)
+    }
+
+    /// Insert a feature with a geometry given as WKT and ordered property
+    /// values.
+    ///
+    /// Invalid WKT fails with `GpkgError::Wkt`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.insert_wkt("POINT (1 2)", params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "wkt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "wkt")))]
+    pub fn insert_wkt<P>(&self, wkt: &str, properties: P) -> Result<()>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let geometry = <wkt::Wkt<f64> as std::str::FromStr>::from_str(wkt)
+            .map_err(|err| GpkgError::Wkt(err.to_string()))?;
+        self.insert(geometry, properties)
+    }
+
+    /// Insert the features of a GeoJSON FeatureCollection (or a single
+    /// Feature) and return the number of inserted rows.
+    ///
+    /// Properties are matched to the layer's columns by name; missing
+    /// properties are inserted as `NULL` and unknown ones are ignored. A
+    /// single geometry is promoted to the Multi- type of the layer (e.g.
+    /// `Polygon` into a `MULTIPOLYGON` layer). As with `insert_many()`,
+    /// nothing is inserted if any feature fails.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// let _inserted = layer.insert_geojson(std::fs::File::open("points.geojson")?)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    #[cfg(feature = "geojson")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+    pub fn insert_geojson<R: std::io::Read>(&self, reader: R) -> Result<u64> {
+        self.ensure_writable()?;
+        let features = crate::geojson::read_features(reader)?;
+        self.with_savepoint(|| crate::geojson::insert_features(self, &features))
+    }
+
+    // Insert a feature whose geometry is NULL.
+    #[cfg(any(feature = "geojson", feature = "csv"))]
+    pub(crate) fn insert_without_geometry(&self, properties: &[Value]) -> Result<()> {
+        self.ensure_writable()?;
+        let expected = self.property_columns.len();
+        let got = properties.len();
+        if expected != got {
+            return Err(GpkgError::InvalidPropertyCount { expected, got });
+        }
+        self.check_property_types(properties)?;
+
+        let params = std::iter::once(&Value::Null).chain(properties);
+        let mut stmt = self.conn.prepare_cached(&self.insert_sql)?;
+        stmt.execute(rusqlite::params_from_iter(params))?;
+        self.touch_last_change()
+    }
+
+    /// Insert a feature without checking that the geometry matches the
+    /// layer's geometry type and dimension.
+    ///
+    /// Other tools may refuse to read a layer whose geometries don't match the
+    /// type declared in `gpkg_geometry_columns`, so prefer `insert()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::{LineString, coord};
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// let line = LineString::new(vec![coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 1.0 }]);
+    /// layer.insert_unchecked(line, params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_unchecked<G, P>(&self, geometry: G, properties: P) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        self.insert_impl(geometry, properties, false)
+    }
+
+    fn insert_impl<G, P>(&self, geometry: G, properties: P, check_geometry: bool) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties = self.collect_properties(properties)?;
+
+        let (geom, bounds) = self.encode_geometry(geometry, check_geometry)?;
+        let params = params_from_geom_and_properties(geom, properties, None);
+        let mut stmt = self.conn.prepare_cached(&self.insert_sql)?;
+        stmt.execute(params)?;
+        self.expand_extent(bounds)?;
+        self.touch_last_change()
+    }
+
+    /// Insert a feature with an explicit primary key.
+    ///
+    /// Fails with `GpkgError::DuplicatePrimaryKey` if a feature with the same
+    /// id already exists.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// layer.insert_with_id(10, Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_with_id<G, P>(&self, id: i64, geometry: G, properties: P) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties = self.collect_properties(properties)?;
+
+        let (geom, bounds) = self.geom_from_geometry(geometry)?;
+        let params = params_from_geom_and_properties(geom, properties, Some(id));
+        let mut stmt = self.conn.prepare_cached(&self.insert_with_id_sql)?;
+        match stmt.execute(params) {
+            Ok(_) => {}
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY =>
+            {
+                return Err(GpkgError::DuplicatePrimaryKey { id });
+            }
+            Err(err) => return Err(err.into()),
+        }
+        self.expand_extent(bounds)?;
+        self.touch_last_change()
+    }
+
+    /// Insert many features at once and return the number of inserted rows.
+    ///
+    /// All the features are inserted in a single transaction (a savepoint if
+    /// a transaction is already open) with one prepared statement. If any
+    /// feature fails, nothing is inserted and `GpkgError::InsertManyFailed`
+    /// reports the index of the failing feature.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// let features = (0..1000).map(|i| {
+    ///     let properties = vec![Value::from("alpha"), Value::from(i)];
+    ///     (Point::new(i as f64, 0.0), properties)
+    /// });
+    /// let _inserted = layer.insert_many(features)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_many<G, P, I>(&self, features: I) -> Result<u64>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+        I: IntoIterator<Item = (G, P)>,
+    {
+        self.insert_many_with_progress(features, 0, no_progress)
+    }
+
+    /// Insert many features at once like [`insert_many`](Self::insert_many),
+    /// calling `progress` with the number of inserted rows every `every_n`
+    /// rows and once at the end.
+    ///
+    /// If `progress` returns `ControlFlow::Break`, nothing is inserted and
+    /// `GpkgError::Cancelled` is returned.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    /// use std::ops::ControlFlow;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// let features = (0..1_000_000).map(|i| {
+    ///     let properties = vec![Value::from("alpha"), Value::from(i)];
+    ///     (Point::new(i as f64, 0.0), properties)
+    /// });
+    /// layer.insert_many_with_progress(features, 10_000, |done| {
+    ///     println!("{done} rows");
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_many_with_progress<G, P, I, F>(
+        &self,
+        features: I,
+        every_n: u64,
+        progress: F,
+    ) -> Result<u64>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+        I: IntoIterator<Item = (G, P)>,
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
+        self.ensure_writable()?;
+        let mut progress = Progress::new(every_n, progress);
+        self.with_savepoint(|| self.insert_many_in_savepoint(features, &mut progress))
+    }
+
+    fn insert_many_in_savepoint<G, P, I, F>(
+        &self,
+        features: I,
+        progress: &mut Progress<F>,
+    ) -> Result<u64>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+        I: IntoIterator<Item = (G, P)>,
+        F: FnMut(u64) -> ControlFlow<()>,
+    {
+        let mut stmt = self.conn.prepare_cached(&self.insert_sql)?;
+        let mut bounds = None;
+        let mut inserted = 0;
+        for (index, (geometry, properties)) in features.into_iter().enumerate() {
+            let geom_bounds = self
+                .execute_insert(&mut stmt, geometry, properties)
+                .map_err(|err| GpkgError::InsertManyFailed {
+                    index,
+                    source: Box::new(err),
+                })?;
+            if let Some(geom_bounds) = geom_bounds {
+                merge_bounds(&mut bounds, geom_bounds);
+            }
+            inserted += 1;
+            progress.update(inserted)?;
+        }
+        progress.finish(inserted)?;
+
+        if inserted > 0 {
+            self.expand_extent(bounds)?;
+            self.touch_last_change()?;
+        }
+        Ok(inserted)
+    }
+
+    fn execute_insert<G, P>(
+        &self,
+        stmt: &mut rusqlite::CachedStatement<'_>,
+        geometry: G,
+        properties: P,
+    ) -> Result<Option<Bounds>>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties = self.collect_properties(properties)?;
+
+        let (geom, bounds) = self.geom_from_geometry(geometry)?;
+        stmt.execute(params_from_geom_and_properties(geom, properties, None))?;
+        Ok(bounds)
+    }
+
+    /// Append the features of `source`, which may be a layer of another
+    /// GeoPackage, and return the number of appended features.
+    ///
+    /// Only the features matching `where_clause` are appended if it's given;
+    /// it's inserted verbatim as in `features_where()`, but without
+    /// parameters. Geometries are copied without decoding them, properties are
+    /// matched by name and new primary keys are assigned. Everything is
+    /// appended in a single savepoint.
+    ///
+    /// The property columns must have the same names and types, in any order.
+    /// The geometry type must be the same unless this layer is declared
+    /// `GEOMETRY`, and the Z/M flags of this layer must allow the dimension of
+    /// `source`. A different srs_id is accepted if both SRSs have the same
+    /// definition or organization code, in which case the srs_id in each
+    /// geometry header is rewritten. Otherwise this fails with
+    /// `GpkgError::IncompatibleSchema` listing all the differences.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let master = Gpkg::open("data/master.gpkg")?;
+    /// let points = master.get_layer("points")?;
+    /// let field = Gpkg::open_read_only("data/field_day1.gpkg")?;
+    /// let n = points.append_from(&field.get_layer("points")?, Some(r#""checked" = 1"#))?;
+    /// println!("appended {n} features");
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn append_from(&self, source: &GpkgLayer, where_clause: Option<&str>) -> Result<u64> {
+        self.ensure_writable()?;
+        if let Some(where_clause) = where_clause {
+            validate_where_clause(where_clause)?;
+        }
+        let srs_id = self.check_append_compatible(source)?;
+
+        // Bound the primary key so that appending a layer to itself doesn't
+        // read the appended rows again.
+        let max_id: Option<i64> = source.conn.query_row(
+            &sql_max_primary_key(&source.layer_name, &source.primary_key_column),
+            [],
+            |row| row.get(0),
+        )?;
+        let Some(max_id) = max_id else {
+            return Ok(0);
+        };
+        let mut conditions = format!(r#""{}" <= {max_id}"#, source.primary_key_column);
+        if let Some(where_clause) = where_clause {
+            conditions.push_str(&format!(" AND ({where_clause})"));
+        }
+        let columns = self.property_columns.iter().map(|spec| spec.name.as_str());
+        let select_sql = sql_select_features(
+            &source.layer_name,
+            &source.geometry_column,
+            &source.primary_key_column,
+            columns,
+            Some(&conditions),
+            None,
+        );
+
+        self.with_savepoint(|| {
+            let mut select = source.conn.prepare(&select_sql)?;
+            let mut insert = self.conn.prepare_cached(&self.insert_sql)?;
+            let mut rows = select.query([])?;
+            let mut bounds = None;
+            // Whether `bounds` covers all the geometries; it doesn't if some
+            // can't be decoded, e.g. curves.
+            let mut bounds_complete = true;
+            let mut values = Vec::with_capacity(self.property_columns.len() + 1);
+            let mut appended = 0;
+            while let Some(row) = rows.next()? {
+                let id: i64 = row.get(PRIMARY_INDEX)?;
+                values.clear();
+                match row.get::<_, Option<Vec<u8>>>(GEOMETRY_INDEX)? {
+                    Some(mut geom) => {
+                        if let Some(srs_id) = srs_id {
+                            set_gpkg_geometry_srs_id(&mut geom, srs_id)?;
+                        }
+                        match gpkg_geometry_to_wkb(&geom) {
+                            Ok(wkb) => {
+                                if let Some(geom_bounds) = bounds_from_geometry(&wkb) {
+                                    merge_bounds(&mut bounds, geom_bounds);
+                                }
+                            }
+                            Err(_) => bounds_complete = false,
+                        }
+                        values.push(Value::Geometry(geom));
+                    }
+                    None => values.push(Value::Null),
+                }
+                for idx in 0..self.property_columns.len() {
+                    values.push(value_from_row(row, idx + 2, Some(id))?);
+                }
+                insert.execute(params_from_properties(&values, None))?;
+                appended += 1;
+            }
+
+            if appended > 0 {
+                if bounds_complete {
+                    self.expand_extent(bounds)?;
+                } else {
+                    self.update_extent()?;
+                }
+                self.touch_last_change()?;
+            }
+            Ok(appended)
+        })
+    }
+
+    // Check that the features of `source` can be appended to this layer as
+    // they are. Returns the srs_id to write into the geometry headers if the
+    // SRS is the same under another srs_id.
+    fn check_append_compatible(&self, source: &GpkgLayer) -> Result<Option<i32>> {
+        let mut differences = Vec::new();
+        for spec in &source.property_columns {
+            match self
+                .property_columns
+                .iter()
+                .find(|dst| dst.name == spec.name)
+            {
+                None => differences.push(format!(
+                    r#"column "{}" is missing in the destination"#,
+                    spec.name
+                )),
+                Some(dst) if dst.column_type != spec.column_type => differences.push(format!(
+                    r#"column "{}" is {} in the source but {} in the destination"#,
+                    spec.name,
+                    column_type_to_str(spec.column_type),
+                    column_type_to_str(dst.column_type)
+                )),
+                Some(_) => {}
+            }
+        }
+        for spec in &self.property_columns {
+            if !source.property_index_by_name.contains_key(&spec.name) {
+                differences.push(format!(
+                    r#"column "{}" is missing in the source"#,
+                    spec.name
+                ));
+            }
+        }
+
+        if !self.geometry_type_name.eq_ignore_ascii_case("GEOMETRY")
+            && !self
+                .geometry_type_name
+                .eq_ignore_ascii_case(&source.geometry_type_name)
+        {
+            differences.push(format!(
+                "geometry type is {} in the source but {} in the destination",
+                source.geometry_type_name, self.geometry_type_name
+            ));
+        }
+        if !dimension_allowed_by_zm_flags(source.geometry_dimension, self.z_flag, self.m_flag) {
+            differences.push(format!(
+                "geometry dimension is {:?} in the source but {:?} in the destination",
+                source.geometry_dimension, self.geometry_dimension
+            ));
+        }
+
+        let mut srs_id = None;
+        if source.srs_id != self.srs_id {
+            if is_same_srs(&source.srs()?, &self.srs()?) {
+                srs_id = Some(self.srs_id as i32);
+            } else {
+                differences.push(format!(
+                    "srs_id is {} in the source but {} in the destination",
+                    source.srs_id, self.srs_id
+                ));
+            }
+        }
+
+        if differences.is_empty() {
+            Ok(srs_id)
+        } else {
+            Err(GpkgError::IncompatibleSchema { differences })
+        }
+    }
+
+    /// Insert a feature with geometry and named property values.
+    ///
+    /// Properties not listed are set to `NULL`. Fails with
+    /// `GpkgError::MissingProperty` if a name is not a property column of the
+    /// layer.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// layer.insert_named(Point::new(1.0, 2.0), [("name", Value::from("alpha"))])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_named<'n, G, P>(&self, geometry: G, properties: P) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator<Item = (&'n str, Value)>,
+    {
+        let properties = self
+            .named_properties(properties)?
+            .into_iter()
+            .map(|value| value.unwrap_or(Value::Null))
+            .collect::<Vec<Value>>();
+        self.insert(geometry, &properties)
+    }
+
+    /// Insert a feature converted from `value` by its `ToFeature` impl.
+    ///
+    /// This is `insert_named()` with the geometry and the properties given by
+    /// `value.to_feature()`, so properties not listed are set to `NULL` and an
+    /// unknown name fails with `GpkgError::MissingProperty`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, ToFeature, Value};
+    ///
+    /// struct Station {
+    ///     name: String,
+    ///     geom: Point<f64>,
+    /// }
+    ///
+    /// impl ToFeature for Station {
+    ///     type Geometry<'a> = &'a Point<f64>;
+    ///
+    ///     fn to_feature(&self) -> (&Point<f64>, Vec<(&str, Value)>) {
+    ///         (&self.geom, vec![("name", Value::from(self.name.as_str()))])
+    ///     }
+    /// }
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("stations")?;
+    /// let station = Station { name: "summit".to_string(), geom: Point::new(1.0, 2.0) };
+    /// layer.insert_struct(&station)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn insert_struct<T: ToFeature>(&self, value: &T) -> Result<()> {
+        let (geometry, properties) = value.to_feature();
+        self.insert_named(geometry, properties)
+    }
+
+    /// Return a builder to insert or update features with the properties set
+    /// by name.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer
+    ///     .feature_builder()
+    ///     .geometry(Point::new(1.0, 2.0))?
+    ///     .set("name", "alpha")?
+    ///     .insert()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn feature_builder(&self) -> GpkgFeatureBuilder<'_> {
+        GpkgFeatureBuilder::new(self)
+    }
+
+    /// Insert the feature with the given id, or update it if it already exists.
+    ///
+    /// This runs atomically and returns whether a new feature was inserted or
+    /// an existing one was updated. The rtree spatial index is updated in
+    /// place for an existing feature.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, UpsertOutcome, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    ///
+    /// let outcome = layer.upsert(10, Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// if outcome == UpsertOutcome::Inserted {
+    ///     println!("new feature");
+    /// }
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn upsert<G, P>(&self, id: i64, geometry: G, properties: P) -> Result<UpsertOutcome>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties = self.collect_properties(properties)?;
+
+        let (geom, bounds) = self.geom_from_geometry(geometry)?;
+        self.with_savepoint(|| {
+            let exists_sql = sql_feature_exists(&self.layer_name, &self.primary_key_column);
+            let exists: bool = self
+                .conn
+                .prepare_cached(&exists_sql)?
+                .query_row([id], |row| row.get(0))?;
+
+            let params = params_from_geom_and_properties(geom, properties, Some(id));
+            self.conn
+                .prepare_cached(&self.upsert_sql)?
+                .execute(params)?;
+            self.expand_extent(bounds)?;
+            self.touch_last_change()?;
+
+            Ok(if exists {
+                UpsertOutcome::Updated
+            } else {
+                UpsertOutcome::Inserted
+            })
+        })
+    }
+
+    /// Update the feature with geometry and ordered property values.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.update(Point::new(3.0, 4.0), params!["beta", false], 1)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update<G, P>(&self, geometry: G, properties: P, id: i64) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties = self.collect_properties(properties)?;
+
+        let (geom, bounds) = self.geom_from_geometry(geometry)?;
+        let params = params_from_geom_and_properties(geom, properties, Some(id));
+        let mut stmt = self.conn.prepare_cached(&self.update_sql)?;
+        stmt.execute(params)?;
+        // The stored extent is only grown here. Call `update_extent()` to
+        // shrink it after moving features inward.
+        self.expand_extent(bounds)?;
+        self.touch_last_change()
+    }
+
+    /// Update only the named properties of the feature, keeping its geometry
+    /// and the other properties as they are.
+    ///
+    /// Fails with `GpkgError::MissingProperty` if a name is not a property
+    /// column of the layer. If a name is given more than once, the last value
+    /// wins.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, Value};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.update_named(1, [("name", Value::from("beta"))])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update_named<'n, P>(&self, id: i64, properties: P) -> Result<()>
+    where
+        P: IntoIterator<Item = (&'n str, Value)>,
+    {
+        self.ensure_writable()?;
+        let properties = self.named_properties(properties)?;
+
+        let mut columns = Vec::new();
+        let mut params = Vec::new();
+        for (spec, value) in self.property_columns.iter().zip(properties) {
+            if let Some(value) = value {
+                self.check_property_type(spec, &value)?;
+                columns.push(spec.name.as_str());
+                params.push(rusqlite::types::Value::from(value));
+            }
+        }
+        if columns.is_empty() {
+            return Ok(());
+        }
+        params.push(rusqlite::types::Value::Integer(id));
+
+        let sql = sql_update_columns(&self.layer_name, &self.primary_key_column, columns);
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        stmt.execute(rusqlite::params_from_iter(params))?;
+        self.touch_last_change()
+    }
+
+    // Arrange named values in the order of `property_columns`. Columns that
+    // are not mentioned are `None`.
+    fn named_properties<'n, P>(&self, properties: P) -> Result<Vec<Option<Value>>>
+    where
+        P: IntoIterator<Item = (&'n str, Value)>,
+    {
+        let mut values = vec![None; self.property_columns.len()];
+        for (name, value) in properties {
+            let idx = *self.property_index_by_name.get(name).ok_or_else(|| {
+                GpkgError::MissingProperty {
+                    property: name.to_string(),
+                }
+            })?;
+            values[idx] = Some(value);
+        }
+        Ok(values)
+    }
+
+    /// Update only the property values of the feature, keeping its geometry.
+    ///
+    /// Fails with `GpkgError::FeatureNotFound` if no feature has the given id.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.update_properties(1, params!["beta", false])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update_properties<P>(&self, id: i64, properties: P) -> Result<()>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        self.ensure_writable()?;
+        let properties = self.collect_properties(properties)?;
+
+        // There is nothing to SET, but a missing feature is still an error.
+        if properties.is_empty() {
+            return match self.get_feature(id)? {
+                Some(_) => Ok(()),
+                None => Err(GpkgError::FeatureNotFound { id }),
+            };
+        }
+
+        let params = params_from_properties(properties, Some(id));
+        let mut stmt = self.conn.prepare_cached(&self.update_properties_sql)?;
+        if stmt.execute(params)? == 0 {
+            return Err(GpkgError::FeatureNotFound { id });
+        }
+        self.touch_last_change()
+    }
+
+    /// Update only the geometry of the feature, keeping its property values.
+    ///
+    /// Fails with `GpkgError::FeatureNotFound` if no feature has the given id.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let layer = gpkg.get_layer("points")?;
+    /// layer.update_geometry(1, Point::new(3.0, 4.0))?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn update_geometry<G>(&self, id: i64, geometry: G) -> Result<()>
+    where
+        G: GeometryTrait<T = f64>,
+    {
+        let (geom, bounds) = self.geom_from_geometry(geometry)?;
+        let mut stmt = self.conn.prepare_cached(&self.update_geometry_sql)?;
+        if stmt.execute(rusqlite::params![geom, id])? == 0 {
+            return Err(GpkgError::FeatureNotFound { id });
+        }
+        // As with `update()`, the stored extent is only grown here.
+        self.expand_extent(bounds)?;
+        self.touch_last_change()
+    }
+
+    /// Enable or disable strict type checking of property values.
+    ///
+    /// SQLite accepts any value in any column, so by default a `Value::Text`
+    /// can be written into an INTEGER column. In strict mode, every write
+    /// (`insert()`, `update()`, `upsert()`, `insert_many()` and their
+    /// variants) first checks each value against the `ColumnType` of its
+    /// column and fails with `GpkgError::PropertyTypeMismatch`. `NULL` is
+    /// accepted unless the column is declared `NOT NULL`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let mut layer = gpkg.get_layer("points")?;
+    /// layer.set_strict_types(true);
+    /// layer.insert(Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_strict_types(&mut self, strict: bool) {
+        self.strict_types = strict;
+    }
+
+    /// Enable or disable writing the bounding box of each geometry into the
+    /// envelope of its GeoPackage binary header.
+    ///
+    /// Some readers use the envelope to filter features without parsing the
+    /// WKB. The envelope includes the Z and M ranges of geometries that have
+    /// them. Empty geometries are written without an envelope. The default is
+    /// `false` for `Gpkg::get_layer()` and `CreateLayerOptions::write_envelope`
+    /// for the layers created with `Gpkg::create_layer_with_options()`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let mut layer = gpkg.get_layer("points")?;
+    /// layer.set_write_envelope(true);
+    /// layer.insert(Point::new(1.0, 2.0), params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_write_envelope(&mut self, write_envelope: bool) {
+        self.write_envelope = write_envelope;
+    }
+
+    /// Set a transform applied to the X and Y of each coordinate of the
+    /// geometries written by this layer, e.g. to reproject them into the
+    /// layer's SRS.
+    ///
+    /// It's applied by every method taking a geometry (`insert()`,
+    /// `update()`, `upsert()`, `insert_many()`, `feature_builder()` and their
+    /// variants) before the geometry type check and the computation of the
+    /// extent and the envelope. Z and M values are kept as they are. It's not
+    /// applied to geometries copied as GeoPackage binary, i.e. by
+    /// `append_from()`, `Gpkg::copy_layer()` or a `Value::Geometry` property.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::{Gpkg, params};
+    ///
+    /// let gpkg = Gpkg::open("data/example.gpkg")?;
+    /// let mut layer = gpkg.get_layer("points")?;
+    /// // A simple equirectangular projection from degrees to meters.
+    /// layer.set_write_transform(|xy: &mut [f64; 2]| {
+    ///     xy[0] *= 111_320.0;
+    ///     xy[1] *= 110_540.0;
+    /// });
+    /// layer.insert(Point::new(139.7, 35.7), params!["alpha", 1])?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn set_write_transform<F>(&mut self, transform: F)
+    where
+        F: Fn(&mut [f64; 2]) + Send + Sync + 'static,
+    {
+        self.write_transform = Some(CoordTransform(std::sync::Arc::new(transform)));
+    }
+
+    /// Remove the transform set by `set_write_transform()`.
+    pub fn clear_write_transform(&mut self) {
+        self.write_transform = None;
+    }
+
+    // Collect the ordered property values, checking their count and types.
+    fn collect_properties<P>(&self, properties: P) -> Result<Vec<P::Item>>
+    where
+        P: IntoIterator,
+        P::Item: Borrow<Value>,
+    {
+        let properties: Vec<P::Item> = properties.into_iter().collect();
+        let expected = self.property_columns.len();
+        let got = properties.len();
+        if expected != got {
+            return Err(GpkgError::InvalidPropertyCount { expected, got });
+        }
+        self.check_property_types(properties.iter().map(Borrow::borrow))?;
+        Ok(properties)
+    }
+
+    fn check_property_types<'v, I>(&self, properties: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'v Value>,
+    {
+        if !self.strict_types {
+            return Ok(());
+        }
+        for (spec, value) in self.property_columns.iter().zip(properties) {
+            self.check_property_type(spec, value)?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn check_property_type(&self, spec: &ColumnSpec, value: &Value) -> Result<()> {
+        if self.strict_types {
+            check_value_type(spec, value)?;
+        }
+        Ok(())
+    }
+
+    // Run `f` inside a savepoint, which is rolled back if `f` fails.
+    fn with_savepoint<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        with_savepoint(&self.conn, f)
+    }
+
+    fn check_geometry_type(&self, wkb: &Wkb) -> Result<()> {
+        let actual = wkb.geometry_type();
+        let actual_dimension = wkb.dimension();
+        // GEOMETRY and GEOMETRYCOLLECTION are both read as GeometryCollection,
+        // so the declared name is checked.
+        let type_matches = is_assignable(&self.geometry_type_name, geometry_type_to_str(actual));
+        if type_matches && dimension_allowed_by_zm_flags(actual_dimension, self.z_flag, self.m_flag)
+        {
+            return Ok(());
+        }
+        Err(GpkgError::GeometryTypeMismatch {
+            expected: self.geometry_type,
+            expected_dimension: self.geometry_dimension,
+            actual,
+            actual_dimension,
+        })
+    }
+
+    /// Return the organization and its id of the layer's SRS.
+    #[cfg(feature = "geojson")]
+    pub(crate) fn srs_organization(&self) -> Result<Option<(String, i64)>> {
+        Ok(self
+            .conn
+            .query_row(
+                crate::ogc_sql::SQL_SELECT_SRS_ORGANIZATION,
+                [self.srs_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?)
+    }
+
+    pub(super) fn ensure_writable(&self) -> Result<()> {
+        if self.is_read_only {
+            return Err(GpkgError::ReadOnly);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn build_insert_sql(
+        layer_name: &str,
+        geometry_column: &str,
+        property_columns: &[ColumnSpec],
     ) -> String {
         let mut columns = Vec::with_capacity(property_columns.len() + 1);
         columns.push(format!(r#""{}""#, geometry_column));
         columns.extend(
             property_columns
                 .iter()
-                .map(|spec| format!(r#""{}""#, spec.name)),
+                .map(|spec| format!(r#""{}""#, spec.name)),
+        );
+
+        let placeholders = (1..=columns.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        sql_insert_feature(layer_name, &columns.join(","), &placeholders)
+    }
+
+    // Same as `build_insert_sql()`, but the primary key is bound to the last
+    // placeholder, in the same order as `build_update_sql()`.
+    pub(crate) fn build_insert_with_id_sql(
+        layer_name: &str,
+        geometry_column: &str,
+        primary_key_column: &str,
+        property_columns: &[ColumnSpec],
+    ) -> String {
+        let mut columns = Vec::with_capacity(property_columns.len() + 2);
+        columns.push(format!(r#""{}""#, geometry_column));
+        columns.extend(
+            property_columns
+                .iter()
+                .map(|spec| format!(r#""{}""#, spec.name)),
+        );
+        columns.push(format!(r#""{}""#, primary_key_column));
+
+        let placeholders = (1..=columns.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        sql_insert_feature(layer_name, &columns.join(","), &placeholders)
+    }
+
+    // Same column order as `build_insert_with_id_sql()`. On conflict, every
+    // column except the primary key is overwritten.
+    pub(crate) fn build_upsert_sql(
+        layer_name: &str,
+        geometry_column: &str,
+        primary_key_column: &str,
+        property_columns: &[ColumnSpec],
+    ) -> String {
+        let mut column_names = Vec::with_capacity(property_columns.len() + 2);
+        column_names.push(geometry_column);
+        column_names.extend(property_columns.iter().map(|spec| spec.name.as_str()));
+
+        let assignments = column_names
+            .iter()
+            .map(|name| format!(r#""{name}"=excluded."{name}""#))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        column_names.push(primary_key_column);
+        let columns = column_names
+            .iter()
+            .map(|name| format!(r#""{name}""#))
+            .collect::<Vec<String>>()
+            .join(",");
+        let placeholders = (1..=column_names.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        sql_upsert_feature(
+            layer_name,
+            &columns,
+            &placeholders,
+            primary_key_column,
+            &assignments,
+        )
+    }
+
+    pub(crate) fn build_update_sql(
+        layer_name: &str,
+        geometry_column: &str,
+        primary_key_column: &str,
+        property_columns: &[ColumnSpec],
+    ) -> String {
+        let mut column_names = Vec::with_capacity(property_columns.len() + 1);
+        column_names.push(geometry_column);
+        column_names.extend(property_columns.iter().map(|spec| spec.name.as_str()));
+
+        let assignments = column_names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| format!(r#""{}"=?{}"#, name, idx + 1))
+            .collect::<Vec<String>>()
+            .join(",");
+        let id_idx = column_names.len() + 1;
+
+        format!(
+            r#"UPDATE "{}" SET {} WHERE "{}"=?{}"#,
+            layer_name, assignments, primary_key_column, id_idx
+        )
+    }
+
+    pub(crate) fn build_update_properties_sql(
+        layer_name: &str,
+        primary_key_column: &str,
+        property_columns: &[ColumnSpec],
+    ) -> String {
+        sql_update_columns(
+            layer_name,
+            primary_key_column,
+            property_columns.iter().map(|spec| spec.name.as_str()),
+        )
+    }
+
+    pub(crate) fn build_update_geometry_sql(
+        layer_name: &str,
+        geometry_column: &str,
+        primary_key_column: &str,
+    ) -> String {
+        sql_update_columns(layer_name, primary_key_column, [geometry_column])
+    }
+
+    pub(crate) fn build_property_index_by_name(property_columns: &[ColumnSpec]) -> PropertyIndex {
+        PropertyIndex::new(property_columns.iter().map(|column| column.name.as_str()))
+    }
+
+    fn geom_from_geometry<G>(&self, geometry: G) -> Result<(Vec<u8>, Option<Bounds>)>
+    where
+        G: GeometryTrait<T = f64>,
+    {
+        self.encode_geometry(geometry, true)
+    }
+
+    pub(super) fn encode_geometry<G>(
+        &self,
+        geometry: G,
+        check_geometry: bool,
+    ) -> Result<(Vec<u8>, Option<Bounds>)>
+    where
+        G: GeometryTrait<T = f64>,
+    {
+        self.ensure_writable()?;
+
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
+        if let Some(transform) = &self.write_transform {
+            transform_wkb(&mut buf, &*transform.0)?;
+        }
+        let wkb = Wkb::try_new(&buf)?;
+        if check_geometry {
+            self.check_geometry_type(&wkb)?;
+        }
+        let bounds = bounds_from_geometry(&wkb);
+        let envelope = if self.write_envelope {
+            bounds.as_ref()
+        } else {
+            None
+        };
+        let geom = wkb_to_gpkg_geometry(wkb, self.srs_id, envelope)?;
+
+        Ok((geom, bounds))
+    }
+}
+
+// Whether two SRSs registered under different srs_ids are the same, judging
+// from the definition or the organization code. The undefined SRSs (`-1` and
+// `0`) are never the same as another one.
+fn is_same_srs(a: &SpatialRefSys, b: &SpatialRefSys) -> bool {
+    let defined = |srs: &SpatialRefSys| {
+        !srs.organization.eq_ignore_ascii_case("NONE") && srs.definition != "undefined"
+    };
+    if !defined(a) || !defined(b) {
+        return false;
+    }
+    a.definition == b.definition
+        || (a.organization.eq_ignore_ascii_case(&b.organization)
+            && a.organization_coordsys_id == b.organization_coordsys_id)
+}
+
+// The WHERE expression is embedded in a larger statement, so it must not be
+// able to terminate it and start another one.
+fn validate_where_clause(where_clause: &str) -> Result<()> {
+    if where_clause.contains(';') {
+        return Err(GpkgError::InvalidWhereClause {
+            clause: where_clause.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn extent_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Option<(f64, f64, f64, f64)>> {
+    let min_x: Option<f64> = row.get(0)?;
+    let min_y: Option<f64> = row.get(1)?;
+    let max_x: Option<f64> = row.get(2)?;
+    let max_y: Option<f64> = row.get(3)?;
+    match (min_x, min_y, max_x, max_y) {
+        (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+            Ok(Some((min_x, min_y, max_x, max_y)))
+        }
+        _ => Ok(None),
+    }
+}
+
+// The columns of `gpkg_contents` that complete `GpkgLayerMetadata`.
+pub(crate) struct ContentsMetadata {
+    pub(crate) identifier: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) last_change: String,
+    pub(crate) extent: Option<(f64, f64, f64, f64)>,
+}
+
+pub(crate) fn select_contents_metadata(
+    conn: &rusqlite::Connection,
+    layer_name: &str,
+) -> Result<ContentsMetadata> {
+    let contents = conn.query_row(SQL_SELECT_CONTENTS_METADATA, [layer_name], |row| {
+        let extent = match (row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?) {
+            (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                Some((min_x, min_y, max_x, max_y))
+            }
+            _ => None,
+        };
+        Ok(ContentsMetadata {
+            identifier: row.get(0)?,
+            description: row.get(1)?,
+            last_change: row.get(2)?,
+            extent,
+        })
+    })?;
+    Ok(contents)
+}
+
+fn select_data_column_constraint(
+    conn: &rusqlite::Connection,
+    name: &str,
+) -> Result<Option<DataColumnConstraint>> {
+    let has_constraints: bool =
+        conn.query_row(SQL_TABLE_EXISTS, ["gpkg_data_column_constraints"], |row| {
+            row.get(0)
+        })?;
+    if !has_constraints {
+        return Ok(None);
+    }
+
+    let mut stmt = conn.prepare(SQL_SELECT_DATA_COLUMN_CONSTRAINT)?;
+    let mut rows = stmt.query([name])?;
+    let mut values = Vec::new();
+    while let Some(row) = rows.next()? {
+        let constraint_type: String = row.get(0)?;
+        match constraint_type.as_str() {
+            "range" => {
+                return Ok(Some(DataColumnConstraint::Range {
+                    name: name.to_string(),
+                    min: row.get(2)?,
+                    min_is_inclusive: row.get::<_, Option<bool>>(3)?.unwrap_or(true),
+                    max: row.get(4)?,
+                    max_is_inclusive: row.get::<_, Option<bool>>(5)?.unwrap_or(true),
+                }));
+            }
+            "glob" => {
+                return Ok(Some(DataColumnConstraint::Glob {
+                    name: name.to_string(),
+                    pattern: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                }));
+            }
+            "enum" => {
+                if let Some(value) = row.get::<_, Option<String>>(1)? {
+                    values.push((value, row.get(6)?));
+                }
+            }
+            // Unknown constraint types are ignored.
+            _ => {}
+        }
+    }
+
+    if values.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(DataColumnConstraint::Enum {
+        name: name.to_string(),
+        values,
+    }))
+}
+
+fn write_data_column_constraint(
+    conn: &rusqlite::Connection,
+    constraint: &DataColumnConstraint,
+) -> Result<()> {
+    let name = constraint.name();
+    conn.execute(SQL_DELETE_DATA_COLUMN_CONSTRAINT, [name])?;
+    let mut stmt = conn.prepare(SQL_INSERT_DATA_COLUMN_CONSTRAINT)?;
+    match constraint {
+        DataColumnConstraint::Range {
+            min,
+            min_is_inclusive,
+            max,
+            max_is_inclusive,
+            ..
+        } => {
+            stmt.execute(rusqlite::params![
+                name,
+                "range",
+                None::<String>,
+                min,
+                min_is_inclusive,
+                max,
+                max_is_inclusive,
+                None::<String>,
+            ])?;
+        }
+        DataColumnConstraint::Enum { values, .. } => {
+            for (value, description) in values {
+                stmt.execute(rusqlite::params![
+                    name,
+                    "enum",
+                    value,
+                    None::<f64>,
+                    None::<bool>,
+                    None::<f64>,
+                    None::<bool>,
+                    description,
+                ])?;
+            }
+        }
+        DataColumnConstraint::Glob { pattern, .. } => {
+            stmt.execute(rusqlite::params![
+                name,
+                "glob",
+                pattern,
+                None::<f64>,
+                None::<bool>,
+                None::<f64>,
+                None::<bool>,
+                None::<String>,
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+// The metadata documents linked with the reference scope and table name.
+pub(crate) fn select_referenced_metadata(
+    conn: &rusqlite::Connection,
+    reference_scope: &str,
+    table_name: Option<&str>,
+) -> Result<Vec<MetadataEntry>> {
+    let has_metadata_reference: bool =
+        conn.query_row(SQL_TABLE_EXISTS, ["gpkg_metadata_reference"], |row| {
+            row.get(0)
+        })?;
+    if !has_metadata_reference {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(SQL_SELECT_REFERENCED_METADATA)?;
+    let entries = stmt
+        .query_map(rusqlite::params![reference_scope, table_name], |row| {
+            Ok(MetadataEntry {
+                id: row.get(0)?,
+                scope: row.get(1)?,
+                standard_uri: row.get(2)?,
+                mime_type: row.get(3)?,
+                metadata: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(entries)
+}
+
+// Fail with `GpkgError::DuplicateIdentifier` if another table in
+// `gpkg_contents` already uses the identifier.
+pub(crate) fn ensure_identifier_available(
+    conn: &rusqlite::Connection,
+    layer_name: &str,
+    identifier: &str,
+) -> Result<()> {
+    let conflicting: Option<String> = conn
+        .query_row(
+            SQL_SELECT_TABLE_BY_IDENTIFIER,
+            [identifier, layer_name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match conflicting {
+        Some(conflicting) => Err(GpkgError::DuplicateIdentifier {
+            identifier: identifier.to_string(),
+            layer_name: conflicting,
+        }),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn row_to_feature(
+    row: &rusqlite::Row<'_>,
+    layer_name: &str,
+    property_columns: &[ColumnSpec],
+    geometry_column: &str,
+    primary_key_column: &str,
+    property_index_by_name: &Rc<PropertyIndex>,
+) -> std::result::Result<GpkgFeature, rusqlite::Error> {
+    read_feature_row(
+        row,
+        property_columns,
+        geometry_column,
+        primary_key_column,
+        property_index_by_name,
+    )
+    .map_err(|err| feature_row_error(row, layer_name, PRIMARY_INDEX, err))
+}
+
+fn read_feature_row(
+    row: &rusqlite::Row<'_>,
+    property_columns: &[ColumnSpec],
+    geometry_column: &str,
+    primary_key_column: &str,
+    property_index_by_name: &Rc<PropertyIndex>,
+) -> std::result::Result<GpkgFeature, rusqlite::Error> {
+    // The primary key and the geometry are read as they are, without going
+    // through `Value`.
+    let id = match row.get_ref(PRIMARY_INDEX)? {
+        ValueRef::Integer(id) => id,
+        value => {
+            return Err(rusqlite::Error::InvalidColumnType(
+                PRIMARY_INDEX,
+                primary_key_column.to_string(),
+                value.data_type(),
+            ));
+        }
+    };
+    let geometry = match row.get_ref(GEOMETRY_INDEX)? {
+        ValueRef::Blob(bytes) => Some(bytes.to_vec()),
+        ValueRef::Null => None,
+        value => {
+            return Err(rusqlite::Error::InvalidColumnType(
+                GEOMETRY_INDEX,
+                geometry_column.to_string(),
+                value.data_type(),
+            ));
+        }
+    };
+
+    let mut properties = Vec::with_capacity(property_columns.len());
+    for idx in 2..property_columns.len() + 2 {
+        properties.push(value_from_row(row, idx, Some(id))?);
+    }
+
+    Ok(GpkgFeature {
+        id,
+        geometry,
+        properties,
+        property_index_by_name: property_index_by_name.clone(),
+    })
+}
+
+// Run `f` inside a savepoint on `conn`, which is rolled back if `f` fails.
+pub(crate) fn with_savepoint<T>(
+    conn: &rusqlite::Connection,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    conn.execute_batch(SQL_SAVEPOINT)?;
+    match f() {
+        Ok(result) => {
+            conn.execute_batch(SQL_RELEASE_SAVEPOINT)?;
+            Ok(result)
+        }
+        Err(err) => {
+            conn.execute_batch(SQL_ROLLBACK_TO_SAVEPOINT)?;
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GpkgLayer, extent_from_row};
+    use crate::GpkgError;
+    use crate::Result;
+    use crate::Value;
+    use crate::conversions::geometry_type_to_str;
+    use crate::gpkg::{Gpkg, GpkgFeature};
+    use crate::ogc_sql::SQL_SELECT_CONTENTS_EXTENT;
+    use crate::params;
+    use crate::sql_functions::bounds_from_geometry;
+    use crate::types::{
+        Collation, ColumnSpec, ColumnType, CreateLayerOptions, DataColumnConstraint,
+        DataColumnInfo, FeatureReadOptions, SortKey, SortOrder, UpsertOutcome, ZmFlag,
+    };
+    use geo_traits::GeometryTrait;
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        Polygon,
+    };
+    use std::str::FromStr;
+    use wkb::reader::{GeometryType, Wkb};
+    use wkt::Wkt;
+
+    fn generated_gpkg_path() -> &'static str {
+        "src/test/test_generated.gpkg"
+    }
+
+    fn gpkg_blob_from_geometry<G: GeometryTrait<T = f64>>(
+        geometry: G,
+        srs_id: u32,
+    ) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
+        let wkb = Wkb::try_new(&buf)?;
+        super::super::wkb_to_gpkg_geometry(wkb, srs_id, None)
+    }
+
+    fn assert_geometry_roundtrip<G: GeometryTrait<T = f64> + Clone>(
+        gpkg: &Gpkg,
+        layer_name: &str,
+        geometry_type: GeometryType,
+        geometry_dimension: wkb::reader::Dimension,
+        geometry: G,
+    ) -> Result<()> {
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            layer_name,
+            "geom",
+            geometry_type,
+            geometry_dimension,
+            4326,
+            &columns,
+        )?;
+
+        let expected_blob = gpkg_blob_from_geometry(geometry.clone(), 4326)?;
+        let mut expected_wkb_bytes = Vec::new();
+        wkb::writer::write_geometry(&mut expected_wkb_bytes, &geometry, &Default::default())?;
+        let expected_wkb = Wkb::try_new(&expected_wkb_bytes)?;
+
+        layer.insert(geometry, std::iter::empty::<&Value>())?;
+
+        let geom_blob: Vec<u8> = layer.conn.query_row(
+            &format!(r#"SELECT "geom" FROM "{}""#, layer_name),
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(geom_blob, expected_blob);
+
+        let features = layer.features()?;
+        let feature = features.first().expect("inserted feature");
+        let geom = feature.geometry()?;
+        assert_eq!(geom.geometry_type(), geometry_type);
+        assert_eq!(geom.dimension(), geometry_dimension);
+        assert_eq!(geom.buf(), expected_wkb.buf());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_generated_layers_and_counts() -> Result<()> {
+        let gpkg = Gpkg::open_read_only(generated_gpkg_path())?;
+        let mut layers = gpkg.list_layers()?;
+        layers.sort();
+        assert_eq!(layers, vec!["lines", "points", "polygons"]);
+
+        let points = gpkg.get_layer("points")?;
+        let lines = gpkg.get_layer("lines")?;
+        let polygons = gpkg.get_layer("polygons")?;
+
+        assert_eq!(points.features()?.len(), 5);
+        assert_eq!(lines.features()?.len(), 3);
+        assert_eq!(polygons.features()?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_geometry_and_properties_from_points() -> Result<()> {
+        let gpkg = Gpkg::open_read_only(generated_gpkg_path())?;
+        let layer = gpkg.get_layer("points")?;
+        let features = layer.features()?;
+        let feature = features.first().expect("first feature");
+
+        let geom = feature.geometry()?;
+        assert_eq!(geom.geometry_type(), GeometryType::Point);
+
+        assert_eq!(feature.id(), 1);
+        let name: String = feature
+            .property("name")
+            .ok_or_else(|| GpkgError::MissingProperty {
+                property: "name".to_string(),
+            })?
+            .try_into()?;
+        assert_eq!(name, "alpha");
+
+        let active: bool = feature
+            .property("active")
+            .ok_or_else(|| GpkgError::MissingProperty {
+                property: "active".to_string(),
+            })?
+            .try_into()?;
+        assert!(active);
+
+        let note = feature
+            .property("note")
+            .ok_or_else(|| GpkgError::MissingProperty {
+                property: "note".to_string(),
+            })?;
+        assert_eq!(note, Value::Text("first".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_envelope_written_by_gdal() -> Result<()> {
+        let gpkg = Gpkg::open_read_only(generated_gpkg_path())?;
+        for layer_name in ["lines", "polygons"] {
+            let features = gpkg.get_layer(layer_name)?.features()?;
+            assert!(!features.is_empty());
+            for feature in &features {
+                let envelope = feature.envelope()?.expect("envelope");
+                let bounds = bounds_from_geometry(&feature.geometry()?).expect("bounds");
+                assert_eq!(
+                    (
+                        envelope.min_x,
+                        envelope.max_x,
+                        envelope.min_y,
+                        envelope.max_y
+                    ),
+                    (bounds.minx, bounds.maxx, bounds.miny, bounds.maxy)
+                );
+                assert_eq!(envelope.min_z, None);
+                assert_eq!(envelope.min_m, None);
+            }
+        }
+
+        // GDAL doesn't write an envelope for points.
+        for feature in gpkg.get_layer("points")?.features()? {
+            assert_eq!(feature.envelope()?, None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn creates_layer_metadata() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+
+        gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let (geometry_type_name, srs_id, z, m): (String, u32, i8, i8) =
+            gpkg.conn.query_row(
+                "SELECT geometry_type_name, srs_id, z, m FROM gpkg_geometry_columns WHERE table_name = 'points'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )?;
+
+        assert_eq!(
+            geometry_type_name,
+            geometry_type_to_str(GeometryType::Point)
+        );
+        assert_eq!(srs_id, 4326);
+        assert_eq!(z, 0);
+        assert_eq!(m, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_and_updates_by_primary_key() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let point_a = Point::new(1.0, 2.0);
+        let name_a = "alpha".to_string();
+        let value_a = 7_i64;
+        layer.insert(point_a, params![name_a, value_a])?;
+        let id = layer.conn.last_insert_rowid();
+
+        let point_b = Point::new(4.0, 5.0);
+        let name_b = "beta".to_string();
+        let value_b = 9_i64;
+        layer.update(point_b, params![name_b, value_b], id)?;
+
+        let (geom_blob, name, value): (Vec<u8>, String, i64) = layer.conn.query_row(
+            "SELECT geom, name, value FROM points WHERE fid = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let expected_geom = gpkg_blob_from_geometry(Point::new(4.0, 5.0), 4326)?;
+        assert_eq!(geom_blob, expected_geom);
+        assert_eq!(name, "beta");
+        assert_eq!(value, 9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrips_all_geometry_types() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+
+        let line = LineString::from(vec![(0.0, 0.0), (1.5, 1.0), (2.0, 0.5)]);
+        let line_b = LineString::from(vec![(-1.0, -1.0), (-2.0, -3.0)]);
+        let exterior = LineString::from(vec![
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 0.0),
+        ]);
+        let polygon = Polygon::new(exterior, vec![]);
+        let polygon_b = Polygon::new(
+            LineString::from(vec![
+                (10.0, 10.0),
+                (12.0, 10.0),
+                (12.0, 12.0),
+                (10.0, 12.0),
+                (10.0, 10.0),
+            ]),
+            vec![],
+        );
+
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_points",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            Point::new(1.0, 2.0),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_lines",
+            GeometryType::LineString,
+            wkb::reader::Dimension::Xy,
+            line.clone(),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_polygons",
+            GeometryType::Polygon,
+            wkb::reader::Dimension::Xy,
+            polygon.clone(),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_multi_points",
+            GeometryType::MultiPoint,
+            wkb::reader::Dimension::Xy,
+            MultiPoint::from(vec![Point::new(1.0, 1.0), Point::new(2.0, 2.0)]),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_multi_lines",
+            GeometryType::MultiLineString,
+            wkb::reader::Dimension::Xy,
+            MultiLineString::new(vec![line.clone(), line_b]),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_multi_polygons",
+            GeometryType::MultiPolygon,
+            wkb::reader::Dimension::Xy,
+            MultiPolygon::new(vec![polygon.clone(), polygon_b]),
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_geometry_collections",
+            GeometryType::GeometryCollection,
+            wkb::reader::Dimension::Xy,
+            GeometryCollection::from(vec![
+                Geometry::Point(Point::new(-1.0, -2.0)),
+                Geometry::LineString(line),
+                Geometry::Polygon(polygon),
+            ]),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrips_z_and_m_dimensions() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+
+        let point_z = Wkt::from_str("POINT Z (1 2 3)")
+            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+        let line_m = Wkt::from_str("LINESTRING M (0 0 5, 1 1 6)")
+            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+        let polygon_zm = Wkt::from_str("POLYGON ZM ((0 0 1 10, 2 0 2 11, 2 2 3 12, 0 0 1 10))")
+            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_point_z",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xyz,
+            point_z,
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_linestring_m",
+            GeometryType::LineString,
+            wkb::reader::Dimension::Xym,
+            line_m,
+        )?;
+        assert_geometry_roundtrip(
+            &gpkg,
+            "rt_polygon_zm",
+            GeometryType::Polygon,
+            wkb::reader::Dimension::Xyzm,
+            polygon_zm,
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn rtree_updates_on_insert_update_delete() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "rtree_points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let point_a = Point::new(1.5, -2.0);
+        layer.insert(point_a, std::iter::empty::<&Value>())?;
+        let id = layer.conn.last_insert_rowid();
+
+        let (minx, maxx, miny, maxy): (f64, f64, f64, f64) = layer.conn.query_row(
+            "SELECT minx, maxx, miny, maxy FROM rtree_rtree_points_geom WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        assert_eq!(minx, 1.5);
+        assert_eq!(maxx, 1.5);
+        assert_eq!(miny, -2.0);
+        assert_eq!(maxy, -2.0);
+
+        let point_b = Point::new(-4.0, 6.25);
+        layer.update(point_b, std::iter::empty::<&Value>(), id)?;
+        let (minx, maxx, miny, maxy): (f64, f64, f64, f64) = layer.conn.query_row(
+            "SELECT minx, maxx, miny, maxy FROM rtree_rtree_points_geom WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        assert_eq!(minx, -4.0);
+        assert_eq!(maxx, -4.0);
+        assert_eq!(miny, 6.25);
+        assert_eq!(maxy, 6.25);
+
+        layer.truncate()?;
+        let count: i64 =
+            layer
+                .conn
+                .query_row("SELECT COUNT(*) FROM rtree_rtree_points_geom", [], |row| {
+                    row.get(0)
+                })?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gets_feature_by_primary_key() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        layer.insert(Point::new(1.0, 2.0), params!["alpha"])?;
+        layer.insert(Point::new(3.0, 4.0), params!["beta"])?;
+        let id = layer.conn.last_insert_rowid();
+
+        let feature = layer.get_feature(id)?.expect("inserted feature");
+        assert_eq!(feature.id(), id);
+        assert_eq!(feature.geometry()?.geometry_type(), GeometryType::Point);
+        let name: String = feature.property("name").unwrap().try_into()?;
+        assert_eq!(name, "beta");
+
+        assert!(layer.get_feature(id + 100)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn queries_features_in_bbox() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        layer.insert(Point::new(0.0, 0.0), params!["origin"])?;
+        layer.insert(Point::new(5.0, 5.0), params!["inside"])?;
+        layer.insert(Point::new(20.0, 20.0), params!["outside"])?;
+        layer.conn.execute(
+            r#"INSERT INTO points (geom, name) VALUES (NULL, 'null geom')"#,
+            [],
+        )?;
+
+        let names = |features: Vec<crate::GpkgFeature>| -> Result<Vec<String>> {
+            features
+                .iter()
+                .map(|f| f.property("name").unwrap().try_into())
+                .collect()
+        };
+
+        let with_rtree = layer.features_in_bbox(-1.0, -1.0, 10.0, 10.0)?;
+        assert_eq!(names(with_rtree)?, vec!["origin", "inside"]);
+
+        // Inverted bbox returns nothing.
+        assert!(layer.features_in_bbox(10.0, 10.0, -1.0, -1.0)?.is_empty());
+
+        // Without the rtree, fall back to a full scan.
+        layer.conn.execute_batch("DROP TABLE rtree_points_geom")?;
+        let without_rtree = layer.features_in_bbox(-1.0, -1.0, 10.0, 10.0)?;
+        assert_eq!(names(without_rtree)?, vec!["origin", "inside"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn streams_features_in_bbox() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        // More matches than a single batch, with some features outside.
+        gpkg.with_transaction(|_| {
+            for i in 0..2500 {
+                let x = if i % 10 == 0 { 100.0 } else { (i % 50) as f64 };
+                layer.insert(Point::new(x, x), std::iter::empty::<&Value>())?;
+            }
+            Ok(())
+        })?;
+
+        for use_rtree in [true, false] {
+            if !use_rtree {
+                layer.drop_spatial_index()?;
+            }
+            let ids = layer
+                .features_in_bbox_iter(0.0, 0.0, 60.0, 60.0)?
+                .map(|feature| feature.map(|f| f.id()))
+                .collect::<Result<Vec<_>>>()?;
+            assert_eq!(ids.len(), 2250);
+            assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(ids.iter().all(|id| (id - 1) % 10 != 0));
+
+            let collected = layer.features_in_bbox(0.0, 0.0, 60.0, 60.0)?;
+            assert_eq!(collected.iter().map(|f| f.id()).collect::<Vec<_>>(), ids);
+
+            assert!(
+                layer
+                    .features_in_bbox_iter(60.0, 60.0, 0.0, 0.0)?
+                    .next()
+                    .is_none()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn cached_read_statements_allow_schema_changes() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let mut layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        for i in 0..10 {
+            layer.insert(Point::new(i as f64, i as f64), params![i])?;
+        }
+
+        // Run every read twice so that the second run uses the cached statements.
+        for _ in 0..2 {
+            assert_eq!(layer.features()?.len(), 10);
+            assert_eq!(layer.features_in_bbox(2.5, 2.5, 5.5, 5.5)?.len(), 3);
+            assert_eq!(layer.features_where(r#""value" < ?"#, params![4])?.len(), 4);
+            assert!(layer.get_feature(1)?.is_some());
+            let mut batches = layer.features_batch(4)?;
+            assert_eq!(batches.next().transpose()?.map(|b| b.len()), Some(4));
+        }
+
+        // The cached statements don't block schema changes or VACUUM.
+        layer.add_column(&ColumnSpec {
+            name: "label".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        })?;
+        assert_eq!(layer.features()?[0].property("label"), Some(Value::Null));
+        layer.drop_spatial_index()?;
+        assert_eq!(layer.features_in_bbox(2.5, 2.5, 5.5, 5.5)?.len(), 3);
+        gpkg.vacuum()?;
+        gpkg.delete_layer("points")?;
+        assert!(gpkg.list_layers()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reads_features_without_properties() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["a"])?;
+        layer.conn.execute(
+            r#"INSERT INTO points (geom, name) VALUES (NULL, 'null geom')"#,
+            [],
+        )?;
+
+        let options = FeatureReadOptions {
+            skip_properties: true,
+        };
+        let features = layer.features_with_options(&options)?;
+        assert_eq!(
+            features.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(features[0].geometry()?.geometry_type(), GeometryType::Point);
+        assert!(features[1].geometry_opt()?.is_none());
+        assert!(features[0].properties().is_empty());
+        assert_eq!(features[0].property_names().count(), 0);
+        assert_eq!(features[0].property("name"), None);
+
+        // The full read is unaffected.
+        assert_eq!(
+            layer.features()?[0].property("name"),
+            Some(Value::Text("a".to_string()))
+        );
+
+        // A geometry that isn't a BLOB is still an error naming the feature.
+        // (The rtree triggers would reject it.)
+        layer.drop_spatial_index()?;
+        layer
+            .conn
+            .execute(r#"UPDATE points SET geom = 'not a blob' WHERE fid = 2"#, [])?;
+        let err = layer
+            .features_with_options(&options)
+            .err()
+            .expect("TEXT geometry");
+        assert!(
+            matches!(&err, GpkgError::Feature { id: Some(2), .. }),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn counts_features() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        assert_eq!(layer.count()?, 0);
+
+        for i in 0..5 {
+            layer.insert(Point::new(i as f64, i as f64), params![i])?;
+        }
+        assert_eq!(layer.count()?, 5);
+        assert_eq!(layer.count_where(r#""value" >= ?1"#, params![3])?, 2);
+
+        let read_only = Gpkg::open_read_only(generated_gpkg_path())?;
+        assert_eq!(read_only.get_layer("points")?.count()?, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filters_features_with_where_clause() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        for i in 0..10 {
+            layer.insert(Point::new(i as f64, i as f64), params![i])?;
+        }
+
+        let values = |features: &[GpkgFeature]| -> Result<Vec<i64>> {
+            features
+                .iter()
+                .map(|f| f.property("value").unwrap().try_into())
+                .collect()
+        };
+
+        let filtered = layer.features_where(r#""value" >= ?1 AND "value" < ?2"#, params![3, 8])?;
+        assert_eq!(values(&filtered)?, vec![3, 4, 5, 6, 7]);
+
+        let mut batched = Vec::new();
+        for batch in
+            layer.features_batch_where(2, r#""value" >= ?1 AND "value" < ?2"#, params![3, 8])?
+        {
+            batched.extend(values(&batch?)?);
+        }
+        assert_eq!(batched, vec![3, 4, 5, 6, 7]);
+
+        assert!(
+            layer
+                .features_where(r#""value" > ?"#, params![100])?
+                .is_empty()
+        );
+
+        let injected = r#""value" > 0; DROP TABLE "points""#;
+        assert!(matches!(
+            layer.features_where(injected, params![]),
+            Err(GpkgError::InvalidWhereClause { .. })
+        ));
+        assert!(matches!(
+            layer.count_where(injected, params![]),
+            Err(GpkgError::InvalidWhereClause { .. })
+        ));
+        assert_eq!(layer.count()?, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn orders_features_stably() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        for (name, value) in [("b", 1), ("a", 2), ("b", 3), ("a", 4), ("c", 5), ("b", 6)] {
+            layer.insert(Point::new(0.0, 0.0), params![name, value])?;
+        }
+        let ids = |features: Vec<GpkgFeature>| features.iter().map(|f| f.id()).collect::<Vec<_>>();
+
+        // Duplicate names keep the primary key order.
+        let by_name_desc = ids(layer.features_ordered(&[("name", SortOrder::Desc)])?);
+        assert_eq!(by_name_desc, vec![5, 1, 3, 6, 2, 4]);
+        assert_eq!(
+            ids(layer.features_ordered(&[("name", SortOrder::Asc), ("fid", SortOrder::Desc)])?),
+            vec![4, 2, 6, 3, 1, 5]
+        );
+
+        // Batches follow the same order.
+        let mut batched = Vec::new();
+        let mut sizes = Vec::new();
+        for batch in layer.features_batch_ordered(4, &[("name", SortOrder::Desc)])? {
+            let batch = batch?;
+            sizes.push(batch.len());
+            batched.extend(ids(batch));
+        }
+        assert_eq!(sizes, vec![4, 2]);
+        assert_eq!(batched, by_name_desc);
+
+        // Only known columns are accepted.
+        for column in ["geom", r#"name" DESC; DROP TABLE points; --"#] {
+            assert!(matches!(
+                layer.features_ordered(&[(column, SortOrder::Asc)]),
+                Err(GpkgError::MissingProperty { property }) if property == column
+            ));
+        }
+        assert!(
+            layer
+                .features_batch_ordered(4, &[("missing", SortOrder::Asc)])
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn orders_batches_by_collation_and_nulls() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let names = [
+            Some("b"),
+            Some("A"),
+            Some("a"),
+            Some("B"),
+            None,
+            Some("c"),
+            Some("C"),
+            None,
+        ];
+        for name in names {
+            layer.insert(Point::new(0.0, 0.0), params![name])?;
+        }
+        let ids = |features: Vec<GpkgFeature>| features.iter().map(|f| f.id()).collect::<Vec<_>>();
+        let batched = |order_by: &[SortKey<'_>]| -> Result<Vec<Vec<i64>>> {
+            layer
+                .features_batch_ordered(3, order_by)?
+                .map(|batch| Ok(ids(batch?)))
+                .collect()
+        };
+
+        // NULLs sort last in descending order, and batches split the groups of
+        // names equal under NOCASE.
+        let nocase_desc = [SortKey::from(("name", SortOrder::Desc, Collation::NoCase))];
+        assert_eq!(
+            ids(layer.features_ordered(&nocase_desc)?),
+            vec![6, 7, 1, 4, 2, 3, 5, 8]
+        );
+        assert_eq!(
+            batched(&nocase_desc)?,
+            vec![vec![6, 7, 1], vec![4, 2, 3], vec![5, 8]]
+        );
+
+        // Mixed directions, with NULLs first in ascending order.
+        let mixed = [
+            SortKey::from(("name", SortOrder::Asc, Collation::NoCase)),
+            SortKey::from(("fid", SortOrder::Desc)),
+        ];
+        assert_eq!(
+            batched(&mixed)?.concat(),
+            ids(layer.features_ordered(&mixed)?)
+        );
+        assert_eq!(batched(&mixed)?.concat(), vec![8, 5, 3, 2, 4, 1, 7, 6]);
+
+        // Without a collation, the column's BINARY one puts uppercase first.
+        let binary = [SortKey::from(("name", SortOrder::Asc))];
+        assert_eq!(batched(&binary)?.concat(), vec![5, 8, 2, 4, 7, 3, 1, 6]);
+
+        // Deleting rows already read doesn't shift the following batches.
+        let mut batches = layer.features_batch_ordered(3, &nocase_desc)?;
+        assert_eq!(ids(batches.next().expect("first batch")?), vec![6, 7, 1]);
+        layer.delete(6)?;
+        layer.delete(7)?;
+        let rest = batches
+            .map(|batch| Ok(ids(batch?)))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(rest, vec![vec![4, 2, 3], vec![5, 8]]);
+        Ok(())
+    }
+
+    #[test]
+    fn computes_layer_extent() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        assert_eq!(layer.extent()?, None);
+
+        layer.insert(Point::new(1.0, -2.0), std::iter::empty::<&Value>())?;
+        layer.insert(Point::new(-3.0, 4.0), std::iter::empty::<&Value>())?;
+        assert_eq!(layer.extent()?, Some((-3.0, -2.0, 1.0, 4.0)));
+
+        // Without the rtree, fall back to scanning the geometries.
+        layer.conn.execute_batch("DROP TABLE rtree_points_geom")?;
+        assert_eq!(layer.extent()?, Some((-3.0, -2.0, 1.0, 4.0)));
+
+        // Values stored in gpkg_contents take precedence.
+        layer.conn.execute(
+            "UPDATE gpkg_contents SET min_x = 0, min_y = 0, max_x = 10, max_y = 10 WHERE table_name = 'points'",
+            [],
+        )?;
+        assert_eq!(layer.extent()?, Some((0.0, 0.0, 10.0, 10.0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_contents_extent_up_to_date() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let stored_extent = |layer: &GpkgLayer| -> Result<Option<(f64, f64, f64, f64)>> {
+            Ok(layer
+                .conn
+                .query_row(SQL_SELECT_CONTENTS_EXTENT, ["points"], extent_from_row)?)
+        };
+        assert_eq!(stored_extent(&layer)?, None);
+
+        layer.insert(Point::new(1.0, -2.0), std::iter::empty::<&Value>())?;
+        assert_eq!(stored_extent(&layer)?, Some((1.0, -2.0, 1.0, -2.0)));
+        layer.insert(Point::new(-3.0, 4.0), std::iter::empty::<&Value>())?;
+        layer.insert(Point::new(0.0, 5.0), std::iter::empty::<&Value>())?;
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 1.0, 5.0)));
+
+        // Updating grows the extent.
+        layer.update(Point::new(10.0, 0.0), std::iter::empty::<&Value>(), 3)?;
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 10.0, 5.0)));
+
+        // Deleting a feature on the edge recomputes it.
+        assert_eq!(layer.delete(3)?, 1);
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 1.0, 4.0)));
+        assert_eq!(layer.delete(3)?, 0);
+
+        // Deleting a feature inside can't shrink it, so it's not recomputed
+        // (the stale extent written here stays).
+        layer.insert(Point::new(0.0, 0.0), std::iter::empty::<&Value>())?;
+        let inside = layer.conn.last_insert_rowid();
+        layer.conn.execute(
+            "UPDATE gpkg_contents SET max_x = 50 WHERE table_name = 'points'",
+            [],
+        )?;
+        assert_eq!(layer.delete(inside)?, 1);
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 50.0, 4.0)));
+        layer.update_extent()?;
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 1.0, 4.0)));
+
+        // A stale extent written by another tool can be recomputed.
+        layer.conn.execute(
+            "UPDATE gpkg_contents SET min_x = 0, min_y = 0, max_x = 100, max_y = 100 WHERE table_name = 'points'",
+            [],
+        )?;
+        layer.update_extent()?;
+        assert_eq!(stored_extent(&layer)?, Some((-3.0, -2.0, 1.0, 4.0)));
+
+        layer.truncate()?;
+        assert_eq!(stored_extent(&layer)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn updates_last_change_on_write() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let last_change = |layer: &GpkgLayer| -> Result<String> {
+            Ok(layer.conn.query_row(
+                "SELECT last_change FROM gpkg_contents WHERE table_name = 'points'",
+                [],
+                |row| row.get(0),
+            )?)
+        };
+
+        // Pretend the layer was created long ago so that the timestamps differ.
+        let created = "2000-01-01T00:00:00.000Z";
+        let reset = |layer: &GpkgLayer| -> Result<()> {
+            layer.conn.execute(
+                "UPDATE gpkg_contents SET last_change = ?1 WHERE table_name = 'points'",
+                [created],
+            )?;
+            Ok(())
+        };
+
+        reset(&layer)?;
+        layer.insert(Point::new(1.0, 2.0), std::iter::empty::<&Value>())?;
+        let changed = last_change(&layer)?;
+        assert_ne!(changed, created);
+        assert!(changed.ends_with('Z'), "{changed}");
+
+        reset(&layer)?;
+        layer.update(Point::new(3.0, 4.0), std::iter::empty::<&Value>(), 1)?;
+        assert_ne!(last_change(&layer)?, created);
+
+        reset(&layer)?;
+        layer.delete(1)?;
+        assert_ne!(last_change(&layer)?, created);
+
+        reset(&layer)?;
+        layer.truncate()?;
+        assert_ne!(last_change(&layer)?, created);
+
+        Ok(())
+    }
+
+    #[test]
+    fn adds_and_drops_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let mut layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(0.0, 0.0), params!["a"])?;
+
+        layer.add_column(&ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        })?;
+        assert_eq!(layer.property_columns.len(), 2);
+        assert!(matches!(
+            layer.insert(Point::new(1.0, 1.0), params!["b"]),
+            Err(GpkgError::InvalidPropertyCount {
+                expected: 2,
+                got: 1
+            })
+        ));
+        layer.insert(Point::new(1.0, 1.0), params!["b", 2])?;
+        layer.update(Point::new(1.0, 1.0), params!["b", 3], 2)?;
+
+        let features = layer.features()?;
+        assert_eq!(features[0].property("value"), Some(Value::Null));
+        assert_eq!(features[1].property("value"), Some(Value::Integer(3)));
+
+        // The schema change is visible when the layer is loaded again.
+        let reloaded = gpkg.get_layer("points")?;
+        assert_eq!(reloaded.property_columns.len(), 2);
+        assert_eq!(
+            reloaded.property_columns[1].column_type,
+            ColumnType::Integer
+        );
+
+        layer.drop_column("name")?;
+        assert_eq!(layer.property_columns.len(), 1);
+        layer.insert(Point::new(2.0, 2.0), params![4])?;
+        let feature = layer.get_feature(3)?.expect("feature");
+        assert_eq!(feature.property("name"), None);
+        assert_eq!(feature.property("value"), Some(Value::Integer(4)));
+
+        assert!(matches!(
+            layer.drop_column("geom"),
+            Err(GpkgError::CannotDropColumn { .. })
+        ));
+        assert!(matches!(
+            layer.drop_column("fid"),
+            Err(GpkgError::CannotDropColumn { .. })
+        ));
+        assert!(matches!(
+            layer.drop_column("missing"),
+            Err(GpkgError::MissingProperty { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_with_explicit_id() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        layer.insert_with_id(10, Point::new(10.0, 10.0), params!["ten"])?;
+        layer.insert_with_id(5, Point::new(5.0, 5.0), params!["five"])?;
+        assert!(matches!(
+            layer.insert_with_id(5, Point::new(0.0, 0.0), params!["again"]),
+            Err(GpkgError::DuplicatePrimaryKey { id: 5 })
+        ));
+
+        let features = layer.features()?;
+        assert_eq!(
+            features.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            vec![5, 10]
+        );
+        assert_eq!(
+            features[0].property("name"),
+            Some(Value::Text("five".to_string()))
         );
 
-        let placeholders = (1..=columns.len())
-            .map(|i| format!("?{i}"))
-            .collect::<Vec<String>>()
-            .join(",");
+        // The rtree is kept in sync.
+        let in_bbox = layer.features_in_bbox(9.0, 9.0, 11.0, 11.0)?;
+        assert_eq!(in_bbox.len(), 1);
+        assert_eq!(in_bbox[0].id(), 10);
+
+        // Plain inserts continue after the largest id.
+        layer.insert(Point::new(0.0, 0.0), params!["next"])?;
+        assert!(layer.get_feature(11)?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_and_updates_named_properties() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "note".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        // Order of the pairs does not matter and missing columns are NULL.
+        layer.insert_named(
+            Point::new(1.0, 2.0),
+            [("value", Value::from(7)), ("name", Value::from("alpha"))],
+        )?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.property("name"), Some(Value::from("alpha")));
+        assert_eq!(feature.property("value"), Some(Value::Integer(7)));
+        assert_eq!(feature.property("note"), Some(Value::Null));
+
+        let err = layer
+            .insert_named(Point::new(0.0, 0.0), [("missing", Value::from(1))])
+            .expect_err("unknown column");
+        assert!(matches!(err, GpkgError::MissingProperty { property } if property == "missing"));
+        assert_eq!(layer.count()?, 1);
+
+        layer.update_named(1, [("note", Value::from("patched"))])?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.property("name"), Some(Value::from("alpha")));
+        assert_eq!(feature.property("value"), Some(Value::Integer(7)));
+        assert_eq!(feature.property("note"), Some(Value::from("patched")));
+        assert_eq!(layer.features_in_bbox(0.5, 1.5, 1.5, 2.5)?.len(), 1);
+
+        assert!(matches!(
+            layer.update_named(1, [("missing", Value::Null)]),
+            Err(GpkgError::MissingProperty { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn updates_properties_and_geometry_separately() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["alpha", 1])?;
+
+        layer.update_properties(1, params!["beta", 2])?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.property("name"), Some(Value::from("beta")));
+        assert_eq!(feature.property("value"), Some(Value::Integer(2)));
+        assert_eq!(layer.features_in_bbox(0.5, 1.5, 1.5, 2.5)?.len(), 1);
+
+        layer.update_geometry(1, Point::new(10.0, 20.0))?;
+        let feature = layer.get_feature(1)?.expect("feature");
+        assert_eq!(feature.property("name"), Some(Value::from("beta")));
+        assert_eq!(feature.property("value"), Some(Value::Integer(2)));
+        // The rtree follows the new geometry.
+        assert!(layer.features_in_bbox(0.5, 1.5, 1.5, 2.5)?.is_empty());
+        assert_eq!(layer.features_in_bbox(9.5, 19.5, 10.5, 20.5)?.len(), 1);
+        assert_eq!(layer.extent()?, Some((1.0, 2.0, 10.0, 20.0)));
+
+        assert!(matches!(
+            layer.update_properties(99, params!["gamma", 3]),
+            Err(GpkgError::FeatureNotFound { id: 99 })
+        ));
+        assert!(matches!(
+            layer.update_geometry(99, Point::new(0.0, 0.0)),
+            Err(GpkgError::FeatureNotFound { id: 99 })
+        ));
+        assert!(matches!(
+            layer.update_properties(1, params!["gamma"]),
+            Err(GpkgError::InvalidPropertyCount {
+                expected: 2,
+                got: 1
+            })
+        ));
+
+        let read_only = Gpkg::open_read_only(generated_gpkg_path())?.get_layer("points")?;
+        assert!(matches!(
+            read_only.update_properties(1, params!["gamma", 3]),
+            Err(GpkgError::ReadOnly)
+        ));
+        assert!(matches!(
+            read_only.update_geometry(1, Point::new(0.0, 0.0)),
+            Err(GpkgError::ReadOnly)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn inserts_many_features_in_one_transaction() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let features =
+            (0..10_000).map(|i| (Point::new(i as f64, -(i as f64)), vec![Value::from(i)]));
+        assert_eq!(layer.insert_many(features)?, 10_000);
+        assert_eq!(layer.count()?, 10_000);
+        assert_eq!(layer.extent()?, Some((0.0, -9999.0, 9999.0, 0.0)));
+        assert_eq!(layer.features_in_bbox(4.5, -5.5, 5.5, -4.5)?.len(), 1);
 
-        sql_insert_feature(layer_name, &columns.join(","), &placeholders)
+        // A bad element rolls back the whole batch and reports its index.
+        let features = (0..10).map(|i| {
+            let properties = if i == 5 { vec![] } else { vec![Value::from(i)] };
+            (Point::new(0.0, 0.0), properties)
+        });
+        let err = layer.insert_many(features).expect_err("bad element");
+        match err {
+            GpkgError::InsertManyFailed { index, source } => {
+                assert_eq!(index, 5);
+                assert!(matches!(
+                    *source,
+                    GpkgError::InvalidPropertyCount {
+                        expected: 1,
+                        got: 0
+                    }
+                ));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert_eq!(layer.count()?, 10_000);
+
+        // Nesting inside an outer transaction uses a savepoint.
+        gpkg.with_transaction(|_| {
+            layer.insert_many([(Point::new(0.0, 0.0), vec![Value::from(-1)])])?;
+            assert!(
+                layer
+                    .insert_many([(Point::new(0.0, 0.0), Vec::<Value>::new())])
+                    .is_err()
+            );
+            Ok(())
+        })?;
+        assert_eq!(layer.count()?, 10_001);
+
+        Ok(())
     }
 
-    pub(crate) fn build_update_sql(
-        layer_name: &str,
-        geometry_column: &str,
-        primary_key_column: &str,
-        property_columns: &[ColumnSpec],
-    ) -> String {
-        let mut column_names = Vec::with_capacity(property_columns.len() + 1);
-        column_names.push(geometry_column);
-        column_names.extend(property_columns.iter().map(|spec| spec.name.as_str()));
+    #[test]
+    fn computes_column_stats_and_distinct_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "population".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "region".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "cities",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert_many([
+            (Point::new(0.0, 0.0), params![10, "north"]),
+            (Point::new(1.0, 0.0), params![30, "south"]),
+            (Point::new(2.0, 0.0), params![Value::Null, "north"]),
+            (Point::new(3.0, 0.0), params![20, Value::Null]),
+        ])?;
 
-        let assignments = column_names
-            .iter()
-            .enumerate()
-            .map(|(idx, name)| format!(r#""{}"=?{}"#, name, idx + 1))
-            .collect::<Vec<String>>()
-            .join(",");
-        let id_idx = column_names.len() + 1;
+        let stats = layer.column_stats("population")?;
+        assert_eq!(stats.min, Some(Value::Integer(10)));
+        assert_eq!(stats.max, Some(Value::Integer(30)));
+        assert_eq!(stats.mean, Some(20.0));
+        assert_eq!((stats.null_count, stats.distinct_count), (1, 3));
 
-        format!(
-            r#"UPDATE "{}" SET {} WHERE "{}"=?{}"#,
-            layer_name, assignments, primary_key_column, id_idx
-        )
-    }
+        let stats = layer.column_stats("region")?;
+        assert_eq!(stats.min, Some(Value::from("north")));
+        assert_eq!(stats.max, Some(Value::from("south")));
+        assert_eq!(stats.mean, None);
+        assert_eq!((stats.null_count, stats.distinct_count), (1, 2));
 
-    pub(crate) fn build_property_index_by_name(
-        property_columns: &[ColumnSpec],
-    ) -> HashMap<String, usize> {
-        let mut property_index_by_name = HashMap::with_capacity(property_columns.len());
-        for (idx, column) in property_columns.iter().enumerate() {
-            property_index_by_name.insert(column.name.clone(), idx);
-        }
-        property_index_by_name
+        assert_eq!(
+            layer.distinct_values("region", None)?,
+            vec![Value::from("north"), Value::from("south")]
+        );
+        assert_eq!(
+            layer.distinct_values("population", Some(2))?,
+            vec![Value::Integer(10), Value::Integer(20)]
+        );
+        assert_eq!(layer.column_stats("fid")?.distinct_count, 4);
+
+        let err = layer.column_stats("missing").expect_err("unknown column");
+        assert!(matches!(err, GpkgError::MissingProperty { .. }), "{err}");
+        let err = layer
+            .distinct_values("geom", None)
+            .expect_err("geometry column");
+        assert!(
+            matches!(err, GpkgError::UnsupportedColumnType { .. }),
+            "{err}"
+        );
+
+        // An empty layer has no min, max or mean.
+        layer.truncate()?;
+        assert_eq!(layer.column_stats("population")?, Default::default());
+        Ok(())
     }
 
-    fn geom_from_geometry<G>(&self, geometry: G) -> Result<Vec<u8>>
-    where
-        G: GeometryTrait<T = f64>,
-    {
-        self.ensure_writable()?;
+    #[test]
+    fn cancelling_insert_many_rolls_back() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &Vec::<ColumnSpec>::new(),
+        )?;
+        let features = || (0..1000).map(|i| (Point::new(i as f64, 0.0), Vec::<Value>::new()));
 
-        let mut buf = Vec::new();
-        wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
-        let wkb = Wkb::try_new(&buf)?;
-        let geom = wkb_to_gpkg_geometry(wkb, self.srs_id)?;
+        let mut calls = Vec::new();
+        let err = layer
+            .insert_many_with_progress(features(), 50, |done| {
+                calls.push(done);
+                if done >= 100 {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
+                }
+            })
+            .expect_err("cancelled");
+        assert!(matches!(err, GpkgError::Cancelled), "{err}");
+        assert_eq!(calls, vec![50, 100]);
+        assert_eq!(layer.count()?, 0);
+        assert_eq!(layer.extent()?, None);
 
-        Ok(geom)
+        let mut calls = Vec::new();
+        let inserted = layer.insert_many_with_progress(features(), 300, |done| {
+            calls.push(done);
+            std::ops::ControlFlow::Continue(())
+        })?;
+        assert_eq!(inserted, 1000);
+        assert_eq!(calls, vec![300, 600, 900, 1000]);
+        assert_eq!(layer.count()?, 1000);
+        Ok(())
     }
-}
 
-pub(crate) fn row_to_feature(
-    row: &rusqlite::Row<'_>,
-    property_columns: &[ColumnSpec],
-    geometry_column: &str,
-    primary_key_column: &str,
-    property_index_by_name: &Rc<HashMap<String, usize>>,
-) -> std::result::Result<GpkgFeature, rusqlite::Error> {
-    let mut id: Option<i64> = None;
-    let mut geometry: Option<Vec<u8>> = None;
-    let mut properties = Vec::with_capacity(property_columns.len());
-    let row_len = property_columns.len() + 2;
-
-    for idx in 0..row_len {
-        let value_ref = row.get_ref(idx)?;
-        let value = Value::from(value_ref);
-        let name = if idx == GEOMETRY_INDEX {
-            geometry_column
-        } else if idx == PRIMARY_INDEX {
-            primary_key_column
-        } else {
-            property_columns[idx - 2].name.as_str()
-        };
-
-        if idx == GEOMETRY_INDEX {
-            match value {
-                Value::Blob(bytes) => geometry = Some(bytes),
-                Value::Null => geometry = None,
-                _ => {
-                    return Err(rusqlite::Error::InvalidColumnType(
-                        idx,
-                        name.to_string(),
-                        value_ref.data_type(),
-                    ));
-                }
-            }
-        } else if idx == PRIMARY_INDEX {
-            match &value {
-                Value::Integer(value) => id = Some(*value),
-                _ => {
-                    return Err(rusqlite::Error::InvalidColumnType(
-                        idx,
-                        name.to_string(),
-                        value_ref.data_type(),
-                    ));
-                }
-            }
-        } else {
-            properties.push(value);
-        }
+    #[test]
+    fn verifies_and_rebuilds_spatial_index() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let features = (1..=5).map(|i| {
+            (
+                Point::new(i as f64 * 0.1, 1e6 + i as f64),
+                Vec::<Value>::new(),
+            )
+        });
+        layer.insert_many(features)?;
+        layer.insert_unchecked(Point::new(f64::NAN, f64::NAN), params![])?;
+
+        let report = layer.verify_spatial_index()?;
+        assert!(report.is_consistent(), "{report:?}");
+        assert_eq!((report.feature_count, report.rtree_count), (5, 5));
+
+        // Simulate edits made without the rtree triggers.
+        gpkg.conn.execute_batch(
+            "DELETE FROM rtree_points_geom WHERE id = 1;
+             INSERT INTO rtree_points_geom VALUES (99, 0, 0, 0, 0);
+             UPDATE rtree_points_geom SET maxy = maxy + 10 WHERE id = 3;",
+        )?;
+        let report = layer.verify_spatial_index()?;
+        assert!(!report.is_consistent());
+        assert_eq!((report.feature_count, report.rtree_count), (5, 5));
+        assert_eq!(report.missing_ids, vec![1]);
+        assert_eq!(report.orphan_ids, vec![99]);
+        assert_eq!(report.bounds_mismatches, vec![3]);
+
+        layer.rebuild_spatial_index()?;
+        let report = layer.verify_spatial_index()?;
+        assert!(report.is_consistent(), "{report:?}");
+        assert_eq!(report.rtree_count, 5);
+
+        layer.drop_spatial_index()?;
+        let err = layer.verify_spatial_index().expect_err("no spatial index");
+        assert!(matches!(err, GpkgError::MissingSpatialIndex { .. }));
+        Ok(())
     }
 
-    let id = id.ok_or_else(|| {
-        rusqlite::Error::InvalidColumnType(
-            PRIMARY_INDEX,
-            primary_key_column.to_string(),
-            Type::Null,
-        )
-    })?;
+    #[test]
+    fn blob_property_roundtrip() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "photo".to_string(),
+            column_type: ColumnType::Blob,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let bytes = vec![0x89, b'P', b'N', b'G', 0x00, 0xff];
+        layer.insert(Point::new(1.0, 2.0), params![bytes.clone()])?;
+        layer.insert(Point::new(3.0, 4.0), params![Value::Null])?;
 
-    Ok(GpkgFeature {
-        id,
-        geometry,
-        properties,
-        property_index_by_name: property_index_by_name.clone(),
-    })
-}
+        let features = layer.features()?;
+        assert_eq!(features[0].get::<Vec<u8>>("photo")?, bytes);
+        let photo = features[0].property("photo").expect("photo column");
+        assert_eq!(<&[u8]>::try_from(&photo)?, bytes.as_slice());
+        assert_eq!(features[1].get_opt::<Vec<u8>>("photo")?, None);
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::GpkgError;
-    use crate::Result;
-    use crate::Value;
-    use crate::conversions::geometry_type_to_str;
-    use crate::gpkg::Gpkg;
-    use crate::params;
-    use crate::types::{ColumnSpec, ColumnType};
-    use geo_traits::GeometryTrait;
-    use geo_types::{
-        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
-        Polygon,
-    };
-    use std::str::FromStr;
-    use wkb::reader::{GeometryType, Wkb};
-    use wkt::Wkt;
+    #[test]
+    fn invalid_utf8_text_is_an_error() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params!["ok"])?;
+        layer.insert(Point::new(3.0, 4.0), params!["ok"])?;
+        // Latin-1 bytes stored as TEXT.
+        gpkg.conn.execute(
+            "UPDATE points SET name = CAST(X'43E9' AS TEXT) WHERE fid = 2",
+            [],
+        )?;
 
-    fn generated_gpkg_path() -> &'static str {
-        "src/test/test_generated.gpkg"
+        let assert_invalid_utf8 = |err: GpkgError| match err {
+            GpkgError::Feature {
+                layer_name,
+                id: Some(2),
+                source,
+            } if layer_name == "points" => match *source {
+                GpkgError::InvalidUtf8Text { column, id } => {
+                    assert_eq!((column.as_str(), id), ("name", Some(2)));
+                }
+                other => panic!("unexpected error: {other}"),
+            },
+            other => panic!("unexpected error: {other}"),
+        };
+        assert_invalid_utf8(layer.features().err().expect("invalid UTF-8"));
+        assert_invalid_utf8(
+            layer
+                .features_batch(10)?
+                .next()
+                .expect("batch")
+                .err()
+                .expect("invalid UTF-8"),
+        );
+        let mut seen = Vec::new();
+        let err = layer
+            .for_each_feature(|feature| {
+                seen.push(feature.id());
+                Ok(())
+            })
+            .expect_err("invalid UTF-8");
+        assert_invalid_utf8(err);
+        assert_eq!(seen, vec![1]);
+        assert!(layer.get_feature(1)?.is_some());
+        Ok(())
     }
 
-    fn gpkg_blob_from_geometry<G: GeometryTrait<T = f64>>(
-        geometry: G,
-        srs_id: u32,
-    ) -> Result<Vec<u8>> {
-        let mut buf = Vec::new();
-        wkb::writer::write_geometry(&mut buf, &geometry, &Default::default())?;
-        let wkb = Wkb::try_new(&buf)?;
-        super::super::wkb_to_gpkg_geometry(wkb, srs_id)
+    #[test]
+    fn read_errors_carry_layer_name_and_feature_id() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        layer.insert(Point::new(1.0, 2.0), Vec::<Value>::new())?;
+        layer.insert(Point::new(3.0, 4.0), Vec::<Value>::new())?;
+        // The rtree triggers would reject a TEXT geometry.
+        layer.drop_spatial_index()?;
+        gpkg.conn
+            .execute("UPDATE points SET geom = 'oops' WHERE fid = 2", [])?;
+
+        let err = layer.features().err().expect("TEXT geometry");
+        assert!(
+            matches!(
+                &err,
+                GpkgError::Feature { layer_name, id: Some(2), source }
+                    if layer_name == "points"
+                        && matches!(**source, GpkgError::Sql(rusqlite::Error::InvalidColumnType(..)))
+            ),
+            "{err:?}"
+        );
+        assert!(
+            err.to_string()
+                .starts_with("failed to read feature 2 of layer 'points': "),
+            "{err}"
+        );
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err = layer
+            .features_batch(10)?
+            .next()
+            .expect("batch")
+            .err()
+            .expect("TEXT geometry");
+        assert!(
+            matches!(err, GpkgError::Feature { id: Some(2), .. }),
+            "{err:?}"
+        );
+        let err = layer.get_feature(2).err().expect("TEXT geometry");
+        assert!(
+            matches!(err, GpkgError::Feature { id: Some(2), .. }),
+            "{err:?}"
+        );
+        assert!(layer.get_feature(1)?.is_some());
+        Ok(())
     }
 
-    fn assert_geometry_roundtrip<G: GeometryTrait<T = f64> + Clone>(
-        gpkg: &Gpkg,
-        layer_name: &str,
-        geometry_type: GeometryType,
-        geometry_dimension: wkb::reader::Dimension,
-        geometry: G,
-    ) -> Result<()> {
+    #[test]
+    fn insert_passes_gdal_geometry_type_and_srs_id_triggers() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
         let columns: Vec<ColumnSpec> = Vec::new();
         let layer = gpkg.create_layer(
-            layer_name,
+            "points",
             "geom",
-            geometry_type,
-            geometry_dimension,
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
             4326,
             &columns,
         )?;
+        // The triggers of the (deprecated) geometry type and srs_id trigger
+        // extensions, as written by GDAL.
+        gpkg.conn.execute_batch(
+            "CREATE TRIGGER fgti_points_geom BEFORE INSERT ON 'points' FOR EACH ROW BEGIN
+               SELECT RAISE (ABORT, 'insert on points violates constraint: ST_GeometryType(geom) is not assignable from gpkg_geometry_columns.geometry_type_name value')
+               WHERE (SELECT geometry_type_name FROM gpkg_geometry_columns
+                      WHERE Lower(table_name) = Lower('points') AND Lower(column_name) = Lower('geom')
+                        AND gpkg_IsAssignable(geometry_type_name, ST_GeometryType(NEW.geom)) = 0);
+             END;
+             CREATE TRIGGER fgsi_points_geom BEFORE INSERT ON 'points' FOR EACH ROW BEGIN
+               SELECT RAISE (ABORT, 'insert on points violates constraint: ST_SRID(geom) does not match gpkg_geometry_columns.srs_id value')
+               WHERE (SELECT srs_id FROM gpkg_geometry_columns
+                      WHERE Lower(table_name) = Lower('points') AND Lower(column_name) = Lower('geom')
+                        AND ST_SRID(NEW.geom) <> srs_id);
+             END;",
+        )?;
 
-        let expected_blob = gpkg_blob_from_geometry(geometry.clone(), 4326)?;
-        let mut expected_wkb_bytes = Vec::new();
-        wkb::writer::write_geometry(&mut expected_wkb_bytes, &geometry, &Default::default())?;
-        let expected_wkb = Wkb::try_new(&expected_wkb_bytes)?;
-
-        layer.insert(geometry, std::iter::empty::<&Value>())?;
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+        assert_eq!(layer.count()?, 1);
 
-        let geom_blob: Vec<u8> = layer.conn.query_row(
-            &format!(r#"SELECT "geom" FROM "{}""#, layer_name),
-            [],
+        // The geometry type trigger can't actually abort, because its WHERE
+        // clause evaluates to the geometry type name, which SQLite treats as
+        // false. Check the expression it relies on instead.
+        let line = gpkg_blob_from_geometry(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]), 4326)?;
+        let assignable: i64 = gpkg.conn.query_row(
+            "SELECT gpkg_IsAssignable(geometry_type_name, ST_GeometryType(?1)) \
+             FROM gpkg_geometry_columns WHERE table_name = 'points'",
+            [line],
             |row| row.get(0),
         )?;
-        assert_eq!(geom_blob, expected_blob);
-
-        let features = layer.features()?;
-        let feature = features.first().expect("inserted feature");
-        let geom = feature.geometry()?;
-        assert_eq!(geom.geometry_type(), geometry_type);
-        assert_eq!(geom.dimension(), geometry_dimension);
-        assert_eq!(geom.buf(), expected_wkb.buf());
+        assert_eq!(assignable, 0);
 
+        let other_srs = gpkg_blob_from_geometry(Point::new(1.0, 2.0), 0)?;
+        let err = gpkg
+            .conn
+            .execute("INSERT INTO points (geom) VALUES (?1)", [other_srs])
+            .expect_err("srs_id mismatch");
+        assert!(err.to_string().contains("ST_SRID"), "{err}");
         Ok(())
     }
 
+    fn rtree_trigger_names(gpkg: &Gpkg) -> Result<Vec<String>> {
+        let mut stmt = gpkg.conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'trigger' AND tbl_name = 'points' ORDER BY name",
+        )?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(names)
+    }
+
     #[test]
-    fn reads_generated_layers_and_counts() -> Result<()> {
-        let gpkg = Gpkg::open_read_only(generated_gpkg_path())?;
-        let mut layers = gpkg.list_layers()?;
-        layers.sort();
-        assert_eq!(layers, vec!["lines", "points", "polygons"]);
+    fn upgrades_legacy_rtree_triggers() -> Result<()> {
+        // The trigger set of GeoPackage 1.4.
+        let expected: Vec<String> = [
+            "delete", "insert", "update2", "update4", "update5", "update6", "update7",
+        ]
+        .iter()
+        .map(|suffix| format!("rtree_points_geom_{suffix}"))
+        .collect();
 
-        let points = gpkg.get_layer("points")?;
-        let lines = gpkg.get_layer("lines")?;
-        let polygons = gpkg.get_layer("polygons")?;
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns: Vec<ColumnSpec> = Vec::new();
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        assert_eq!(rtree_trigger_names(&gpkg)?, expected);
 
-        assert_eq!(points.features()?.len(), 5);
-        assert_eq!(lines.features()?.len(), 3);
-        assert_eq!(polygons.features()?.len(), 2);
+        // Replace the 1.4 update triggers with the deprecated 1.2 ones.
+        gpkg.conn.execute_batch(
+            "DROP TRIGGER rtree_points_geom_update5;
+             DROP TRIGGER rtree_points_geom_update6;
+             DROP TRIGGER rtree_points_geom_update7;
+             CREATE TRIGGER rtree_points_geom_update1 AFTER UPDATE OF geom ON points
+               WHEN OLD.fid = NEW.fid AND (NEW.geom NOTNULL AND NOT ST_IsEmpty(NEW.geom))
+             BEGIN
+               INSERT OR REPLACE INTO rtree_points_geom VALUES (
+                 NEW.fid, ST_MinX(NEW.geom), ST_MaxX(NEW.geom), ST_MinY(NEW.geom), ST_MaxY(NEW.geom)
+               );
+             END;
+             CREATE TRIGGER rtree_points_geom_update3 AFTER UPDATE OF geom ON points
+               WHEN OLD.fid != NEW.fid AND (NEW.geom NOTNULL AND NOT ST_IsEmpty(NEW.geom))
+             BEGIN
+               DELETE FROM rtree_points_geom WHERE id = OLD.fid;
+               INSERT OR REPLACE INTO rtree_points_geom VALUES (
+                 NEW.fid, ST_MinX(NEW.geom), ST_MaxX(NEW.geom), ST_MinY(NEW.geom), ST_MaxY(NEW.geom)
+               );
+             END;",
+        )?;
 
+        layer.upgrade_rtree_triggers()?;
+        assert_eq!(rtree_trigger_names(&gpkg)?, expected);
+
+        layer.insert(Point::new(1.0, 1.0), params![])?;
+        layer.update_geometry(1, Point::new(2.0, 3.0))?;
+        assert_eq!(layer.features_in_bbox(1.5, 2.5, 2.5, 3.5)?.len(), 1);
+        assert!(layer.verify_spatial_index()?.is_consistent());
         Ok(())
     }
 
     #[test]
-    fn reads_geometry_and_properties_from_points() -> Result<()> {
-        let gpkg = Gpkg::open_read_only(generated_gpkg_path())?;
-        let layer = gpkg.get_layer("points")?;
-        let features = layer.features()?;
-        let feature = features.first().expect("first feature");
+    fn drops_and_recreates_spatial_index() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert(Point::new(-1.0, -1.0), params![-1])?;
+        assert!(layer.has_spatial_index()?);
 
-        let geom = feature.geometry()?;
-        assert_eq!(geom.geometry_type(), GeometryType::Point);
+        let count_rtree_objects = || -> Result<i64> {
+            Ok(gpkg.conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM sqlite_master WHERE name LIKE 'rtree_points_geom%') + \
+                 (SELECT COUNT(*) FROM gpkg_extensions WHERE extension_name = 'gpkg_rtree_index')",
+                [],
+                |row| row.get(0),
+            )?)
+        };
+        layer.drop_spatial_index()?;
+        assert!(!layer.has_spatial_index()?);
+        assert_eq!(count_rtree_objects()?, 0);
+        layer.drop_spatial_index()?;
 
-        assert_eq!(feature.id(), 1);
-        let name: String = feature
-            .property("name")
-            .ok_or_else(|| GpkgError::MissingProperty {
-                property: "name".to_string(),
-            })?
-            .try_into()?;
-        assert_eq!(name, "alpha");
+        let features = (0..1000).map(|i| (Point::new(i as f64, i as f64), vec![Value::from(i)]));
+        assert_eq!(layer.insert_many(features)?, 1000);
+        assert_eq!(layer.features_in_bbox(4.5, 4.5, 5.5, 5.5)?.len(), 1);
 
-        let active: bool = feature
-            .property("active")
-            .ok_or_else(|| GpkgError::MissingProperty {
-                property: "active".to_string(),
-            })?
-            .try_into()?;
-        assert_eq!(active, true);
+        layer.create_spatial_index()?;
+        assert!(layer.has_spatial_index()?);
+        assert!(count_rtree_objects()? > 0);
+        let rtree_rows: i64 =
+            gpkg.conn
+                .query_row("SELECT COUNT(*) FROM rtree_points_geom", [], |row| {
+                    row.get(0)
+                })?;
+        assert_eq!(rtree_rows as u64, layer.count()?);
+        assert_eq!(layer.features_in_bbox(4.5, 4.5, 5.5, 5.5)?.len(), 1);
 
-        let note = feature
-            .property("note")
-            .ok_or_else(|| GpkgError::MissingProperty {
-                property: "note".to_string(),
-            })?;
-        assert_eq!(note, Value::Text("first".to_string()));
+        // The triggers are back, so new features are indexed too.
+        layer.insert(Point::new(2000.0, 2000.0), params![2000])?;
+        assert_eq!(
+            layer
+                .features_in_bbox(1999.0, 1999.0, 2001.0, 2001.0)?
+                .len(),
+            1
+        );
 
+        let err = layer
+            .create_spatial_index()
+            .expect_err("index already exists");
+        assert!(matches!(err, GpkgError::SpatialIndexAlreadyExists { .. }));
         Ok(())
     }
 
     #[test]
-    fn creates_layer_metadata() -> Result<()> {
+    fn upserts_features() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
-        let columns = vec![
-            ColumnSpec {
-                name: "name".to_string(),
-                column_type: ColumnType::Varchar,
-            },
-            ColumnSpec {
-                name: "value".to_string(),
-                column_type: ColumnType::Integer,
-            },
-        ];
-
-        gpkg.create_layer(
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
             "points",
             "geom",
             GeometryType::Point,
@@ -492,39 +4952,61 @@ mod tests {
             &columns,
         )?;
 
-        let (geometry_type_name, srs_id, z, m): (String, u32, i8, i8) =
-            gpkg.conn.query_row(
-                "SELECT geometry_type_name, srs_id, z, m FROM gpkg_geometry_columns WHERE table_name = 'points'",
-                [],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-            )?;
-
         assert_eq!(
-            geometry_type_name,
-            geometry_type_to_str(GeometryType::Point)
+            layer.upsert(7, Point::new(1.0, 1.0), params!["first"])?,
+            UpsertOutcome::Inserted
         );
-        assert_eq!(srs_id, 4326);
-        assert_eq!(z, 0);
-        assert_eq!(m, 0);
+        assert_eq!(
+            layer.upsert(7, Point::new(5.0, 5.0), params!["second"])?,
+            UpsertOutcome::Updated
+        );
+
+        let features = layer.features()?;
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].id(), 7);
+        assert_eq!(features[0].property("name"), Some(Value::from("second")));
+
+        // The rtree row is updated in place rather than duplicated.
+        let rtree_rows: i64 = gpkg.conn.query_row(
+            "SELECT COUNT(*) FROM rtree_points_geom WHERE id = 7",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(rtree_rows, 1);
+        assert!(layer.features_in_bbox(0.5, 0.5, 1.5, 1.5)?.is_empty());
+        assert_eq!(layer.features_in_bbox(4.5, 4.5, 5.5, 5.5)?.len(), 1);
+
+        assert!(matches!(
+            layer.upsert(8, Point::new(0.0, 0.0), params![]),
+            Err(GpkgError::InvalidPropertyCount { .. })
+        ));
+        assert_eq!(layer.count()?, 1);
 
         Ok(())
     }
 
     #[test]
-    fn inserts_and_updates_by_primary_key() -> Result<()> {
+    fn strict_types_rejects_mismatched_values() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
         let columns = vec![
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                not_null: true,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "ratio".to_string(),
+                column_type: ColumnType::Double,
+                ..Default::default()
             },
         ];
-
-        let layer = gpkg.create_layer(
+        let mut layer = gpkg.create_layer(
             "points",
             "geom",
             GeometryType::Point,
@@ -533,196 +5015,498 @@ mod tests {
             &columns,
         )?;
 
-        let point_a = Point::new(1.0, 2.0);
-        let name_a = "alpha".to_string();
-        let value_a = 7_i64;
-        layer.insert(point_a, params![name_a, value_a])?;
-        let id = layer.conn.last_insert_rowid();
+        // SQLite's type affinity accepts this unless strict mode is on.
+        layer.insert(Point::new(0.0, 0.0), params!["a", "not a number", 1.0])?;
 
-        let point_b = Point::new(4.0, 5.0);
-        let name_b = "beta".to_string();
-        let value_b = 9_i64;
-        layer.update(point_b, params![name_b, value_b], id)?;
+        layer.set_strict_types(true);
+        let is_mismatch = |result: Result<()>, expected_column: &str| match result {
+            Err(GpkgError::PropertyTypeMismatch { column, .. }) => column == expected_column,
+            _ => false,
+        };
 
-        let (geom_blob, name, value): (Vec<u8>, String, i64) = layer.conn.query_row(
-            "SELECT geom, name, value FROM points WHERE fid = ?1",
-            [id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )?;
+        let point = Point::new(1.0, 1.0);
+        assert!(is_mismatch(
+            layer.insert(point, params!["b", "not a number", 1.0]),
+            "value"
+        ));
+        assert!(is_mismatch(
+            layer.insert(point, params![Value::Null, 1, 1.0]),
+            "name"
+        ));
+        assert!(is_mismatch(
+            layer.update(point, params!["b", 1, "x"], 1),
+            "ratio"
+        ));
+        assert!(is_mismatch(
+            layer.update_properties(1, params!["b", 1.5, 1.0]),
+            "value"
+        ));
+        assert!(is_mismatch(
+            layer.update_named(1, [("value", Value::from("x"))]),
+            "value"
+        ));
+        assert!(is_mismatch(
+            layer
+                .upsert(5, point, params!["b", 1, Value::Blob(vec![1])])
+                .map(|_| ()),
+            "ratio"
+        ));
+        let err = layer
+            .insert_many([(
+                point,
+                vec![Value::from(1), Value::from(1), Value::from(1.0)],
+            )])
+            .expect_err("mismatch");
+        assert!(matches!(err, GpkgError::InsertManyFailed { index: 0, .. }));
+        assert_eq!(layer.count()?, 1);
 
-        let expected_geom = gpkg_blob_from_geometry(Point::new(4.0, 5.0), 4326)?;
-        assert_eq!(geom_blob, expected_geom);
-        assert_eq!(name, "beta");
-        assert_eq!(value, 9);
+        // NULL in a nullable column and an INTEGER in a REAL column are fine.
+        layer.insert(point, params!["b", Value::Null, 2])?;
+        assert_eq!(layer.count()?, 2);
 
+        layer.set_strict_types(false);
+        layer.insert(point, params!["c", "still not a number", 1.0])?;
+        assert_eq!(layer.count()?, 3);
         Ok(())
     }
 
     #[test]
-    fn roundtrips_all_geometry_types() -> Result<()> {
+    fn opens_layer_with_curve_geometry_type() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        gpkg.create_layer(
+            "arcs",
+            "geom",
+            GeometryType::Polygon,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        gpkg.conn.execute(
+            "UPDATE gpkg_geometry_columns SET geometry_type_name = 'CURVEPOLYGON'
+             WHERE table_name = 'arcs'",
+            [],
+        )?;
 
-        let line = LineString::from(vec![(0.0, 0.0), (1.5, 1.0), (2.0, 0.5)]);
-        let line_b = LineString::from(vec![(-1.0, -1.0), (-2.0, -3.0)]);
-        let exterior = LineString::from(vec![
-            (0.0, 0.0),
-            (3.0, 0.0),
-            (3.0, 3.0),
-            (0.0, 3.0),
-            (0.0, 0.0),
-        ]);
-        let polygon = Polygon::new(exterior, vec![]);
-        let polygon_b = Polygon::new(
-            LineString::from(vec![
-                (10.0, 10.0),
-                (12.0, 10.0),
-                (12.0, 12.0),
-                (10.0, 12.0),
-                (10.0, 10.0),
-            ]),
+        let layer = gpkg.get_layer("arcs")?;
+        assert_eq!(layer.geometry_type_name, "CURVEPOLYGON");
+        assert_eq!(layer.geometry_type, GeometryType::GeometryCollection);
+        let square = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
             vec![],
         );
+        layer.insert(square, params!["square"])?;
 
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_points",
+        let copy = gpkg.copy_layer("arcs", "arcs_copy")?;
+        assert_eq!(copy.geometry_type_name, "CURVEPOLYGON");
+        assert_eq!(
+            gpkg.get_layer("arcs_copy")?.geometry_type_name,
+            "CURVEPOLYGON"
+        );
+
+        // The rtree triggers can't compute the bounds of a curve.
+        layer.drop_spatial_index()?;
+        // CURVEPOLYGON (CIRCULARSTRING (0 0, 1 1, 2 0, 1 -1, 0 0))
+        gpkg.conn.execute_batch(
+            "INSERT INTO arcs (geom, name) VALUES (X'47500001E6100000010A0000000100000001080000000500000000000000000000000000000000000000000000000000F03F000000000000F03F00000000000000400000000000000000000000000000F03F000000000000F0BF00000000000000000000000000000000', 'circle');",
+        )?;
+
+        let features = layer.features()?;
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0].geometry()?.geometry_type(),
+            GeometryType::Polygon
+        );
+        assert!(matches!(
+            features[1].geometry(),
+            Err(GpkgError::UnsupportedGeometryType(name)) if name == "CURVEPOLYGON"
+        ));
+        let name: String = features[1].property("name").expect("name").try_into()?;
+        assert_eq!(name, "circle");
+
+        let info = gpkg.list_layers_detailed()?;
+        assert_eq!(info[0].geometry_type_name, "CURVEPOLYGON");
+        assert_eq!(
+            gpkg.layer_metadata("arcs")?.geometry_type_name,
+            "CURVEPOLYGON"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_layer_with_optional_z() -> Result<()> {
+        // Written like ArcGIS does, with z = 2 in gpkg_geometry_columns.
+        let gpkg = Gpkg::open_read_only("src/test/test_z_optional.gpkg")?;
+        let layer = gpkg.get_layer("wells")?;
+        assert_eq!(layer.z_flag, ZmFlag::Optional);
+        assert_eq!(layer.m_flag, ZmFlag::Prohibited);
+        assert_eq!(layer.geometry_dimension, wkb::reader::Dimension::Xyz);
+
+        let dimensions = layer
+            .features()?
+            .iter()
+            .map(|feature| Ok(feature.geometry()?.dimension()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            dimensions,
+            vec![
+                wkb::reader::Dimension::Xy,
+                wkb::reader::Dimension::Xyz,
+                wkb::reader::Dimension::Xyz
+            ]
+        );
+
+        let info = gpkg.list_layers_detailed()?;
+        assert_eq!(
+            info[0].geometry_dimension,
+            Some(wkb::reader::Dimension::Xyz)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn layer_with_optional_z_accepts_xy_and_xyz() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        gpkg.create_layer(
+            "points",
+            "geom",
             GeometryType::Point,
-            wkb::reader::Dimension::Xy,
-            Point::new(1.0, 2.0),
+            wkb::reader::Dimension::Xyz,
+            4326,
+            &[],
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_lines",
-            GeometryType::LineString,
-            wkb::reader::Dimension::Xy,
-            line.clone(),
+        gpkg.conn.execute(
+            "UPDATE gpkg_geometry_columns SET z = 2 WHERE table_name = 'points'",
+            [],
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_polygons",
-            GeometryType::Polygon,
+        let layer = gpkg.get_layer("points")?;
+
+        let point_z = Wkt::from_str("POINT Z (1 2 3)")
+            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+        let point_m = Wkt::from_str("POINT M (1 2 3)")
+            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+        layer.insert(point_z, params![])?;
+        let err = layer
+            .insert(point_m, params![])
+            .expect_err("M is prohibited");
+        assert!(matches!(
+            err,
+            GpkgError::GeometryTypeMismatch {
+                actual_dimension: wkb::reader::Dimension::Xym,
+                ..
+            }
+        ));
+        assert_eq!(layer.count()?, 2);
+
+        let copy = gpkg.copy_layer("points", "points_copy")?;
+        assert_eq!(copy.z_flag, ZmFlag::Optional);
+        assert_eq!(gpkg.get_layer("points_copy")?.z_flag, ZmFlag::Optional);
+        assert_eq!(copy.count()?, 2);
+
+        gpkg.conn.execute(
+            "UPDATE gpkg_geometry_columns SET z = 3 WHERE table_name = 'points'",
+            [],
+        )?;
+        assert!(matches!(
+            gpkg.get_layer("points"),
+            Err(GpkgError::InvalidDimension { z: 3, m: 0 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_mismatched_geometry_type_and_dimension() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let points = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
             wkb::reader::Dimension::Xy,
-            polygon.clone(),
+            4326,
+            &[],
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_multi_points",
-            GeometryType::MultiPoint,
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]);
+        let is_mismatch = |result: Result<()>| {
+            matches!(
+                result,
+                Err(GpkgError::GeometryTypeMismatch {
+                    expected: GeometryType::Point,
+                    actual: GeometryType::LineString,
+                    ..
+                })
+            )
+        };
+
+        points.insert(Point::new(0.0, 0.0), params![])?;
+        assert!(is_mismatch(points.insert(line.clone(), params![])));
+        assert!(is_mismatch(points.update(line.clone(), params![], 1)));
+        assert!(is_mismatch(points.update_geometry(1, line.clone())));
+        assert!(is_mismatch(
+            points.upsert(2, line.clone(), params![]).map(|_| ())
+        ));
+        let err = points
+            .insert_many([(line.clone(), Vec::<Value>::new())])
+            .expect_err("mismatch");
+        assert!(matches!(err, GpkgError::InsertManyFailed { index: 0, .. }));
+        assert_eq!(points.count()?, 1);
+
+        points.insert_unchecked(line.clone(), params![])?;
+        assert_eq!(points.count()?, 2);
+
+        let points_z = gpkg.create_layer(
+            "points_z",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xyz,
+            4326,
+            &[],
+        )?;
+        let err = points_z
+            .insert(Point::new(0.0, 0.0), params![])
+            .expect_err("mismatch");
+        assert!(matches!(
+            err,
+            GpkgError::GeometryTypeMismatch {
+                expected_dimension: wkb::reader::Dimension::Xyz,
+                actual_dimension: wkb::reader::Dimension::Xy,
+                ..
+            }
+        ));
+
+        let any = gpkg.create_layer_with_options(
+            "any",
+            "geom",
+            GeometryType::GeometryCollection,
             wkb::reader::Dimension::Xy,
-            MultiPoint::from(vec![Point::new(1.0, 1.0), Point::new(2.0, 2.0)]),
+            4326,
+            &[],
+            &CreateLayerOptions {
+                any_geometry_type: true,
+                ..Default::default()
+            },
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_multi_lines",
-            GeometryType::MultiLineString,
+        any.insert(Point::new(0.0, 0.0), params![])?;
+        any.insert(line, params![])?;
+        assert_eq!(any.count()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_point_in_geometry_collection_layer() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "collections",
+            "geom",
+            GeometryType::GeometryCollection,
             wkb::reader::Dimension::Xy,
-            MultiLineString::new(vec![line.clone(), line_b]),
+            4326,
+            &[],
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_multi_polygons",
-            GeometryType::MultiPolygon,
+        let err = layer
+            .insert(Point::new(0.0, 0.0), params![])
+            .expect_err("a POINT is not a GEOMETRYCOLLECTION");
+        assert!(matches!(
+            err,
+            GpkgError::GeometryTypeMismatch {
+                expected: GeometryType::GeometryCollection,
+                actual: GeometryType::Point,
+                ..
+            }
+        ));
+
+        let collection = GeometryCollection::new_from(vec![Point::new(0.0, 0.0).into()]);
+        layer.insert(collection, params![])?;
+        assert_eq!(layer.count()?, 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "wkt")]
+    #[test]
+    fn inserts_wkt() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
             wkb::reader::Dimension::Xy,
-            MultiPolygon::new(vec![polygon.clone(), polygon_b]),
+            4326,
+            &columns,
         )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_geometry_collections",
-            GeometryType::GeometryCollection,
+        layer.insert_wkt("POINT (1.5 2)", params!["a"])?;
+
+        let err = layer
+            .insert_wkt("POINT (1.5", params!["b"])
+            .expect_err("invalid wkt");
+        assert!(matches!(err, GpkgError::Wkt(_)));
+        let err = layer
+            .insert_wkt("LINESTRING (0 0, 1 1)", params!["c"])
+            .expect_err("mismatched geometry type");
+        assert!(matches!(err, GpkgError::GeometryTypeMismatch { .. }));
+
+        let features = layer.features()?;
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].geometry_wkt()?, "POINT(1.5 2)");
+        Ok(())
+    }
+
+    #[test]
+    fn flags_empty_geometries() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "lines",
+            "geom",
+            GeometryType::LineString,
             wkb::reader::Dimension::Xy,
-            GeometryCollection::from(vec![
-                Geometry::Point(Point::new(-1.0, -2.0)),
-                Geometry::LineString(line),
-                Geometry::Polygon(polygon),
-            ]),
+            4326,
+            &[],
         )?;
+        layer.insert(LineString::<f64>::new(vec![]), params![])?;
+        layer.insert(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]), params![])?;
+
+        let features = layer.features()?;
+        assert!(features[0].is_empty_geometry()?);
+        assert!(!features[1].is_empty_geometry()?);
 
+        let rtree_ids: Vec<i64> = gpkg
+            .conn
+            .prepare("SELECT id FROM rtree_lines_geom")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        assert_eq!(rtree_ids, vec![features[1].id()]);
         Ok(())
     }
 
     #[test]
-    fn roundtrips_z_and_m_dimensions() -> Result<()> {
+    fn writes_envelope_when_enabled() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
+        let mut layer = gpkg.create_layer(
+            "lines",
+            "geom",
+            GeometryType::LineString,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        let line = LineString::from(vec![(0.0, 5.0), (2.0, -1.0)]);
+        layer.insert(line.clone(), params![])?;
+        layer.set_write_envelope(true);
+        layer.insert(line.clone(), params![])?;
+        layer.update_geometry(1, line)?;
 
-        let point_z = Wkt::from_str("POINT Z (1 2 3)")
-            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
-        let line_m = Wkt::from_str("LINESTRING M (0 0 5, 1 1 6)")
-            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
-        let polygon_zm = Wkt::from_str("POLYGON ZM ((0 0 1 10, 2 0 2 11, 2 2 3 12, 0 0 1 10))")
-            .map_err(|err| GpkgError::UnsupportedGeometryType(err.to_string()))?;
+        let blobs = gpkg
+            .conn
+            .prepare("SELECT geom FROM lines ORDER BY fid")?
+            .query_map([], |row| row.get::<_, Vec<u8>>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let envelope: Vec<u8> = [0.0f64, 2.0, -1.0, 5.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        for blob in &blobs {
+            assert_eq!(blob[3], 0x03);
+            assert_eq!(&blob[8..40], envelope.as_slice());
+        }
+        let features = layer.features()?;
+        assert_eq!(features.len(), 2);
+        for feature in &features {
+            assert_eq!(
+                feature.geometry()?.geometry_type(),
+                GeometryType::LineString
+            );
+        }
 
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_point_z",
+        // POINT Z (1 2 3), written by hand because geo-types has no Z.
+        let mut point_z = vec![0x01];
+        point_z.extend_from_slice(&1001u32.to_le_bytes());
+        for v in [1.0f64, 2.0, 3.0] {
+            point_z.extend_from_slice(&v.to_le_bytes());
+        }
+        let mut layer_z = gpkg.create_layer_with_options(
+            "points_z",
+            "geom",
             GeometryType::Point,
             wkb::reader::Dimension::Xyz,
-            point_z,
-        )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_linestring_m",
-            GeometryType::LineString,
-            wkb::reader::Dimension::Xym,
-            line_m,
-        )?;
-        assert_geometry_roundtrip(
-            &gpkg,
-            "rt_polygon_zm",
-            GeometryType::Polygon,
-            wkb::reader::Dimension::Xyzm,
-            polygon_zm,
+            4326,
+            &[],
+            &crate::types::CreateLayerOptions {
+                write_envelope: true,
+                ..Default::default()
+            },
         )?;
+        layer_z.insert(Wkb::try_new(&point_z)?, params![])?;
+        layer_z.set_write_envelope(false);
+        layer_z.insert(Wkb::try_new(&point_z)?, params![])?;
 
+        let blobs = gpkg
+            .conn
+            .prepare("SELECT geom FROM points_z ORDER BY fid")?
+            .query_map([], |row| row.get::<_, Vec<u8>>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let envelope: Vec<u8> = [1.0f64, 1.0, 2.0, 2.0, 3.0, 3.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        assert_eq!(blobs[0][3], 0x05);
+        assert_eq!(&blobs[0][8..56], envelope.as_slice());
+        assert_eq!(&blobs[0][56..], point_z.as_slice());
+        assert_eq!(blobs[1][3], 0x01);
+        assert_eq!(&blobs[1][8..], point_z.as_slice());
         Ok(())
     }
 
     #[test]
-    fn rtree_updates_on_insert_update_delete() -> Result<()> {
+    fn for_each_feature_matches_features() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
-        let columns: Vec<ColumnSpec> = Vec::new();
+        let columns = vec![ColumnSpec {
+            name: "value".to_string(),
+            column_type: ColumnType::Integer,
+            ..Default::default()
+        }];
         let layer = gpkg.create_layer(
-            "rtree_points",
+            "points",
             "geom",
             GeometryType::Point,
             wkb::reader::Dimension::Xy,
             4326,
             &columns,
         )?;
+        gpkg.with_transaction(|_| {
+            for i in 0..3000 {
+                layer.insert(Point::new(i as f64, -(i as f64)), params![i])?;
+            }
+            Ok(())
+        })?;
 
-        let point_a = Point::new(1.5, -2.0);
-        layer.insert(point_a, std::iter::empty::<&Value>())?;
-        let id = layer.conn.last_insert_rowid();
-
-        let (minx, maxx, miny, maxy): (f64, f64, f64, f64) = layer.conn.query_row(
-            "SELECT minx, maxx, miny, maxy FROM rtree_rtree_points_geom WHERE id = ?1",
-            [id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-        )?;
-        assert_eq!(minx, 1.5);
-        assert_eq!(maxx, 1.5);
-        assert_eq!(miny, -2.0);
-        assert_eq!(maxy, -2.0);
-
-        let point_b = Point::new(-4.0, 6.25);
-        layer.update(point_b, std::iter::empty::<&Value>(), id)?;
-        let (minx, maxx, miny, maxy): (f64, f64, f64, f64) = layer.conn.query_row(
-            "SELECT minx, maxx, miny, maxy FROM rtree_rtree_points_geom WHERE id = ?1",
-            [id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-        )?;
-        assert_eq!(minx, -4.0);
-        assert_eq!(maxx, -4.0);
-        assert_eq!(miny, 6.25);
-        assert_eq!(maxy, 6.25);
+        let expected = layer.features()?;
+        let mut seen = 0;
+        layer.for_each_feature(|feature| {
+            let other = &expected[seen];
+            assert_eq!(feature.id(), other.id());
+            assert_eq!(feature.properties(), other.properties());
+            assert_eq!(feature.geometry()?.buf(), other.geometry()?.buf());
+            seen += 1;
+            Ok(())
+        })?;
+        assert_eq!(seen, 3000);
 
-        layer.truncate()?;
-        let count: i64 =
-            layer
-                .conn
-                .query_row("SELECT COUNT(*) FROM rtree_rtree_points_geom", [], |row| {
-                    row.get(0)
-                })?;
-        assert_eq!(count, 0);
+        // An error from the callback stops the iteration.
+        let mut calls = 0;
+        let result = layer.for_each_feature(|_| {
+            calls += 1;
+            Err(GpkgError::NullGeometryValue)
+        });
+        assert!(matches!(result, Err(GpkgError::NullGeometryValue)));
+        assert_eq!(calls, 1);
 
         Ok(())
     }
@@ -733,6 +5517,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "name".to_string(),
             column_type: ColumnType::Varchar,
+            ..Default::default()
         }];
 
         let layer = gpkg.create_layer(
@@ -767,10 +5552,12 @@ mod tests {
             ColumnSpec {
                 name: "created_date".to_string(),
                 column_type: ColumnType::Date,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "updated_at".to_string(),
                 column_type: ColumnType::Datetime,
+                ..Default::default()
             },
         ];
 
@@ -797,6 +5584,24 @@ mod tests {
         assert_eq!(date, "2024-01-15");
         assert_eq!(datetime, "2024-01-15T10:30:00.000Z");
 
+        #[cfg(feature = "chrono")]
+        {
+            use chrono::{DateTime, NaiveDate, Utc};
+
+            let date: NaiveDate = feature.get("created_date")?;
+            let datetime: DateTime<Utc> = feature.get("updated_at")?;
+            layer.insert(Point::new(3.0, 4.0), params![date, datetime])?;
+            let features = layer.features()?;
+            assert_eq!(
+                features[1].property("created_date"),
+                feature.property("created_date")
+            );
+            assert_eq!(
+                features[1].property("updated_at"),
+                feature.property("updated_at")
+            );
+        }
+
         // Verify schema metadata round-trips through get_layer
         let reloaded = gpkg.get_layer("dated_points")?;
         let date_col = reloaded
@@ -821,6 +5626,7 @@ mod tests {
         let columns = vec![ColumnSpec {
             name: "data".to_string(),
             column_type: ColumnType::Blob,
+            ..Default::default()
         }];
 
         let layer = gpkg.create_layer(
@@ -853,6 +5659,216 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn property_arguments_accept_owned_and_borrowed_values() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "value".to_string(),
+                column_type: ColumnType::Integer,
+                ..Default::default()
+            },
+        ];
+        let layer = gpkg.create_layer(
+            "shapes",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+
+        let values = vec![Value::from("a"), Value::from(1)];
+        let slice: &[Value] = &values;
+        layer.insert(Point::new(0.0, 0.0), slice)?;
+        layer.insert(Point::new(0.0, 0.0), &values)?;
+        layer.insert(Point::new(0.0, 0.0), values.iter())?;
+        layer.insert(Point::new(0.0, 0.0), values.clone())?;
+        layer.insert(Point::new(0.0, 0.0), params!["a", 1])?;
+        layer.insert(
+            Point::new(0.0, 0.0),
+            crate::params_from([Some(Value::from("a")), Some(Value::from(1))]),
+        )?;
+        layer.insert_with_id(100, Point::new(0.0, 0.0), values.clone())?;
+        layer.upsert(100, Point::new(0.0, 0.0), &values)?;
+        layer.update(Point::new(1.0, 1.0), values.clone(), 1)?;
+        layer.update_properties(2, values.iter())?;
+
+        let inserted = layer.insert_many([
+            (Point::new(0.0, 0.0), values.clone()),
+            (Point::new(0.0, 0.0), values.clone()),
+        ])?;
+        assert_eq!(inserted, 2);
+        let inserted = layer.insert_many(vec![(Point::new(0.0, 0.0), params!["b", 2])])?;
+        assert_eq!(inserted, 1);
+        let inserted = layer.insert_many([(Point::new(0.0, 0.0), values.iter())])?;
+        assert_eq!(inserted, 1);
+
+        assert_eq!(
+            layer.count_where(r#""value" = ?"#, vec![Value::from(1)])?,
+            10
+        );
+        assert_eq!(layer.count_where(r#""name" = ?"#, params!["b"])?, 1);
+        Ok(())
+    }
+
+    fn schema_test_layer(gpkg: &Gpkg) -> Result<GpkgLayer> {
+        let columns = vec![
+            ColumnSpec {
+                name: "kind".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "percent".to_string(),
+                column_type: ColumnType::Double,
+                ..Default::default()
+            },
+        ];
+        gpkg.create_layer(
+            "samples",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &columns,
+        )
+    }
+
+    #[test]
+    fn reads_gdal_style_data_columns() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = schema_test_layer(&gpkg)?;
+        assert!(layer.column_info("kind")?.is_none());
+
+        gpkg.conn.execute_batch(
+            "CREATE TABLE gpkg_data_columns (
+               table_name TEXT NOT NULL, column_name TEXT NOT NULL, name TEXT, title TEXT,
+               description TEXT, mime_type TEXT, constraint_name TEXT,
+               CONSTRAINT pk_gdc PRIMARY KEY (table_name, column_name),
+               CONSTRAINT gdc_tn UNIQUE (table_name, name));
+             CREATE TABLE gpkg_data_column_constraints (
+               constraint_name TEXT NOT NULL, constraint_type TEXT NOT NULL, value TEXT,
+               min NUMERIC, min_is_inclusive BOOLEAN, max NUMERIC, max_is_inclusive BOOLEAN,
+               description TEXT,
+               CONSTRAINT gdcc_ntv UNIQUE (constraint_name, constraint_type, value));
+             INSERT INTO gpkg_data_column_constraints VALUES
+               ('kind_domain', 'enum', 'A', NULL, NULL, NULL, NULL, 'Type A'),
+               ('kind_domain', 'enum', 'B', NULL, NULL, NULL, NULL, NULL),
+               ('percent_range', 'range', NULL, 0, 0, 100, 1, 'A percentage');
+             INSERT INTO gpkg_data_columns VALUES
+               ('samples', 'kind', 'kind', 'Kind of sample', NULL, NULL, 'kind_domain'),
+               ('samples', 'percent', NULL, NULL, 'Coverage', NULL, 'percent_range'),
+               ('samples', 'geom', NULL, 'Location', NULL, NULL, 'missing_domain');",
+        )?;
+
+        let kind = layer.column_info("kind")?.expect("kind");
+        assert_eq!(kind.name.as_deref(), Some("kind"));
+        assert_eq!(kind.title.as_deref(), Some("Kind of sample"));
+        assert_eq!(
+            kind.constraint,
+            Some(DataColumnConstraint::Enum {
+                name: "kind_domain".to_string(),
+                values: vec![
+                    ("A".to_string(), Some("Type A".to_string())),
+                    ("B".to_string(), None),
+                ],
+            })
+        );
+
+        let percent = layer.column_info("percent")?.expect("percent");
+        assert_eq!(percent.title, None);
+        assert_eq!(percent.description.as_deref(), Some("Coverage"));
+        assert_eq!(
+            percent.constraint,
+            Some(DataColumnConstraint::Range {
+                name: "percent_range".to_string(),
+                min: Some(0.0),
+                min_is_inclusive: false,
+                max: Some(100.0),
+                max_is_inclusive: true,
+            })
+        );
+
+        let geom = layer.column_info("geom")?.expect("geom");
+        assert_eq!(geom.title.as_deref(), Some("Location"));
+        assert_eq!(geom.constraint, None);
+        assert!(layer.column_info("fid")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn set_column_info_creates_schema_extension() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = schema_test_layer(&gpkg)?;
+
+        let kind = DataColumnInfo {
+            title: Some("Kind of sample".to_string()),
+            constraint: Some(DataColumnConstraint::Enum {
+                name: "kind_domain".to_string(),
+                values: vec![
+                    ("A".to_string(), Some("Type A".to_string())),
+                    ("B".to_string(), None),
+                ],
+            }),
+            ..Default::default()
+        };
+        let percent = DataColumnInfo {
+            name: Some("pct".to_string()),
+            description: Some("Coverage".to_string()),
+            constraint: Some(DataColumnConstraint::Range {
+                name: "percent_range".to_string(),
+                min: Some(0.0),
+                min_is_inclusive: true,
+                max: None,
+                max_is_inclusive: false,
+            }),
+            ..Default::default()
+        };
+        layer.set_column_info("kind", &kind)?;
+        layer.set_column_info("percent", &percent)?;
+        // Setting it again replaces the entry and the constraint rows.
+        layer.set_column_info("kind", &kind)?;
+
+        assert_eq!(layer.column_info("kind")?, Some(kind));
+        assert_eq!(layer.column_info("percent")?, Some(percent));
+
+        let count =
+            |sql: &str| -> Result<i64> { Ok(gpkg.conn.query_row(sql, [], |row| row.get(0))?) };
+        assert_eq!(
+            count("SELECT COUNT(*) FROM gpkg_extensions WHERE extension_name = 'gpkg_schema'")?,
+            2
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM gpkg_data_column_constraints")?,
+            3
+        );
+
+        let glob = DataColumnInfo {
+            constraint: Some(DataColumnConstraint::Glob {
+                name: "kind_pattern".to_string(),
+                pattern: "[A-Z]".to_string(),
+            }),
+            ..Default::default()
+        };
+        layer.set_column_info("kind", &glob)?;
+        assert_eq!(layer.column_info("kind")?, Some(glob));
+
+        assert!(matches!(
+            layer.set_column_info("missing", &DataColumnInfo::default()),
+            Err(GpkgError::MissingProperty { .. })
+        ));
+
+        gpkg.delete_layer("samples")?;
+        assert_eq!(count("SELECT COUNT(*) FROM gpkg_data_columns")?, 0);
+        Ok(())
+    }
+
     #[test]
     fn rejects_invalid_property_count() -> Result<()> {
         let gpkg = Gpkg::open_in_memory()?;
@@ -860,10 +5876,12 @@ mod tests {
             ColumnSpec {
                 name: "name".to_string(),
                 column_type: ColumnType::Varchar,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "value".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -896,10 +5914,12 @@ mod tests {
             ColumnSpec {
                 name: "a".to_string(),
                 column_type: ColumnType::Double,
+                ..Default::default()
             },
             ColumnSpec {
                 name: "b".to_string(),
                 column_type: ColumnType::Integer,
+                ..Default::default()
             },
         ];
 
@@ -928,4 +5948,201 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn append_from_copies_features_from_another_gpkg() -> Result<()> {
+        let src = Gpkg::open_read_only(generated_gpkg_path())?;
+        let source = src.get_layer("points")?;
+
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer_like(&source, "points")?;
+        assert_eq!(layer.append_from(&source, None)?, 5);
+        assert_eq!(layer.append_from(&source, Some(r#""category" = 'A'"#))?, 2);
+        assert_eq!(layer.count()?, 7);
+
+        let expected = source.features()?;
+        let appended = layer.features()?;
+        for (a, b) in appended.iter().zip(&expected) {
+            assert_eq!(a.properties(), b.properties());
+            assert_eq!(a.geometry()?.buf(), b.geometry()?.buf());
+        }
+        assert_eq!(layer.features_in_bbox(-180.0, -90.0, 180.0, 90.0)?.len(), 7);
+        // The first extent comes from the rtree, which stores f32 values.
+        let (a, b) = (
+            layer.extent()?.expect("extent"),
+            source.extent()?.expect("extent"),
+        );
+        for (a, b) in [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)] {
+            assert!((a - b).abs() < 1e-4, "{a} != {b}");
+        }
+
+        // Appending a layer to itself reads only the rows that existed before.
+        assert_eq!(layer.append_from(&layer, None)?, 7);
+        assert_eq!(layer.count()?, 14);
+
+        assert!(matches!(
+            layer.append_from(&source, Some("1; DROP TABLE points")),
+            Err(GpkgError::InvalidWhereClause { .. })
+        ));
+        assert!(matches!(
+            source.append_from(&layer, None),
+            Err(GpkgError::ReadOnly)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn append_from_lists_schema_differences() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let column = |name: &str, column_type| ColumnSpec {
+            name: name.to_string(),
+            column_type,
+            ..Default::default()
+        };
+        let create = |name: &str, geometry_type, columns: &[ColumnSpec]| {
+            gpkg.create_layer(
+                name,
+                "geom",
+                geometry_type,
+                wkb::reader::Dimension::Xy,
+                4326,
+                columns,
+            )
+        };
+        let source = create(
+            "source",
+            GeometryType::Point,
+            &[
+                column("name", ColumnType::Varchar),
+                column("value", ColumnType::Integer),
+                column("extra", ColumnType::Double),
+            ],
+        )?;
+        source.insert(Point::new(1.0, 2.0), params!["a", 1, 0.5])?;
+        let lines = create(
+            "lines",
+            GeometryType::LineString,
+            &[
+                column("value", ColumnType::Varchar),
+                column("name", ColumnType::Varchar),
+                column("other", ColumnType::Double),
+            ],
+        )?;
+
+        let err = lines.append_from(&source, None).expect_err("incompatible");
+        let GpkgError::IncompatibleSchema { differences } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(
+            differences,
+            &vec![
+                r#"column "value" is INTEGER in the source but TEXT in the destination"#
+                    .to_string(),
+                r#"column "extra" is missing in the destination"#.to_string(),
+                r#"column "other" is missing in the source"#.to_string(),
+                "geometry type is POINT in the source but LINESTRING in the destination"
+                    .to_string(),
+            ]
+        );
+        assert_eq!(lines.count()?, 0);
+
+        // A GEOMETRY layer accepts any geometry type, and the column order
+        // doesn't matter.
+        let any = create(
+            "any",
+            GeometryType::GeometryCollection,
+            &[
+                column("extra", ColumnType::Double),
+                column("name", ColumnType::Varchar),
+                column("value", ColumnType::Integer),
+            ],
+        )?;
+        gpkg.conn.execute(
+            "UPDATE gpkg_geometry_columns SET geometry_type_name = 'GEOMETRY' WHERE table_name = 'any'",
+            [],
+        )?;
+        let any = GpkgLayer {
+            geometry_type_name: "GEOMETRY".to_string(),
+            ..any
+        };
+        assert_eq!(any.append_from(&source, None)?, 1);
+        let feature = &any.features()?[0];
+        assert_eq!(feature.get::<String>("name")?, "a");
+        assert_eq!(feature.get::<i64>("value")?, 1);
+        assert_eq!(feature.get::<f64>("extra")?, 0.5);
+        Ok(())
+    }
+
+    #[test]
+    fn append_from_rewrites_srs_id_of_the_same_srs() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let wgs84 = gpkg.get_srs(4326)?.expect("EPSG:4326");
+        gpkg.register_srs(
+            "WGS 84 (copy)",
+            104_326,
+            &wgs84.organization,
+            wgs84.organization_coordsys_id,
+            &wgs84.definition,
+            "",
+        )?;
+        gpkg.register_srs("Local grid", 990_001, "local", 1, "LOCAL_CS[\"grid\"]", "")?;
+        let create = |name: &str, srs_id| {
+            gpkg.create_layer(
+                name,
+                "geom",
+                GeometryType::Point,
+                wkb::reader::Dimension::Xy,
+                srs_id,
+                &[],
+            )
+        };
+        let source = create("source", 104_326)?;
+        source.insert(Point::new(1.0, 2.0), params![])?;
+        let target = create("target", 4326)?;
+        let local = create("local", 990_001)?;
+
+        assert_eq!(target.append_from(&source, None)?, 1);
+        assert_eq!(target.features()?[0].geometry_srs_id()?, Some(4326));
+
+        let err = local.append_from(&source, None).expect_err("different srs");
+        assert!(
+            matches!(&err, GpkgError::IncompatibleSchema { differences }
+                if differences == &["srs_id is 104326 in the source but 990001 in the destination"]),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write_transform_applies_on_insert_and_reads_back() -> Result<()> {
+        let point_wkb = |x: f64, y: f64| -> Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            wkb::writer::write_geometry(&mut buf, &Point::new(x, y), &Default::default())?;
+            Ok(buf)
+        };
+        let gpkg = Gpkg::open_in_memory()?;
+        let mut layer = gpkg.create_layer(
+            "shifted",
+            "geom",
+            GeometryType::Point,
+            wkb::reader::Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        layer.set_write_transform(|xy: &mut [f64; 2]| xy[0] += 10.0);
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+        layer.clear_write_transform();
+        layer.insert(Point::new(3.0, 4.0), params![])?;
+
+        let stored = layer.features()?;
+        assert_eq!(stored[0].geometry()?.buf(), point_wkb(11.0, 2.0)?);
+        assert_eq!(stored[1].geometry()?.buf(), point_wkb(3.0, 4.0)?);
+        assert_eq!(layer.extent()?, Some((3.0, 2.0, 11.0, 4.0)));
+
+        let read = layer.features_transformed(|xy: &mut [f64; 2]| xy[0] -= 10.0)?;
+        assert_eq!(read[0].geometry()?.buf(), point_wkb(1.0, 2.0)?);
+        assert_eq!(read[1].geometry()?.buf(), point_wkb(-7.0, 4.0)?);
+        assert_eq!(read[0].geometry_srs_id()?, Some(4326));
+        Ok(())
+    }
 }
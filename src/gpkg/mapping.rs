@@ -0,0 +1,239 @@
+use crate::Value;
+use crate::error::Result;
+use geo_traits::GeometryTrait;
+
+use super::GpkgFeature;
+
+/// Conversion from a feature of a layer into a Rust type, used by
+/// [`GpkgLayer::features_as`](crate::GpkgLayer::features_as).
+///
+/// Implementations usually read each field with the typed accessors
+/// `GpkgFeature::get()` and `GpkgFeature::get_opt()`, whose errors name the
+/// property: `GpkgError::MissingProperty` for a missing column and
+/// `GpkgError::InvalidPropertyValue` for a value of the wrong type.
+///
+/// Example:
+/// ```no_run
+/// use rusqlite_gpkg::{FromFeature, Gpkg, GpkgFeature};
+///
+/// struct Station {
+///     id: i64,
+///     name: String,
+///     elevation: Option<f64>,
+/// }
+///
+/// impl FromFeature for Station {
+///     fn from_feature(feature: &GpkgFeature) -> rusqlite_gpkg::Result<Self> {
+///         Ok(Self {
+///             id: feature.id(),
+///             name: feature.get("name")?,
+///             elevation: feature.get_opt("elevation")?,
+///         })
+///     }
+/// }
+///
+/// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+/// let stations: Vec<Station> = gpkg.get_layer("stations")?.features_as()?;
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub trait FromFeature: Sized {
+    fn from_feature(feature: &GpkgFeature) -> Result<Self>;
+}
+
+/// Conversion from a Rust type into the geometry and the named property
+/// values of a feature, used by
+/// [`GpkgLayer::insert_struct`](crate::GpkgLayer::insert_struct).
+///
+/// Example:
+/// ```no_run
+/// use geo_types::Point;
+/// use rusqlite_gpkg::{Gpkg, ToFeature, Value};
+///
+/// struct Station {
+///     name: String,
+///     elevation: Option<f64>,
+///     geom: Point<f64>,
+/// }
+///
+/// impl ToFeature for Station {
+///     type Geometry<'a> = &'a Point<f64>;
+///
+///     fn to_feature(&self) -> (&Point<f64>, Vec<(&str, Value)>) {
+///         let properties = vec![
+///             ("name", Value::from(self.name.as_str())),
+///             ("elevation", Value::from(self.elevation)),
+///         ];
+///         (&self.geom, properties)
+///     }
+/// }
+///
+/// let gpkg = Gpkg::open("data/example.gpkg")?;
+/// let layer = gpkg.get_layer("stations")?;
+/// layer.insert_struct(&Station {
+///     name: "summit".to_string(),
+///     elevation: Some(2456.0),
+///     geom: Point::new(138.7, 35.4),
+/// })?;
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub trait ToFeature {
+    /// The geometry type, which may borrow from `self`.
+    type Geometry<'a>: GeometryTrait<T = f64>
+    where
+        Self: 'a;
+
+    /// Return the geometry and the `(column name, value)` pairs of the
+    /// properties. Properties not listed are written as `NULL`.
+    fn to_feature(&self) -> (Self::Geometry<'_>, Vec<(&str, Value)>);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FromFeature, ToFeature};
+    use crate::types::{ColumnSpec, ColumnType};
+    use crate::{Gpkg, GpkgError, GpkgFeature, GpkgLayer, Result, Value};
+    use geo_types::Point;
+    use wkb::reader::{Dimension, GeometryType};
+
+    #[derive(Debug, PartialEq)]
+    struct Station {
+        id: i64,
+        name: String,
+        elevation: Option<f64>,
+        geom: Point<f64>,
+    }
+
+    #[cfg(feature = "geo")]
+    impl FromFeature for Station {
+        fn from_feature(feature: &GpkgFeature) -> Result<Self> {
+            let geom = Point::try_from(feature.geometry_as_geo()?)
+                .map_err(|e| GpkgError::UnsupportedGeometryType(e.to_string()))?;
+            Ok(Self {
+                id: feature.id(),
+                name: feature.get("name")?,
+                elevation: feature.get_opt("elevation")?,
+                geom,
+            })
+        }
+    }
+
+    impl ToFeature for Station {
+        type Geometry<'a> = &'a Point<f64>;
+
+        fn to_feature(&self) -> (&Point<f64>, Vec<(&str, Value)>) {
+            let properties = vec![
+                ("name", Value::from(self.name.as_str())),
+                ("elevation", Value::from(self.elevation)),
+            ];
+            (&self.geom, properties)
+        }
+    }
+
+    // Reads the name as an integer.
+    struct NameAsNumber;
+
+    impl FromFeature for NameAsNumber {
+        fn from_feature(feature: &GpkgFeature) -> Result<Self> {
+            feature.get::<i64>("name").map(|_| Self)
+        }
+    }
+
+    struct Unknown;
+
+    impl FromFeature for Unknown {
+        fn from_feature(feature: &GpkgFeature) -> Result<Self> {
+            feature.get::<String>("height").map(|_| Self)
+        }
+    }
+
+    fn create_layer(gpkg: &Gpkg) -> Result<GpkgLayer> {
+        let columns = vec![
+            ColumnSpec {
+                name: "name".to_string(),
+                column_type: ColumnType::Varchar,
+                ..Default::default()
+            },
+            ColumnSpec {
+                name: "elevation".to_string(),
+                column_type: ColumnType::Double,
+                ..Default::default()
+            },
+        ];
+        gpkg.create_layer(
+            "stations",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn structs_roundtrip_through_layer() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+        let stations = vec![
+            Station {
+                id: 1,
+                name: "summit".to_string(),
+                elevation: Some(2456.0),
+                geom: Point::new(138.7, 35.4),
+            },
+            Station {
+                id: 2,
+                name: "harbor".to_string(),
+                elevation: None,
+                geom: Point::new(139.6, 35.4),
+            },
+        ];
+        for station in &stations {
+            layer.insert_struct(station)?;
+        }
+
+        assert_eq!(layer.features_as::<Station>()?, stations);
+        Ok(())
+    }
+
+    #[test]
+    fn schema_mismatches_name_the_field_and_feature() -> Result<()> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = create_layer(&gpkg)?;
+        layer.insert_struct(&Station {
+            id: 1,
+            name: "summit".to_string(),
+            elevation: None,
+            geom: Point::new(0.0, 0.0),
+        })?;
+
+        let err = layer
+            .features_as::<NameAsNumber>()
+            .err()
+            .expect("TEXT as i64");
+        let GpkgError::Feature {
+            layer_name,
+            id: Some(1),
+            source,
+        } = err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(layer_name, "stations");
+        assert!(
+            matches!(&*source, GpkgError::InvalidPropertyValue { property, .. } if property == "name"),
+            "{source}"
+        );
+
+        let err = layer
+            .features_as::<Unknown>()
+            .err()
+            .expect("missing column");
+        assert!(
+            matches!(&err, GpkgError::Feature { source, .. }
+                if matches!(&**source, GpkgError::MissingProperty { property } if property == "height")),
+            "{err}"
+        );
+        Ok(())
+    }
+}
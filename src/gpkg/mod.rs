@@ -11,16 +11,34 @@ mod attribute_row;
 mod attribute_table;
 mod batch_iterator;
 mod feature;
+mod feature_builder;
+#[allow(clippy::module_inception)]
 mod gpkg;
 mod layer;
+mod mapping;
+mod pool;
+mod progress;
+mod transform;
+mod validate;
 
 pub use attribute_row::GpkgAttributeRow;
 pub use attribute_table::GpkgAttributeTable;
 pub use batch_iterator::GpkgFeatureBatchIterator;
 pub use feature::GpkgFeature;
+pub use feature_builder::GpkgFeatureBuilder;
 pub use gpkg::Gpkg;
 pub use layer::GpkgLayer;
+pub use mapping::{FromFeature, ToFeature};
+pub use pool::GpkgPool;
+
+#[cfg(feature = "arrow")]
+pub(crate) use layer::with_savepoint;
+pub(crate) use progress::{Progress, no_progress};
+pub(crate) use transform::CoordTransform;
 
 #[cfg(feature = "arrow")]
 pub(crate) use feature::gpkg_geometry_to_wkb_bytes;
-pub(crate) use feature::{gpkg_geometry_to_wkb, wkb_to_gpkg_geometry};
+pub(crate) use feature::{
+    PropertyIndex, gpkg_geometry_is_empty, gpkg_geometry_srs_id, gpkg_geometry_to_wkb,
+    set_gpkg_geometry_srs_id, wkb_to_gpkg_geometry,
+};
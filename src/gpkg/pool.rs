@@ -0,0 +1,258 @@
+use crate::error::{GpkgError, Result};
+use rusqlite::OpenFlags;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use super::gpkg::Gpkg;
+
+/// A pool of connections to the same GeoPackage file, for using it from
+/// multiple threads.
+///
+/// [`Gpkg`] is neither `Send` nor `Sync`, so a single `Gpkg` cannot be shared
+/// between threads. `GpkgPool` is both: share it (e.g. by reference in a
+/// `rayon` closure or in an `Arc`), and call [`GpkgPool::get`] on each thread
+/// to obtain a `Gpkg` with its own SQLite connection and the spatial SQL
+/// functions registered. Passing the `Gpkg` back with [`GpkgPool::put`] keeps
+/// the connection open for later `get()` calls, up to `max_idle` connections.
+///
+/// Each connection is opened with the same path and flags. The default flags
+/// contain `SQLITE_OPEN_NO_MUTEX` (the "multi-thread" threading mode), which is
+/// safe here because a connection is only used by the thread holding its `Gpkg`.
+/// SQLite allows many readers but only one writer at a time, so writing while
+/// other connections read needs `JournalMode::Wal` and possibly
+/// [`Gpkg::busy_timeout`]. An in-memory database cannot be shared this way, as
+/// each connection would open a different database.
+///
+/// Example:
+/// ```no_run
+/// use rusqlite_gpkg::GpkgPool;
+///
+/// let pool = GpkgPool::open_read_only("data/example.gpkg", 4)?;
+/// std::thread::scope(|s| {
+///     for name in ["points", "lines"] {
+///         let pool = &pool;
+///         s.spawn(move || -> rusqlite_gpkg::Result<usize> {
+///             let gpkg = pool.get()?;
+///             let n = gpkg.get_layer(name)?.features()?.len();
+///             pool.put(gpkg)?;
+///             Ok(n)
+///         });
+///     }
+/// });
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+#[derive(Debug)]
+pub struct GpkgPool {
+    path: PathBuf,
+    flags: OpenFlags,
+    read_only: bool,
+    max_idle: usize,
+    idle: Mutex<Vec<rusqlite::Connection>>,
+}
+
+impl GpkgPool {
+    /// Create a pool of read-only connections to an existing GeoPackage.
+    ///
+    /// Fails with `GpkgError::FileNotFound` if the file doesn't exist.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::GpkgPool;
+    ///
+    /// let pool = GpkgPool::open_read_only("data/example.gpkg", 4)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open_read_only<P: AsRef<Path>>(path: P, max_idle: usize) -> Result<Self> {
+        Self::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY, max_idle)
+    }
+
+    /// Create a pool of read-write connections to a new or existing GeoPackage.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::GpkgPool;
+    ///
+    /// let pool = GpkgPool::open("data/example.gpkg", 4)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P, max_idle: usize) -> Result<Self> {
+        Self::open_with_flags(path, OpenFlags::default(), max_idle)
+    }
+
+    /// Create a pool of connections opened with the given SQLite open flags.
+    ///
+    /// The first connection is opened immediately, the same as
+    /// [`Gpkg::open_with_flags`], so a missing file or a new file is handled
+    /// here. Fails with `GpkgError::InMemoryDatabase` if the path refers to an
+    /// in-memory database.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{GpkgPool, OpenFlags};
+    ///
+    /// let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    /// let pool = GpkgPool::open_with_flags("data/example.gpkg", flags, 4)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn open_with_flags<P: AsRef<Path>>(
+        path: P,
+        flags: OpenFlags,
+        max_idle: usize,
+    ) -> Result<Self> {
+        let gpkg = Gpkg::open_with_flags(path.as_ref(), flags)?;
+        if gpkg.conn.path().is_none_or(str::is_empty) {
+            return Err(GpkgError::InMemoryDatabase);
+        }
+
+        let pool = Self {
+            path: path.as_ref().to_path_buf(),
+            flags,
+            read_only: gpkg.read_only,
+            max_idle,
+            idle: Mutex::new(Vec::new()),
+        };
+        pool.put(gpkg)?;
+        Ok(pool)
+    }
+
+    /// Return a `Gpkg` backed by an idle connection of the pool, or by a new
+    /// connection if there is none.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::GpkgPool;
+    ///
+    /// let pool = GpkgPool::open_read_only("data/example.gpkg", 4)?;
+    /// let gpkg = pool.get()?;
+    /// let _layers = gpkg.list_layers()?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn get(&self) -> Result<Gpkg> {
+        let conn = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        match conn {
+            Some(conn) => Gpkg::new_from_conn(Rc::new(conn), self.read_only),
+            None => Gpkg::open_with_flags(&self.path, self.flags),
+        }
+    }
+
+    /// Return the connection of a `Gpkg` obtained from [`GpkgPool::get`] to
+    /// the pool.
+    ///
+    /// The connection is closed instead if the pool already has `max_idle`
+    /// idle connections. This fails with `GpkgError::ConnectionInUse` if any
+    /// layer or attribute table obtained from `gpkg` is still alive. Dropping
+    /// the `Gpkg` instead of calling this is fine; the connection is just
+    /// closed.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::GpkgPool;
+    ///
+    /// let pool = GpkgPool::open_read_only("data/example.gpkg", 4)?;
+    /// let gpkg = pool.get()?;
+    /// pool.put(gpkg)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn put(&self, gpkg: Gpkg) -> Result<()> {
+        let conn = gpkg.into_connection()?;
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.max_idle {
+            idle.push(conn);
+        }
+        Ok(())
+    }
+
+    /// Return the number of idle connections in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use crate::types::ColumnSpec;
+    use geo_types::Point;
+    use wkb::reader::{Dimension, GeometryType};
+
+    fn temp_gpkg_path(name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("rusqlite_gpkg_{name}_{}.gpkg", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn pool_reads_layers_from_multiple_threads() -> Result<()> {
+        let path = temp_gpkg_path("pool_threads");
+        {
+            let gpkg = Gpkg::open(&path)?;
+            for (name, n) in [("first", 3), ("second", 5)] {
+                let layer = gpkg.create_layer(
+                    name,
+                    "geom",
+                    GeometryType::Point,
+                    Dimension::Xy,
+                    4326,
+                    &Vec::<ColumnSpec>::new(),
+                )?;
+                for i in 0..n {
+                    layer.insert(Point::new(i as f64, 0.0), Vec::<Value>::new())?;
+                }
+            }
+        }
+
+        let pool = GpkgPool::open_read_only(&path, 2)?;
+        let counts = std::thread::scope(|s| {
+            let handles: Vec<_> = ["first", "second"]
+                .into_iter()
+                .map(|name| {
+                    let pool = &pool;
+                    s.spawn(move || -> Result<usize> {
+                        let gpkg = pool.get()?;
+                        // The spatial SQL functions are registered on every connection.
+                        let empty: bool = gpkg.conn.query_row(
+                            &format!("SELECT ST_IsEmpty(geom) FROM {name} LIMIT 1"),
+                            [],
+                            |row| row.get(0),
+                        )?;
+                        assert!(!empty);
+                        let n = gpkg.get_layer(name)?.features()?.len();
+                        pool.put(gpkg)?;
+                        Ok(n)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("reader thread"))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        assert_eq!(counts, vec![3, 5]);
+        assert!(pool.idle_count() >= 1 && pool.idle_count() <= 2);
+
+        let gpkg = pool.get()?;
+        assert!(gpkg.read_only);
+        let layer = gpkg.get_layer("first")?;
+        assert!(matches!(pool.put(gpkg), Err(GpkgError::ConnectionInUse)));
+        drop(layer);
+
+        drop(pool);
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[test]
+    fn pool_rejects_in_memory_database() {
+        let err = GpkgPool::open(":memory:", 2).expect_err("in-memory database");
+        assert!(matches!(err, GpkgError::InMemoryDatabase), "{err}");
+    }
+
+    #[test]
+    fn pool_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GpkgPool>();
+    }
+}
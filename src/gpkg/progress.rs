@@ -0,0 +1,102 @@
+use crate::error::{GpkgError, Result};
+use std::ops::ControlFlow;
+
+/// Calls a progress callback with the number of processed rows every
+/// `every_n` rows and once more at the end, unless the last call already
+/// reported the total. `every_n == 0` reports only at the end.
+pub(crate) struct Progress<F> {
+    every_n: u64,
+    reported: Option<u64>,
+    callback: F,
+}
+
+impl<F: FnMut(u64) -> ControlFlow<()>> Progress<F> {
+    pub(crate) fn new(every_n: u64, callback: F) -> Self {
+        Self {
+            every_n,
+            reported: None,
+            callback,
+        }
+    }
+
+    /// Report `done` rows if it's a multiple of `every_n`.
+    pub(crate) fn update(&mut self, done: u64) -> Result<()> {
+        if self.every_n > 0 && done.is_multiple_of(self.every_n) {
+            self.report(done)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self, done: u64) -> Result<()> {
+        if self.reported != Some(done) {
+            self.report(done)?;
+        }
+        Ok(())
+    }
+
+    fn report(&mut self, done: u64) -> Result<()> {
+        self.reported = Some(done);
+        match (self.callback)(done) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(GpkgError::Cancelled),
+        }
+    }
+}
+
+// A callback for the variants without progress reporting.
+pub(crate) fn no_progress(_done: u64) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_n_rows_and_once_at_the_end() -> Result<()> {
+        let mut calls = Vec::new();
+        let mut progress = Progress::new(3, |done| {
+            calls.push(done);
+            ControlFlow::Continue(())
+        });
+        for done in 1..=7 {
+            progress.update(done)?;
+        }
+        progress.finish(7)?;
+        assert_eq!(calls, vec![3, 6, 7]);
+
+        calls.clear();
+        let mut progress = Progress::new(3, |done| {
+            calls.push(done);
+            ControlFlow::Continue(())
+        });
+        for done in 1..=6 {
+            progress.update(done)?;
+        }
+        progress.finish(6)?;
+        assert_eq!(calls, vec![3, 6]);
+
+        calls.clear();
+        let mut progress = Progress::new(0, |done| {
+            calls.push(done);
+            ControlFlow::Continue(())
+        });
+        progress.update(1)?;
+        progress.finish(0)?;
+        assert_eq!(calls, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn break_cancels() {
+        let mut progress = Progress::new(2, |done| {
+            if done >= 4 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert!(progress.update(2).is_ok());
+        assert!(matches!(progress.update(4), Err(GpkgError::Cancelled)));
+    }
+}
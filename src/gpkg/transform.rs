@@ -0,0 +1,234 @@
+//! Coordinate transforms applied to WKB in place, used by
+//! `GpkgLayer::set_write_transform()` and `GpkgLayer::features_transformed()`.
+
+use crate::error::{GpkgError, Result};
+use std::sync::Arc;
+
+type TransformFn = dyn Fn(&mut [f64; 2]) + Send + Sync;
+
+/// A transform of the X and Y of each coordinate, shared by the copies of a
+/// layer.
+#[derive(Clone)]
+pub(crate) struct CoordTransform(pub(crate) Arc<TransformFn>);
+
+impl std::fmt::Debug for CoordTransform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CoordTransform")
+    }
+}
+
+/// Apply `transform` to the X and Y of every coordinate of a WKB geometry in
+/// place. Z and M values are left as they are, and so are the NaN coordinates
+/// of an empty point.
+pub(crate) fn transform_wkb<F: Fn(&mut [f64; 2]) + ?Sized>(
+    buf: &mut [u8],
+    transform: &F,
+) -> Result<()> {
+    let end = transform_geometry(buf, 0, transform)?;
+    if end != buf.len() {
+        return Err(invalid_wkb("trailing bytes"));
+    }
+    Ok(())
+}
+
+// Transform the geometry starting at `offset` and return the offset right
+// after it.
+fn transform_geometry<F: Fn(&mut [f64; 2]) + ?Sized>(
+    buf: &mut [u8],
+    offset: usize,
+    transform: &F,
+) -> Result<usize> {
+    let little_endian = match buf.get(offset) {
+        Some(0) => false,
+        Some(1) => true,
+        Some(_) => return Err(invalid_wkb("invalid byte order")),
+        None => return Err(invalid_wkb("unexpected end")),
+    };
+    let mut reader = Reader {
+        buf,
+        offset: offset + 1,
+        little_endian,
+    };
+    let code = reader.read_u32()?;
+    // ISO WKB adds 1000 for Z, 2000 for M and 3000 for ZM.
+    let (base, dims) = match code / 1000 {
+        0 => (code, 2),
+        1 | 2 => (code % 1000, 3),
+        3 => (code % 1000, 4),
+        _ => return Err(invalid_wkb("unsupported geometry type code")),
+    };
+
+    match base {
+        // Point
+        1 => reader.transform_coords(1, dims, transform)?,
+        // LineString, CircularString
+        2 | 8 => {
+            let n = reader.read_u32()?;
+            reader.transform_coords(n, dims, transform)?;
+        }
+        // Polygon, Triangle
+        3 | 17 => {
+            for _ in 0..reader.read_u32()? {
+                let n = reader.read_u32()?;
+                reader.transform_coords(n, dims, transform)?;
+            }
+        }
+        // Multi*, GeometryCollection, CompoundCurve, CurvePolygon,
+        // MultiCurve, MultiSurface, PolyhedralSurface and TIN all consist of
+        // geometries with their own headers.
+        4..=7 | 9..=12 | 15 | 16 => {
+            let n = reader.read_u32()?;
+            let mut offset = reader.offset;
+            for _ in 0..n {
+                offset = transform_geometry(reader.buf, offset, transform)?;
+            }
+            reader.offset = offset;
+        }
+        _ => return Err(invalid_wkb("unsupported geometry type code")),
+    }
+    Ok(reader.offset)
+}
+
+struct Reader<'a> {
+    buf: &'a mut [u8],
+    offset: usize,
+    little_endian: bool,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&mut [u8]> {
+        let start = self.offset;
+        let end = start
+            .checked_add(len)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| invalid_wkb("unexpected end"))?;
+        self.offset = end;
+        Ok(&mut self.buf[start..end])
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let little_endian = self.little_endian;
+        let bytes: [u8; 4] = self.take(4)?.try_into().expect("4 bytes");
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn transform_coords<F: Fn(&mut [f64; 2]) + ?Sized>(
+        &mut self,
+        n: u32,
+        dims: usize,
+        transform: &F,
+    ) -> Result<()> {
+        let little_endian = self.little_endian;
+        for _ in 0..n {
+            let coord = self.take(8 * dims)?;
+            let read = |bytes: &[u8]| {
+                let bytes: [u8; 8] = bytes.try_into().expect("8 bytes");
+                if little_endian {
+                    f64::from_le_bytes(bytes)
+                } else {
+                    f64::from_be_bytes(bytes)
+                }
+            };
+            let mut xy = [read(&coord[0..8]), read(&coord[8..16])];
+            if xy[0].is_nan() && xy[1].is_nan() {
+                continue;
+            }
+            transform(&mut xy);
+            for (i, value) in xy.into_iter().enumerate() {
+                let bytes = if little_endian {
+                    value.to_le_bytes()
+                } else {
+                    value.to_be_bytes()
+                };
+                coord[8 * i..8 * (i + 1)].copy_from_slice(&bytes);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid_wkb(reason: &str) -> GpkgError {
+    GpkgError::Wkb(wkb::error::WkbError::General(format!(
+        "invalid WKB: {reason}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transform_wkb;
+    use crate::Result;
+    use geo_traits::GeometryTrait;
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPolygon, Point, Polygon};
+    use std::str::FromStr;
+    use wkb::Endianness;
+    use wkb::writer::WriteOptions;
+
+    fn shift(xy: &mut [f64; 2]) {
+        xy[0] += 10.0;
+        xy[1] *= 2.0;
+    }
+
+    fn wkb_of<G: GeometryTrait<T = f64>>(geometry: &G, endianness: Endianness) -> Vec<u8> {
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, geometry, &WriteOptions { endianness })
+            .expect("write wkb");
+        buf
+    }
+
+    #[test]
+    fn transforms_nested_geometries_in_both_byte_orders() -> Result<()> {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            vec![],
+        );
+        let geometry = Geometry::GeometryCollection(GeometryCollection(vec![
+            Geometry::Point(Point::new(1.0, 2.0)),
+            Geometry::MultiPolygon(MultiPolygon(vec![polygon])),
+        ]));
+        let expected = Geometry::GeometryCollection(GeometryCollection(vec![
+            Geometry::Point(Point::new(11.0, 4.0)),
+            Geometry::MultiPolygon(MultiPolygon(vec![Polygon::new(
+                LineString::from(vec![(10.0, 0.0), (11.0, 0.0), (11.0, 2.0), (10.0, 0.0)]),
+                vec![],
+            )])),
+        ]));
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut buf = wkb_of(&geometry, endianness);
+            transform_wkb(&mut buf, &shift)?;
+            assert_eq!(buf, wkb_of(&expected, endianness));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_z_values_and_empty_points() -> Result<()> {
+        let line = wkt::Wkt::<f64>::from_str("LINESTRING Z (1 2 3, 4 5 6)").expect("wkt");
+        let mut buf = wkb_of(&line, Endianness::LittleEndian);
+        transform_wkb(&mut buf, &shift)?;
+        let expected = wkt::Wkt::<f64>::from_str("LINESTRING Z (11 4 3, 14 10 6)").expect("wkt");
+        assert_eq!(buf, wkb_of(&expected, Endianness::LittleEndian));
+
+        let empty = wkt::Wkt::<f64>::from_str("POINT EMPTY").expect("wkt");
+        let mut buf = wkb_of(&empty, Endianness::LittleEndian);
+        let original = buf.clone();
+        transform_wkb(&mut buf, &|_: &mut [f64; 2]| {
+            panic!("called for an empty point")
+        })?;
+        assert_eq!(buf, original);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_truncated_wkb() {
+        let mut buf = wkb_of(&Point::new(1.0, 2.0), Endianness::LittleEndian);
+        buf.pop();
+        assert!(transform_wkb(&mut buf, &shift).is_err());
+        buf.extend_from_slice(&[0, 0]);
+        assert!(transform_wkb(&mut buf, &shift).is_err());
+    }
+}
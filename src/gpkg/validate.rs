@@ -0,0 +1,594 @@
+use crate::conversions::geometry_type_to_str;
+use crate::error::{GpkgError, Result};
+use crate::ogc_sql::{
+    GPKG_APPLICATION_ID, LEGACY_RTREE_TRIGGER_SUFFIXES, RTREE_TRIGGER_SUFFIXES, SQL_COLUMN_EXISTS,
+    SQL_RTREE_EXTENSION_EXISTS, SQL_SCHEMA_OBJECT_EXISTS, SQL_SELECT_CONTENTS_TABLES,
+    SQL_SELECT_EXTENSION_TARGETS, SQL_SELECT_GEOMETRY_COLUMNS, SQL_SRS_EXISTS, SQL_TABLE_EXISTS,
+    SQL_TRIGGER_EXISTS, sql_select_geometries,
+};
+use crate::sql_functions::is_assignable;
+use crate::types::{ValidationIssue, ValidationOptions, ValidationReport};
+
+use super::Gpkg;
+use super::gpkg_geometry_to_wkb;
+
+// The columns required by the spec for each of the core tables.
+// cf. https://www.geopackage.org/spec140/index.html#table_definition_sql
+const SPATIAL_REF_SYS_COLUMNS: &[&str] = &[
+    "srs_name",
+    "srs_id",
+    "organization",
+    "organization_coordsys_id",
+    "definition",
+    "description",
+];
+const CONTENTS_COLUMNS: &[&str] = &[
+    "table_name",
+    "data_type",
+    "identifier",
+    "description",
+    "last_change",
+    "min_x",
+    "min_y",
+    "max_x",
+    "max_y",
+    "srs_id",
+];
+const GEOMETRY_COLUMNS_COLUMNS: &[&str] = &[
+    "table_name",
+    "column_name",
+    "geometry_type_name",
+    "srs_id",
+    "z",
+    "m",
+];
+const EXTENSIONS_COLUMNS: &[&str] = &[
+    "table_name",
+    "column_name",
+    "extension_name",
+    "definition",
+    "scope",
+];
+
+// 10200 = spec version 1.2.0 in MMNNPP format
+const MIN_USER_VERSION: i32 = 10200;
+
+impl Gpkg {
+    /// Check the GeoPackage against the requirements of the spec and return
+    /// the issues found.
+    ///
+    /// This checks the `application_id` and `user_version` pragmas, the
+    /// schema of the `gpkg_*` tables, that the tables listed in
+    /// `gpkg_contents` and `gpkg_extensions` exist, that geometry columns use
+    /// a known SRS, that every geometry is a GeoPackage geometry blob of the
+    /// declared type, and that the rtree triggers are in place. Every
+    /// geometry is read; use `validate_with_options()` to check only a sample.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Gpkg;
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let report = gpkg.validate()?;
+    /// for issue in report.errors() {
+    ///     eprintln!("error: {issue}");
+    /// }
+    /// assert!(report.is_valid());
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn validate(&self) -> Result<ValidationReport> {
+        self.validate_with_options(&ValidationOptions::default())
+    }
+
+    /// Same as `validate()`, but with options, e.g. to check only the first
+    /// rows of each layer.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::{Gpkg, ValidationOptions};
+    ///
+    /// let gpkg = Gpkg::open_read_only("data/example.gpkg")?;
+    /// let options = ValidationOptions {
+    ///     max_geometries_per_layer: Some(1000),
+    /// };
+    /// let report = gpkg.validate_with_options(&options)?;
+    /// println!("{} warnings", report.warnings().count());
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn validate_with_options(&self, options: &ValidationOptions) -> Result<ValidationReport> {
+        let mut issues = Vec::new();
+        self.validate_pragmas(&mut issues)?;
+
+        let has_srs = self.validate_table_schema(
+            "gpkg_spatial_ref_sys",
+            SPATIAL_REF_SYS_COLUMNS,
+            true,
+            &mut issues,
+        )?;
+        let has_contents =
+            self.validate_table_schema("gpkg_contents", CONTENTS_COLUMNS, true, &mut issues)?;
+        let has_features = has_contents && self.validate_contents(&mut issues)?;
+        let has_geometry_columns = self.validate_table_schema(
+            "gpkg_geometry_columns",
+            GEOMETRY_COLUMNS_COLUMNS,
+            has_features,
+            &mut issues,
+        )?;
+        let has_extensions =
+            self.validate_table_schema("gpkg_extensions", EXTENSIONS_COLUMNS, false, &mut issues)?;
+
+        if has_geometry_columns {
+            self.validate_geometry_columns(has_srs, has_extensions, options, &mut issues)?;
+        }
+        if has_extensions {
+            self.validate_extensions(&mut issues)?;
+        }
+
+        Ok(ValidationReport { issues })
+    }
+
+    fn validate_pragmas(&self, issues: &mut Vec<ValidationIssue>) -> Result<()> {
+        let application_id: i32 = self
+            .conn
+            .pragma_query_value(None, "application_id", |row| row.get(0))?;
+        if application_id != GPKG_APPLICATION_ID {
+            issues.push(ValidationIssue::InvalidApplicationId { application_id });
+        }
+        let user_version: i32 = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if user_version < MIN_USER_VERSION {
+            issues.push(ValidationIssue::UnexpectedUserVersion { user_version });
+        }
+        Ok(())
+    }
+
+    // Check that the table has the required columns. Returns whether the
+    // table exists and is usable for the later checks.
+    fn validate_table_schema(
+        &self,
+        table_name: &str,
+        columns: &[&str],
+        required: bool,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Result<bool> {
+        let exists: bool = self
+            .conn
+            .query_row(SQL_TABLE_EXISTS, [table_name], |row| row.get(0))?;
+        if !exists {
+            if required {
+                issues.push(ValidationIssue::MissingTable {
+                    table_name: table_name.to_string(),
+                });
+            }
+            return Ok(false);
+        }
+
+        let mut complete = true;
+        for column_name in columns {
+            let has_column: bool =
+                self.conn
+                    .query_row(SQL_COLUMN_EXISTS, [table_name, column_name], |row| {
+                        row.get(0)
+                    })?;
+            if !has_column {
+                complete = false;
+                issues.push(ValidationIssue::MissingColumn {
+                    table_name: table_name.to_string(),
+                    column_name: column_name.to_string(),
+                });
+            }
+        }
+        Ok(complete)
+    }
+
+    // Check that every gpkg_contents row has a table. Returns whether any of
+    // the rows is a feature table.
+    fn validate_contents(&self, issues: &mut Vec<ValidationIssue>) -> Result<bool> {
+        let mut stmt = self.conn.prepare(SQL_SELECT_CONTENTS_TABLES)?;
+        let contents = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut has_features = false;
+        for (table_name, data_type) in contents {
+            has_features |= data_type == "features";
+            if !self.schema_object_exists(&table_name)? {
+                issues.push(ValidationIssue::MissingContentsTable { table_name });
+            }
+        }
+        Ok(has_features)
+    }
+
+    fn validate_geometry_columns(
+        &self,
+        has_srs: bool,
+        has_extensions: bool,
+        options: &ValidationOptions,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Result<()> {
+        let mut stmt = self.conn.prepare(SQL_SELECT_GEOMETRY_COLUMNS)?;
+        let geometry_columns = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i32>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for (table_name, column_name, geometry_type_name, srs_id) in geometry_columns {
+            if has_srs {
+                let srs_exists: bool = self
+                    .conn
+                    .query_row(SQL_SRS_EXISTS, [srs_id], |row| row.get(0))?;
+                if !srs_exists {
+                    issues.push(ValidationIssue::UnknownSrsId {
+                        table_name: table_name.clone(),
+                        srs_id,
+                    });
+                }
+            }
+
+            // A missing table is already reported from gpkg_contents.
+            let is_table: bool = self
+                .conn
+                .query_row(SQL_TABLE_EXISTS, [&table_name], |row| row.get(0))?;
+            if !is_table {
+                continue;
+            }
+            self.validate_geometries(
+                &table_name,
+                &column_name,
+                &geometry_type_name,
+                options.max_geometries_per_layer,
+                issues,
+            )?;
+            self.validate_rtree(&table_name, &column_name, has_extensions, issues)?;
+        }
+        Ok(())
+    }
+
+    fn validate_geometries(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        geometry_type_name: &str,
+        limit: Option<u64>,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare(&sql_select_geometries(table_name, column_name, limit))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let invalid = |reason: String| ValidationIssue::InvalidGeometryBlob {
+                table_name: table_name.to_string(),
+                column_name: column_name.to_string(),
+                id,
+                reason,
+            };
+
+            let blob = match row.get_ref(1)?.as_blob() {
+                Ok(blob) => blob,
+                Err(_) => {
+                    issues.push(invalid("not a blob".to_string()));
+                    continue;
+                }
+            };
+            if !blob.starts_with(b"GP") {
+                issues.push(invalid("missing GP magic".to_string()));
+                continue;
+            }
+            let found = match gpkg_geometry_to_wkb(blob) {
+                Ok(wkb) => wkb.geometry_type(),
+                // Curves of the non-linear geometry extension can't be decoded
+                // here, but they are valid.
+                Err(GpkgError::UnsupportedGeometryType(_)) => continue,
+                Err(e) => {
+                    issues.push(invalid(e.to_string()));
+                    continue;
+                }
+            };
+            if !is_assignable(geometry_type_name, geometry_type_to_str(found)) {
+                issues.push(ValidationIssue::GeometryTypeMismatch {
+                    table_name: table_name.to_string(),
+                    column_name: column_name.to_string(),
+                    id,
+                    declared: geometry_type_name.to_string(),
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_rtree(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        has_extensions: bool,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Result<()> {
+        let rtree_name = format!("rtree_{table_name}_{column_name}");
+        let rtree_exists: bool = self
+            .conn
+            .query_row(SQL_TABLE_EXISTS, [&rtree_name], |row| row.get(0))?;
+        if !rtree_exists {
+            let registered: bool = has_extensions
+                && self.conn.query_row(
+                    SQL_RTREE_EXTENSION_EXISTS,
+                    [table_name, column_name],
+                    |row| row.get(0),
+                )?;
+            if registered {
+                issues.push(ValidationIssue::MissingRtreeIndex {
+                    table_name: table_name.to_string(),
+                    column_name: column_name.to_string(),
+                });
+            }
+            return Ok(());
+        }
+
+        // Files written against GeoPackage 1.3 or earlier have the legacy set
+        // of triggers, which is still valid.
+        let mut missing = Vec::new();
+        let mut missing_legacy = Vec::new();
+        for (suffixes, missing) in [
+            (&RTREE_TRIGGER_SUFFIXES[..], &mut missing),
+            (&LEGACY_RTREE_TRIGGER_SUFFIXES[..], &mut missing_legacy),
+        ] {
+            for suffix in suffixes {
+                let trigger_name = format!("{rtree_name}_{suffix}");
+                let exists: bool =
+                    self.conn
+                        .query_row(SQL_TRIGGER_EXISTS, [&trigger_name], |row| row.get(0))?;
+                if !exists {
+                    missing.push(trigger_name);
+                }
+            }
+        }
+        if missing_legacy.is_empty() {
+            return Ok(());
+        }
+        issues.extend(missing.into_iter().map(|trigger_name| {
+            ValidationIssue::MissingRtreeTrigger {
+                table_name: table_name.to_string(),
+                trigger_name,
+            }
+        }));
+        Ok(())
+    }
+
+    fn validate_extensions(&self, issues: &mut Vec<ValidationIssue>) -> Result<()> {
+        let mut stmt = self.conn.prepare(SQL_SELECT_EXTENSION_TARGETS)?;
+        let targets = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for (extension_name, table_name, column_name) in targets {
+            let exists = if self.schema_object_exists(&table_name)? {
+                match &column_name {
+                    Some(column_name) => self.conn.query_row(
+                        SQL_COLUMN_EXISTS,
+                        [&table_name, column_name],
+                        |row| row.get(0),
+                    )?,
+                    None => true,
+                }
+            } else {
+                false
+            };
+            if !exists {
+                issues.push(ValidationIssue::MissingExtensionTarget {
+                    extension_name,
+                    table_name,
+                    column_name,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn schema_object_exists(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .query_row(SQL_SCHEMA_OBJECT_EXISTS, [name], |row| row.get(0))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gpkg;
+    use crate::error::GpkgError;
+    use crate::params;
+    use crate::types::{ValidationIssue, ValidationOptions, ValidationSeverity};
+    use geo_types::{LineString, Point, coord};
+    use wkb::reader::{Dimension, GeometryType};
+
+    fn gpkg_with_points() -> Result<Gpkg, GpkgError> {
+        let gpkg = Gpkg::open_in_memory()?;
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        layer.insert(Point::new(1.0, 2.0), params![])?;
+        layer.insert(Point::new(3.0, 4.0), params![])?;
+        Ok(gpkg)
+    }
+
+    #[test]
+    fn new_gpkg_is_valid() -> Result<(), GpkgError> {
+        let gpkg = gpkg_with_points()?;
+        gpkg.create_attribute_table("codes", &[])?;
+        let report = gpkg.validate()?;
+        assert_eq!(report.issues, vec![]);
+        assert!(report.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn generated_fixture_is_valid() -> Result<(), GpkgError> {
+        let gpkg = Gpkg::open_read_only("src/test/test_generated.gpkg")?;
+        let report = gpkg.validate()?;
+        assert!(report.is_valid(), "{:?}", report.issues);
+        Ok(())
+    }
+
+    #[test]
+    fn reports_broken_gpkg() -> Result<(), GpkgError> {
+        let gpkg = gpkg_with_points()?;
+        let broken = gpkg.create_layer(
+            "broken",
+            "geom",
+            GeometryType::LineString,
+            Dimension::Xy,
+            4326,
+            &[],
+        )?;
+        broken.drop_spatial_index()?;
+        broken.insert(
+            LineString::new(vec![coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 1.0 }]),
+            params![],
+        )?;
+        gpkg.conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             PRAGMA application_id = 0;
+             PRAGMA user_version = 0;
+             INSERT INTO broken (geom) VALUES (X'0001020304050607080900');
+             INSERT INTO broken (geom) VALUES ('POINT (1 2)');
+             UPDATE gpkg_geometry_columns SET geometry_type_name = 'POINT', srs_id = 9999
+               WHERE table_name = 'broken';
+             INSERT INTO gpkg_extensions VALUES
+               ('broken', 'geom', 'gpkg_rtree_index', 'http://www.geopackage.org/spec/#extension_rtree', 'write-only'),
+               ('missing', NULL, 'custom_extension', 'http://example.com', 'read-write'),
+               ('points', 'missing', 'custom_extension', 'http://example.com', 'read-write');
+             INSERT INTO gpkg_contents (table_name, data_type) VALUES ('gone', 'features');
+             DROP TRIGGER rtree_points_geom_update6;",
+        )?;
+
+        let report = gpkg.validate()?;
+        let expected = vec![
+            ValidationIssue::InvalidApplicationId { application_id: 0 },
+            ValidationIssue::UnexpectedUserVersion { user_version: 0 },
+            ValidationIssue::MissingContentsTable {
+                table_name: "gone".to_string(),
+            },
+            ValidationIssue::UnknownSrsId {
+                table_name: "broken".to_string(),
+                srs_id: 9999,
+            },
+            ValidationIssue::GeometryTypeMismatch {
+                table_name: "broken".to_string(),
+                column_name: "geom".to_string(),
+                id: 1,
+                declared: "POINT".to_string(),
+                found: GeometryType::LineString,
+            },
+            ValidationIssue::InvalidGeometryBlob {
+                table_name: "broken".to_string(),
+                column_name: "geom".to_string(),
+                id: 2,
+                reason: "missing GP magic".to_string(),
+            },
+            ValidationIssue::InvalidGeometryBlob {
+                table_name: "broken".to_string(),
+                column_name: "geom".to_string(),
+                id: 3,
+                reason: "not a blob".to_string(),
+            },
+            ValidationIssue::MissingRtreeIndex {
+                table_name: "broken".to_string(),
+                column_name: "geom".to_string(),
+            },
+            ValidationIssue::MissingRtreeTrigger {
+                table_name: "points".to_string(),
+                trigger_name: "rtree_points_geom_update6".to_string(),
+            },
+            ValidationIssue::MissingExtensionTarget {
+                extension_name: "custom_extension".to_string(),
+                table_name: "missing".to_string(),
+                column_name: None,
+            },
+            ValidationIssue::MissingExtensionTarget {
+                extension_name: "custom_extension".to_string(),
+                table_name: "points".to_string(),
+                column_name: Some("missing".to_string()),
+            },
+        ];
+        assert_eq!(report.issues, expected);
+        assert!(!report.is_valid());
+        assert_eq!(report.errors().count(), expected.len() - 1);
+        let warnings: Vec<_> = report.warnings().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity(), ValidationSeverity::Warning);
+        assert_eq!(warnings[0].to_string(), "unexpected user_version: 0");
+
+        // Only the first geometry is read when sampling.
+        let options = ValidationOptions {
+            max_geometries_per_layer: Some(1),
+        };
+        let sampled = gpkg.validate_with_options(&options)?;
+        assert_eq!(
+            sampled
+                .issues
+                .iter()
+                .filter(|issue| matches!(issue, ValidationIssue::InvalidGeometryBlob { .. }))
+                .count(),
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_missing_core_tables() -> Result<(), GpkgError> {
+        let gpkg = gpkg_with_points()?;
+        gpkg.conn.execute_batch(
+            "ALTER TABLE gpkg_spatial_ref_sys DROP COLUMN description;
+             DROP TABLE gpkg_geometry_columns;",
+        )?;
+        let report = gpkg.validate()?;
+        assert_eq!(
+            report.issues,
+            vec![
+                ValidationIssue::MissingColumn {
+                    table_name: "gpkg_spatial_ref_sys".to_string(),
+                    column_name: "description".to_string(),
+                },
+                ValidationIssue::MissingTable {
+                    table_name: "gpkg_geometry_columns".to_string(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accepts_legacy_rtree_triggers() -> Result<(), GpkgError> {
+        let gpkg = gpkg_with_points()?;
+        gpkg.conn.execute_batch(
+            "DROP TRIGGER rtree_points_geom_update5;
+             DROP TRIGGER rtree_points_geom_update6;
+             DROP TRIGGER rtree_points_geom_update7;
+             CREATE TRIGGER rtree_points_geom_update1 AFTER UPDATE OF geom ON points BEGIN SELECT 1; END;
+             CREATE TRIGGER rtree_points_geom_update3 AFTER UPDATE ON points BEGIN SELECT 1; END;",
+        )?;
+        assert!(gpkg.validate()?.issues.is_empty());
+        Ok(())
+    }
+}
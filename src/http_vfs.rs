@@ -0,0 +1,780 @@
+//! Read-only VFS that reads a GeoPackage with HTTP range requests.
+//!
+//! SQLite reads the database page by page, so only the parts of the file a
+//! query touches are downloaded. Downloaded data is cached in fixed-size
+//! blocks. Files other than the main database (e.g. temporary files for
+//! sorting) are delegated to the default VFS.
+
+use crate::{Gpkg, GpkgError, Result};
+use rusqlite::{OpenFlags, ffi};
+use std::collections::HashMap;
+use std::ffi::{CStr, c_char, c_int, c_void};
+use std::io::Read;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+const VFS_NAME: &CStr = c"rusqlite-gpkg-http";
+// Prefix of the names under which remote files are registered for `x_open`.
+const FILE_PREFIX: &str = "rusqlite-gpkg-http-";
+const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+const DEFAULT_CACHE_BUDGET: usize = 16 * 1024 * 1024;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A source of byte ranges of a remote file, used by [`HttpVfsBuilder`].
+///
+/// The built-in implementation uses `reqwest`'s blocking client. Implement
+/// this trait with the HTTP client of your choice (e.g. to add
+/// authentication) and pass it to [`HttpVfsBuilder::reader`].
+pub trait RangeReader: Send + Sync {
+    /// Return the size of the file in bytes.
+    fn size(&self) -> std::io::Result<u64>;
+
+    /// Fill `buf` with the bytes of the file starting at `offset`.
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<()>;
+}
+
+/// Builder for opening a remote GeoPackage read-only over HTTP range requests.
+///
+/// Example:
+/// ```no_run
+/// use rusqlite_gpkg::HttpVfsBuilder;
+///
+/// let gpkg = HttpVfsBuilder::new("http://example.com/data.gpkg")
+///     .cache_budget(64 * 1024 * 1024)
+///     .open()?;
+/// let layer = gpkg.get_layer("points")?;
+/// let _features = layer.features_in_bbox(0.0, 0.0, 1.0, 1.0)?;
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub struct HttpVfsBuilder {
+    url: String,
+    reader: Option<Box<dyn RangeReader>>,
+    block_size: usize,
+    cache_budget: usize,
+}
+
+impl HttpVfsBuilder {
+    /// Create a builder for the GeoPackage at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            reader: None,
+            block_size: DEFAULT_BLOCK_SIZE,
+            cache_budget: DEFAULT_CACHE_BUDGET,
+        }
+    }
+
+    /// Use `reader` to fetch the file instead of the built-in HTTP client.
+    /// The URL is then only used in error messages.
+    pub fn reader<R: RangeReader + 'static>(mut self, reader: R) -> Self {
+        self.reader = Some(Box::new(reader));
+        self
+    }
+
+    /// Set the number of bytes fetched per request (default: 64 KiB).
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Set the maximum number of bytes kept in the block cache (default:
+    /// 16 MiB). The least recently used blocks are dropped first.
+    pub fn cache_budget(mut self, cache_budget: usize) -> Self {
+        self.cache_budget = cache_budget;
+        self
+    }
+
+    /// Open the remote GeoPackage.
+    ///
+    /// The returned `Gpkg` is read-only. Failing to reach the file or to read
+    /// its schema is reported as `GpkgError::Vfs`; a read that fails later,
+    /// while running a query, is reported by SQLite as a disk I/O error.
+    pub fn open(self) -> Result<Gpkg> {
+        let url = self.url;
+        let vfs_error = |e: &dyn std::fmt::Display| GpkgError::Vfs(format!("{url}: {e}"));
+
+        let reader = match self.reader {
+            Some(reader) => reader,
+            None => Box::new(HttpRangeReader::new(&url).map_err(|e| vfs_error(&e))?),
+        };
+        let size = reader.size().map_err(|e| vfs_error(&e))?;
+        register_vfs()?;
+
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let name = format!("{FILE_PREFIX}{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        let file = RemoteFile {
+            reader,
+            size,
+            block_size: self.block_size,
+            cache: Mutex::new(BlockCache::new(self.cache_budget)),
+        };
+        // `x_open` takes the file out of the registry while the connection opens.
+        lock(registry()).insert(name.clone(), Arc::new(file));
+        let conn = rusqlite::Connection::open_with_flags_and_vfs(
+            format!("file:{name}?immutable=1"),
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            VFS_NAME,
+        );
+        lock(registry()).remove(&name);
+        let conn = conn.map_err(|e| vfs_error(&e))?;
+
+        conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .map_err(|e| vfs_error(&e))?;
+        Gpkg::new_from_conn(Rc::new(conn), true)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Arc<RemoteFile>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Arc<RemoteFile>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+struct RemoteFile {
+    reader: Box<dyn RangeReader>,
+    size: u64,
+    block_size: usize,
+    cache: Mutex<BlockCache>,
+}
+
+impl RemoteFile {
+    /// Read into `buf` from `offset` and return the number of bytes read, which
+    /// is less than `buf.len()` only at the end of the file.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+        let block_size = self.block_size as u64;
+        let end = offset.saturating_add(buf.len() as u64).min(self.size);
+        let mut cache = lock(&self.cache);
+        let mut pos = offset;
+        while pos < end {
+            let index = pos / block_size;
+            let block = match cache.get(index) {
+                Some(block) => block,
+                None => {
+                    let start = index * block_size;
+                    let mut block = vec![0; block_size.min(self.size - start) as usize];
+                    self.reader.read_exact_at(start, &mut block)?;
+                    cache.insert(index, block.into())
+                }
+            };
+            let from = (pos - index * block_size) as usize;
+            let n = (block.len() - from).min((end - pos) as usize);
+            let to = (pos - offset) as usize;
+            buf[to..to + n].copy_from_slice(&block[from..from + n]);
+            pos += n as u64;
+        }
+        Ok(end.saturating_sub(offset) as usize)
+    }
+}
+
+struct BlockCache {
+    blocks: HashMap<u64, (Arc<[u8]>, u64)>,
+    budget: usize,
+    bytes: usize,
+    clock: u64,
+}
+
+impl BlockCache {
+    fn new(budget: usize) -> Self {
+        Self {
+            blocks: HashMap::new(),
+            budget,
+            bytes: 0,
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, index: u64) -> Option<Arc<[u8]>> {
+        self.clock += 1;
+        let (block, last_used) = self.blocks.get_mut(&index)?;
+        *last_used = self.clock;
+        Some(block.clone())
+    }
+
+    fn insert(&mut self, index: u64, block: Arc<[u8]>) -> Arc<[u8]> {
+        while self.bytes + block.len() > self.budget {
+            let Some(oldest) = self
+                .blocks
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(index, _)| *index)
+            else {
+                break;
+            };
+            if let Some((evicted, _)) = self.blocks.remove(&oldest) {
+                self.bytes -= evicted.len();
+            }
+        }
+        self.clock += 1;
+        self.bytes += block.len();
+        self.blocks.insert(index, (block.clone(), self.clock));
+        block
+    }
+}
+
+// The built-in client, on top of `reqwest`'s blocking client. It follows
+// redirects and supports both `http://` and `https://` URLs.
+struct HttpRangeReader {
+    client: reqwest::blocking::Client,
+    url: reqwest::Url,
+}
+
+impl HttpRangeReader {
+    fn new(url: &str) -> std::io::Result<Self> {
+        let url = reqwest::Url::parse(url)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let client = reqwest::blocking::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .map_err(std::io::Error::other)?;
+        Ok(Self { client, url })
+    }
+
+    /// GET the inclusive byte range `first..=last` and return the total size
+    /// of the file from `Content-Range`, and the body. The body is read up to
+    /// the length of the range, whatever `Content-Length` the server claims.
+    fn get_range(&self, first: u64, last: u64) -> std::io::Result<(Option<u64>, Vec<u8>)> {
+        let response = self
+            .client
+            .get(self.url.clone())
+            .header(reqwest::header::RANGE, format!("bytes={first}-{last}"))
+            .send()
+            .map_err(std::io::Error::other)?;
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            return Err(std::io::Error::other(format!(
+                "unexpected response to a range request: {}",
+                response.status()
+            )));
+        }
+
+        let total = response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit_once('/'))
+            .and_then(|(_, total)| total.parse().ok());
+
+        // Read one byte more than the range to tell a longer body apart.
+        let len = last - first + 1;
+        let mut body = Vec::new();
+        response.take(len + 1).read_to_end(&mut body)?;
+        if body.len() as u64 > len {
+            return Err(std::io::Error::other(format!(
+                "response body is longer than the requested {len} bytes"
+            )));
+        }
+        Ok((total, body))
+    }
+}
+
+impl RangeReader for HttpRangeReader {
+    fn size(&self) -> std::io::Result<u64> {
+        let (total, _) = self.get_range(0, 0)?;
+        total.ok_or_else(|| std::io::Error::other("missing file size in Content-Range"))
+    }
+
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<()> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        let (_, body) = self.get_range(offset, offset + buf.len() as u64 - 1)?;
+        if body.len() != buf.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("expected {} bytes, got {}", buf.len(), body.len()),
+            ));
+        }
+        buf.copy_from_slice(&body);
+        Ok(())
+    }
+}
+
+// The VFS
+
+#[repr(C)]
+struct RemoteFileHandle {
+    base: ffi::sqlite3_file,
+    file: *const RemoteFile,
+}
+
+fn register_vfs() -> Result<()> {
+    static RC: OnceLock<c_int> = OnceLock::new();
+    let rc = *RC.get_or_init(|| unsafe {
+        let default = ffi::sqlite3_vfs_find(std::ptr::null());
+        if default.is_null() {
+            return ffi::SQLITE_ERROR;
+        }
+        let d = &*default;
+        let vfs = Box::new(ffi::sqlite3_vfs {
+            iVersion: 2,
+            // Other files are opened by the default VFS in the same memory.
+            szOsFile: d.szOsFile.max(size_of::<RemoteFileHandle>() as c_int),
+            mxPathname: d.mxPathname,
+            pNext: std::ptr::null_mut(),
+            zName: VFS_NAME.as_ptr(),
+            pAppData: default.cast(),
+            xOpen: Some(x_open),
+            xDelete: Some(x_delete),
+            xAccess: Some(x_access),
+            xFullPathname: Some(x_full_pathname),
+            xDlOpen: d.xDlOpen,
+            xDlError: d.xDlError,
+            xDlSym: d.xDlSym,
+            xDlClose: d.xDlClose,
+            xRandomness: d.xRandomness,
+            xSleep: d.xSleep,
+            xCurrentTime: d.xCurrentTime,
+            xGetLastError: d.xGetLastError,
+            xCurrentTimeInt64: d.xCurrentTimeInt64,
+            xSetSystemCall: None,
+            xGetSystemCall: None,
+            xNextSystemCall: None,
+        });
+        // The VFS stays registered for the lifetime of the process.
+        ffi::sqlite3_vfs_register(Box::into_raw(vfs), 0)
+    });
+
+    if rc != ffi::SQLITE_OK {
+        return Err(GpkgError::Vfs(format!(
+            "failed to register the HTTP VFS (SQLite error code {rc})"
+        )));
+    }
+    Ok(())
+}
+
+unsafe fn default_vfs(vfs: *mut ffi::sqlite3_vfs) -> *mut ffi::sqlite3_vfs {
+    unsafe { (*vfs).pAppData.cast() }
+}
+
+unsafe fn is_remote_name(name: *const c_char) -> bool {
+    !name.is_null()
+        && unsafe { CStr::from_ptr(name) }
+            .to_bytes()
+            .starts_with(FILE_PREFIX.as_bytes())
+}
+
+unsafe extern "C" fn x_open(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: ffi::sqlite3_filename,
+    file: *mut ffi::sqlite3_file,
+    flags: c_int,
+    out_flags: *mut c_int,
+) -> c_int {
+    unsafe {
+        if flags & ffi::SQLITE_OPEN_MAIN_DB == 0 || !is_remote_name(name) {
+            let default = default_vfs(vfs);
+            return match (*default).xOpen {
+                Some(open) => open(default, name, file, flags, out_flags),
+                None => ffi::SQLITE_CANTOPEN,
+            };
+        }
+
+        let handle = file.cast::<RemoteFileHandle>();
+        (*handle).base.pMethods = std::ptr::null();
+        let name = CStr::from_ptr(name).to_string_lossy();
+        let Some(remote) = lock(registry()).get(name.as_ref()).cloned() else {
+            return ffi::SQLITE_CANTOPEN;
+        };
+        (*handle).file = Arc::into_raw(remote);
+        (*handle).base.pMethods = &IO_METHODS;
+        if !out_flags.is_null() {
+            *out_flags = ffi::SQLITE_OPEN_READONLY | ffi::SQLITE_OPEN_MAIN_DB;
+        }
+        ffi::SQLITE_OK
+    }
+}
+
+unsafe extern "C" fn x_delete(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    sync_dir: c_int,
+) -> c_int {
+    unsafe {
+        if is_remote_name(name) {
+            return ffi::SQLITE_READONLY;
+        }
+        let default = default_vfs(vfs);
+        match (*default).xDelete {
+            Some(delete) => delete(default, name, sync_dir),
+            None => ffi::SQLITE_IOERR_DELETE,
+        }
+    }
+}
+
+unsafe extern "C" fn x_access(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    flags: c_int,
+    res_out: *mut c_int,
+) -> c_int {
+    unsafe {
+        // There are no journal or WAL files next to a remote database.
+        if is_remote_name(name) {
+            *res_out = 0;
+            return ffi::SQLITE_OK;
+        }
+        let default = default_vfs(vfs);
+        match (*default).xAccess {
+            Some(access) => access(default, name, flags, res_out),
+            None => ffi::SQLITE_IOERR_ACCESS,
+        }
+    }
+}
+
+unsafe extern "C" fn x_full_pathname(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    n_out: c_int,
+    z_out: *mut c_char,
+) -> c_int {
+    unsafe {
+        if is_remote_name(name) {
+            let bytes = CStr::from_ptr(name).to_bytes_with_nul();
+            if bytes.len() > n_out as usize {
+                return ffi::SQLITE_CANTOPEN;
+            }
+            std::ptr::copy_nonoverlapping(bytes.as_ptr().cast(), z_out, bytes.len());
+            return ffi::SQLITE_OK;
+        }
+        let default = default_vfs(vfs);
+        match (*default).xFullPathname {
+            Some(full_pathname) => full_pathname(default, name, n_out, z_out),
+            None => ffi::SQLITE_CANTOPEN,
+        }
+    }
+}
+
+static IO_METHODS: ffi::sqlite3_io_methods = ffi::sqlite3_io_methods {
+    iVersion: 1,
+    xClose: Some(x_close),
+    xRead: Some(x_read),
+    xWrite: Some(x_write),
+    xTruncate: Some(x_truncate),
+    xSync: Some(x_sync),
+    xFileSize: Some(x_file_size),
+    xLock: Some(x_lock),
+    xUnlock: Some(x_lock),
+    xCheckReservedLock: Some(x_check_reserved_lock),
+    xFileControl: Some(x_file_control),
+    xSectorSize: Some(x_sector_size),
+    xDeviceCharacteristics: Some(x_device_characteristics),
+    xShmMap: None,
+    xShmLock: None,
+    xShmBarrier: None,
+    xShmUnmap: None,
+    xFetch: None,
+    xUnfetch: None,
+};
+
+unsafe fn remote_file<'a>(file: *mut ffi::sqlite3_file) -> &'a RemoteFile {
+    unsafe { &*(*file.cast::<RemoteFileHandle>()).file }
+}
+
+unsafe extern "C" fn x_close(file: *mut ffi::sqlite3_file) -> c_int {
+    unsafe {
+        let handle = file.cast::<RemoteFileHandle>();
+        if !(*handle).file.is_null() {
+            drop(Arc::from_raw((*handle).file));
+            (*handle).file = std::ptr::null();
+        }
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_read(
+    file: *mut ffi::sqlite3_file,
+    buf: *mut c_void,
+    amount: c_int,
+    offset: ffi::sqlite3_int64,
+) -> c_int {
+    let (remote, buf) = unsafe {
+        (
+            remote_file(file),
+            std::slice::from_raw_parts_mut(buf.cast::<u8>(), amount as usize),
+        )
+    };
+    // A panic in a user-provided reader must not unwind into SQLite.
+    match catch_unwind(AssertUnwindSafe(|| remote.read_at(offset as u64, buf))) {
+        Ok(Ok(n)) if n == buf.len() => ffi::SQLITE_OK,
+        Ok(Ok(n)) => {
+            buf[n..].fill(0);
+            ffi::SQLITE_IOERR_SHORT_READ
+        }
+        Ok(Err(_)) | Err(_) => ffi::SQLITE_IOERR_READ,
+    }
+}
+
+unsafe extern "C" fn x_write(
+    _file: *mut ffi::sqlite3_file,
+    _buf: *const c_void,
+    _amount: c_int,
+    _offset: ffi::sqlite3_int64,
+) -> c_int {
+    ffi::SQLITE_READONLY
+}
+
+unsafe extern "C" fn x_truncate(_file: *mut ffi::sqlite3_file, _size: ffi::sqlite3_int64) -> c_int {
+    ffi::SQLITE_READONLY
+}
+
+unsafe extern "C" fn x_sync(_file: *mut ffi::sqlite3_file, _flags: c_int) -> c_int {
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_file_size(
+    file: *mut ffi::sqlite3_file,
+    size: *mut ffi::sqlite3_int64,
+) -> c_int {
+    unsafe {
+        *size = remote_file(file).size as ffi::sqlite3_int64;
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_lock(_file: *mut ffi::sqlite3_file, _level: c_int) -> c_int {
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_check_reserved_lock(
+    _file: *mut ffi::sqlite3_file,
+    res_out: *mut c_int,
+) -> c_int {
+    unsafe {
+        *res_out = 0;
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_file_control(
+    _file: *mut ffi::sqlite3_file,
+    _op: c_int,
+    _arg: *mut c_void,
+) -> c_int {
+    ffi::SQLITE_NOTFOUND
+}
+
+unsafe extern "C" fn x_sector_size(_file: *mut ffi::sqlite3_file) -> c_int {
+    512
+}
+
+unsafe extern "C" fn x_device_characteristics(_file: *mut ffi::sqlite3_file) -> c_int {
+    ffi::SQLITE_IOCAP_IMMUTABLE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use crate::types::{ColumnSpec, ColumnType};
+    use geo_types::Point;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use wkb::reader::{Dimension, GeometryType};
+
+    fn create_fixture(name: &str) -> Result<PathBuf> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("rusqlite_gpkg_{name}_{nanos}.gpkg"));
+        let gpkg = Gpkg::open(&path)?;
+        let columns = vec![ColumnSpec {
+            name: "note".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        let note = "x".repeat(200);
+        layer.insert_many((0..10_000).map(|i| {
+            let point = Point::new((i % 100) as f64, (i / 100) as f64);
+            (point, vec![Value::from(note.as_str())])
+        }))?;
+        Ok(path)
+    }
+
+    // Serve `data` at `/test.gpkg` with range requests, redirect
+    // `/redirect.gpkg` to it, pad the ranges of `/long.gpkg` with extra bytes,
+    // and 404 otherwise.
+    fn serve(data: Vec<u8>, served: Arc<AtomicU64>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let port = listener.local_addr().expect("address").port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    let Some((name, value)) = line.split_once(':') else {
+                        continue;
+                    };
+                    if let Some(value) = name
+                        .eq_ignore_ascii_case("range")
+                        .then(|| value.trim().strip_prefix("bytes="))
+                        .flatten()
+                    {
+                        let (first, last) = value.split_once('-').expect("range");
+                        range = Some((first.parse::<usize>().unwrap(), last.parse().unwrap()));
+                    }
+                }
+                let padding = if request_line.starts_with("GET /long.gpkg ") {
+                    16
+                } else {
+                    0
+                };
+                match range {
+                    _ if request_line.starts_with("GET /redirect.gpkg ") => {
+                        let _ = write!(
+                            stream,
+                            "HTTP/1.1 302 Found\r\nLocation: /test.gpkg\r\nContent-Length: 0\r\n\r\n"
+                        );
+                    }
+                    Some((first, last))
+                        if request_line.starts_with("GET /test.gpkg ") || padding > 0 =>
+                    {
+                        let last = usize::min(last, data.len() - 1);
+                        let body = &data[first..=last];
+                        served.fetch_add(body.len() as u64, Ordering::SeqCst);
+                        let _ = write!(
+                            stream,
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {first}-{last}/{}\r\nContent-Length: {}\r\n\r\n",
+                            data.len(),
+                            body.len() + padding
+                        );
+                        let _ = stream.write_all(body);
+                        let _ = stream.write_all(&vec![0; padding]);
+                    }
+                    _ => {
+                        let _ = write!(
+                            stream,
+                            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+                        );
+                    }
+                }
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn reads_bbox_over_http_without_fetching_whole_file() -> Result<()> {
+        let path = create_fixture("http_vfs")?;
+        let data = std::fs::read(&path)?;
+        let file_size = data.len() as u64;
+        let served = Arc::new(AtomicU64::new(0));
+        let port = serve(data, served.clone());
+
+        let local = Gpkg::open_read_only(&path)?;
+        let expected = local
+            .get_layer("points")?
+            .features_in_bbox(10.0, 10.0, 12.0, 12.0)?;
+        assert_eq!(expected.len(), 9);
+
+        let remote = HttpVfsBuilder::new(format!("http://127.0.0.1:{port}/test.gpkg"))
+            .block_size(4096)
+            .open()?;
+        let layer = remote.get_layer("points")?;
+        let actual = layer.features_in_bbox(10.0, 10.0, 12.0, 12.0)?;
+        assert_eq!(
+            actual.iter().map(|f| f.id()).collect::<Vec<_>>(),
+            expected.iter().map(|f| f.id()).collect::<Vec<_>>()
+        );
+        assert_eq!(actual[0].properties(), expected[0].properties());
+        let fetched = served.load(Ordering::SeqCst);
+        assert!(fetched < file_size / 10, "{fetched} of {file_size} bytes");
+
+        let err = layer
+            .insert(Point::new(0.0, 0.0), vec![Value::from("no")])
+            .expect_err("read-only");
+        assert!(matches!(err, GpkgError::ReadOnly), "{err}");
+
+        let err = HttpVfsBuilder::new(format!("http://127.0.0.1:{port}/missing.gpkg"))
+            .open()
+            .expect_err("404");
+        assert!(
+            matches!(&err, GpkgError::Vfs(msg) if msg.contains("404")),
+            "{err}"
+        );
+
+        let redirected =
+            HttpVfsBuilder::new(format!("http://127.0.0.1:{port}/redirect.gpkg")).open()?;
+        assert_eq!(redirected.get_layer("points")?.count()?, 10_000);
+
+        let err = HttpVfsBuilder::new(format!("http://127.0.0.1:{port}/long.gpkg"))
+            .open()
+            .expect_err("body longer than the range");
+        assert!(
+            matches!(&err, GpkgError::Vfs(msg) if msg.contains("longer")),
+            "{err}"
+        );
+
+        drop(local);
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    struct CountingReader {
+        data: Vec<u8>,
+        reads: Arc<AtomicU64>,
+    }
+
+    impl RangeReader for CountingReader {
+        fn size(&self) -> std::io::Result<u64> {
+            Ok(self.data.len() as u64)
+        }
+
+        fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<()> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            let offset = offset as usize;
+            buf.copy_from_slice(&self.data[offset..offset + buf.len()]);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn caches_blocks_of_custom_reader() -> Result<()> {
+        let path = create_fixture("http_vfs_reader")?;
+        let reads = Arc::new(AtomicU64::new(0));
+        let reader = CountingReader {
+            data: std::fs::read(&path)?,
+            reads: reads.clone(),
+        };
+        std::fs::remove_file(&path).ok();
+
+        let gpkg = HttpVfsBuilder::new("memory://test.gpkg")
+            .reader(reader)
+            .open()?;
+        let layer = gpkg.get_layer("points")?;
+        assert_eq!(layer.count()?, 10_000);
+        let after_first = reads.load(Ordering::SeqCst);
+        assert_eq!(layer.count()?, 10_000);
+        assert_eq!(reads.load(Ordering::SeqCst), after_first);
+
+        let err = HttpVfsBuilder::new("not a url")
+            .open()
+            .expect_err("invalid URL");
+        assert!(matches!(err, GpkgError::Vfs(_)), "{err}");
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Main database file of the hybrid VFS (`vfs.rs` on wasm, `native_vfs.rs`
+//! elsewhere).
+//!
+//! The file lives entirely in a user-provided `Read + Write + Seek` handle
+//! (e.g. an OPFS file): reads and writes seek to the requested offset, so no
+//! copy of the database is kept in memory and an existing file can be opened.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+#[cfg(target_family = "wasm")]
+pub(crate) trait HybridHandle: Read + Write + Seek {}
+#[cfg(target_family = "wasm")]
+impl<T: Read + Write + Seek> HybridHandle for T {}
+
+// A registered VFS can be used by a connection on any thread natively.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) trait HybridHandle: Read + Write + Seek + Send {}
+#[cfg(not(target_family = "wasm"))]
+impl<T: Read + Write + Seek + Send> HybridHandle for T {}
+
+/// Whether `name` is the main database file rather than a sidecar file
+/// (`-wal`, `-shm`, `-journal`), which the hybrid VFS keeps in memory.
+pub(crate) fn is_main_sqlite_file(name: &str) -> bool {
+    !name.ends_with("-wal") && !name.ends_with("-shm") && !name.ends_with("-journal")
+}
+
+pub(crate) struct HandleState {
+    handle: Box<dyn HybridHandle>,
+    /// Last known cursor position. `None` means unknown — initial state,
+    /// after a handle replacement, or after a failed seek/read/write that may
+    /// have left the cursor at an indeterminate offset.
+    pos: Option<u64>,
+    /// Size of the file as seen by SQLite, read from the handle on first use.
+    /// `truncate()` only lowers this, as `Seek` can't shrink the handle; SQLite
+    /// ignores bytes past the page count in the database header.
+    size: Option<u64>,
+}
+
+impl HandleState {
+    pub(crate) fn new(handle: Box<dyn HybridHandle>) -> Self {
+        Self {
+            handle,
+            pos: None,
+            size: None,
+        }
+    }
+
+    /// Move the cursor to `offset`, skipping the seek when it's already there.
+    /// SQLite emits long runs of contiguous page reads and writes; each `seek`
+    /// otherwise forces `BufWriter::flush_buf` plus, on OPFS, a synchronous
+    /// `get_size` JS round-trip.
+    fn seek_to(&mut self, offset: u64) -> std::io::Result<()> {
+        if self.pos != Some(offset) {
+            self.pos = None;
+            self.handle.seek(SeekFrom::Start(offset))?;
+            self.pos = Some(offset);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn size(&mut self) -> std::io::Result<u64> {
+        if let Some(size) = self.size {
+            return Ok(size);
+        }
+        self.pos = None;
+        let size = self.handle.seek(SeekFrom::End(0))?;
+        self.pos = Some(size);
+        self.size = Some(size);
+        Ok(size)
+    }
+
+    /// Read into `buf` from `offset`, filling the part past the end of the
+    /// file with zeros. Returns whether `buf` was filled from the file.
+    pub(crate) fn read_at(&mut self, buf: &mut [u8], offset: u64) -> std::io::Result<bool> {
+        let size = self.size()?;
+        let available = size.saturating_sub(offset).min(buf.len() as u64) as usize;
+        if available > 0 {
+            self.seek_to(offset)?;
+            if let Err(e) = self.handle.read_exact(&mut buf[..available]) {
+                self.pos = None;
+                return Err(e);
+            }
+            self.pos = Some(offset + available as u64);
+        }
+        buf[available..].fill(0);
+        Ok(available == buf.len())
+    }
+
+    pub(crate) fn write_at(&mut self, buf: &[u8], offset: u64) -> std::io::Result<()> {
+        let size = self.size()?;
+        self.seek_to(offset)?;
+        match self.handle.write_all(buf) {
+            Ok(()) => {
+                let end = offset + buf.len() as u64;
+                self.pos = Some(end);
+                self.size = Some(size.max(end));
+                Ok(())
+            }
+            Err(e) => {
+                self.pos = None;
+                Err(e)
+            }
+        }
+    }
+
+    pub(crate) fn truncate(&mut self, size: u64) -> std::io::Result<()> {
+        let current = self.size()?;
+        self.size = Some(current.min(size));
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        self.handle.flush()
+    }
+
+    pub(crate) fn replace(&mut self, handle: Box<dyn HybridHandle>) {
+        self.handle = handle;
+        self.pos = None;
+        self.size = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Cursor};
+    use std::sync::{Arc, Mutex};
+
+    /// Test handle that delegates to a shared `Cursor<Vec<u8>>` and counts
+    /// seeks, so the test can inspect it after it's boxed.
+    #[derive(Clone, Default)]
+    struct SharedCursor {
+        inner: Arc<Mutex<Cursor<Vec<u8>>>>,
+        seek_count: Arc<Mutex<usize>>,
+    }
+
+    impl SharedCursor {
+        fn with_data(data: Vec<u8>) -> Self {
+            Self {
+                inner: Arc::new(Mutex::new(Cursor::new(data))),
+                ..Default::default()
+            }
+        }
+
+        fn data(&self) -> Vec<u8> {
+            self.inner.lock().unwrap().get_ref().clone()
+        }
+    }
+
+    impl Read for SharedCursor {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.inner.lock().unwrap().read(buf)
+        }
+    }
+
+    impl Write for SharedCursor {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.inner.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.lock().unwrap().flush()
+        }
+    }
+
+    impl Seek for SharedCursor {
+        fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+            *self.seek_count.lock().unwrap() += 1;
+            self.inner.lock().unwrap().seek(pos)
+        }
+    }
+
+    #[test]
+    fn writes_land_at_offset_and_reads_come_from_handle() -> io::Result<()> {
+        let cursor = SharedCursor::default();
+        let mut file = HandleState::new(Box::new(cursor.clone()));
+
+        // The second write lands at offset 1, not appended at offset 3.
+        file.write_at(&[1, 2, 3], 0)?;
+        file.write_at(&[9], 1)?;
+        file.flush()?;
+        assert_eq!(cursor.data(), vec![1, 9, 3]);
+        assert_eq!(file.size()?, 3);
+
+        let mut buf = [7_u8; 4];
+        assert!(!file.read_at(&mut buf, 0)?);
+        assert_eq!(buf, [1, 9, 3, 0]);
+
+        let mut buf = [7_u8; 2];
+        assert!(!file.read_at(&mut buf, 5)?);
+        assert_eq!(buf, [0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn opens_existing_contents_and_rewrites_first_page() -> io::Result<()> {
+        // An existing file of two "pages"; SQLite rewrites page 1 when growing.
+        let cursor = SharedCursor::with_data(vec![1; 8]);
+        let mut file = HandleState::new(Box::new(cursor.clone()));
+        assert_eq!(file.size()?, 8);
+
+        file.write_at(&[3; 4], 8)?;
+        file.write_at(&[2; 4], 0)?;
+        assert_eq!(file.size()?, 12);
+
+        let mut buf = [0_u8; 12];
+        assert!(file.read_at(&mut buf, 0)?);
+        assert_eq!(buf, [2, 2, 2, 2, 1, 1, 1, 1, 3, 3, 3, 3]);
+        assert_eq!(cursor.data(), buf.to_vec());
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_hides_bytes_past_the_new_size() -> io::Result<()> {
+        let mut file = HandleState::new(Box::new(SharedCursor::with_data(vec![5; 8])));
+        file.truncate(4)?;
+        assert_eq!(file.size()?, 4);
+
+        let mut buf = [9_u8; 6];
+        assert!(!file.read_at(&mut buf, 2)?);
+        assert_eq!(buf, [5, 5, 0, 0, 0, 0]);
+
+        file.write_at(&[6], 6)?;
+        assert_eq!(file.size()?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn skips_seek_for_sequential_access() -> io::Result<()> {
+        let cursor = SharedCursor::default();
+        let mut file = HandleState::new(Box::new(cursor.clone()));
+
+        // Finding the size seeks to the end, which is where the first write goes.
+        file.write_at(&[1, 2, 3], 0)?;
+        assert_eq!(*cursor.seek_count.lock().unwrap(), 1);
+
+        // Sequential write at offset 3 should reuse the cursor position.
+        file.write_at(&[4, 5, 6], 3)?;
+        assert_eq!(*cursor.seek_count.lock().unwrap(), 1);
+
+        // Non-sequential access must seek again.
+        let mut buf = [0_u8; 2];
+        file.read_at(&mut buf, 0)?;
+        assert_eq!(*cursor.seek_count.lock().unwrap(), 2);
+        file.read_at(&mut buf, 2)?;
+        assert_eq!(*cursor.seek_count.lock().unwrap(), 2);
+        assert_eq!(buf, [3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn replace_resets_the_size() -> io::Result<()> {
+        let mut file = HandleState::new(Box::new(SharedCursor::with_data(vec![1; 4])));
+        assert_eq!(file.size()?, 4);
+        file.replace(Box::new(SharedCursor::default()));
+        assert_eq!(file.size()?, 0);
+        Ok(())
+    }
+}
@@ -15,13 +15,43 @@
 //!
 //! [`Value`] represents a single property value in both cases.
 //!
-//! Apache Arrow support is available behind the `arrow` feature flag.
+//! Apache Arrow support is available behind the `arrow` feature flag (with the
+//! Arrow C stream export, `ArrowGpkgReader::into_ffi_stream()`, behind `ffi`), and
+//! conversion into `geo_types` geometries behind the `geo` feature flag.
+//! `GpkgLayer::to_geojson_writer()` requires the `geojson` feature flag, and
+//! `GpkgLayer::to_flatgeobuf_writer()` the `flatgeobuf` feature flag.
+//! `Gpkg::create_layer_from_csv()` requires the `csv` feature flag.
+//! `Gpkg::export_parquet()` writes a layer as GeoParquet behind the `parquet`
+//! feature flag, which implies `arrow`.
+//! The `geozero` feature flag implements `geozero::GeozeroDatasource` for
+//! [`GpkgLayer`], to stream a layer into any geozero `FeatureProcessor`.
+//! The `epsg-common` feature flag bundles the definitions of a few common EPSG
+//! codes (e.g. 3857), which `create_layer()` registers automatically.
+//! The `serde` feature flag implements `Serialize`/`Deserialize` for [`Value`]
+//! and `Serialize` for [`GpkgFeature`].
+//! The `chrono` feature flag converts `DATE` and `DATETIME` values from and to
+//! `chrono::NaiveDate` and `chrono::DateTime<Utc>`.
+//! The `http` feature flag adds `Gpkg::open_http()` for reading a remote
+//! GeoPackage with HTTP range requests (not on wasm targets).
 //!
 //! `Gpkg` is the entry point and supports several open modes:
 //! `Gpkg::open_read_only(path)`, `Gpkg::open(path)`, and `Gpkg::open_in_memory()`.
 //!
 //! `GpkgLayer::features()` loads all features into memory. For large datasets,
-//! use `features_batch(batch_size)` to stream in chunks.
+//! use `features_batch(batch_size)` to stream in chunks. `GpkgLayer::count()`
+//! returns the number of features without loading them, which is handy for
+//! sizing batches or progress bars.
+//!
+//! ```no_run
+//! use rusqlite_gpkg::Gpkg;
+//! let layer = Gpkg::open_read_only("data.gpkg")?.get_layer("points")?;
+//! let total = layer.count()?;
+//! let batch_size = (total / 10).max(1) as u32;
+//! for batch in layer.features_batch(batch_size)? {
+//!     let _features = batch?;
+//! }
+//! # Ok::<(), rusqlite_gpkg::GpkgError>(())
+//! ```
 //!
 //! `GpkgLayer::insert` and `GpkgLayer::update` accept any geometry that implements
 //! `geo_traits::GeometryTrait<T = f64>` (for example `geo_types::Point`).
@@ -35,12 +65,16 @@
 //! # #[cfg(target_family = "wasm")]
 //! use rusqlite_gpkg::Gpkg;
 //! # #[cfg(target_family = "wasm")]
-//! # fn open_from_opfs<W: std::io::Write + std::io::Seek + 'static>(opfs_writer: W) -> Result<(), rusqlite_gpkg::GpkgError> {
+//! # fn open_from_opfs<W: std::io::Read + std::io::Write + std::io::Seek + 'static>(opfs_writer: W) -> Result<(), rusqlite_gpkg::GpkgError> {
 //! let _gpkg = Gpkg::open_with_writer("demo.sqlite", opfs_writer)?;
 //! # Ok(())
 //! # }
 //! ```
 //!
+//! `open_with_writer()`, `open_with_vfs()` and `HybridVfsBuilder` exist on
+//! native targets too (the writer must also be `Send` there), so the same code
+//! can be tested outside the browser, e.g. with a `std::io::Cursor`.
+//!
 //! If you prefer a storage-agnostic flow, use `to_bytes()` / `from_bytes()`:
 //!
 //! ```no_run
@@ -66,6 +100,9 @@
 //! # Ok::<(), rusqlite_gpkg::GpkgError>(())
 //! ```
 //!
+//! `Gpkg` is not `Send`. To read from multiple threads, share a [`GpkgPool`]
+//! and call `get()` on each thread to obtain a `Gpkg` with its own connection.
+//!
 //! ## GpkgLayer
 //!
 //! `GpkgLayer` models a single feature table. It exposes schema information
@@ -106,7 +143,7 @@
 //!     .features()?;
 //! let feature = features.first().expect("feature");
 //! let name: String = feature
-//!     .property("name")
+//!     .property_ref("name")
 //!     .ok_or_else(|| rusqlite_gpkg::GpkgError::MissingProperty {
 //!         property: "name".to_string(),
 //!     })?
@@ -123,8 +160,8 @@
 //! use rusqlite_gpkg::{ColumnSpec, ColumnType, Gpkg, params};
 //! let gpkg = Gpkg::open_in_memory()?;
 //! let columns = vec![
-//!     ColumnSpec { name: "name".to_string(), column_type: ColumnType::Varchar },
-//!     ColumnSpec { name: "value".to_string(), column_type: ColumnType::Integer },
+//!     ColumnSpec { name: "name".to_string(), column_type: ColumnType::Varchar, ..Default::default() },
+//!     ColumnSpec { name: "value".to_string(), column_type: ColumnType::Integer, ..Default::default() },
 //! ];
 //! let table = gpkg.create_attribute_table("observations", &columns)?;
 //! table.insert(params!["alpha", 7_i64])?;
@@ -158,8 +195,23 @@ mod sql_functions;
 
 #[cfg(feature = "arrow")]
 mod arrow;
+#[cfg(feature = "csv")]
+mod csv_import;
+#[cfg(feature = "flatgeobuf")]
+mod fgb;
+#[cfg(feature = "geojson")]
+mod geojson;
+#[cfg(feature = "geozero")]
+mod geozero_source;
 
 mod conversions;
+#[cfg(feature = "epsg-common")]
+mod epsg;
+#[cfg(all(feature = "http", not(target_family = "wasm")))]
+mod http_vfs;
+mod hybrid_file;
+#[cfg(not(target_family = "wasm"))]
+mod native_vfs;
 mod ogc_sql;
 mod types;
 #[cfg(target_family = "wasm")]
@@ -171,20 +223,39 @@ pub use arrow::attribute_reader::ArrowGpkgAttributeReader;
 #[cfg(feature = "arrow")]
 pub use arrow::attribute_writer::ArrowGpkgAttributeWriter;
 #[cfg(feature = "arrow")]
-pub use arrow::reader::ArrowGpkgReader;
+pub use arrow::reader::{ArrowGpkgReader, ArrowGpkgReaderBuilder, GeometryEncoding};
 #[cfg(feature = "arrow")]
 pub use arrow::writer::ArrowGpkgWriter;
 
 pub use error::{GpkgError, Result};
 pub use gpkg::{
-    Gpkg, GpkgAttributeRow, GpkgAttributeTable, GpkgFeature, GpkgFeatureBatchIterator, GpkgLayer,
+    FromFeature, Gpkg, GpkgAttributeRow, GpkgAttributeTable, GpkgFeature, GpkgFeatureBatchIterator,
+    GpkgFeatureBuilder, GpkgLayer, GpkgPool, ToFeature,
 };
+#[cfg(all(feature = "http", not(target_family = "wasm")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub use http_vfs::{HttpVfsBuilder, RangeReader};
 pub use sql_functions::register_spatial_functions;
-pub use types::{ColumnSpec, ColumnType, GpkgLayerMetadata, Value};
+#[cfg(feature = "geojson")]
+pub use types::GeoJsonImportOptions;
+pub use types::{
+    Collation, ColumnSpec, ColumnStats, ColumnType, CreateLayerOptions, DataColumnConstraint,
+    DataColumnInfo, Envelope, FeatureReadOptions, GpkgLayerMetadata, JournalMode, MetadataEntry,
+    SortKey, SortOrder, SpatialIndexReport, SpatialRefSys, UpsertOutcome, ValidationIssue,
+    ValidationOptions, ValidationReport, ValidationSeverity, Value, ZmFlag, params_from,
+};
+#[cfg(feature = "csv")]
+pub use types::{CsvImportOptions, GeometryFrom};
+#[cfg(feature = "parquet")]
+pub use types::{ParquetCompression, ParquetExportOptions};
 
 // Re-export types used in public fields to keep the public API stable.
 pub use wkb::reader::{Dimension, GeometryType};
 
+// Re-export the open flags accepted by `Gpkg::open_with_flags()`.
+pub use rusqlite::OpenFlags;
+
+#[cfg(not(target_family = "wasm"))]
+pub use native_vfs::{HybridVfsBuilder, HybridVfsHandle};
 #[cfg(target_family = "wasm")]
-#[cfg_attr(docsrs, doc(cfg(target_family = "wasm")))]
 pub use vfs::{HybridVfsBuilder, HybridVfsHandle};
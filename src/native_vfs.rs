@@ -0,0 +1,613 @@
+//! Single-file hybrid VFS for native targets, mirroring `vfs.rs` on wasm.
+//!
+//! - The main database file is read from and written to a user-provided
+//!   `Read + Write + Seek` handle at the requested offsets.
+//! - Sidecar files (`-wal`, `-shm`, `-journal`) and temporary files stay in
+//!   memory.
+//! - There is no file locking, so only one connection at a time should use a
+//!   registered VFS.
+
+use crate::hybrid_file::{HandleState, HybridHandle, is_main_sqlite_file};
+use crate::{Gpkg, GpkgError, Result};
+use rusqlite::ffi;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::io::{Read, Seek, Write};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+type SharedFile = Arc<Mutex<Vec<u8>>>;
+
+thread_local! {
+    static DEFAULT_HYBRID_VFS: RefCell<Option<HybridVfsHandle>> = const { RefCell::new(None) };
+}
+
+/// Builder that holds the handle backing the main database file.
+///
+/// This is the native counterpart of the wasm `HybridVfsBuilder`. As a
+/// registered VFS can be used from any thread, the handle must be `Send`.
+///
+/// Example:
+/// ```no_run
+/// use rusqlite_gpkg::HybridVfsBuilder;
+/// use std::io::Cursor;
+///
+/// let vfs = HybridVfsBuilder::new(Cursor::new(Vec::new())).register_reusable("my-vfs", false)?;
+/// let gpkg = vfs.open_gpkg("demo.gpkg")?;
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub struct HybridVfsBuilder {
+    writer: Box<dyn HybridHandle>,
+}
+
+/// A registered hybrid VFS, returned by [`HybridVfsBuilder::register_reusable`].
+#[derive(Clone)]
+pub struct HybridVfsHandle {
+    vfs_name: String,
+    state: Arc<HybridState>,
+}
+
+struct HybridState {
+    writer: Mutex<HandleState>,
+    files: Mutex<HashMap<String, SharedFile>>,
+}
+
+impl HybridVfsBuilder {
+    /// Create a single-file hybrid VFS builder.
+    ///
+    /// The main database file is read from and written to `writer` at the
+    /// offsets SQLite requests, so `writer` may already contain a GeoPackage.
+    pub fn new<W: Read + Write + Seek + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Register the VFS with SQLite under `vfs_name` and return a handle that
+    /// can open GeoPackages and replace the writer.
+    ///
+    /// The registration lives for the rest of the process. Fails with
+    /// `GpkgError::Vfs` if a VFS with the same name is already registered.
+    pub fn register_reusable(self, vfs_name: &str, default_vfs: bool) -> Result<HybridVfsHandle> {
+        let state = Arc::new(HybridState {
+            writer: Mutex::new(HandleState::new(self.writer)),
+            files: Mutex::new(HashMap::new()),
+        });
+        register_vfs(vfs_name, state.clone(), default_vfs)?;
+        Ok(HybridVfsHandle {
+            vfs_name: vfs_name.to_string(),
+            state,
+        })
+    }
+
+    /// Register/reuse a default hybrid VFS of the current thread and open a
+    /// GeoPackage.
+    ///
+    /// On first use, this registers a VFS for the current thread. On subsequent
+    /// calls, it reuses the same registration, replaces the writer, and clears
+    /// the in-memory files so SQLite sees the database in the new writer (an
+    /// empty writer gives a fresh GeoPackage). Any `Gpkg` instances from a
+    /// previous call must be dropped before calling this again.
+    pub fn open_gpkg<P: AsRef<Path>>(self, sqlite_filename: P) -> Result<Gpkg> {
+        let writer = self.writer;
+        let handle = DEFAULT_HYBRID_VFS.with(|slot| -> Result<HybridVfsHandle> {
+            let mut slot = slot.borrow_mut();
+            if let Some(handle) = slot.as_ref() {
+                handle.replace_boxed_writer(writer);
+                handle.clear_files();
+                return Ok(handle.clone());
+            }
+
+            static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+            let name = format!("hybrid-default-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+            let vfs = HybridVfsBuilder { writer }.register_reusable(&name, false)?;
+            *slot = Some(vfs.clone());
+            Ok(vfs)
+        })?;
+
+        handle.open_gpkg(sqlite_filename)
+    }
+}
+
+impl HybridVfsHandle {
+    /// Replace the handle backing the main database file.
+    pub fn replace_writer<W: Read + Write + Seek + Send + 'static>(&self, writer: W) {
+        self.replace_boxed_writer(Box::new(writer));
+    }
+
+    fn replace_boxed_writer(&self, writer: Box<dyn HybridHandle>) {
+        lock(&self.state.writer).replace(writer);
+    }
+
+    // Drop every in-memory file so the next `open_gpkg` starts from the
+    // contents of the current writer.
+    fn clear_files(&self) {
+        lock(&self.state.files).clear();
+    }
+
+    /// Open a GeoPackage using this registered hybrid VFS.
+    pub fn open_gpkg<P: AsRef<Path>>(&self, sqlite_filename: P) -> Result<Gpkg> {
+        Gpkg::open_with_vfs(sqlite_filename, &self.vfs_name)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn register_vfs(vfs_name: &str, state: Arc<HybridState>, default_vfs: bool) -> Result<()> {
+    let name = CString::new(vfs_name)
+        .map_err(|_| GpkgError::Vfs(format!("invalid VFS name: {vfs_name:?}")))?;
+    unsafe {
+        if !ffi::sqlite3_vfs_find(name.as_ptr()).is_null() {
+            return Err(GpkgError::Vfs(format!(
+                "VFS already registered: {vfs_name}"
+            )));
+        }
+        let default = ffi::sqlite3_vfs_find(std::ptr::null());
+        if default.is_null() {
+            return Err(GpkgError::Vfs("no default VFS".to_string()));
+        }
+        let d = &*default;
+        let vfs = Box::new(ffi::sqlite3_vfs {
+            iVersion: 2,
+            szOsFile: size_of::<HybridFileHandle>() as c_int,
+            mxPathname: d.mxPathname,
+            pNext: std::ptr::null_mut(),
+            zName: name.into_raw(),
+            pAppData: Box::into_raw(Box::new(state)).cast(),
+            xOpen: Some(x_open),
+            xDelete: Some(x_delete),
+            xAccess: Some(x_access),
+            xFullPathname: Some(x_full_pathname),
+            xDlOpen: d.xDlOpen,
+            xDlError: d.xDlError,
+            xDlSym: d.xDlSym,
+            xDlClose: d.xDlClose,
+            xRandomness: d.xRandomness,
+            xSleep: d.xSleep,
+            xCurrentTime: d.xCurrentTime,
+            xGetLastError: d.xGetLastError,
+            xCurrentTimeInt64: d.xCurrentTimeInt64,
+            xSetSystemCall: None,
+            xGetSystemCall: None,
+            xNextSystemCall: None,
+        });
+        // The VFS stays registered for the lifetime of the process.
+        let rc = ffi::sqlite3_vfs_register(Box::into_raw(vfs), default_vfs as c_int);
+        if rc != ffi::SQLITE_OK {
+            return Err(GpkgError::Vfs(format!(
+                "failed to register VFS {vfs_name} (SQLite error code {rc})"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// The VFS
+
+enum HybridFile {
+    Main(Arc<HybridState>),
+    Mem(SharedFile),
+}
+
+#[repr(C)]
+struct HybridFileHandle {
+    base: ffi::sqlite3_file,
+    file: *mut HybridFile,
+}
+
+unsafe fn vfs_state<'a>(vfs: *mut ffi::sqlite3_vfs) -> &'a Arc<HybridState> {
+    unsafe { &*(*vfs).pAppData.cast::<Arc<HybridState>>() }
+}
+
+unsafe fn file_name<'a>(name: *const c_char) -> Option<std::borrow::Cow<'a, str>> {
+    (!name.is_null()).then(|| unsafe { CStr::from_ptr(name) }.to_string_lossy())
+}
+
+unsafe extern "C" fn x_open(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: ffi::sqlite3_filename,
+    file: *mut ffi::sqlite3_file,
+    flags: c_int,
+    out_flags: *mut c_int,
+) -> c_int {
+    unsafe {
+        let handle = file.cast::<HybridFileHandle>();
+        (*handle).base.pMethods = std::ptr::null();
+        let state = vfs_state(vfs);
+
+        let opened = match file_name(name) {
+            Some(name) if is_main_sqlite_file(&name) && flags & ffi::SQLITE_OPEN_MAIN_DB != 0 => {
+                HybridFile::Main(state.clone())
+            }
+            // Temporary files are never opened again, so they aren't tracked.
+            Some(name) if flags & ffi::SQLITE_OPEN_DELETEONCLOSE == 0 => {
+                let mut files = lock(&state.files);
+                match files.get(name.as_ref()) {
+                    Some(file) => HybridFile::Mem(file.clone()),
+                    None if flags & ffi::SQLITE_OPEN_CREATE != 0 => {
+                        let file = SharedFile::default();
+                        files.insert(name.into_owned(), file.clone());
+                        HybridFile::Mem(file)
+                    }
+                    None => return ffi::SQLITE_CANTOPEN,
+                }
+            }
+            _ => HybridFile::Mem(SharedFile::default()),
+        };
+
+        (*handle).file = Box::into_raw(Box::new(opened));
+        (*handle).base.pMethods = &IO_METHODS;
+        if !out_flags.is_null() {
+            *out_flags = flags;
+        }
+        ffi::SQLITE_OK
+    }
+}
+
+unsafe extern "C" fn x_delete(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    _sync_dir: c_int,
+) -> c_int {
+    unsafe {
+        let Some(name) = file_name(name) else {
+            return ffi::SQLITE_IOERR_DELETE;
+        };
+        match lock(&vfs_state(vfs).files).remove(name.as_ref()) {
+            Some(_) => ffi::SQLITE_OK,
+            None => ffi::SQLITE_IOERR_DELETE_NOENT,
+        }
+    }
+}
+
+unsafe extern "C" fn x_access(
+    vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    _flags: c_int,
+    res_out: *mut c_int,
+) -> c_int {
+    unsafe {
+        let state = vfs_state(vfs);
+        let exists = match file_name(name) {
+            Some(name) if is_main_sqlite_file(&name) => {
+                lock(&state.writer).size().is_ok_and(|size| size > 0)
+            }
+            Some(name) => lock(&state.files).contains_key(name.as_ref()),
+            None => false,
+        };
+        *res_out = exists as c_int;
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_full_pathname(
+    _vfs: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+    n_out: c_int,
+    z_out: *mut c_char,
+) -> c_int {
+    unsafe {
+        let bytes = CStr::from_ptr(name).to_bytes_with_nul();
+        if bytes.len() > n_out as usize {
+            return ffi::SQLITE_CANTOPEN;
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr().cast(), z_out, bytes.len());
+    }
+    ffi::SQLITE_OK
+}
+
+static IO_METHODS: ffi::sqlite3_io_methods = ffi::sqlite3_io_methods {
+    iVersion: 1,
+    xClose: Some(x_close),
+    xRead: Some(x_read),
+    xWrite: Some(x_write),
+    xTruncate: Some(x_truncate),
+    xSync: Some(x_sync),
+    xFileSize: Some(x_file_size),
+    xLock: Some(x_lock),
+    xUnlock: Some(x_lock),
+    xCheckReservedLock: Some(x_check_reserved_lock),
+    xFileControl: Some(x_file_control),
+    xSectorSize: Some(x_sector_size),
+    xDeviceCharacteristics: Some(x_device_characteristics),
+    xShmMap: None,
+    xShmLock: None,
+    xShmBarrier: None,
+    xShmUnmap: None,
+    xFetch: None,
+    xUnfetch: None,
+};
+
+unsafe fn hybrid_file<'a>(file: *mut ffi::sqlite3_file) -> &'a HybridFile {
+    unsafe { &*(*file.cast::<HybridFileHandle>()).file }
+}
+
+// Run `f`, making sure a panic in a user-provided handle doesn't unwind into
+// SQLite.
+fn guarded(error_code: c_int, f: impl FnOnce() -> std::io::Result<c_int>) -> c_int {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(rc)) => rc,
+        Ok(Err(_)) | Err(_) => error_code,
+    }
+}
+
+unsafe extern "C" fn x_close(file: *mut ffi::sqlite3_file) -> c_int {
+    unsafe {
+        let handle = file.cast::<HybridFileHandle>();
+        if !(*handle).file.is_null() {
+            drop(Box::from_raw((*handle).file));
+            (*handle).file = std::ptr::null_mut();
+        }
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_read(
+    file: *mut ffi::sqlite3_file,
+    buf: *mut c_void,
+    amount: c_int,
+    offset: ffi::sqlite3_int64,
+) -> c_int {
+    let (file, buf) = unsafe {
+        (
+            hybrid_file(file),
+            std::slice::from_raw_parts_mut(buf.cast::<u8>(), amount as usize),
+        )
+    };
+    let offset = offset as u64;
+    guarded(ffi::SQLITE_IOERR_READ, || {
+        let filled = match file {
+            HybridFile::Main(state) => lock(&state.writer).read_at(buf, offset)?,
+            HybridFile::Mem(data) => {
+                let data = lock(data);
+                let start = (offset as usize).min(data.len());
+                let n = (data.len() - start).min(buf.len());
+                buf[..n].copy_from_slice(&data[start..start + n]);
+                buf[n..].fill(0);
+                n == buf.len()
+            }
+        };
+        Ok(if filled {
+            ffi::SQLITE_OK
+        } else {
+            ffi::SQLITE_IOERR_SHORT_READ
+        })
+    })
+}
+
+unsafe extern "C" fn x_write(
+    file: *mut ffi::sqlite3_file,
+    buf: *const c_void,
+    amount: c_int,
+    offset: ffi::sqlite3_int64,
+) -> c_int {
+    let (file, buf) = unsafe {
+        (
+            hybrid_file(file),
+            std::slice::from_raw_parts(buf.cast::<u8>(), amount as usize),
+        )
+    };
+    let offset = offset as u64;
+    guarded(ffi::SQLITE_IOERR_WRITE, || {
+        match file {
+            HybridFile::Main(state) => lock(&state.writer).write_at(buf, offset)?,
+            HybridFile::Mem(data) => {
+                let mut data = lock(data);
+                let start = offset as usize;
+                let end = start + buf.len();
+                if data.len() < end {
+                    data.resize(end, 0);
+                }
+                data[start..end].copy_from_slice(buf);
+            }
+        }
+        Ok(ffi::SQLITE_OK)
+    })
+}
+
+unsafe extern "C" fn x_truncate(file: *mut ffi::sqlite3_file, size: ffi::sqlite3_int64) -> c_int {
+    let file = unsafe { hybrid_file(file) };
+    guarded(ffi::SQLITE_IOERR_TRUNCATE, || {
+        match file {
+            HybridFile::Main(state) => lock(&state.writer).truncate(size as u64)?,
+            HybridFile::Mem(data) => lock(data).truncate(size as usize),
+        }
+        Ok(ffi::SQLITE_OK)
+    })
+}
+
+unsafe extern "C" fn x_sync(file: *mut ffi::sqlite3_file, _flags: c_int) -> c_int {
+    let file = unsafe { hybrid_file(file) };
+    guarded(ffi::SQLITE_IOERR_FSYNC, || {
+        if let HybridFile::Main(state) = file {
+            lock(&state.writer).flush()?;
+        }
+        Ok(ffi::SQLITE_OK)
+    })
+}
+
+unsafe extern "C" fn x_file_size(
+    file: *mut ffi::sqlite3_file,
+    size_out: *mut ffi::sqlite3_int64,
+) -> c_int {
+    let file = unsafe { hybrid_file(file) };
+    guarded(ffi::SQLITE_IOERR_FSTAT, || {
+        let size = match file {
+            HybridFile::Main(state) => lock(&state.writer).size()?,
+            HybridFile::Mem(data) => lock(data).len() as u64,
+        };
+        unsafe {
+            *size_out = size as ffi::sqlite3_int64;
+        }
+        Ok(ffi::SQLITE_OK)
+    })
+}
+
+unsafe extern "C" fn x_lock(_file: *mut ffi::sqlite3_file, _level: c_int) -> c_int {
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_check_reserved_lock(
+    _file: *mut ffi::sqlite3_file,
+    res_out: *mut c_int,
+) -> c_int {
+    unsafe {
+        *res_out = 0;
+    }
+    ffi::SQLITE_OK
+}
+
+unsafe extern "C" fn x_file_control(
+    _file: *mut ffi::sqlite3_file,
+    _op: c_int,
+    _arg: *mut c_void,
+) -> c_int {
+    ffi::SQLITE_NOTFOUND
+}
+
+unsafe extern "C" fn x_sector_size(_file: *mut ffi::sqlite3_file) -> c_int {
+    512
+}
+
+unsafe extern "C" fn x_device_characteristics(_file: *mut ffi::sqlite3_file) -> c_int {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use crate::types::{ColumnSpec, ColumnType};
+    use geo_types::Point;
+    use std::io::{self, Cursor};
+    use wkb::reader::{Dimension, GeometryType};
+
+    /// Writer backed by a shared `Cursor<Vec<u8>>`, so the test can read the
+    /// bytes after the VFS is done with it.
+    #[derive(Clone, Default)]
+    struct SharedCursor(Arc<Mutex<Cursor<Vec<u8>>>>);
+
+    impl SharedCursor {
+        fn data(&self) -> Vec<u8> {
+            self.0.lock().unwrap().get_ref().clone()
+        }
+    }
+
+    impl Read for SharedCursor {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().read(buf)
+        }
+    }
+
+    impl Write for SharedCursor {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for SharedCursor {
+        fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+            self.0.lock().unwrap().seek(pos)
+        }
+    }
+
+    fn create_points(gpkg: &Gpkg, n: usize) -> Result<()> {
+        let columns = vec![ColumnSpec {
+            name: "name".to_string(),
+            column_type: ColumnType::Varchar,
+            ..Default::default()
+        }];
+        let layer = gpkg.create_layer(
+            "points",
+            "geom",
+            GeometryType::Point,
+            Dimension::Xy,
+            4326,
+            &columns,
+        )?;
+        layer.insert_many((0..n).map(|i| {
+            let name = format!("p{i}");
+            (Point::new(i as f64, 0.0), vec![Value::from(name)])
+        }))?;
+        Ok(())
+    }
+
+    #[test]
+    fn cursor_backed_vfs_produces_a_valid_gpkg() -> Result<()> {
+        let cursor = SharedCursor::default();
+        let vfs = HybridVfsBuilder::new(cursor.clone())
+            .register_reusable("rusqlite-gpkg-test-cursor", false)?;
+        {
+            let gpkg = vfs.open_gpkg("demo.gpkg")?;
+            create_points(&gpkg, 100)?;
+        }
+
+        let bytes = cursor.data();
+        let gpkg = Gpkg::from_bytes(&bytes)?;
+        assert!(gpkg.is_geopackage()?);
+        let layer = gpkg.get_layer("points")?;
+        assert_eq!(layer.count()?, 100);
+        drop(gpkg);
+
+        // Reopening the same bytes through the VFS appends to them.
+        vfs.replace_writer(Cursor::new(bytes));
+        let gpkg = vfs.open_gpkg("demo.gpkg")?;
+        let layer = gpkg.get_layer("points")?;
+        layer.insert(Point::new(-1.0, 0.0), vec![Value::from("extra")])?;
+        assert_eq!(layer.count()?, 101);
+
+        let err = HybridVfsBuilder::new(Cursor::new(Vec::new()))
+            .register_reusable("rusqlite-gpkg-test-cursor", false)
+            .err()
+            .expect("duplicate name");
+        assert!(matches!(err, GpkgError::Vfs(_)), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn open_gpkg_reuses_the_default_registration() -> Result<()> {
+        let first = SharedCursor::default();
+        {
+            let gpkg = HybridVfsBuilder::new(first.clone()).open_gpkg("first.gpkg")?;
+            create_points(&gpkg, 3)?;
+        }
+        let second = SharedCursor::default();
+        {
+            let gpkg = Gpkg::open_with_writer("second.gpkg", second.clone())?;
+            assert!(gpkg.list_layers()?.is_empty());
+            create_points(&gpkg, 5)?;
+        }
+
+        assert_eq!(
+            Gpkg::from_bytes(first.data())?
+                .get_layer("points")?
+                .count()?,
+            3
+        );
+        assert_eq!(
+            Gpkg::from_bytes(second.data())?
+                .get_layer("points")?
+                .count()?,
+            5
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_with_vfs_rejects_unknown_vfs() {
+        let err = Gpkg::open_with_vfs("demo.gpkg", "rusqlite-gpkg-no-such-vfs")
+            .expect_err("unregistered VFS");
+        assert!(
+            matches!(&err, GpkgError::Vfs(msg) if msg.contains("rusqlite-gpkg-no-such-vfs")),
+            "{err}"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 // cf. https://www.geopackage.org/spec140/index.html#table_definition_sql
 
+use crate::types::SortOrder;
+
 // gpkg_contents: lists all geospatial contents in the package with identifying
 // and descriptive metadata for user display and access.
 pub(crate) const SQL_GPKG_CONTENTS: &str = "
@@ -40,11 +42,24 @@ pub(crate) const SQL_LIST_ATTRIBUTE_TABLES: &str =
 pub(crate) const SQL_SELECT_DATA_TYPE: &str =
     "SELECT data_type FROM gpkg_contents WHERE table_name = ?";
 
+pub(crate) const SQL_VACUUM: &str = "VACUUM";
+pub(crate) const SQL_VACUUM_INTO: &str = "VACUUM INTO ?1";
+pub(crate) const SQL_ANALYZE: &str = "ANALYZE";
+
+pub(crate) const SQL_TABLE_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)";
+
+pub(crate) const SQL_SELECT_COLUMN_TYPE: &str =
+    "SELECT type FROM pragma_table_info(?1) WHERE name = ?2";
+
+pub(crate) const SQL_VIEW_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'view' AND name = ?1)";
+
 pub(crate) const SQL_INSERT_GPKG_CONTENTS: &str = "
 INSERT INTO gpkg_contents
   (table_name, data_type, identifier, description, srs_id)
 VALUES
-  (?1, 'features', ?2, '', ?3)
+  (?1, 'features', ?2, ?3, ?4)
 ";
 
 pub(crate) const SQL_INSERT_GPKG_CONTENTS_ATTRIBUTES: &str = "
@@ -61,12 +76,100 @@ VALUES
   (?1, ?2, ?3, ?4, ?5, ?6)
 ";
 
+pub(crate) const SQL_UPDATE_GEOMETRY_COLUMN_DEFINITION: &str = "
+UPDATE gpkg_geometry_columns SET geometry_type_name = ?2, z = ?3, m = ?4
+WHERE table_name = ?1
+";
+
 pub(crate) const SQL_SELECT_GEOMETRY_COLUMN_META: &str = "
 SELECT column_name, geometry_type_name, z, m, srs_id
 FROM gpkg_geometry_columns
 WHERE table_name = ?
 ";
 
+pub(crate) const SQL_LIST_LAYERS_DETAILED: &str = "
+SELECT c.table_name, c.identifier, c.description, c.data_type,
+       g.column_name, g.geometry_type_name, g.z, g.m, g.srs_id,
+       c.min_x, c.min_y, c.max_x, c.max_y, c.last_change
+FROM gpkg_contents AS c
+JOIN gpkg_geometry_columns AS g ON c.table_name = g.table_name
+WHERE c.data_type = 'features'
+";
+
+pub(crate) const SQL_SELECT_CONTENTS_METADATA: &str = "
+SELECT identifier, description, last_change, min_x, min_y, max_x, max_y
+FROM gpkg_contents
+WHERE table_name = ?1
+";
+
+pub(crate) const SQL_SELECT_TABLE_BY_IDENTIFIER: &str = "
+SELECT table_name
+FROM gpkg_contents
+WHERE identifier = ?1 AND table_name <> ?2
+";
+
+pub(crate) const SQL_UPDATE_CONTENTS_IDENTIFIER: &str = "
+UPDATE gpkg_contents
+SET identifier = ?2
+WHERE table_name = ?1
+";
+
+pub(crate) const SQL_UPDATE_CONTENTS_DESCRIPTION: &str = "
+UPDATE gpkg_contents
+SET description = ?2
+WHERE table_name = ?1
+";
+
+pub(crate) const SQL_SELECT_CONTENTS_EXTENT: &str = "
+SELECT min_x, min_y, max_x, max_y
+FROM gpkg_contents
+WHERE table_name = ?1
+";
+
+pub(crate) const SQL_UPDATE_CONTENTS_EXTENT: &str = "
+UPDATE gpkg_contents
+SET min_x = ?1, min_y = ?2, max_x = ?3, max_y = ?4
+WHERE table_name = ?5
+";
+
+// Only expand an extent that is already known. If any of the values is NULL,
+// this updates nothing and the caller should recompute the extent instead.
+pub(crate) const SQL_EXPAND_CONTENTS_EXTENT: &str = "
+UPDATE gpkg_contents
+SET min_x = MIN(min_x, ?1), min_y = MIN(min_y, ?2), max_x = MAX(max_x, ?3), max_y = MAX(max_y, ?4)
+WHERE table_name = ?5
+  AND min_x IS NOT NULL AND min_y IS NOT NULL AND max_x IS NOT NULL AND max_y IS NOT NULL
+";
+
+pub(crate) const SQL_UPDATE_CONTENTS_LAST_CHANGE: &str = "
+UPDATE gpkg_contents
+SET last_change = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+WHERE table_name = ?1
+";
+
+// A savepoint works both on its own and nested in an outer transaction.
+pub(crate) const SQL_SAVEPOINT: &str = "SAVEPOINT gpkg_layer_write";
+pub(crate) const SQL_RELEASE_SAVEPOINT: &str = "RELEASE gpkg_layer_write";
+pub(crate) const SQL_ROLLBACK_TO_SAVEPOINT: &str =
+    "ROLLBACK TO gpkg_layer_write; RELEASE gpkg_layer_write";
+
+pub(crate) fn sql_compute_extent(
+    layer_name: &str,
+    geometry_column: &str,
+    use_rtree: bool,
+) -> String {
+    if use_rtree {
+        format!(
+            r#"SELECT MIN(minx), MIN(miny), MAX(maxx), MAX(maxy) FROM "rtree_{layer_name}_{geometry_column}""#
+        )
+    } else {
+        format!(
+            r#"SELECT MIN(ST_MinX("{g}")), MIN(ST_MinY("{g}")), MAX(ST_MaxX("{g}")), MAX(ST_MaxY("{g}")) FROM "{layer_name}" WHERE "{g}" NOT NULL AND NOT ST_IsEmpty("{g}")"#,
+            g = geometry_column
+        )
+    }
+}
+
 pub(crate) fn sql_create_table(layer_name: &str, column_defs: &str) -> String {
     format!(r#"CREATE TABLE "{}" ({})"#, layer_name, column_defs)
 }
@@ -75,16 +178,108 @@ pub(crate) fn sql_drop_table(layer_name: &str) -> String {
     format!(r#"DROP TABLE "{layer_name}""#)
 }
 
+pub(crate) fn sql_create_view(layer_name: &str, select_sql: &str) -> String {
+    format!(r#"CREATE VIEW "{layer_name}" AS {select_sql}"#)
+}
+
+pub(crate) fn sql_drop_view(layer_name: &str) -> String {
+    format!(r#"DROP VIEW "{layer_name}""#)
+}
+
+// The first non-NULL geometry, used to infer the srs_id and dimension of a view.
+pub(crate) fn sql_select_first_geometry(layer_name: &str, geometry_column: &str) -> String {
+    format!(
+        r#"SELECT "{geometry_column}" FROM "{layer_name}" WHERE "{geometry_column}" IS NOT NULL LIMIT 1"#
+    )
+}
+
+pub(crate) fn sql_analyze_table(table_name: &str) -> String {
+    format!(r#"ANALYZE "{table_name}""#)
+}
+
+pub(crate) fn sql_add_column(layer_name: &str, column_definition: &str) -> String {
+    format!(r#"ALTER TABLE "{layer_name}" ADD COLUMN {column_definition}"#)
+}
+
+pub(crate) fn sql_drop_column(layer_name: &str, column_name: &str) -> String {
+    format!(r#"ALTER TABLE "{layer_name}" DROP COLUMN "{column_name}""#)
+}
+
+pub(crate) fn sql_rename_table(old_name: &str, new_name: &str) -> String {
+    format!(r#"ALTER TABLE "{old_name}" RENAME TO "{new_name}""#)
+}
+
+pub(crate) fn sql_copy_rows(
+    src_table: &str,
+    src_columns: &str,
+    dst_table: &str,
+    dst_columns: &str,
+) -> String {
+    format!(r#"INSERT INTO "{dst_table}" ({dst_columns}) SELECT {src_columns} FROM "{src_table}""#)
+}
+
+pub(crate) fn sql_max_primary_key(table_name: &str, primary_key: &str) -> String {
+    format!(r#"SELECT MAX("{primary_key}") FROM "{table_name}""#)
+}
+
+// Copy at most ?2 rows with a primary key greater than ?1, in primary key order.
+pub(crate) fn sql_copy_rows_after(
+    src_table: &str,
+    src_columns: &str,
+    src_primary_key: &str,
+    dst_table: &str,
+    dst_columns: &str,
+) -> String {
+    format!(
+        r#"INSERT INTO "{dst_table}" ({dst_columns}) SELECT {src_columns} FROM "{src_table}" WHERE "{src_primary_key}" > ?1 ORDER BY "{src_primary_key}" LIMIT ?2"#
+    )
+}
+
+pub(crate) const SQL_RENAME_CONTENTS: &str = "
+UPDATE gpkg_contents
+SET table_name = ?2,
+    identifier = CASE WHEN identifier = ?1 THEN ?2 ELSE identifier END
+WHERE table_name = ?1
+";
+
+pub(crate) const SQL_RENAME_GEOMETRY_COLUMNS: &str =
+    "UPDATE gpkg_geometry_columns SET table_name = ?2 WHERE table_name = ?1";
+
+pub(crate) const SQL_RENAME_EXTENSIONS: &str =
+    "UPDATE gpkg_extensions SET table_name = ?2 WHERE table_name = ?1";
+
+pub(crate) const SQL_DELETE_CONTENTS: &str = "DELETE FROM gpkg_contents WHERE table_name = ?1";
+
+pub(crate) const SQL_DELETE_GEOMETRY_COLUMNS: &str =
+    "DELETE FROM gpkg_geometry_columns WHERE table_name = ?1";
+
+pub(crate) const SQL_DELETE_EXTENSIONS: &str = "DELETE FROM gpkg_extensions WHERE table_name = ?1";
+
+// gpkg_metadata_reference only exists when the metadata extension is used.
+pub(crate) const SQL_DELETE_METADATA_REFERENCE: &str =
+    "DELETE FROM gpkg_metadata_reference WHERE table_name = ?1";
+
 pub(crate) fn sql_table_columns(layer_name: &str) -> String {
-    format!("SELECT name, type, pk FROM pragma_table_info('{layer_name}')")
+    format!(
+        r#"SELECT name, type, pk, "notnull", dflt_value FROM pragma_table_info('{layer_name}')"#
+    )
 }
 
-pub(crate) fn sql_select_features<'a, I>(
-    layer_name: &'a str,
+// Columns with a single-column UNIQUE constraint.
+pub(crate) fn sql_unique_columns(layer_name: &str) -> String {
+    format!(
+        r#"SELECT ii.name
+FROM pragma_index_list('{layer_name}') AS il, pragma_index_info(il.name) AS ii
+WHERE il."unique" = 1 AND il.origin = 'u'
+GROUP BY il.name
+HAVING COUNT(*) = 1"#
+    )
+}
+
+fn sql_feature_columns<'a, I>(
     geometry_column: &'a str,
     primary_key_column: &'a str,
     other_columns: I,
-    limit: Option<u32>,
 ) -> String
 where
     I: IntoIterator<Item = &'a str>,
@@ -95,19 +290,169 @@ where
         .collect::<Vec<String>>()
         .join(", ");
 
-    let limit_clause = match limit {
-        Some(n) => format!("LIMIT {n} OFFSET ?"),
-        None => "".to_string(),
-    };
-
-    let columns = if joined.is_empty() {
+    if joined.is_empty() {
         format!(r#""{geometry_column}", "{primary_key_column}""#,)
     } else {
         format!(r#""{geometry_column}", "{primary_key_column}", {joined}"#,)
+    }
+}
+
+// Filter by the optional `WHERE` expression and order by the primary key.
+// With a limit, read one page of rows after the last-seen primary key, which
+// unlike OFFSET doesn't need to rescan the previous pages. The last-seen
+// primary key is bound to the placeholder following the ones in the `WHERE`
+// expression, i.e. `?1` if there's no expression.
+fn sql_keyset_page(
+    primary_key_column: &str,
+    where_clause: Option<&str>,
+    limit: Option<u32>,
+) -> String {
+    let mut conditions = Vec::new();
+    if let Some(where_clause) = where_clause {
+        conditions.push(format!("({where_clause})"));
+    }
+    if limit.is_some() {
+        conditions.push(format!(r#""{primary_key_column}" > ?"#));
+    }
+
+    let mut clause = String::new();
+    if !conditions.is_empty() {
+        clause.push_str(&format!("WHERE {} ", conditions.join(" AND ")));
+    }
+    clause.push_str(&format!(r#"ORDER BY "{primary_key_column}""#));
+    if let Some(n) = limit {
+        clause.push_str(&format!(" LIMIT {n}"));
+    }
+    clause
+}
+
+pub(crate) fn sql_select_features<'a, I>(
+    layer_name: &'a str,
+    geometry_column: &'a str,
+    primary_key_column: &'a str,
+    other_columns: I,
+    where_clause: Option<&str>,
+    limit: Option<u32>,
+) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let columns = sql_feature_columns(geometry_column, primary_key_column, other_columns);
+
+    format!(
+        r#"SELECT {columns} FROM "{layer_name}" {}"#,
+        sql_keyset_page(primary_key_column, where_clause, limit)
+    )
+}
+
+// `order_by` is a validated list of column expressions (quoted, optionally
+// with `COLLATE`) and directions, ending with the primary key so that the
+// sort key of a row is unique. With a limit, read one page of rows after the
+// sort key of the last row read: ?1 is true for the first page, and ?2, ?3,
+// ... are bound to the last sort key. Like `sql_keyset_page()`, this seeks to
+// where the previous page ended instead of skipping rows with OFFSET.
+pub(crate) fn sql_select_features_ordered<'a, I>(
+    layer_name: &'a str,
+    geometry_column: &'a str,
+    primary_key_column: &'a str,
+    other_columns: I,
+    order_by: &[(String, SortOrder)],
+    limit: Option<u32>,
+) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let columns = sql_feature_columns(geometry_column, primary_key_column, other_columns);
+    let terms = order_by
+        .iter()
+        .map(|(expr, order)| match order {
+            SortOrder::Asc => format!("{expr} ASC"),
+            SortOrder::Desc => format!("{expr} DESC"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match limit {
+        Some(n) => format!(
+            r#"SELECT {columns} FROM "{layer_name}" WHERE ?1 OR {} ORDER BY {terms} LIMIT {n}"#,
+            sql_after_sort_key(order_by)
+        ),
+        None => format!(r#"SELECT {columns} FROM "{layer_name}" ORDER BY {terms}"#),
+    }
+}
+
+// Whether a row sorts after the sort key bound to ?2, ?3, ..., i.e. it's equal
+// up to some term and comes after on that term. SQLite sorts NULLs first in
+// ascending order, so the comparisons treat NULL as the smallest value. A row
+// value comparison can't be used, as it doesn't allow mixed directions nor
+// order NULLs.
+fn sql_after_sort_key(order_by: &[(String, SortOrder)]) -> String {
+    let alternatives = (0..order_by.len())
+        .map(|i| {
+            let mut conditions = order_by[..i]
+                .iter()
+                .enumerate()
+                .map(|(j, (expr, _))| format!("{expr} IS ?{}", j + 2))
+                .collect::<Vec<_>>();
+            let (expr, order) = &order_by[i];
+            let param = format!("?{}", i + 2);
+            conditions.push(match order {
+                SortOrder::Asc => {
+                    format!("({expr} > {param} OR ({param} IS NULL AND {expr} IS NOT NULL))")
+                }
+                SortOrder::Desc => {
+                    format!("({expr} < {param} OR ({expr} IS NULL AND {param} IS NOT NULL))")
+                }
+            });
+            format!("({})", conditions.join(" AND "))
+        })
+        .collect::<Vec<_>>();
+    format!("({})", alternatives.join(" OR "))
+}
+
+pub(crate) fn sql_select_feature_by_id<'a, I>(
+    layer_name: &'a str,
+    geometry_column: &'a str,
+    primary_key_column: &'a str,
+    other_columns: I,
+) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let columns = sql_feature_columns(geometry_column, primary_key_column, other_columns);
+
+    format!(r#"SELECT {columns} FROM "{layer_name}" WHERE "{primary_key_column}" = ?1"#)
+}
+
+// The bbox is bound as ?1 = min_x, ?2 = min_y, ?3 = max_x, ?4 = max_y.
+pub(crate) fn sql_select_features_in_bbox<'a, I>(
+    layer_name: &'a str,
+    geometry_column: &'a str,
+    primary_key_column: &'a str,
+    other_columns: I,
+    use_rtree: bool,
+    limit: Option<u32>,
+) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let columns = sql_feature_columns(geometry_column, primary_key_column, other_columns);
+
+    let condition = if use_rtree {
+        format!(
+            r#""{primary_key_column}" IN (SELECT id FROM "rtree_{layer_name}_{geometry_column}" WHERE maxx >= ?1 AND minx <= ?3 AND maxy >= ?2 AND miny <= ?4)"#
+        )
+    } else {
+        format!(
+            r#""{g}" NOT NULL AND NOT ST_IsEmpty("{g}") AND ST_MaxX("{g}") >= ?1 AND ST_MinX("{g}") <= ?3 AND ST_MaxY("{g}") >= ?2 AND ST_MinY("{g}") <= ?4"#,
+            g = geometry_column
+        )
     };
 
+    // The bbox takes ?1 to ?4, so the last-seen primary key of a page is ?5.
     format!(
-        r#"SELECT {columns} FROM "{layer_name}" ORDER BY "{primary_key_column}" {limit_clause}"#,
+        r#"SELECT {columns} FROM "{layer_name}" {}"#,
+        sql_keyset_page(primary_key_column, Some(&condition), limit)
     )
 }
 
@@ -126,11 +471,6 @@ where
         .collect::<Vec<String>>()
         .join(", ");
 
-    let limit_clause = match limit {
-        Some(n) => format!("LIMIT {n} OFFSET ?"),
-        None => "".to_string(),
-    };
-
     let columns = if joined.is_empty() {
         format!(r#""{primary_key_column}""#)
     } else {
@@ -138,10 +478,73 @@ where
     };
 
     format!(
-        r#"SELECT {columns} FROM "{table_name}" ORDER BY "{primary_key_column}" {limit_clause}"#,
+        r#"SELECT {columns} FROM "{table_name}" {}"#,
+        sql_keyset_page(primary_key_column, None, limit)
     )
 }
 
+// The mean only takes INTEGER and REAL values into account, as `AVG()` would
+// read a TEXT value as 0.
+pub(crate) fn sql_column_stats(table_name: &str, column: &str) -> String {
+    format!(
+        r#"SELECT MIN("{column}"), MAX("{column}"), AVG(CASE WHEN typeof("{column}") IN ('integer', 'real') THEN "{column}" END), COUNT(*) - COUNT("{column}"), COUNT(DISTINCT "{column}") FROM "{table_name}""#
+    )
+}
+
+pub(crate) fn sql_distinct_values(table_name: &str, column: &str, limit: Option<u32>) -> String {
+    let mut sql = format!(
+        r#"SELECT DISTINCT "{column}" FROM "{table_name}" WHERE "{column}" IS NOT NULL ORDER BY "{column}""#
+    );
+    if let Some(limit) = limit {
+        sql.push_str(&format!(" LIMIT {limit}"));
+    }
+    sql
+}
+
+pub(crate) fn sql_count(layer_name: &str, where_clause: Option<&str>) -> String {
+    match where_clause {
+        Some(where_clause) => {
+            format!(r#"SELECT COUNT(*) FROM "{layer_name}" WHERE {where_clause}"#)
+        }
+        None => format!(r#"SELECT COUNT(*) FROM "{layer_name}""#),
+    }
+}
+
+pub(crate) fn sql_select_geometry_by_id(
+    layer_name: &str,
+    geometry_column: &str,
+    primary_key_column: &str,
+) -> String {
+    format!(r#"SELECT "{geometry_column}" FROM "{layer_name}" WHERE "{primary_key_column}" = ?1"#)
+}
+
+pub(crate) fn sql_delete_by_id(layer_name: &str, primary_key_column: &str) -> String {
+    format!(r#"DELETE FROM "{layer_name}" WHERE "{primary_key_column}" = ?1"#)
+}
+
+// Columns are bound to ?1..?n in the given order and the primary key to ?(n+1).
+pub(crate) fn sql_update_columns<'a>(
+    layer_name: &str,
+    primary_key_column: &str,
+    columns: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut n = 0;
+    let assignments = columns
+        .into_iter()
+        .map(|name| {
+            n += 1;
+            format!(r#""{name}"=?{n}"#)
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let id_idx = n + 1;
+    format!(r#"UPDATE "{layer_name}" SET {assignments} WHERE "{primary_key_column}"=?{id_idx}"#)
+}
+
+pub(crate) fn sql_feature_exists(layer_name: &str, primary_key_column: &str) -> String {
+    format!(r#"SELECT EXISTS(SELECT 1 FROM "{layer_name}" WHERE "{primary_key_column}" = ?1)"#)
+}
+
 pub(crate) fn sql_delete_all(layer_name: &str) -> String {
     format!(r#"DELETE FROM "{}""#, layer_name)
 }
@@ -153,9 +556,23 @@ pub(crate) fn sql_insert_feature(layer_name: &str, columns: &str, values: &str)
     )
 }
 
+pub(crate) fn sql_upsert_feature(
+    layer_name: &str,
+    columns: &str,
+    values: &str,
+    primary_key_column: &str,
+    assignments: &str,
+) -> String {
+    format!(
+        r#"INSERT INTO "{layer_name}" ({columns}) VALUES ({values}) ON CONFLICT("{primary_key_column}") DO UPDATE SET {assignments}"#
+    )
+}
+
+// 0x47504B47 = ASCII "GPKG" (GeoPackage 1.2+)
+pub(crate) const GPKG_APPLICATION_ID: i32 = 0x47504B47;
+
 pub(crate) fn initialize_gpkg(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
-    // 0x47504B47 = ASCII "GPKG" (GeoPackage 1.2+)
-    conn.execute_batch("PRAGMA application_id = 0x47504B47;")?;
+    conn.pragma_update(None, "application_id", GPKG_APPLICATION_ID)?;
     // 10400 = spec version 1.4.0 in MMNNPP format
     conn.execute_batch("PRAGMA user_version = 10400;")?;
     conn.execute_batch(SQL_GPKG_SPATIAL_REF_SYS)?;
@@ -198,6 +615,206 @@ CREATE TABLE gpkg_spatial_ref_sys (
 );
 ";
 
+// Select the columns of `gpkg_spatial_ref_sys`. The `definition_12_063` column
+// only exists with the `gpkg_crs_wkt` extension, so it is selected as NULL
+// otherwise. Callers append the WHERE / ORDER BY clause.
+pub(crate) fn sql_select_spatial_ref_sys(crs_wkt: bool) -> String {
+    let definition_12_063 = if crs_wkt { "definition_12_063" } else { "NULL" };
+    format!(
+        "SELECT srs_name, srs_id, organization, organization_coordsys_id, definition, description, {definition_12_063} \
+         FROM gpkg_spatial_ref_sys"
+    )
+}
+
+// Queries used by `Gpkg::validate()`.
+pub(crate) const SQL_SCHEMA_OBJECT_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type IN ('table', 'view') AND name = ?1)";
+
+pub(crate) const SQL_TRIGGER_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'trigger' AND name = ?1)";
+
+pub(crate) const SQL_COLUMN_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)";
+
+pub(crate) const SQL_SELECT_CONTENTS_TABLES: &str =
+    "SELECT table_name, data_type FROM gpkg_contents ORDER BY table_name";
+
+pub(crate) const SQL_SELECT_GEOMETRY_COLUMNS: &str = "
+SELECT table_name, column_name, geometry_type_name, srs_id
+FROM gpkg_geometry_columns
+ORDER BY table_name
+";
+
+pub(crate) const SQL_SELECT_EXTENSION_TARGETS: &str = "
+SELECT extension_name, table_name, column_name
+FROM gpkg_extensions
+WHERE table_name IS NOT NULL
+ORDER BY rowid
+";
+
+pub(crate) const SQL_RTREE_EXTENSION_EXISTS: &str = "
+SELECT EXISTS(
+  SELECT 1 FROM gpkg_extensions
+  WHERE table_name = ?1 AND column_name = ?2 AND extension_name = 'gpkg_rtree_index'
+)
+";
+
+pub(crate) fn sql_select_geometries(
+    layer_name: &str,
+    geometry_column: &str,
+    limit: Option<u64>,
+) -> String {
+    let mut sql = format!(
+        r#"SELECT rowid, "{geometry_column}" FROM "{layer_name}" WHERE "{geometry_column}" IS NOT NULL ORDER BY rowid"#
+    );
+    if let Some(limit) = limit {
+        sql.push_str(&format!(" LIMIT {limit}"));
+    }
+    sql
+}
+
+pub(crate) const SQL_CRS_WKT_COLUMN_EXISTS: &str = "SELECT EXISTS(SELECT 1 FROM pragma_table_info('gpkg_spatial_ref_sys') WHERE name = 'definition_12_063')";
+
+// gpkg_crs_wkt: adds the WKT2 (OGC 12-063r5) definition of each SRS.
+pub(crate) const SQL_ENABLE_CRS_WKT: &str = "
+ALTER TABLE gpkg_spatial_ref_sys ADD COLUMN definition_12_063 TEXT NOT NULL DEFAULT 'undefined';
+INSERT OR IGNORE INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+VALUES ('gpkg_spatial_ref_sys', 'definition_12_063', 'gpkg_crs_wkt', 'http://www.geopackage.org/spec120/#extension_crs_wkt', 'read-write');
+";
+
+// gpkg_schema: titles, descriptions and constraints of the columns.
+pub(crate) const SQL_ENABLE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS gpkg_data_columns (
+  table_name TEXT NOT NULL,
+  column_name TEXT NOT NULL,
+  name TEXT,
+  title TEXT,
+  description TEXT,
+  mime_type TEXT,
+  constraint_name TEXT,
+  CONSTRAINT pk_gdc PRIMARY KEY (table_name, column_name),
+  CONSTRAINT gdc_tn UNIQUE (table_name, name)
+);
+CREATE TABLE IF NOT EXISTS gpkg_data_column_constraints (
+  constraint_name TEXT NOT NULL,
+  constraint_type TEXT NOT NULL,
+  value TEXT,
+  min NUMERIC,
+  min_is_inclusive BOOLEAN,
+  max NUMERIC,
+  max_is_inclusive BOOLEAN,
+  description TEXT,
+  CONSTRAINT gdcc_ntv UNIQUE (constraint_name, constraint_type, value)
+);
+INSERT INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+SELECT 'gpkg_data_columns', NULL, 'gpkg_schema', 'http://www.geopackage.org/spec/#extension_schema', 'read-write'
+WHERE NOT EXISTS (SELECT 1 FROM gpkg_extensions WHERE table_name = 'gpkg_data_columns' AND extension_name = 'gpkg_schema');
+INSERT INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+SELECT 'gpkg_data_column_constraints', NULL, 'gpkg_schema', 'http://www.geopackage.org/spec/#extension_schema', 'read-write'
+WHERE NOT EXISTS (SELECT 1 FROM gpkg_extensions WHERE table_name = 'gpkg_data_column_constraints' AND extension_name = 'gpkg_schema');
+";
+
+pub(crate) const SQL_SELECT_DATA_COLUMN: &str = "
+SELECT name, title, description, mime_type, constraint_name
+FROM gpkg_data_columns
+WHERE table_name = ?1 AND column_name = ?2
+";
+
+pub(crate) const SQL_SELECT_DATA_COLUMN_CONSTRAINT: &str = "
+SELECT constraint_type, value, min, min_is_inclusive, max, max_is_inclusive, description
+FROM gpkg_data_column_constraints
+WHERE constraint_name = ?1
+ORDER BY rowid
+";
+
+pub(crate) const SQL_UPSERT_DATA_COLUMN: &str = "
+INSERT OR REPLACE INTO gpkg_data_columns
+  (table_name, column_name, name, title, description, mime_type, constraint_name)
+VALUES
+  (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+";
+
+pub(crate) const SQL_DELETE_DATA_COLUMN_CONSTRAINT: &str =
+    "DELETE FROM gpkg_data_column_constraints WHERE constraint_name = ?1";
+
+pub(crate) const SQL_INSERT_DATA_COLUMN_CONSTRAINT: &str = "
+INSERT INTO gpkg_data_column_constraints
+  (constraint_name, constraint_type, value, min, min_is_inclusive, max, max_is_inclusive, description)
+VALUES
+  (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+";
+
+pub(crate) const SQL_DELETE_DATA_COLUMNS: &str =
+    "DELETE FROM gpkg_data_columns WHERE table_name = ?1";
+
+// gpkg_metadata: metadata documents and what they describe.
+pub(crate) const SQL_ENABLE_METADATA: &str = "
+CREATE TABLE IF NOT EXISTS gpkg_metadata (
+  id INTEGER CONSTRAINT m_pk PRIMARY KEY ASC NOT NULL,
+  md_scope TEXT NOT NULL DEFAULT 'dataset',
+  md_standard_uri TEXT NOT NULL,
+  mime_type TEXT NOT NULL DEFAULT 'text/xml',
+  metadata TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE IF NOT EXISTS gpkg_metadata_reference (
+  reference_scope TEXT NOT NULL,
+  table_name TEXT,
+  column_name TEXT,
+  row_id_value INTEGER,
+  timestamp DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+  md_file_id INTEGER NOT NULL,
+  md_parent_id INTEGER,
+  CONSTRAINT crmr_mfi_fk FOREIGN KEY (md_file_id) REFERENCES gpkg_metadata(id),
+  CONSTRAINT crmr_mpi_fk FOREIGN KEY (md_parent_id) REFERENCES gpkg_metadata(id)
+);
+INSERT INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+SELECT 'gpkg_metadata', NULL, 'gpkg_metadata', 'http://www.geopackage.org/spec/#extension_metadata', 'read-write'
+WHERE NOT EXISTS (SELECT 1 FROM gpkg_extensions WHERE table_name = 'gpkg_metadata' AND extension_name = 'gpkg_metadata');
+INSERT INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+SELECT 'gpkg_metadata_reference', NULL, 'gpkg_metadata', 'http://www.geopackage.org/spec/#extension_metadata', 'read-write'
+WHERE NOT EXISTS (SELECT 1 FROM gpkg_extensions WHERE table_name = 'gpkg_metadata_reference' AND extension_name = 'gpkg_metadata');
+";
+
+pub(crate) const SQL_INSERT_METADATA: &str = "
+INSERT INTO gpkg_metadata (md_scope, md_standard_uri, mime_type, metadata)
+VALUES (?1, ?2, ?3, ?4)
+";
+
+pub(crate) const SQL_METADATA_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM gpkg_metadata WHERE id = ?1)";
+
+// `reference_scope` is either 'geopackage' (with a NULL `table_name`) or 'table'.
+pub(crate) const SQL_INSERT_METADATA_REFERENCE: &str = "
+INSERT INTO gpkg_metadata_reference (reference_scope, table_name, md_file_id)
+SELECT ?1, ?2, ?3
+WHERE NOT EXISTS (
+  SELECT 1 FROM gpkg_metadata_reference
+  WHERE reference_scope = ?1 AND table_name IS ?2 AND column_name IS NULL
+    AND row_id_value IS NULL AND md_file_id = ?3
+)
+";
+
+pub(crate) const SQL_SELECT_REFERENCED_METADATA: &str = "
+SELECT m.id, m.md_scope, m.md_standard_uri, m.mime_type, m.metadata
+FROM gpkg_metadata_reference AS r
+JOIN gpkg_metadata AS m ON m.id = r.md_file_id
+WHERE r.reference_scope = ?1 AND r.table_name IS ?2
+ORDER BY r.timestamp, m.id
+";
+
+pub(crate) const SQL_RENAME_METADATA_REFERENCE: &str =
+    "UPDATE gpkg_metadata_reference SET table_name = ?2 WHERE table_name = ?1";
+
+pub(crate) const SQL_SRS_EXISTS: &str =
+    "SELECT EXISTS(SELECT 1 FROM gpkg_spatial_ref_sys WHERE srs_id = ?1)";
+
+#[cfg(feature = "geojson")]
+pub(crate) const SQL_SELECT_SRS_ORGANIZATION: &str =
+    "SELECT organization, organization_coordsys_id FROM gpkg_spatial_ref_sys WHERE srs_id = ?1";
+
+#[cfg(feature = "parquet")]
+pub(crate) const SQL_SELECT_SRS_DEFINITION: &str = "SELECT organization, organization_coordsys_id, definition FROM gpkg_spatial_ref_sys WHERE srs_id = ?1";
+
 // This is a bit horrible part. gpkg_spatial_ref_sys requires the WKT of the SRS, but we don't have a good source for this.
 // Adding 4326 is easy, but what should I do to support other SRS?
 fn register_default_srs_ids(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
@@ -282,6 +899,16 @@ CREATE TABLE gpkg_tile_matrix_set (
 ";
 
 // cf. https://www.geopackage.org/spec140/index.html#extension_rtree
+pub(crate) const SQL_INSERT_RTREE_EXTENSION: &str = "
+INSERT OR IGNORE INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+VALUES (?1, ?2, 'gpkg_rtree_index', 'http://www.geopackage.org/spec/#extension_rtree', 'write-only')
+";
+
+pub(crate) const SQL_DELETE_RTREE_EXTENSION: &str = "
+DELETE FROM gpkg_extensions
+WHERE table_name = ?1 AND column_name = ?2 AND extension_name = 'gpkg_rtree_index'
+";
+
 pub(crate) fn gpkg_rtree_create_sql(table: &str, geom_column: &str) -> String {
     format!(
         "CREATE VIRTUAL TABLE rtree_{t}_{c} USING rtree(id, minx, maxx, miny, maxy);",
@@ -292,12 +919,43 @@ pub(crate) fn gpkg_rtree_create_sql(table: &str, geom_column: &str) -> String {
 
 pub(crate) fn gpkg_rtree_drop_sql(table: &str, geom_column: &str) -> String {
     format!(
-        "DROP TABLE rtree_{t}_{c} USING rtree(id, minx, maxx, miny, maxy);",
+        "DROP TABLE IF EXISTS rtree_{t}_{c};",
         t = table,
         c = geom_column,
     )
 }
 
+pub(crate) fn gpkg_rtree_rename_sql(old_table: &str, new_table: &str, geom_column: &str) -> String {
+    format!(
+        "ALTER TABLE rtree_{o}_{c} RENAME TO rtree_{n}_{c};",
+        o = old_table,
+        n = new_table,
+        c = geom_column,
+    )
+}
+
+// Drop all the rtree triggers, including the update1/update3 variants that
+// other tools may have created.
+pub(crate) fn gpkg_rtree_triggers_drop_sql(table: &str, geom_column: &str) -> String {
+    [
+        "insert", "update1", "update2", "update3", "update4", "update5", "update6", "update7",
+        "delete",
+    ]
+    .iter()
+    .map(|suffix| format!("DROP TRIGGER IF EXISTS rtree_{table}_{geom_column}_{suffix};"))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+// The rtree triggers defined by GeoPackage 1.4, and the set used by earlier
+// versions of the spec.
+pub(crate) const RTREE_TRIGGER_SUFFIXES: [&str; 7] = [
+    "insert", "update2", "update4", "update5", "update6", "update7", "delete",
+];
+pub(crate) const LEGACY_RTREE_TRIGGER_SUFFIXES: [&str; 6] = [
+    "insert", "update1", "update2", "update3", "update4", "delete",
+];
+
 pub(crate) fn gpkg_rtree_load_sql(table: &str, geom_column: &str, id_column: &str) -> String {
     format!(
         "INSERT OR REPLACE INTO rtree_{t}_{c}
@@ -309,6 +967,60 @@ pub(crate) fn gpkg_rtree_load_sql(table: &str, geom_column: &str, id_column: &st
     )
 }
 
+pub(crate) fn gpkg_rtree_clear_sql(table: &str, geom_column: &str) -> String {
+    format!("DELETE FROM rtree_{table}_{geom_column};")
+}
+
+pub(crate) fn sql_rtree_missing_ids(table: &str, geom_column: &str, id_column: &str) -> String {
+    format!(
+        "SELECT {i} FROM {t}
+  WHERE {c} NOT NULL AND NOT ST_IsEmpty({c})
+    AND {i} NOT IN (SELECT id FROM rtree_{t}_{c})
+  ORDER BY {i}",
+        t = table,
+        c = geom_column,
+        i = id_column
+    )
+}
+
+pub(crate) fn sql_rtree_orphan_ids(table: &str, geom_column: &str, id_column: &str) -> String {
+    format!(
+        "SELECT id FROM rtree_{t}_{c}
+  WHERE id NOT IN (SELECT {i} FROM {t} WHERE {c} NOT NULL AND NOT ST_IsEmpty({c}))
+  ORDER BY id",
+        t = table,
+        c = geom_column,
+        i = id_column
+    )
+}
+
+// The rtree stores 32-bit floats, so its bounds are compared with a relative
+// tolerance instead of exactly.
+pub(crate) fn sql_rtree_bounds_mismatches(
+    table: &str,
+    geom_column: &str,
+    id_column: &str,
+) -> String {
+    let differs = |rtree: &str, computed: &str| {
+        format!(
+            "abs(r.{rtree} - {computed}(t.{geom_column})) > 1e-6 * max(1.0, abs({computed}(t.{geom_column})))"
+        )
+    };
+    format!(
+        "SELECT r.id FROM rtree_{t}_{c} r JOIN {t} t ON t.{i} = r.id
+  WHERE t.{c} NOT NULL AND NOT ST_IsEmpty(t.{c})
+    AND ({minx} OR {maxx} OR {miny} OR {maxy})
+  ORDER BY r.id",
+        t = table,
+        c = geom_column,
+        i = id_column,
+        minx = differs("minx", "ST_MinX"),
+        maxx = differs("maxx", "ST_MaxX"),
+        miny = differs("miny", "ST_MinY"),
+        maxy = differs("maxy", "ST_MaxY"),
+    )
+}
+
 pub(crate) fn gpkg_rtree_triggers_sql(table: &str, geom_column: &str, id_column: &str) -> String {
     format!(
         "CREATE TRIGGER rtree_{t}_{c}_insert AFTER INSERT ON {t}
@@ -393,8 +1105,7 @@ pub(crate) fn execute_rtree_sqls(
     conn.execute_batch(&gpkg_rtree_load_sql(table, geom_column, id_column))?;
     conn.execute_batch(&gpkg_rtree_triggers_sql(table, geom_column, id_column))?;
     conn.execute(
-        "INSERT INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope) \
-         VALUES (?1, ?2, 'gpkg_rtree_index', 'http://www.geopackage.org/spec/#extension_rtree', 'write-only')",
+        SQL_INSERT_RTREE_EXTENSION,
         rusqlite::params![table, geom_column],
     )?;
     Ok(())
@@ -1,24 +1,34 @@
+use crate::conversions::geometry_type_to_str;
 use crate::error::Result;
-use crate::gpkg::gpkg_geometry_to_wkb;
+use crate::gpkg::{gpkg_geometry_is_empty, gpkg_geometry_srs_id, gpkg_geometry_to_wkb};
 use geo_traits::{
-    CoordTrait, GeometryCollectionTrait, GeometryTrait, LineStringTrait, MultiLineStringTrait,
-    MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
 };
 use rusqlite::functions::{Context, FunctionFlags};
 use rusqlite::types::{Type, ValueRef};
 use rusqlite::{Connection, Error};
 use wkb::reader::Wkb;
 
-#[derive(Clone, Copy)]
-struct Bounds {
-    minx: f64,
-    maxx: f64,
-    miny: f64,
-    maxy: f64,
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Bounds {
+    pub(crate) minx: f64,
+    pub(crate) maxx: f64,
+    pub(crate) miny: f64,
+    pub(crate) maxy: f64,
+    /// `(min, max)` of the Z coordinates, if the geometry has them.
+    pub(crate) z: Option<(f64, f64)>,
+    /// `(min, max)` of the M coordinates, if the geometry has them.
+    pub(crate) m: Option<(f64, f64)>,
 }
 
 /// Register all spatial SQL helper functions in the provided connection.
 ///
+/// These are `ST_MinX`, `ST_MaxX`, `ST_MinY`, `ST_MaxY` and `ST_IsEmpty`,
+/// used by the rtree triggers, and `ST_GeometryType`, `ST_SRID` and
+/// `GPKG_IsAssignable`, used by the geometry type and srs_id triggers that
+/// GDAL may add.
+///
 /// Example:
 /// ```no_run
 /// use rusqlite::Connection;
@@ -34,6 +44,9 @@ pub fn register_spatial_functions(conn: &Connection) -> Result<()> {
     register_st_maxx(conn)?;
     register_st_maxy(conn)?;
     register_st_isempty(conn)?;
+    register_st_geometrytype(conn)?;
+    register_st_srid(conn)?;
+    register_gpkg_isassignable(conn)?;
     Ok(())
 }
 
@@ -59,10 +72,17 @@ pub(crate) fn register_st_isempty(conn: &Connection) -> Result<()> {
         1,
         FunctionFlags::SQLITE_DETERMINISTIC,
         |ctx| {
+            if let ValueRef::Blob(blob) = ctx.get_raw(0)
+                && gpkg_geometry_is_empty(blob)
+                    .map_err(|err| Error::UserFunctionError(Box::new(err)))?
+            {
+                return Ok(Some(1));
+            }
             let wkb = match wkb_from_ctx(ctx)? {
                 Some(wkb) => wkb,
                 None => return Ok(None),
             };
+            // This is also true when all the coordinates are NaN.
             let is_empty = bounds_from_geometry(&wkb).is_none();
             Ok(Some(i64::from(is_empty)))
         },
@@ -70,6 +90,80 @@ pub(crate) fn register_st_isempty(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+pub(crate) fn register_st_geometrytype(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "ST_GeometryType",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let wkb = match wkb_from_ctx(ctx)? {
+                Some(wkb) => wkb,
+                None => return Ok(None),
+            };
+            Ok(Some(geometry_type_to_str(wkb.geometry_type())))
+        },
+    )?;
+    Ok(())
+}
+
+pub(crate) fn register_st_srid(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "ST_SRID",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(0) {
+            ValueRef::Null => Ok(None),
+            ValueRef::Blob(blob) => gpkg_geometry_srs_id(blob)
+                .map(Some)
+                .map_err(|err| Error::UserFunctionError(Box::new(err))),
+            _ => Err(Error::InvalidFunctionParameterType(0, Type::Blob)),
+        },
+    )?;
+    Ok(())
+}
+
+// GPKG_IsAssignable(expected, actual) is 1 if a geometry of type `actual` can
+// be stored in a column of type `expected`.
+pub(crate) fn register_gpkg_isassignable(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "GPKG_IsAssignable",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let expected: Option<String> = ctx.get(0)?;
+            let actual: Option<String> = ctx.get(1)?;
+            Ok(match (expected, actual) {
+                (Some(expected), Some(actual)) => {
+                    Some(i64::from(is_assignable(&expected, &actual)))
+                }
+                _ => None,
+            })
+        },
+    )?;
+    Ok(())
+}
+
+// The geometry type hierarchy of the GeoPackage spec (Annex E).
+pub(crate) fn is_assignable(expected: &str, actual: &str) -> bool {
+    let mut current = actual.to_ascii_uppercase();
+    loop {
+        if current.eq_ignore_ascii_case(expected) {
+            return true;
+        }
+        let parent = match current.as_str() {
+            "POINT" | "CURVE" | "SURFACE" | "GEOMETRYCOLLECTION" => "GEOMETRY",
+            "LINESTRING" | "CIRCULARSTRING" | "COMPOUNDCURVE" => "CURVE",
+            "POLYGON" => "CURVEPOLYGON",
+            "CURVEPOLYGON" => "SURFACE",
+            "MULTIPOINT" | "MULTICURVE" | "MULTISURFACE" => "GEOMETRYCOLLECTION",
+            "MULTILINESTRING" => "MULTICURVE",
+            "MULTIPOLYGON" => "MULTISURFACE",
+            _ => return false,
+        };
+        current = parent.to_string();
+    }
+}
+
 fn register_bounds_component<F>(conn: &Connection, name: &str, f: F) -> Result<()>
 where
     F: Fn(Bounds) -> f64 + Copy + Send + Sync + 'static,
@@ -97,7 +191,7 @@ fn wkb_from_ctx<'a>(ctx: &'a Context<'a>) -> std::result::Result<Option<Wkb<'a>>
     }
 }
 
-fn bounds_from_geometry<G: GeometryTrait<T = f64>>(geom: &G) -> Option<Bounds> {
+pub(crate) fn bounds_from_geometry<G: GeometryTrait<T = f64>>(geom: &G) -> Option<Bounds> {
     use geo_traits::GeometryType as GeoType;
 
     let mut bounds: Option<Bounds> = None;
@@ -165,37 +259,51 @@ fn add_line_string<L: LineStringTrait<T = f64>>(bounds: &mut Option<Bounds>, lin
 }
 
 fn add_coord<C: CoordTrait<T = f64>>(bounds: &mut Option<Bounds>, coord: &C) {
-    let (x, y) = coord.x_y();
-    match bounds {
-        Some(existing) => {
-            existing.minx = existing.minx.min(x);
-            existing.maxx = existing.maxx.max(x);
-            existing.miny = existing.miny.min(y);
-            existing.maxy = existing.maxy.max(y);
-        }
-        None => {
-            *bounds = Some(Bounds {
-                minx: x,
-                maxx: x,
-                miny: y,
-                maxy: y,
-            });
-        }
+    // Empty points are often encoded as NaN coordinates, which must not end
+    // up in the bounds.
+    if (0..coord.dim().size()).all(|i| coord.nth_or_panic(i).is_nan()) {
+        return;
     }
+    let (x, y) = coord.x_y();
+    let (z, m) = match coord.dim() {
+        Dimensions::Xyz => (Some(coord.nth_or_panic(2)), None),
+        Dimensions::Xym => (None, Some(coord.nth_or_panic(2))),
+        Dimensions::Xyzm => (Some(coord.nth_or_panic(2)), Some(coord.nth_or_panic(3))),
+        _ => (None, None),
+    };
+    let other = Bounds {
+        minx: x,
+        maxx: x,
+        miny: y,
+        maxy: y,
+        z: z.map(|z| (z, z)),
+        m: m.map(|m| (m, m)),
+    };
+    merge_bounds(bounds, other);
 }
 
-fn merge_bounds(bounds: &mut Option<Bounds>, other: Bounds) {
+pub(crate) fn merge_bounds(bounds: &mut Option<Bounds>, other: Bounds) {
     match bounds {
         Some(existing) => {
             existing.minx = existing.minx.min(other.minx);
             existing.maxx = existing.maxx.max(other.maxx);
             existing.miny = existing.miny.min(other.miny);
             existing.maxy = existing.maxy.max(other.maxy);
+            existing.z = merge_range(existing.z, other.z);
+            existing.m = merge_range(existing.m, other.m);
         }
         None => *bounds = Some(other),
     }
 }
 
+fn merge_range(a: Option<(f64, f64)>, b: Option<(f64, f64)>) -> Option<(f64, f64)> {
+    match (a, b) {
+        (Some((amin, amax)), Some((bmin, bmax))) => Some((amin.min(bmin), amax.max(bmax))),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::register_spatial_functions;
@@ -211,7 +319,7 @@ mod tests {
         let mut wkb = Vec::new();
         wkb::writer::write_geometry(&mut wkb, &geometry, &Default::default())?;
         let wkb = Wkb::try_new(&wkb)?;
-        wkb_to_gpkg_geometry(wkb, 4326)
+        wkb_to_gpkg_geometry(wkb, 4326, None)
     }
 
     #[test]
@@ -262,6 +370,150 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn st_is_empty_for_gdal_empty_point() -> crate::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_spatial_functions(&conn)?;
+
+        // GDAL writes POINT EMPTY with the empty flag and NaN coordinates.
+        let mut blob = vec![0x47, 0x50, 0x00, 0x11];
+        blob.extend_from_slice(&4326u32.to_le_bytes());
+        blob.push(0x01);
+        blob.extend_from_slice(&1u32.to_le_bytes());
+        blob.extend_from_slice(&f64::NAN.to_le_bytes());
+        blob.extend_from_slice(&f64::NAN.to_le_bytes());
+
+        let (minx, empty): (Option<f64>, i64) =
+            conn.query_row("SELECT ST_MinX(?1), ST_IsEmpty(?1)", params![blob], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        assert!(minx.is_none());
+        assert_eq!(empty, 1);
+
+        // The flag alone is enough.
+        let mut blob = gpkg_blob_from_geometry(Point::new(1.0, 2.0))?;
+        blob[3] |= 0x10;
+        let empty: i64 =
+            conn.query_row("SELECT ST_IsEmpty(?1)", params![blob], |row| row.get(0))?;
+        assert_eq!(empty, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn st_is_empty_for_nan_linestring() -> crate::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_spatial_functions(&conn)?;
+
+        let line = LineString::from(vec![(f64::NAN, f64::NAN), (f64::NAN, f64::NAN)]);
+        let mut wkb = Vec::new();
+        wkb::writer::write_geometry(&mut wkb, &line, &Default::default())?;
+        // Build the header by hand so that the empty flag is not set.
+        let mut blob = vec![0x47, 0x50, 0x00, 0x01];
+        blob.extend_from_slice(&4326u32.to_le_bytes());
+        blob.extend_from_slice(&wkb);
+
+        let (minx, empty): (Option<f64>, i64) =
+            conn.query_row("SELECT ST_MinX(?1), ST_IsEmpty(?1)", params![blob], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        assert!(minx.is_none());
+        assert_eq!(empty, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn st_geometry_type_and_srid() -> crate::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_spatial_functions(&conn)?;
+
+        let cases: Vec<(Geometry<f64>, &str)> = vec![
+            (Point::new(1.0, 2.0).into(), "POINT"),
+            (
+                LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+                "LINESTRING",
+            ),
+            (
+                MultiPoint::from(vec![Point::new(0.0, 0.0)]).into(),
+                "MULTIPOINT",
+            ),
+            (
+                Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                    Point::new(0.0, 0.0).into(),
+                ])),
+                "GEOMETRYCOLLECTION",
+            ),
+        ];
+        for (geometry, expected) in cases {
+            let blob = gpkg_blob_from_geometry(geometry)?;
+            let (geometry_type, srid): (String, i64) =
+                conn.query_row("SELECT ST_GeometryType(?1), ST_SRID(?1)", [blob], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?;
+            assert_eq!(geometry_type, expected);
+            assert_eq!(srid, 4326);
+        }
+
+        let nulls: (Option<String>, Option<i64>) =
+            conn.query_row("SELECT ST_GeometryType(NULL), ST_SRID(NULL)", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        assert_eq!(nulls, (None, None));
+        Ok(())
+    }
+
+    #[test]
+    fn gpkg_is_assignable_follows_type_hierarchy() -> crate::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_spatial_functions(&conn)?;
+
+        for (expected, actual, assignable) in [
+            ("POINT", "POINT", Some(1)),
+            ("GEOMETRY", "MULTIPOLYGON", Some(1)),
+            ("geometry", "Point", Some(1)),
+            ("GEOMETRYCOLLECTION", "MULTILINESTRING", Some(1)),
+            ("MULTISURFACE", "MULTIPOLYGON", Some(1)),
+            ("CURVE", "LINESTRING", Some(1)),
+            ("SURFACE", "POLYGON", Some(1)),
+            ("POINT", "MULTIPOINT", Some(0)),
+            ("MULTIPOINT", "POINT", Some(0)),
+            ("POLYGON", "GEOMETRY", Some(0)),
+            ("LINESTRING", "UNKNOWN", Some(0)),
+            ("POINT", "", Some(0)),
+        ] {
+            let result: Option<i64> = conn.query_row(
+                "SELECT GPKG_IsAssignable(?1, ?2)",
+                [expected, actual],
+                |row| row.get(0),
+            )?;
+            assert_eq!(result, assignable, "{expected} <- {actual}");
+        }
+        let null: Option<i64> =
+            conn.query_row("SELECT GPKG_IsAssignable('POINT', NULL)", [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(null, None);
+        Ok(())
+    }
+
+    #[test]
+    fn st_functions_reject_truncated_blob() -> crate::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_spatial_functions(&conn)?;
+
+        let blob = gpkg_blob_from_geometry(Point::new(1.0, 2.0))?;
+        for len in 0..blob.len() {
+            let truncated = &blob[..len];
+            for function in ["ST_MinX", "ST_IsEmpty"] {
+                let result: rusqlite::Result<Option<f64>> =
+                    conn.query_row(&format!("SELECT {function}(?1)"), [truncated], |row| {
+                        row.get(0)
+                    });
+                assert!(result.is_err(), "{function} len {len}");
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn st_bounds_for_multipoint() -> crate::Result<()> {
         let conn = Connection::open_in_memory()?;
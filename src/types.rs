@@ -1,17 +1,24 @@
 use crate::error::GpkgError;
+use geo_traits::GeometryTrait;
+use std::borrow::Borrow;
 use wkb::reader::{Dimension, GeometryType, Wkb};
 
 /// Logical column types used by GeoPackage layers and DDL helpers.
+///
+/// The narrower numeric types follow the GeoPackage spec (Table 1). SQLite
+/// stores them all as INTEGER or REAL, but the declared type is preserved so
+/// that other tools see the same field definitions.
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
+#[non_exhaustive]
 pub enum ColumnType {
     /// Boolean value stored as an integer 0/1.
     Boolean,
     /// UTF-8 text column.
     Varchar,
-    /// Floating point column (SQLite REAL).
+    /// 64-bit floating point column (`DOUBLE` or `REAL`).
     Double,
-    /// Integer column (SQLite INTEGER).
+    /// 64-bit integer column (`INTEGER` or `INT`).
     Integer,
     /// Geometry column stored as a GeoPackage BLOB.
     Geometry,
@@ -21,24 +28,667 @@ pub enum ColumnType {
     Date,
     /// ISO 8601 date-time stored as TEXT (`YYYY-MM-DDTHH:MM:SS.SSSZ`).
     Datetime,
+    /// 8-bit integer column (`TINYINT`).
+    TinyInt,
+    /// 16-bit integer column (`SMALLINT`).
+    SmallInt,
+    /// 32-bit integer column (`MEDIUMINT`).
+    MediumInt,
+    /// 32-bit floating point column (`FLOAT`).
+    Float,
+}
+
+/// Sort order of a column for `GpkgLayer::features_ordered`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Ascending (`ASC`).
+    Asc,
+    /// Descending (`DESC`).
+    Desc,
+}
+
+/// SQLite collating sequence used to compare text in `GpkgLayer::features_ordered`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collation {
+    /// Compare bytes (`BINARY`).
+    Binary,
+    /// Compare ASCII letters case-insensitively (`NOCASE`).
+    NoCase,
+    /// Ignore trailing spaces (`RTRIM`).
+    RTrim,
+}
+
+/// A sort key of `GpkgLayer::features_ordered`: a column, its order and the
+/// collation to compare it with. `(column, order)` and
+/// `(column, order, collation)` tuples convert into it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey<'a> {
+    pub column: &'a str,
+    pub order: SortOrder,
+    /// The collation, or `None` to use the one declared on the column.
+    pub collation: Option<Collation>,
+}
+
+impl<'a> From<(&'a str, SortOrder)> for SortKey<'a> {
+    fn from((column, order): (&'a str, SortOrder)) -> Self {
+        Self {
+            column,
+            order,
+            collation: None,
+        }
+    }
+}
+
+impl<'a> From<(&'a str, SortOrder, Collation)> for SortKey<'a> {
+    fn from((column, order, collation): (&'a str, SortOrder, Collation)) -> Self {
+        Self {
+            column,
+            order,
+            collation: Some(collation),
+        }
+    }
+}
+
+/// SQLite journal modes that can be set via `Gpkg::set_journal_mode`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JournalMode {
+    /// Delete the rollback journal at the end of each transaction (SQLite's default).
+    Delete,
+    /// Truncate the rollback journal instead of deleting it.
+    Truncate,
+    /// Keep the rollback journal and overwrite its header.
+    Persist,
+    /// Keep the rollback journal in memory.
+    Memory,
+    /// Write-ahead log, which lets readers run concurrently with a writer.
+    Wal,
+    /// Disable the rollback journal.
+    Off,
+}
+
+impl JournalMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            JournalMode::Delete => "delete",
+            JournalMode::Truncate => "truncate",
+            JournalMode::Persist => "persist",
+            JournalMode::Memory => "memory",
+            JournalMode::Wal => "wal",
+            JournalMode::Off => "off",
+        }
+    }
+}
+
+/// Whether `GpkgLayer::upsert` inserted a new feature or updated an existing one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpsertOutcome {
+    /// No feature had the id, so a new one was inserted.
+    Inserted,
+    /// A feature with the id existed and was updated.
+    Updated,
+}
+
+/// Whether the geometries of a layer have Z (or M) values, as declared by
+/// the `z` (or `m`) column of `gpkg_geometry_columns`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZmFlag {
+    /// `0`: the values are prohibited.
+    Prohibited,
+    /// `1`: the values are mandatory.
+    Mandatory,
+    /// `2`: the values are optional, so each geometry may or may not have
+    /// them.
+    Optional,
+}
+
+/// Summary statistics of a column, returned by `GpkgLayer::column_stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnStats {
+    /// The smallest non-`NULL` value, in SQLite's sort order.
+    pub min: Option<Value>,
+    /// The largest non-`NULL` value, in SQLite's sort order.
+    pub max: Option<Value>,
+    /// The mean of the integer and real values, or `None` if there is none.
+    pub mean: Option<f64>,
+    /// Number of `NULL` values.
+    pub null_count: u64,
+    /// Number of distinct non-`NULL` values.
+    pub distinct_count: u64,
+}
+
+/// The result of `GpkgLayer::verify_spatial_index`.
+///
+/// Each list holds primary key values of the layer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpatialIndexReport {
+    /// Number of features with a non-empty geometry.
+    pub feature_count: u64,
+    /// Number of rows in the rtree.
+    pub rtree_count: u64,
+    /// Features with a non-empty geometry but no rtree row.
+    pub missing_ids: Vec<i64>,
+    /// Rtree rows without a matching feature, or whose feature has a `NULL`
+    /// or empty geometry.
+    pub orphan_ids: Vec<i64>,
+    /// Rtree rows whose bounds differ from the feature's geometry.
+    pub bounds_mismatches: Vec<i64>,
+}
+
+impl SpatialIndexReport {
+    /// Return `true` if the rtree matches the features.
+    pub fn is_consistent(&self) -> bool {
+        self.missing_ids.is_empty()
+            && self.orphan_ids.is_empty()
+            && self.bounds_mismatches.is_empty()
+    }
+}
+
+/// Severity of a [`ValidationIssue`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    /// The file violates a requirement of the GeoPackage spec.
+    Error,
+    /// The file is readable but deviates from what the spec recommends.
+    Warning,
+}
+
+/// A problem found by `Gpkg::validate()`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// `PRAGMA application_id` is not `GPKG`.
+    InvalidApplicationId { application_id: i32 },
+    /// `PRAGMA user_version` doesn't name a GeoPackage 1.2 or later version.
+    UnexpectedUserVersion { user_version: i32 },
+    /// A required `gpkg_*` table doesn't exist.
+    MissingTable { table_name: String },
+    /// A `gpkg_*` table lacks a column required by the spec.
+    MissingColumn {
+        table_name: String,
+        column_name: String,
+    },
+    /// A `gpkg_contents` row names a table or view that doesn't exist.
+    MissingContentsTable { table_name: String },
+    /// A `gpkg_geometry_columns` row references an srs_id that is not in
+    /// `gpkg_spatial_ref_sys`.
+    UnknownSrsId { table_name: String, srs_id: i32 },
+    /// A geometry value is not a valid GeoPackage geometry blob.
+    InvalidGeometryBlob {
+        table_name: String,
+        column_name: String,
+        id: i64,
+        reason: String,
+    },
+    /// A geometry's WKB type doesn't match `geometry_type_name` of its column.
+    GeometryTypeMismatch {
+        table_name: String,
+        column_name: String,
+        id: i64,
+        declared: String,
+        found: GeometryType,
+    },
+    /// The `gpkg_rtree_index` extension is registered but the rtree table is
+    /// missing.
+    MissingRtreeIndex {
+        table_name: String,
+        column_name: String,
+    },
+    /// One of the triggers that keep the rtree in sync is missing.
+    MissingRtreeTrigger {
+        table_name: String,
+        trigger_name: String,
+    },
+    /// A `gpkg_extensions` row references a table or column that doesn't
+    /// exist.
+    MissingExtensionTarget {
+        extension_name: String,
+        table_name: String,
+        column_name: Option<String>,
+    },
+}
+
+impl ValidationIssue {
+    /// Return the severity of the issue.
+    pub fn severity(&self) -> ValidationSeverity {
+        match self {
+            Self::UnexpectedUserVersion { .. } => ValidationSeverity::Warning,
+            _ => ValidationSeverity::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidApplicationId { application_id } => {
+                write!(f, "application_id is {application_id:#010x}, not GPKG")
+            }
+            Self::UnexpectedUserVersion { user_version } => {
+                write!(f, "unexpected user_version: {user_version}")
+            }
+            Self::MissingTable { table_name } => write!(f, "missing table: {table_name}"),
+            Self::MissingColumn {
+                table_name,
+                column_name,
+            } => write!(f, "missing column: {table_name}.{column_name}"),
+            Self::MissingContentsTable { table_name } => {
+                write!(f, "gpkg_contents references missing table: {table_name}")
+            }
+            Self::UnknownSrsId { table_name, srs_id } => {
+                write!(
+                    f,
+                    "geometry column of {table_name} uses unknown srs_id {srs_id}"
+                )
+            }
+            Self::InvalidGeometryBlob {
+                table_name,
+                column_name,
+                id,
+                reason,
+            } => write!(
+                f,
+                "invalid geometry in {table_name}.{column_name} (id {id}): {reason}"
+            ),
+            Self::GeometryTypeMismatch {
+                table_name,
+                column_name,
+                id,
+                declared,
+                found,
+            } => write!(
+                f,
+                "geometry in {table_name}.{column_name} (id {id}) is {found:?}, expected {declared}"
+            ),
+            Self::MissingRtreeIndex {
+                table_name,
+                column_name,
+            } => write!(f, "missing rtree index: rtree_{table_name}_{column_name}"),
+            Self::MissingRtreeTrigger {
+                table_name,
+                trigger_name,
+            } => write!(f, "missing rtree trigger on {table_name}: {trigger_name}"),
+            Self::MissingExtensionTarget {
+                extension_name,
+                table_name,
+                column_name: Some(column_name),
+            } => write!(
+                f,
+                "extension {extension_name} references missing column: {table_name}.{column_name}"
+            ),
+            Self::MissingExtensionTarget {
+                extension_name,
+                table_name,
+                column_name: None,
+            } => write!(
+                f,
+                "extension {extension_name} references missing table: {table_name}"
+            ),
+        }
+    }
+}
+
+/// Result of `Gpkg::validate()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Issues in the order they were found.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Return `true` if no issue has `ValidationSeverity::Error`.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Iterate over the issues with `ValidationSeverity::Error`.
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == ValidationSeverity::Error)
+    }
+
+    /// Iterate over the issues with `ValidationSeverity::Warning`.
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == ValidationSeverity::Warning)
+    }
+}
+
+/// Options for `Gpkg::validate_with_options()`.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationOptions {
+    /// Maximum number of geometries to check per geometry column. `None`
+    /// (the default) checks every row.
+    pub max_geometries_per_layer: Option<u64>,
+}
+
+/// Bounding box stored in the header of a GeoPackage geometry blob.
+///
+/// The Z and M ranges are `Some` only when the envelope includes them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+    pub min_z: Option<f64>,
+    pub max_z: Option<f64>,
+    pub min_m: Option<f64>,
+    pub max_m: Option<f64>,
 }
 
 /// Column definition used when creating or describing layer properties.
+///
+/// The constraint fields can be omitted from struct literals with
+/// `..Default::default()`.
+///
+/// ```
+/// use rusqlite_gpkg::{ColumnSpec, ColumnType, Value};
+///
+/// let spec = ColumnSpec {
+///     name: "name".to_string(),
+///     column_type: ColumnType::Varchar,
+///     not_null: true,
+///     default: Some(Value::from("unknown")),
+///     ..Default::default()
+/// };
+/// assert!(!spec.unique);
+/// ```
 #[derive(Clone, Debug)]
 pub struct ColumnSpec {
     pub name: String,
     pub column_type: ColumnType,
+    /// Whether the column is declared `NOT NULL`.
+    pub not_null: bool,
+    /// Whether the column is declared `UNIQUE`.
+    pub unique: bool,
+    /// Literal `DEFAULT` value of the column. Default expressions such as
+    /// `CURRENT_TIMESTAMP` are not represented and read as `None`.
+    pub default: Option<Value>,
+}
+
+impl Default for ColumnSpec {
+    /// An unnamed, unconstrained `Varchar` column.
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            column_type: ColumnType::Varchar,
+            not_null: false,
+            unique: false,
+            default: None,
+        }
+    }
+}
+
+/// Options for `Gpkg::create_layer_with_options`.
+#[derive(Clone, Debug)]
+pub struct CreateLayerOptions {
+    /// Name of the integer primary key column. Defaults to `fid`.
+    pub primary_key_column: String,
+    /// Whether the primary key is declared `AUTOINCREMENT`, which prevents
+    /// the ids of deleted rows from being reused but creates the
+    /// `sqlite_sequence` table. Defaults to `true`.
+    pub autoincrement: bool,
+    /// Whether the layer writes the bounding box of each geometry into the
+    /// GeoPackage binary header. See `GpkgLayer::set_write_envelope()`.
+    /// Defaults to `false`.
+    pub write_envelope: bool,
+    /// Whether a missing `srs_id` is registered from the bundled EPSG
+    /// definitions (see `Gpkg::register_epsg()`). Defaults to `true`.
+    #[cfg(feature = "epsg-common")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "epsg-common")))]
+    pub register_epsg: bool,
+    /// The `identifier` of the layer in `gpkg_contents`, which tools like
+    /// QGIS show as the layer's title. Defaults to `None`, which uses the
+    /// layer name.
+    pub identifier: Option<String>,
+    /// The `description` of the layer in `gpkg_contents`. Defaults to `None`,
+    /// which writes an empty description.
+    pub description: Option<String>,
+    /// Whether the geometry column is declared as `GEOMETRY`, which accepts
+    /// any geometry type, instead of as the given geometry type. The layer's
+    /// `geometry_type` is then `GeometryCollection`, as for any `GEOMETRY`
+    /// layer. Defaults to `false`.
+    pub any_geometry_type: bool,
+}
+
+impl Default for CreateLayerOptions {
+    fn default() -> Self {
+        Self {
+            primary_key_column: "fid".to_string(),
+            autoincrement: true,
+            write_envelope: false,
+            #[cfg(feature = "epsg-common")]
+            register_epsg: true,
+            identifier: None,
+            description: None,
+            any_geometry_type: false,
+        }
+    }
+}
+
+/// Options for `GpkgLayer::features_with_options`.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureReadOptions {
+    /// Whether only the id and the geometry are read, e.g. to build a spatial
+    /// index. The features then have no properties. Defaults to `false`.
+    pub skip_properties: bool,
+}
+
+/// Options for `Gpkg::create_layer_from_geojson_with_options`.
+#[cfg(feature = "geojson")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+#[derive(Clone, Debug)]
+pub struct GeoJsonImportOptions {
+    /// Number of features used to infer the columns, the geometry type and
+    /// the dimension. `None` uses all the features. Defaults to `Some(1000)`.
+    pub sample_size: Option<usize>,
+    /// Whether a property whose values have different types (other than
+    /// integers mixed with numbers, which become `DOUBLE`) is created as a
+    /// `TEXT` column. If `false`, such a property fails with
+    /// `GpkgError::GeoJsonPropertyTypeConflict`. Defaults to `true`.
+    pub widen_conflicting_types: bool,
+}
+
+#[cfg(feature = "geojson")]
+impl Default for GeoJsonImportOptions {
+    fn default() -> Self {
+        Self {
+            sample_size: Some(1000),
+            widen_conflicting_types: true,
+        }
+    }
+}
+
+/// How `Gpkg::create_layer_from_csv` builds the geometry of a row.
+#[cfg(feature = "csv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[derive(Clone, Debug)]
+pub enum GeometryFrom {
+    /// A point from a longitude (X) and a latitude (Y) column.
+    LonLat { lon: String, lat: String },
+    /// A geometry from a WKT column.
+    Wkt { column: String },
+}
+
+/// Options for `Gpkg::create_layer_from_csv`.
+#[cfg(feature = "csv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[derive(Clone, Debug)]
+pub struct CsvImportOptions {
+    /// The columns the geometry is read from. They don't become property
+    /// columns.
+    pub geometry: GeometryFrom,
+    /// The SRS of the layer. Defaults to 4326.
+    pub srs_id: u32,
+    /// Column types to use instead of the inferred ones, by column name.
+    pub column_types: std::collections::HashMap<String, ColumnType>,
+    /// Number of rows used to infer the column types and, for WKT, the
+    /// geometry type and dimension. `None` uses all the rows. Defaults to
+    /// `Some(1000)`.
+    pub sample_size: Option<usize>,
+    /// The field delimiter. Defaults to `b','`.
+    pub delimiter: u8,
+    /// Whether a malformed row fails the import with `GpkgError::InvalidCsv`.
+    /// If `false`, such rows are skipped. Defaults to `true`.
+    pub strict: bool,
+}
+
+#[cfg(feature = "csv")]
+impl CsvImportOptions {
+    /// Create options with the defaults, reading the geometry as `geometry`.
+    pub fn new(geometry: GeometryFrom) -> Self {
+        Self {
+            geometry,
+            srs_id: 4326,
+            column_types: Default::default(),
+            sample_size: Some(1000),
+            delimiter: b',',
+            strict: true,
+        }
+    }
+}
+
+/// Compression codec of `Gpkg::export_parquet`.
+#[cfg(feature = "parquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetCompression {
+    /// No compression.
+    Uncompressed,
+    /// Snappy, the most widely supported codec.
+    #[default]
+    Snappy,
+    /// Zstandard with the given level (1 to 22).
+    Zstd(i32),
+}
+
+/// Options for `Gpkg::export_parquet`.
+#[cfg(feature = "parquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+#[derive(Clone, Debug)]
+pub struct ParquetExportOptions {
+    /// Number of rows read from the layer per record batch. Defaults to 1024.
+    pub batch_size: u32,
+    /// Compression of the column chunks. Defaults to `ParquetCompression::Snappy`.
+    pub compression: ParquetCompression,
+}
+
+#[cfg(feature = "parquet")]
+impl Default for ParquetExportOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1024,
+            compression: ParquetCompression::default(),
+        }
+    }
 }
 
-/// Layer-wide metadata and property column definitions.
+/// Layer-wide metadata and property column definitions, returned by
+/// `GpkgLayer::metadata`, `Gpkg::layer_metadata` and
+/// `Gpkg::list_layers_detailed`.
 #[derive(Clone, Debug)]
 pub struct GpkgLayerMetadata {
+    pub table_name: String,
+    /// The `data_type` column of `gpkg_contents`, i.e. `features`.
+    pub data_type: String,
     pub primary_key_column: String,
     pub geometry_column: String,
-    pub geometry_type: GeometryType,
-    pub geometry_dimension: Dimension,
-    pub srs_id: u32,
+    /// The geometry type name as stored in `gpkg_geometry_columns`.
+    pub geometry_type_name: String,
+    /// The parsed geometry type, or `None` if `geometry_type_name` is unknown.
+    pub geometry_type: Option<GeometryType>,
+    /// The geometry dimension, or `None` if the `z` / `m` flags are invalid.
+    pub geometry_dimension: Option<Dimension>,
+    pub srs_id: i32,
     pub other_columns: Vec<ColumnSpec>,
+    /// The `identifier` column of `gpkg_contents`.
+    pub identifier: Option<String>,
+    /// The `description` column of `gpkg_contents`.
+    pub description: Option<String>,
+    /// The `last_change` column of `gpkg_contents`.
+    pub last_change: String,
+    /// The extent stored in `gpkg_contents` as `(min_x, min_y, max_x, max_y)`.
+    pub extent: Option<(f64, f64, f64, f64)>,
+}
+
+/// Column title, description and constraint stored with the `gpkg_schema`
+/// extension, returned by `GpkgLayer::column_info`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataColumnInfo {
+    /// A human-readable identifier of the column, e.g. a short name. It must
+    /// be unique within the table.
+    pub name: Option<String>,
+    /// A human-readable formal title of the column, e.g. its alias.
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// The MIME type of a `BLOB` column, e.g. `image/png`.
+    pub mime_type: Option<String>,
+    /// The constraint on the column values. Reading a constraint name that
+    /// has no rows in `gpkg_data_column_constraints` gives `None`.
+    pub constraint: Option<DataColumnConstraint>,
+}
+
+/// A named constraint of `gpkg_data_column_constraints`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataColumnConstraint {
+    /// The value must be within the range. A `None` bound is unbounded.
+    Range {
+        name: String,
+        min: Option<f64>,
+        min_is_inclusive: bool,
+        max: Option<f64>,
+        max_is_inclusive: bool,
+    },
+    /// The value must be one of the values, given with their optional
+    /// descriptions.
+    Enum {
+        name: String,
+        values: Vec<(String, Option<String>)>,
+    },
+    /// The value must match the SQLite `GLOB` pattern.
+    Glob { name: String, pattern: String },
+}
+
+impl DataColumnConstraint {
+    /// Return the `constraint_name` of the constraint.
+    pub fn name(&self) -> &str {
+        match self {
+            DataColumnConstraint::Range { name, .. }
+            | DataColumnConstraint::Enum { name, .. }
+            | DataColumnConstraint::Glob { name, .. } => name,
+        }
+    }
+}
+
+/// A row of the `gpkg_metadata` table, returned by `Gpkg::metadata_entries`
+/// and `GpkgLayer::metadata_entries`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataEntry {
+    pub id: i64,
+    /// The `md_scope` of the metadata, e.g. `dataset` or `featureType`.
+    pub scope: String,
+    /// The `md_standard_uri`, e.g. `http://schemas.opengis.net/iso/19139/`.
+    pub standard_uri: String,
+    pub mime_type: String,
+    pub metadata: String,
+}
+
+/// A row of the `gpkg_spatial_ref_sys` table, returned by `Gpkg::list_srs`
+/// and `Gpkg::get_srs`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpatialRefSys {
+    pub srs_name: String,
+    pub srs_id: i32,
+    pub organization: String,
+    pub organization_coordsys_id: i32,
+    /// The WKT definition of the SRS, or `"undefined"`.
+    pub definition: String,
+    pub description: Option<String>,
+    /// The WKT2 definition of the SRS, only present when the GeoPackage uses
+    /// the `gpkg_crs_wkt` extension (see `Gpkg::enable_crs_wkt_extension`).
+    pub definition_12_063: Option<String>,
 }
 
 /// Owned dynamic value used for feature properties.
@@ -70,6 +720,70 @@ pub enum Value {
     Geometry(Vec<u8>), // we want to use Wkb struct here, but it requires a lifetime
 }
 
+impl Value {
+    /// Encode a geometry as a GeoPackage geometry blob, e.g. to write a
+    /// geometry into a property column.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Value;
+    ///
+    /// let value = Value::from_geometry(&Point::new(1.0, 2.0), 4326)?;
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn from_geometry<G: GeometryTrait<T = f64>>(
+        geometry: &G,
+        srs_id: u32,
+    ) -> Result<Self, GpkgError> {
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, geometry, &Default::default())?;
+        let wkb = Wkb::try_new(&buf)?;
+        Ok(Value::Geometry(crate::gpkg::wkb_to_gpkg_geometry(
+            wkb, srs_id, None,
+        )?))
+    }
+
+    /// Return the bytes of a `Blob` or `Geometry` value, or `None` for any
+    /// other variant.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use rusqlite_gpkg::Value;
+    ///
+    /// let value = Value::Blob(vec![1, 2, 3]);
+    /// assert_eq!(value.as_bytes(), Some(&[1, 2, 3][..]));
+    /// ```
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Blob(bytes) | Value::Geometry(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Return `true` if this is a `Blob` or `Geometry` value holding a
+    /// GeoPackage geometry blob, i.e. it starts with the `"GP"` magic and is
+    /// long enough for the fixed header.
+    ///
+    /// This is useful for the results of `Gpkg::query()`, where geometry
+    /// columns are read as `Value::Blob`.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use geo_types::Point;
+    /// use rusqlite_gpkg::Value;
+    ///
+    /// let value = Value::from_geometry(&Point::new(1.0, 2.0), 4326)?;
+    /// assert!(Value::Blob(value.as_bytes().unwrap().to_vec()).is_gpkg_geometry());
+    /// assert!(!Value::Blob(vec![1, 2, 3]).is_gpkg_geometry());
+    /// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+    /// ```
+    pub fn is_gpkg_geometry(&self) -> bool {
+        self.as_bytes()
+            .is_some_and(|bytes| bytes.len() >= 8 && bytes.starts_with(b"GP"))
+    }
+}
+
 impl From<&str> for Value {
     fn from(value: &str) -> Self {
         Value::Text(value.to_string())
@@ -82,6 +796,18 @@ impl From<String> for Value {
     }
 }
 
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Blob(value)
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(value: &[u8]) -> Self {
+        Value::Blob(value.to_vec())
+    }
+}
+
 impl From<bool> for Value {
     fn from(value: bool) -> Self {
         Value::Integer(if value { 1 } else { 0 })
@@ -149,6 +875,32 @@ macro_rules! params {
     };
 }
 
+/// Convert an iterator of values into property values, e.g. when the number of
+/// properties is only known at runtime.
+///
+/// The property arguments of the writing methods accept anything that yields
+/// `Value` or `&Value`, so `Vec<Value>`, `&[Value]`, `params![...]` and the
+/// result of this function all work.
+///
+/// Example:
+/// ```no_run
+/// use geo_types::Point;
+/// use rusqlite_gpkg::{Gpkg, params_from};
+///
+/// let gpkg = Gpkg::open("data/example.gpkg")?;
+/// let layer = gpkg.get_layer("names")?;
+/// let raw = vec!["alpha", "beta"];
+/// layer.insert(Point::new(1.0, 2.0), params_from(raw))?;
+/// # Ok::<(), rusqlite_gpkg::GpkgError>(())
+/// ```
+pub fn params_from<I>(values: I) -> Vec<Value>
+where
+    I: IntoIterator,
+    I::Item: Into<Value>,
+{
+    values.into_iter().map(Into::into).collect()
+}
+
 #[inline]
 fn value_to_sql_output(value: &Value) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
     use rusqlite::types::{ToSqlOutput, ValueRef};
@@ -186,22 +938,65 @@ impl From<rusqlite::types::Value> for Value {
     }
 }
 
-impl<'a> From<rusqlite::types::ValueRef<'a>> for Value {
+/// Fails if a TEXT value is not valid UTF-8.
+impl<'a> TryFrom<rusqlite::types::ValueRef<'a>> for Value {
+    type Error = std::str::Utf8Error;
+
     #[inline]
-    fn from(value: rusqlite::types::ValueRef<'a>) -> Self {
-        match value {
+    fn try_from(value: rusqlite::types::ValueRef<'a>) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
             rusqlite::types::ValueRef::Null => Value::Null,
             rusqlite::types::ValueRef::Integer(value) => Value::Integer(value),
             rusqlite::types::ValueRef::Real(value) => Value::Real(value),
             rusqlite::types::ValueRef::Text(value) => {
-                let s = std::str::from_utf8(value).expect("invalid UTF-8");
-                Value::Text(s.to_string())
+                Value::Text(std::str::from_utf8(value)?.to_string())
             }
             rusqlite::types::ValueRef::Blob(value) => Value::Blob(value.to_vec()),
-        }
+        })
     }
 }
 
+// Read a column of a feature or attribute row, reporting invalid UTF-8 text
+// as `GpkgError::InvalidUtf8Text`.
+pub(crate) fn value_from_row(
+    row: &rusqlite::Row<'_>,
+    idx: usize,
+    id: Option<i64>,
+) -> rusqlite::Result<Value> {
+    Value::try_from(row.get_ref(idx)?).map_err(|_| {
+        let column = row
+            .as_ref()
+            .column_name(idx)
+            .unwrap_or_default()
+            .to_string();
+        rusqlite::Error::FromSqlConversionFailure(
+            idx,
+            rusqlite::types::Type::Text,
+            Box::new(GpkgError::InvalidUtf8Text { column, id }),
+        )
+    })
+}
+
+// Attach the layer name and the primary key (read from column `id_idx`) to an
+// error raised while reading a feature row. Like `value_from_row()`, this
+// passes the error through rusqlite as a conversion failure.
+pub(crate) fn feature_row_error(
+    row: &rusqlite::Row<'_>,
+    layer_name: &str,
+    id_idx: usize,
+    err: rusqlite::Error,
+) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        id_idx,
+        rusqlite::types::Type::Integer,
+        Box::new(GpkgError::Feature {
+            layer_name: layer_name.to_string(),
+            id: row.get(id_idx).ok(),
+            source: Box::new(err.into()),
+        }),
+    )
+}
+
 impl From<Value> for rusqlite::types::Value {
     #[inline]
     fn from(value: Value) -> Self {
@@ -227,6 +1022,48 @@ fn value_type_name(value: &Value) -> &'static str {
     }
 }
 
+// Check that `value` can be stored in the column without relying on SQLite's
+// type affinity. Used by `GpkgLayer` in strict mode.
+pub(crate) fn check_value_type(spec: &ColumnSpec, value: &Value) -> Result<(), GpkgError> {
+    let expected = match spec.column_type {
+        ColumnType::Boolean => "BOOLEAN (INTEGER)",
+        ColumnType::Integer
+        | ColumnType::TinyInt
+        | ColumnType::SmallInt
+        | ColumnType::MediumInt => "INTEGER",
+        // Integers are converted to REAL without loss of meaning.
+        ColumnType::Double | ColumnType::Float => "REAL or INTEGER",
+        ColumnType::Varchar | ColumnType::Date | ColumnType::Datetime => "TEXT",
+        ColumnType::Blob => "BLOB",
+        ColumnType::Geometry => "GEOMETRY or BLOB",
+    };
+    let ok = match (spec.column_type, value) {
+        (_, Value::Null) => !spec.not_null,
+        (
+            ColumnType::Boolean
+            | ColumnType::Integer
+            | ColumnType::TinyInt
+            | ColumnType::SmallInt
+            | ColumnType::MediumInt,
+            Value::Integer(_),
+        ) => true,
+        (ColumnType::Double | ColumnType::Float, Value::Real(_) | Value::Integer(_)) => true,
+        (ColumnType::Varchar | ColumnType::Date | ColumnType::Datetime, Value::Text(_)) => true,
+        (ColumnType::Blob, Value::Blob(_)) => true,
+        (ColumnType::Geometry, Value::Geometry(_) | Value::Blob(_)) => true,
+        _ => false,
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(GpkgError::PropertyTypeMismatch {
+            column: spec.name.clone(),
+            expected,
+            actual: value_type_name(value),
+        })
+    }
+}
+
 #[inline]
 fn invalid_type(expected: &'static str, value: &Value) -> GpkgError {
     GpkgError::ValueTypeMismatch {
@@ -391,6 +1228,18 @@ where
     }
 }
 
+impl TryFrom<&Value> for String {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => Ok(s.clone()),
+            _ => Err(invalid_type("String", value)),
+        }
+    }
+}
+
 impl TryFrom<Value> for String {
     type Error = GpkgError;
 
@@ -415,6 +1264,39 @@ impl<'a> TryFrom<&'a Value> for &'a str {
     }
 }
 
+impl TryFrom<&Value> for Vec<u8> {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value
+            .as_bytes()
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| invalid_type("Vec<u8>", value))
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Blob(bytes) | Value::Geometry(bytes) => Ok(bytes),
+            other => Err(invalid_type("Vec<u8>", &other)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a [u8] {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        value.as_bytes().ok_or_else(|| invalid_type("&[u8]", value))
+    }
+}
+
 impl<'a> TryFrom<&'a Value> for Wkb<'a> {
     type Error = GpkgError;
 
@@ -434,33 +1316,233 @@ impl<'a> TryFrom<&'a Value> for Wkb<'a> {
     }
 }
 
-// When inserting, geom is a owned value, while the rest are borrowed value.
-// This is a utility to handle these transparently.
-enum SqlParam<'a> {
+#[cfg(feature = "chrono")]
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// The format GDAL writes, e.g. `2024-01-02T03:04:05.678Z`.
+#[cfg(feature = "chrono")]
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+/// Encode as a GeoPackage `DATE` value (`YYYY-MM-DD`).
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl From<chrono::NaiveDate> for Value {
+    fn from(value: chrono::NaiveDate) -> Self {
+        Value::Text(value.format(DATE_FORMAT).to_string())
+    }
+}
+
+/// Encode as a GeoPackage `DATETIME` value (`YYYY-MM-DDTHH:MM:SS.SSSZ`).
+/// Anything below milliseconds is truncated.
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl From<chrono::DateTime<chrono::Utc>> for Value {
+    fn from(value: chrono::DateTime<chrono::Utc>) -> Self {
+        Value::Text(value.format(DATETIME_FORMAT).to_string())
+    }
+}
+
+/// Parse a GeoPackage `DATE` value (`YYYY-MM-DD`).
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl TryFrom<&Value> for chrono::NaiveDate {
+    type Error = GpkgError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let Value::Text(text) = value else {
+            return Err(invalid_type("NaiveDate", value));
+        };
+        chrono::NaiveDate::parse_from_str(text, DATE_FORMAT).map_err(|_| {
+            GpkgError::InvalidTemporalValue {
+                expected: "ISO 8601 date (YYYY-MM-DD)",
+                value: text.clone(),
+            }
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl TryFrom<Value> for chrono::NaiveDate {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+/// Parse a GeoPackage `DATETIME` value (`YYYY-MM-DDTHH:MM:SS.SSSZ`). Other
+/// RFC 3339 offsets are converted into UTC, and a value without any offset is
+/// read as UTC.
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl TryFrom<&Value> for chrono::DateTime<chrono::Utc> {
+    type Error = GpkgError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let Value::Text(text) = value else {
+            return Err(invalid_type("DateTime<Utc>", value));
+        };
+        if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+            return Ok(datetime.to_utc());
+        }
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| chrono::NaiveDateTime::parse_from_str(text, format).ok())
+            .map(|datetime| datetime.and_utc())
+            .ok_or_else(|| GpkgError::InvalidTemporalValue {
+                expected: "ISO 8601 date-time (YYYY-MM-DDTHH:MM:SS.SSSZ)",
+                value: text.clone(),
+            })
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl TryFrom<Value> for chrono::DateTime<chrono::Utc> {
+    type Error = GpkgError;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+/// Serializes `Null` as a unit (`null` in JSON), `Integer` as `i64`, `Real` as
+/// `f64`, `Text` as a string, and `Blob` and `Geometry` as bytes (an array of
+/// numbers in JSON).
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Real(f) => serializer.serialize_f64(*f),
+            Value::Text(s) => serializer.serialize_str(s),
+            Value::Blob(bytes) | Value::Geometry(bytes) => serializer.serialize_bytes(bytes),
+        }
+    }
+}
+
+/// The inverse of the `Serialize` implementation. Bytes and sequences of
+/// bytes become `Blob`, so a serialized `Geometry` comes back as a `Blob`.
+/// An unsigned integer that doesn't fit in `i64` is an error.
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ValueVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("null, an integer, a number, a string, or bytes")
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Integer(v))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Value, E> {
+        i64::try_from(v).map(Value::Integer).map_err(|_| {
+            E::invalid_value(serde::de::Unexpected::Unsigned(v), &"an integer within i64")
+        })
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Real(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::Text(v))
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Blob(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Value, E> {
+        Ok(Value::Blob(v))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Ok(Value::Blob(bytes))
+    }
+}
+
+// When inserting, geom is a owned value, while the properties are either owned
+// or borrowed. This is a utility to handle these transparently.
+enum SqlParam<V> {
     Owned(Value),
-    Borrowed(&'a Value),
+    Property(V),
 }
 
-impl<'a> rusqlite::ToSql for SqlParam<'a> {
+impl<V: Borrow<Value>> rusqlite::ToSql for SqlParam<V> {
     #[inline]
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
         match self {
             SqlParam::Owned(value) => value.to_sql(),
-            SqlParam::Borrowed(value) => value.to_sql(),
+            SqlParam::Property(value) => value.borrow().to_sql(),
         }
     }
 }
 
-pub(crate) fn params_from_geom_and_properties<'p, P>(
+pub(crate) fn params_from_geom_and_properties<P>(
     geom: Vec<u8>,
     properties: P,
     id: Option<i64>,
 ) -> impl rusqlite::Params
 where
-    P: IntoIterator<Item = &'p Value>,
+    P: IntoIterator,
+    P::Item: Borrow<Value>,
 {
     let params = std::iter::once(SqlParam::Owned(Value::Geometry(geom)))
-        .chain(properties.into_iter().map(SqlParam::Borrowed))
+        .chain(properties.into_iter().map(SqlParam::Property))
+        .chain(id.into_iter().map(|i| SqlParam::Owned(Value::Integer(i))));
+    rusqlite::params_from_iter(params)
+}
+
+pub(crate) fn params_from_properties<P>(properties: P, id: Option<i64>) -> impl rusqlite::Params
+where
+    P: IntoIterator,
+    P::Item: Borrow<Value>,
+{
+    let params = properties
+        .into_iter()
+        .map(SqlParam::Property)
         .chain(id.into_iter().map(|i| SqlParam::Owned(Value::Integer(i))));
     rusqlite::params_from_iter(params)
 }
@@ -468,6 +1550,56 @@ where
 #[cfg(test)]
 mod tests {
     use super::{GpkgError, Value};
+    use geo_traits::{CoordTrait, GeometryTrait, GeometryType, PointTrait};
+    use wkb::reader::Wkb;
+
+    #[test]
+    fn value_from_geometry_encodes_gpkg_blob() -> Result<(), GpkgError> {
+        let value = Value::from_geometry(&geo_types::Point::new(1.0, 2.0), 3857)?;
+        let Value::Geometry(blob) = &value else {
+            panic!("expected Value::Geometry");
+        };
+        assert_eq!(&blob[0..2], b"GP");
+        assert_eq!(&blob[4..8], &3857u32.to_le_bytes());
+
+        let wkb = Wkb::try_from(&value)?;
+        let GeometryType::Point(point) = wkb.as_type() else {
+            panic!("expected a point");
+        };
+        assert_eq!(point.coord().expect("coord").x_y(), (1.0, 2.0));
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_try_from_value() -> Result<(), GpkgError> {
+        let blob = Value::from(vec![0xde, 0xad]);
+        assert_eq!(blob.as_bytes(), Some(&[0xde, 0xad][..]));
+        assert_eq!(<&[u8]>::try_from(&blob)?, &[0xde, 0xad]);
+        assert_eq!(Vec::<u8>::try_from(&blob)?, vec![0xde, 0xad]);
+        assert_eq!(Vec::<u8>::try_from(blob)?, vec![0xde, 0xad]);
+
+        let geometry = Value::from_geometry(&geo_types::Point::new(1.0, 2.0), 4326)?;
+        assert_eq!(&geometry.as_bytes().expect("geometry bytes")[0..2], b"GP");
+        assert_eq!(Option::<Vec<u8>>::try_from(Value::Null)?, None);
+
+        let text = Value::from("alpha");
+        assert_eq!(text.as_bytes(), None);
+        assert!(matches!(
+            <&[u8]>::try_from(&text),
+            Err(GpkgError::ValueTypeMismatch {
+                expected: "&[u8]",
+                actual: "TEXT"
+            })
+        ));
+        assert!(matches!(
+            Vec::<u8>::try_from(Value::Integer(1)),
+            Err(GpkgError::ValueTypeMismatch {
+                expected: "Vec<u8>",
+                actual: "INTEGER"
+            })
+        ));
+        Ok(())
+    }
 
     #[test]
     fn option_try_from_value_null_is_none() -> Result<(), GpkgError> {
@@ -492,4 +1624,95 @@ mod tests {
         assert_eq!(parsed, Some(42));
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn value_serde_json_roundtrip() {
+        let values = [
+            (Value::Null, "null"),
+            (Value::Integer(-3), "-3"),
+            (Value::Real(1.5), "1.5"),
+            (Value::Text("a\"b".to_string()), r#""a\"b""#),
+            (Value::Blob(vec![0, 255]), "[0,255]"),
+        ];
+        for (value, json) in values {
+            assert_eq!(serde_json::to_string(&value).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Value>(json).unwrap(), value);
+        }
+
+        // Geometry is serialized as bytes and comes back as a blob.
+        let geometry = Value::Geometry(vec![0x47, 0x50]);
+        let json = serde_json::to_string(&geometry).unwrap();
+        assert_eq!(json, "[71,80]");
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            Value::Blob(vec![0x47, 0x50])
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>("true").unwrap(),
+            Value::Integer(1)
+        );
+        assert!(serde_json::from_str::<Value>("18446744073709551615").is_err());
+        assert!(serde_json::from_str::<Value>("[256]").is_err());
+        assert!(serde_json::from_str::<Value>(r#"{"a":1}"#).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_values_roundtrip_in_gdal_format() -> Result<(), GpkgError> {
+        use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).expect("date");
+        let value = Value::from(date);
+        assert_eq!(value, Value::Text("2024-02-29".to_string()));
+        assert_eq!(NaiveDate::try_from(&value)?, date);
+
+        for text in ["2024-01-02T03:04:05.678Z", "1969-12-31T23:59:59.000Z"] {
+            let datetime: DateTime<Utc> = Value::Text(text.to_string()).try_into()?;
+            assert_eq!(Value::from(datetime), Value::Text(text.to_string()));
+        }
+
+        let expected = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        for text in [
+            "2024-01-02T03:04:05Z",
+            "2024-01-02T12:04:05+09:00",
+            "2024-01-02T03:04:05",
+            "2024-01-02 03:04:05.000",
+        ] {
+            let parsed: DateTime<Utc> = (&Value::Text(text.to_string())).try_into()?;
+            assert_eq!(parsed, expected, "{text}");
+        }
+
+        let none: Option<NaiveDate> = Value::Null.try_into()?;
+        assert_eq!(none, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_parse_failures_report_the_text() {
+        use chrono::{DateTime, NaiveDate, Utc};
+
+        let err = NaiveDate::try_from(&Value::Text("2024-02-30".to_string())).unwrap_err();
+        assert!(matches!(
+            &err,
+            GpkgError::InvalidTemporalValue { value, .. } if value == "2024-02-30"
+        ));
+        assert!(err.to_string().contains("\"2024-02-30\""));
+
+        let err = DateTime::<Utc>::try_from(Value::Text("yesterday".to_string())).unwrap_err();
+        assert!(matches!(
+            err,
+            GpkgError::InvalidTemporalValue { value, .. } if value == "yesterday"
+        ));
+
+        assert!(matches!(
+            NaiveDate::try_from(&Value::Integer(20240229)),
+            Err(GpkgError::ValueTypeMismatch {
+                expected: "NaiveDate",
+                actual: "INTEGER"
+            })
+        ));
+    }
 }
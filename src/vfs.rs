@@ -1,9 +1,12 @@
 //! Single-file hybrid VFS for wasm.
 //!
-//! - Writes to the main database file are forwarded to a user-provided writer.
+//! - The main database file is read from and written to a user-provided
+//!   `Read + Write + Seek` handle at the requested offsets, so an existing
+//!   file (e.g. stored in OPFS) can be opened and appended to.
 //! - Writes to sidecar files (`-wal`, `-shm`, `-journal`) stay in memory.
 //! - This VFS intentionally does not validate filename intent.
 
+use crate::hybrid_file::{HandleState, HybridHandle, is_main_sqlite_file};
 use crate::{Gpkg, GpkgError, Result as CrateResult};
 use sqlite_wasm_rs::utils::{
     OsCallback, RegisterVfsError, SQLiteIoMethods, SQLiteVfs, SQLiteVfsFile, VfsError, VfsFile,
@@ -16,70 +19,22 @@ use sqlite_wasm_rs::utils::{
 };
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::io::{Seek, SeekFrom, Write};
+use std::io::{Read, Seek, Write};
 use std::path::Path;
 use std::rc::Rc;
 use std::time::Duration;
 
-trait HybridWriter: Write + Seek {}
-impl<T: Write + Seek> HybridWriter for T {}
-
-type SharedWriter = Rc<RefCell<WriterState>>;
+type SharedWriter = Rc<RefCell<HandleState>>;
 type SharedFiles = Rc<RefCell<HashMap<String, HybridFile>>>;
 type HybridAppData = RefCell<HybridState>;
 
-struct WriterState {
-    writer: Box<dyn HybridWriter>,
-    /// Last known cursor position. `None` means unknown — initial state,
-    /// after a writer replacement, or after a failed seek/write that may
-    /// have left the cursor at an indeterminate offset.
-    pos: Option<u64>,
-}
-
-impl WriterState {
-    fn new(writer: Box<dyn HybridWriter>) -> Self {
-        Self { writer, pos: None }
-    }
-
-    /// Write `buf` at `offset`, skipping the seek when the cursor is already
-    /// there. SQLite emits long runs of contiguous page writes; each `seek`
-    /// otherwise forces `BufWriter::flush_buf` plus, on OPFS, a synchronous
-    /// `get_size` JS round-trip.
-    fn write_at(&mut self, buf: &[u8], offset: u64) -> std::io::Result<()> {
-        if self.pos != Some(offset) {
-            self.pos = None;
-            self.writer.seek(SeekFrom::Start(offset))?;
-            self.pos = Some(offset);
-        }
-        match self.writer.write_all(buf) {
-            Ok(()) => {
-                self.pos = Some(offset + buf.len() as u64);
-                Ok(())
-            }
-            Err(e) => {
-                self.pos = None;
-                Err(e)
-            }
-        }
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.writer.flush()
-    }
-
-    fn replace(&mut self, writer: Box<dyn HybridWriter>) {
-        self.writer = writer;
-        self.pos = None;
-    }
-}
-
 thread_local! {
     static DEFAULT_HYBRID_VFS: RefCell<Option<HybridVfsHandle>> = const { RefCell::new(None) };
 }
 
-/// Builder that holds the writer used for main database file writes.
+/// Builder that holds the handle backing the main database file.
 pub struct HybridVfsBuilder {
-    writer: Box<dyn HybridWriter>,
+    writer: Box<dyn HybridHandle>,
 }
 
 #[derive(Clone)]
@@ -91,7 +46,10 @@ pub struct HybridVfsHandle {
 
 impl HybridVfsBuilder {
     /// Create a single-file hybrid VFS builder.
-    pub fn new<W: Write + Seek + 'static>(writer: W) -> Self {
+    ///
+    /// The main database file is read from and written to `writer` at the
+    /// offsets SQLite requests, so `writer` may already contain a GeoPackage.
+    pub fn new<W: Read + Write + Seek + 'static>(writer: W) -> Self {
         Self {
             writer: Box::new(writer),
         }
@@ -105,7 +63,7 @@ impl HybridVfsBuilder {
     ) -> Result<*mut sqlite3_vfs, RegisterVfsError> {
         let state = HybridState {
             files: Rc::new(RefCell::new(HashMap::new())),
-            writer: Rc::new(RefCell::new(WriterState::new(self.writer))),
+            writer: Rc::new(RefCell::new(HandleState::new(self.writer))),
         };
         register_vfs::<HybridIoMethods, HybridVfsImpl>(vfs_name, RefCell::new(state), default_vfs)
     }
@@ -116,7 +74,7 @@ impl HybridVfsBuilder {
         vfs_name: &str,
         default_vfs: bool,
     ) -> Result<HybridVfsHandle, RegisterVfsError> {
-        let writer: SharedWriter = Rc::new(RefCell::new(WriterState::new(self.writer)));
+        let writer: SharedWriter = Rc::new(RefCell::new(HandleState::new(self.writer)));
         let files: SharedFiles = Rc::new(RefCell::new(HashMap::new()));
         let state = HybridState {
             files: files.clone(),
@@ -134,7 +92,8 @@ impl HybridVfsBuilder {
     ///
     /// On first use, this registers a process-local default VFS. On subsequent calls,
     /// it reuses the same registration, replaces the writer, and clears the in-memory
-    /// file map so SQLite sees a fresh database. Any `Gpkg` instances from a previous
+    /// file map so SQLite sees the database in the new writer (an empty writer
+    /// gives a fresh GeoPackage). Any `Gpkg` instances from a previous
     /// call must be dropped before calling this again.
     pub fn open_gpkg<P: AsRef<Path>>(self, sqlite_filename: P) -> CrateResult<Gpkg> {
         let writer = self.writer;
@@ -158,17 +117,17 @@ impl HybridVfsBuilder {
 }
 
 impl HybridVfsHandle {
-    /// Replace the writer used for main database file writes.
-    pub fn replace_writer<W: Write + Seek + 'static>(&self, writer: W) {
+    /// Replace the handle backing the main database file.
+    pub fn replace_writer<W: Read + Write + Seek + 'static>(&self, writer: W) {
         self.replace_boxed_writer(Box::new(writer));
     }
 
-    fn replace_boxed_writer(&self, writer: Box<dyn HybridWriter>) {
+    fn replace_boxed_writer(&self, writer: Box<dyn HybridHandle>) {
         self.writer.borrow_mut().replace(writer);
     }
 
-    /// Drop every in-memory file tracked by this VFS so the next `open_gpkg`
-    /// starts from an empty database. Calling this while a `Gpkg` from a prior
+    /// Drop every file tracked by this VFS so the next `open_gpkg` starts from
+    /// the contents of the current writer. Calling this while a `Gpkg` from a prior
     /// open is still alive will leave that connection with dangling references.
     fn clear_files(&self) {
         self.files.borrow_mut().clear();
@@ -227,54 +186,35 @@ impl VfsFile for MemFile {
 }
 
 struct MainFile {
-    data: Vec<u8>,
     writer: SharedWriter,
 }
 
 impl MainFile {
     fn new(writer: SharedWriter) -> Self {
-        Self {
-            data: Vec::new(),
-            writer,
-        }
+        Self { writer }
     }
 }
 
 impl VfsFile for MainFile {
     fn read(&self, buf: &mut [u8], offset: usize) -> VfsResult<bool> {
-        let end = offset.saturating_add(buf.len());
-        if self.data.len() <= offset {
-            buf.fill(0);
-            return Ok(false);
-        }
-
-        let read_end = end.min(self.data.len());
-        let read_size = read_end - offset;
-        buf[..read_size].copy_from_slice(&self.data[offset..read_end]);
-        if read_size < buf.len() {
-            buf[read_size..].fill(0);
-            return Ok(false);
-        }
-        Ok(true)
+        self.writer
+            .borrow_mut()
+            .read_at(buf, offset as u64)
+            .map_err(|e| VfsError::new(SQLITE_IOERR_READ, e.to_string()))
     }
 
     fn write(&mut self, buf: &[u8], offset: usize) -> VfsResult<()> {
-        let end = offset.saturating_add(buf.len());
-        if end > self.data.len() {
-            self.data.resize(end, 0);
-        }
-        self.data[offset..end].copy_from_slice(buf);
-
         self.writer
             .borrow_mut()
             .write_at(buf, offset as u64)
-            .map_err(|e| VfsError::new(SQLITE_IOERR_WRITE, e.to_string()))?;
-        Ok(())
+            .map_err(|e| VfsError::new(SQLITE_IOERR_WRITE, e.to_string()))
     }
 
     fn truncate(&mut self, size: usize) -> VfsResult<()> {
-        self.data.truncate(size);
-        Ok(())
+        self.writer
+            .borrow_mut()
+            .truncate(size as u64)
+            .map_err(|e| VfsError::new(SQLITE_IOERR, e.to_string()))
     }
 
     fn flush(&mut self) -> VfsResult<()> {
@@ -285,7 +225,11 @@ impl VfsFile for MainFile {
     }
 
     fn size(&self) -> VfsResult<usize> {
-        Ok(self.data.len())
+        self.writer
+            .borrow_mut()
+            .size()
+            .map(|size| size as usize)
+            .map_err(|e| VfsError::new(SQLITE_IOERR, e.to_string()))
     }
 }
 
@@ -336,10 +280,6 @@ struct HybridState {
     writer: SharedWriter,
 }
 
-fn is_main_sqlite_file(name: &str) -> bool {
-    !name.ends_with("-wal") && !name.ends_with("-shm") && !name.ends_with("-journal")
-}
-
 struct HybridStore;
 
 impl VfsStore<HybridFile, HybridAppData> for HybridStore {
@@ -451,13 +391,19 @@ impl SQLiteVfs<HybridIoMethods> for HybridVfsImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::{self, Cursor, Seek, Write};
+    use std::io::{self, Cursor, Read, Seek, Write};
 
-    /// Test sink that delegates `Write + Seek` to a shared `Cursor<Vec<u8>>`,
-    /// so the test can keep a clone to inspect the resulting bytes after the
-    /// `Box<dyn HybridWriter>` has swallowed the concrete type.
+    /// Test handle that delegates `Read + Write + Seek` to a shared
+    /// `Cursor<Vec<u8>>`, so the test can keep a clone to inspect the resulting
+    /// bytes after the `Box<dyn HybridHandle>` has swallowed the concrete type.
     struct SharedCursor(Rc<RefCell<Cursor<Vec<u8>>>>);
 
+    impl Read for SharedCursor {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.borrow_mut().read(buf)
+        }
+    }
+
     impl Write for SharedCursor {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
             self.0.borrow_mut().write(buf)
@@ -509,7 +455,7 @@ mod tests {
 
     #[test]
     fn handle_clear_files_drops_entries_visible_to_state() {
-        let writer: SharedWriter = Rc::new(RefCell::new(WriterState::new(Box::new(Cursor::new(
+        let writer: SharedWriter = Rc::new(RefCell::new(HandleState::new(Box::new(Cursor::new(
             Vec::<u8>::new(),
         )))));
         let files: SharedFiles = Rc::new(RefCell::new(HashMap::new()));
@@ -540,7 +486,7 @@ mod tests {
     #[test]
     fn main_file_writes_forward_to_writer_at_offset() {
         let cursor = Rc::new(RefCell::new(Cursor::new(Vec::<u8>::new())));
-        let writer: SharedWriter = Rc::new(RefCell::new(WriterState::new(Box::new(SharedCursor(
+        let writer: SharedWriter = Rc::new(RefCell::new(HandleState::new(Box::new(SharedCursor(
             cursor.clone(),
         )))));
         let mut file = MainFile::new(writer);
@@ -563,6 +509,12 @@ mod tests {
         seek_count: Rc<RefCell<usize>>,
     }
 
+    impl Read for CountingSeek {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
     impl Write for CountingSeek {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
             self.inner.write(buf)
@@ -583,12 +535,13 @@ mod tests {
     fn main_file_skips_seek_for_sequential_writes() {
         let seek_count = Rc::new(RefCell::new(0_usize));
         let writer: SharedWriter =
-            Rc::new(RefCell::new(WriterState::new(Box::new(CountingSeek {
+            Rc::new(RefCell::new(HandleState::new(Box::new(CountingSeek {
                 inner: Cursor::new(Vec::<u8>::new()),
                 seek_count: seek_count.clone(),
             }))));
         let mut file = MainFile::new(writer);
 
+        // Finding the size seeks to the end, which is where the first write goes.
         file.write(&[1, 2, 3], 0).expect("first write");
         assert_eq!(*seek_count.borrow(), 1);
 